@@ -0,0 +1,232 @@
+//! 1ゲームごとの実績
+//!
+//! 操作ごとのイベント列（AchievementTracker::observe）から集計し、ゲーム終了時に awards で確定する。
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use super::state::{GameEvent, GameState};
+use super::text::MoneyReason;
+use super::PlayerId;
+
+/// 連続で稼いだ手番がこの回数以上なら LANDING_STREAK の対象
+pub const MIN_LANDING_STREAK: u32 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Achievement {
+    /// 最初に結婚した
+    FirstToMarry,
+    /// 子どもが最も多い（同数なら全員）
+    MostChildren,
+    /// 所持金がマイナスになった後、プラスでゲームを終えた
+    SurvivedBankruptcy,
+    /// 止まったマスで所持金が増えた手番の連続回数が最も長い（MIN_LANDING_STREAK 回以上）
+    LandingStreak,
+}
+
+impl Achievement {
+    pub fn fallback_text(&self) -> &'static str {
+        match self {
+            Achievement::FirstToMarry => "一番乗りの結婚",
+            Achievement::MostChildren => "子だくさん",
+            Achievement::SurvivedBankruptcy => "どん底からの復活",
+            Achievement::LandingStreak => "連続ラッキー",
+        }
+    }
+}
+
+/// プレイヤーが獲得した実績
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct AchievementAward {
+    pub player_id: PlayerId,
+    pub achievement: Achievement,
+    /// achievement の日本語表記（ローカライズ未対応のクライアント向け）
+    pub label: String,
+}
+
+/// 手番ごとの稼ぎの連続記録
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Streak {
+    /// 集計中の手番（ターン番号）
+    turn: Option<u32>,
+    /// 集計中の手番で、止まったマスで所持金が増えたか
+    gained: bool,
+    current: u32,
+    best: u32,
+}
+
+impl Streak {
+    /// 集計中の手番を締める
+    fn close(&mut self) {
+        if self.turn.take().is_none() {
+            return;
+        }
+        if self.gained {
+            self.current += 1;
+            self.best = self.best.max(self.current);
+        } else {
+            self.current = 0;
+        }
+        self.gained = false;
+    }
+}
+
+/// 実績の集計
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AchievementTracker {
+    first_married: Option<PlayerId>,
+    went_negative: HashSet<PlayerId>,
+    streaks: HashMap<PlayerId, Streak>,
+}
+
+impl AchievementTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 1操作分のイベントを集計する。after は操作後の状態
+    pub fn observe(&mut self, after: &GameState, events: &[GameEvent]) {
+        let mover = after.players.get(after.current_turn).map(|p| p.id.as_str());
+        for event in events {
+            match event {
+                GameEvent::Married { player_id } => {
+                    self.first_married.get_or_insert_with(|| player_id.clone());
+                }
+                GameEvent::PlayerMoved { player_id, .. } => {
+                    let streak = self.streaks.entry(player_id.clone()).or_default();
+                    // 分岐で止まった後の移動は同じ手番として数える
+                    if streak.turn != Some(after.turn_number) {
+                        streak.close();
+                        streak.turn = Some(after.turn_number);
+                    }
+                }
+                // 通過した給料日や、他人の手番で受け取ったお金は止まったマスの稼ぎに数えない
+                GameEvent::MoneyChanged {
+                    player_id,
+                    amount,
+                    reason,
+                } if *amount > 0 && !matches!(reason, MoneyReason::PaydayPassed) && mover == Some(player_id.as_str()) => {
+                    if let Some(streak) = self.streaks.get_mut(player_id) {
+                        streak.gained |= streak.turn.is_some();
+                    }
+                }
+                _ => {}
+            }
+        }
+        self.went_negative
+            .extend(after.players.iter().filter(|p| p.money < 0).map(|p| p.id.clone()));
+    }
+
+    /// ゲーム終了時の実績（プレイヤー順、同じプレイヤーなら Achievement の順）
+    pub fn awards(&self, final_state: &GameState) -> Vec<AchievementAward> {
+        let mut streaks = self.streaks.clone();
+        streaks.values_mut().for_each(Streak::close);
+        let best_streak = streaks.values().map(|s| s.best).max().unwrap_or(0);
+        let most_children = final_state.players.iter().map(|p| p.children).max().unwrap_or(0);
+
+        let mut awards = Vec::new();
+        for player in &final_state.players {
+            let mut earned = Vec::new();
+            if self.first_married.as_ref() == Some(&player.id) {
+                earned.push(Achievement::FirstToMarry);
+            }
+            if most_children > 0 && player.children == most_children {
+                earned.push(Achievement::MostChildren);
+            }
+            if self.went_negative.contains(&player.id) && player.money >= 0 {
+                earned.push(Achievement::SurvivedBankruptcy);
+            }
+            if best_streak >= MIN_LANDING_STREAK && streaks.get(&player.id).is_some_and(|s| s.best == best_streak) {
+                earned.push(Achievement::LandingStreak);
+            }
+            awards.extend(earned.into_iter().map(|achievement| AchievementAward {
+                player_id: player.id.clone(),
+                achievement,
+                label: achievement.fallback_text().to_string(),
+            }));
+        }
+        awards
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::ClassicGameEngine;
+    use crate::state::MapData;
+    use crate::traits::GameEngine;
+
+    fn two_players() -> GameState {
+        let map: MapData = serde_json::from_value(serde_json::json!({
+            "id": "m", "name": "m", "version": "1.0",
+            "start_money": 10000, "loan_unit": 20000, "loan_interest_rate": 1.25,
+            "tiles": [
+                { "id": 0, "type": "Start", "position": { "x": 0, "y": 0 }, "next": [] }
+            ],
+            "careers": [], "houses": []
+        }))
+        .unwrap();
+        ClassicGameEngine::new().init(
+            vec![("p1".to_string(), "Alice".to_string()), ("p2".to_string(), "Bob".to_string())],
+            &map,
+        )
+    }
+
+    fn landing(player_id: &str, amount: i64) -> Vec<GameEvent> {
+        vec![
+            GameEvent::PlayerMoved {
+                player_id: player_id.to_string(),
+                path: vec![0],
+                paydays_passed: 0,
+            },
+            GameEvent::MoneyChanged {
+                player_id: player_id.to_string(),
+                amount,
+                reason: MoneyReason::Tax,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_awards() {
+        let mut state = two_players();
+        let mut tracker = AchievementTracker::new();
+
+        // p1: 3手番続けて稼ぐ。p2: 結婚し、所持金がマイナスになってから持ち直す
+        for turn in 1..=3 {
+            state.turn_number = turn * 2 - 1;
+            state.current_turn = 0;
+            tracker.observe(&state, &landing("p1", 1000));
+
+            state.turn_number = turn * 2;
+            state.current_turn = 1;
+            let mut events = landing("p2", -20000);
+            if turn == 1 {
+                events.push(GameEvent::Married {
+                    player_id: "p2".to_string(),
+                });
+                state.players[1].money = -10000;
+            } else {
+                state.players[1].money = 5000;
+            }
+            tracker.observe(&state, &events);
+        }
+        state.players[1].children = 1;
+
+        let awards: Vec<_> = tracker.awards(&state).into_iter().map(|a| (a.player_id, a.achievement)).collect();
+        assert_eq!(
+            awards,
+            vec![
+                ("p1".to_string(), Achievement::LandingStreak),
+                ("p2".to_string(), Achievement::FirstToMarry),
+                ("p2".to_string(), Achievement::MostChildren),
+                ("p2".to_string(), Achievement::SurvivedBankruptcy),
+            ]
+        );
+    }
+}
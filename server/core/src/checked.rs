@@ -0,0 +1,197 @@
+//! エンジンの不変条件チェック
+//!
+//! CheckedEngine は別のエンジンを包み、状態を変える呼び出しのたびに結果の状態が不変条件を満たすかを調べる。
+//! 違反があれば呼び出し名・違反内容・その時点の状態を標準エラーに出し、デバッグビルドでは panic する。
+//! EngineRegistry は `checked-engine` フィーチャーが有効なときだけ、生成するエンジンをこれで包む。
+
+use super::state::*;
+use super::traits::GameEngine;
+use crate::PlayerId;
+
+/// 状態を変える呼び出しの後に不変条件を確かめるエンジン
+pub struct CheckedEngine<E: GameEngine> {
+    inner: E,
+}
+
+impl<E: GameEngine> CheckedEngine<E> {
+    pub fn new(inner: E) -> Self {
+        Self { inner }
+    }
+
+    fn check(&self, call: &str, state: &GameState, events: &[GameEvent]) {
+        let mut violations = violations(state);
+        violations.extend(choice_violations(&self.inner, state, events));
+        if violations.is_empty() {
+            return;
+        }
+        eprintln!(
+            "engine invariant violated after {}: {}\nstate: {}",
+            call,
+            violations.join("; "),
+            serde_json::to_string(state).unwrap_or_default()
+        );
+        debug_assert!(false, "engine invariant violated after {}: {}", call, violations.join("; "));
+    }
+}
+
+impl<E: GameEngine> GameEngine for CheckedEngine<E> {
+    fn init(&self, players: Vec<(PlayerId, String)>, map: &MapData) -> GameState {
+        let state = self.inner.init(players, map);
+        self.check("init", &state, &[]);
+        state
+    }
+
+    fn spin(&self, state: &GameState) -> (GameState, SpinResult) {
+        let (new_state, result) = self.inner.spin(state);
+        self.check("spin", &new_state, &[]);
+        (new_state, result)
+    }
+
+    fn advance(&self, state: &GameState, steps: u32) -> (GameState, Vec<GameEvent>) {
+        let (new_state, events) = self.inner.advance(state, steps);
+        self.check("advance", &new_state, &events);
+        (new_state, events)
+    }
+
+    fn choose_path(&self, state: &GameState, path_index: usize) -> GameState {
+        let new_state = self.inner.choose_path(state, path_index);
+        self.check("choose_path", &new_state, &[]);
+        new_state
+    }
+
+    fn legal_actions(&self, state: &GameState) -> Vec<PlayerAction> {
+        self.inner.legal_actions(state)
+    }
+
+    fn resolve_action(&self, state: &GameState, action: PlayerAction) -> (GameState, Vec<GameEvent>) {
+        let (new_state, events) = self.inner.resolve_action(state, action);
+        self.check("resolve_action", &new_state, &events);
+        (new_state, events)
+    }
+
+    fn end_turn(&self, state: &GameState) -> GameState {
+        let new_state = self.inner.end_turn(state);
+        self.check("end_turn", &new_state, &[]);
+        new_state
+    }
+
+    fn scheduled_events(&self, state: &GameState) -> (GameState, Vec<GameEvent>) {
+        let (new_state, events) = self.inner.scheduled_events(state);
+        self.check("scheduled_events", &new_state, &events);
+        (new_state, events)
+    }
+
+    fn is_finished(&self, state: &GameState) -> bool {
+        self.inner.is_finished(state)
+    }
+
+    fn rankings(&self, state: &GameState) -> Vec<Ranking> {
+        self.inner.rankings(state)
+    }
+}
+
+/// 状態だけで確かめられる不変条件の違反
+pub fn violations(state: &GameState) -> Vec<String> {
+    let mut violations = Vec::new();
+    if state.players.is_empty() {
+        violations.push("no players".to_string());
+    } else if state.current_turn >= state.players.len() {
+        violations.push(format!(
+            "current_turn {} out of range ({} players)",
+            state.current_turn,
+            state.players.len()
+        ));
+    }
+    for player in &state.players {
+        if state.catalog.board.tile(player.position).is_none() {
+            violations.push(format!("{}: position {} is not on the board", player.id, player.position));
+        }
+        if player.children > MAX_CHILDREN {
+            violations.push(format!("{}: {} children exceeds {}", player.id, player.children, MAX_CHILDREN));
+        }
+    }
+    let choosing = matches!(state.phase, TurnPhase::ChoosingPath | TurnPhase::ChoosingAction);
+    if let Some(pending) = state.pending_choice.as_ref().filter(|_| choosing) {
+        if state.players.get(state.current_turn).is_some_and(|p| p.id != pending.player_id) {
+            violations.push(format!("pending choice belongs to {}, not the current player", pending.player_id));
+        }
+    }
+    violations
+}
+
+/// 提示した選択肢と phase・legal_actions の食い違い
+fn choice_violations(engine: &dyn GameEngine, state: &GameState, events: &[GameEvent]) -> Vec<String> {
+    let mut violations = Vec::new();
+    let legal = engine.legal_actions(state);
+    if state.phase == TurnPhase::ChoosingAction && legal.is_empty() {
+        violations.push("ChoosingAction without legal actions".to_string());
+    }
+    if state.phase != TurnPhase::ChoosingAction && !legal.is_empty() {
+        violations.push(format!("{} legal actions outside ChoosingAction", legal.len()));
+    }
+    let offered = events.iter().rev().find_map(|e| match e {
+        GameEvent::ChoiceRequired { choices } => Some(choices),
+        _ => None,
+    });
+    match offered {
+        Some(_) if !matches!(state.phase, TurnPhase::ChoosingPath | TurnPhase::ChoosingAction) => {
+            violations.push(format!("choices offered in {:?}", state.phase));
+        }
+        Some(choices) if state.phase == TurnPhase::ChoosingAction && choices.len() != legal.len() => {
+            violations.push(format!("{} choices offered but {} legal actions", choices.len(), legal.len()));
+        }
+        _ => {}
+    }
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::driver::{self, TurnInput};
+    use crate::engine::ClassicGameEngine;
+
+    #[test]
+    fn test_checked_engine_accepts_valid_games_and_reports_violations() {
+        let map: MapData = serde_json::from_value(serde_json::json!({
+            "id": "m", "name": "m", "version": "1.0",
+            "start_money": 10000, "loan_unit": 20000, "loan_interest_rate": 1.25,
+            "tiles": [
+                { "id": 0, "type": "Start", "position": { "x": 0, "y": 0 }, "next": [1, 2] },
+                { "id": 1, "type": "House", "position": { "x": 1, "y": 0 }, "next": [3] },
+                { "id": 2, "type": "Baby", "position": { "x": 1, "y": 1 }, "next": [3] },
+                { "id": 3, "type": "Insurance", "position": { "x": 2, "y": 0 }, "next": [4] },
+                { "id": 4, "type": "Retire", "position": { "x": 3, "y": 0 }, "next": [] }
+            ],
+            "careers": [],
+            "houses": [{ "id": "h", "name": "h", "price": 5000, "sell_price": 8000 }]
+        }))
+        .unwrap();
+        let engine = CheckedEngine::new(ClassicGameEngine::new());
+        let mut state = engine.init(
+            vec![("p1".to_string(), "Alice".to_string()), ("p2".to_string(), "Bob".to_string())],
+            &map,
+        );
+        // 違反があればデバッグビルドでは panic する
+        for _ in 0..100 {
+            if engine.is_finished(&state) {
+                break;
+            }
+            let input = match (&state.phase, &state.pending_choice) {
+                (TurnPhase::ChoosingPath, _) => TurnInput::ChoosePath { index: 0 },
+                (TurnPhase::ChoosingAction, Some(pending)) => TurnInput::ChooseAction {
+                    id: pending.choices[0].id.clone(),
+                },
+                _ => TurnInput::Spin,
+            };
+            state = driver::step(&engine, &state, &input).unwrap().0;
+        }
+        assert!(engine.is_finished(&state));
+        assert!(violations(&state).is_empty());
+
+        state.current_turn = 2;
+        state.players[0].position = 99;
+        state.players[1].children = MAX_CHILDREN + 1;
+        assert_eq!(violations(&state).len(), 3);
+    }
+}
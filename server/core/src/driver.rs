@@ -0,0 +1,555 @@
+//! ルーム層を介さずにエンジンを1操作ずつ進めるドライバー
+//!
+//! ルーム（`RoomManager`）と同じ順序で spin → advance → 選択 → end_turn を呼ぶ。
+//! 乱数はすべて GameState の seed から引くため、同じ入力列からは常に同じ結果になる。
+
+use super::state::*;
+use super::traits::GameEngine;
+
+/// 手番プレイヤーの操作
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TurnInput {
+    Spin,
+    ChoosePath { index: usize },
+    /// ChoiceRequired の選択肢ID
+    ChooseAction { id: String },
+    /// ルーレット前に持っている株券を売る（手番は進まない）
+    SellStock { stock_id: String },
+    /// ルーレット前に持っている家を抵当に入れる（手番は進まない）
+    MortgageHouse { house_id: String },
+    /// ルーレット前に抵当に入れた家を買い戻す（手番は進まない）
+    RedeemHouse { house_id: String },
+}
+
+/// 選択肢IDを PlayerAction に変換する
+/// 提示中の選択肢に付いた行動を使い、それが無い古い状態からの復元時だけ停止中のマスから推測する
+pub fn parse_action(action_id: &str, state: &GameState) -> PlayerAction {
+    state
+        .pending_choice
+        .as_ref()
+        .and_then(|pending| pending.choices.iter().find(|c| c.id == action_id))
+        .and_then(|choice| choice.action.clone())
+        .unwrap_or_else(|| action_from_tile(action_id, state))
+}
+
+fn action_from_tile(action_id: &str, state: &GameState) -> PlayerAction {
+    let current_pos = state.players[state.current_turn].position;
+    let tile_type = state.catalog.board.tile(current_pos).map(|t| &t.tile_type);
+
+    match tile_type {
+        Some(TileType::House) => {
+            if action_id == "skip" {
+                PlayerAction::SkipAction
+            } else {
+                PlayerAction::BuyHouse {
+                    house_id: action_id.to_string(),
+                }
+            }
+        }
+        Some(TileType::Insurance) => match action_id {
+            "life" => PlayerAction::BuyInsurance {
+                insurance_type: InsuranceType::Life,
+            },
+            "auto" => PlayerAction::BuyInsurance {
+                insurance_type: InsuranceType::Auto,
+            },
+            "fire" => PlayerAction::BuyInsurance {
+                insurance_type: InsuranceType::Fire,
+            },
+            _ => PlayerAction::SkipAction,
+        },
+        Some(TileType::Retire) => PlayerAction::ChooseRetirement {
+            home: if action_id == "luxury" {
+                RetirementHome::Luxury
+            } else {
+                RetirementHome::Modest
+            },
+        },
+        Some(TileType::Lawsuit) => PlayerAction::SelectLawsuitTarget {
+            target_id: action_id.to_string(),
+        },
+        Some(TileType::Swap) => PlayerAction::SwapPosition {
+            target_id: action_id.to_string(),
+        },
+        _ => PlayerAction::SkipAction,
+    }
+}
+
+/// 操作を1つ適用する。ターンが終わればゲーム終了でない限り次の手番へ進め、全員向けの出来事も起こす
+pub fn step(
+    engine: &dyn GameEngine,
+    state: &GameState,
+    input: &TurnInput,
+) -> Result<(GameState, Vec<GameEvent>), String> {
+    let choice_id = match input {
+        TurnInput::Spin
+        | TurnInput::SellStock { .. }
+        | TurnInput::MortgageHouse { .. }
+        | TurnInput::RedeemHouse { .. } => None,
+        TurnInput::ChoosePath { index } => Some(index.to_string()),
+        TurnInput::ChooseAction { id } => Some(id.clone()),
+    };
+    if let Some(id) = choice_id {
+        state
+            .check_choice(&id)
+            .map_err(|valid| format!("invalid choice {} (valid: {})", id, valid.join(", ")))?;
+    }
+
+    let (mut new_state, mut events) = match (input, state.phase) {
+        (TurnInput::Spin, TurnPhase::WaitingForSpin) => {
+            let (spun, result) = engine.spin(state);
+            engine.advance(&spun, result.value)
+        }
+        (TurnInput::ChoosePath { index }, TurnPhase::ChoosingPath) => {
+            let chosen = engine.choose_path(state, *index);
+            if chosen.phase == TurnPhase::Moving {
+                engine.advance(&chosen, chosen.pending_steps)
+            } else {
+                (chosen, Vec::new())
+            }
+        }
+        (TurnInput::ChooseAction { id }, TurnPhase::ChoosingAction) => {
+            engine.resolve_action(state, parse_action(id, state))
+        }
+        (TurnInput::SellStock { stock_id }, TurnPhase::WaitingForSpin) => {
+            if !state.current_player().stocks.iter().any(|s| &s.id == stock_id) {
+                return Err(format!("stock {} is not owned", stock_id));
+            }
+            engine.resolve_action(
+                state,
+                PlayerAction::SellStock {
+                    stock_id: stock_id.clone(),
+                },
+            )
+        }
+        (TurnInput::MortgageHouse { house_id }, TurnPhase::WaitingForSpin) => {
+            let houses = &state.current_player().houses;
+            if !houses.iter().any(|h| &h.id == house_id && !h.mortgaged) {
+                return Err(format!("house {} cannot be mortgaged", house_id));
+            }
+            engine.resolve_action(
+                state,
+                PlayerAction::MortgageHouse {
+                    house_id: house_id.clone(),
+                },
+            )
+        }
+        (TurnInput::RedeemHouse { house_id }, TurnPhase::WaitingForSpin) => {
+            let player = state.current_player();
+            if !player
+                .houses
+                .iter()
+                .any(|h| &h.id == house_id && h.mortgaged && h.redeem_cost() <= player.money)
+            {
+                return Err(format!("house {} cannot be redeemed", house_id));
+            }
+            engine.resolve_action(
+                state,
+                PlayerAction::RedeemHouse {
+                    house_id: house_id.clone(),
+                },
+            )
+        }
+        (input, phase) => return Err(format!("{:?} is not allowed in {:?}", input, phase)),
+    };
+    new_state.update_pending_choice(&events);
+
+    if new_state.phase == TurnPhase::TurnEnd && !engine.is_finished(&new_state) {
+        let (scheduled, scheduled_events) = engine.scheduled_events(&engine.end_turn(&new_state));
+        new_state = scheduled;
+        events.extend(scheduled_events);
+    }
+    Ok((new_state, events))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checked::CheckedEngine;
+    use crate::engine::ClassicGameEngine;
+    use crate::text::{ChoiceText, MoneyReason};
+
+    /// テスト用の xorshift64（ケースごとの seed から再現可能）
+    struct TestRng(u64);
+
+    impl TestRng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, n: usize) -> usize {
+            (self.next() % n.max(1) as u64) as usize
+        }
+
+        fn chance(&mut self, percent: u64) -> bool {
+            self.next() % 100 < percent
+        }
+    }
+
+    fn tile(id: usize, tile_type: TileType, next: Vec<usize>, event: Option<TileEvent>) -> TileData {
+        TileData {
+            id,
+            tile_type,
+            position: Position { x: id as f64, y: 0.0 },
+            next,
+            event,
+            labels: None,
+            landing_bonus: None,
+        }
+    }
+
+    /// ランダムなマップ。分岐は常に先のマスへ進むので、周回マップ以外はループしない
+    fn random_map(rng: &mut TestRng) -> MapData {
+        const TYPES: [TileType; 15] = [
+            TileType::Payday,
+            TileType::Action,
+            TileType::Career,
+            TileType::House,
+            TileType::Marry,
+            TileType::Baby,
+            TileType::Stock,
+            TileType::Insurance,
+            TileType::Tax,
+            TileType::Hospital,
+            TileType::Birthday,
+            TileType::Speed,
+            TileType::Raise,
+            TileType::Swap,
+            TileType::Overtake,
+        ];
+        let count = 8 + rng.below(40);
+        let cyclic = rng.chance(30);
+
+        let mut tiles = vec![tile(0, TileType::Start, vec![1], None)];
+        for id in 1..count - 1 {
+            let mut next = vec![id + 1];
+            let tile_type = if id + 2 < count && rng.chance(15) {
+                next.push(id + 2 + rng.below(count - id - 2));
+                TileType::Branch
+            } else if rng.chance(8) {
+                TileType::Lawsuit
+            } else {
+                TYPES[rng.below(TYPES.len())].clone()
+            };
+            let event = match tile_type {
+                TileType::Lawsuit => rng.chance(50).then(|| TileEvent::Lawsuit {
+                    amount: rng.below(200_000) as i64,
+                }),
+                _ => None,
+            };
+            let event = event.or_else(|| (tile_type == TileType::Action).then(|| match rng.below(4) {
+                0 => TileEvent::Fire {
+                    amount: rng.below(20_000) as i64,
+                },
+                1 => TileEvent::Theft {
+                    amount: rng.below(20_000) as i64,
+                },
+                _ => TileEvent::Money {
+                    amount: rng.below(40_000) as i64 - 20_000,
+                    text: "event".to_string(),
+                },
+            }));
+            tiles.push(tile(id, tile_type, next, event));
+        }
+        tiles.push(if cyclic {
+            tile(count - 1, TileType::Payday, vec![0], None)
+        } else {
+            tile(count - 1, TileType::Retire, vec![], None)
+        });
+
+        MapData {
+            id: "random".to_string(),
+            name: "Random".to_string(),
+            version: "1.0".to_string(),
+            start_money: 10_000,
+            loan_unit: 20_000,
+            loan_interest_rate: 1.25,
+            short_start_tile: None,
+            laps: cyclic.then(|| LapRules {
+                max_paydays_per_lap: rng.chance(50).then(|| rng.below(3) as u32),
+                finish_after_laps: Some(1 + rng.below(3) as u32),
+            }),
+            tiles,
+            careers: vec![
+                Career {
+                    id: "a".to_string(),
+                    name: "A".to_string(),
+                    salary: 10_000,
+                    pool: "basic".to_string(),
+                    abilities: vec![CareerAbility::Police],
+                    salary_ladder: Vec::new(),
+                },
+                Career {
+                    id: "b".to_string(),
+                    name: "B".to_string(),
+                    salary: 30_000,
+                    pool: "basic".to_string(),
+                    abilities: vec![CareerAbility::Doctor],
+                    salary_ladder: vec![35_000, 40_000],
+                },
+            ],
+            houses: vec![House {
+                id: "house".to_string(),
+                name: "House".to_string(),
+                price: 50_000,
+                sell_price: 70_000,
+                mortgaged: false,
+            }],
+        }
+    }
+
+    /// 直前の ChoiceRequired の中からランダムに選ぶ（分岐ではたまに範囲外も送る）
+    /// ルーレット前はたまに株を売ったり、家を抵当に入れたり買い戻したりする（買い戻せない家も送る）
+    fn random_input(rng: &mut TestRng, state: &GameState, choices: &[GameChoice]) -> TurnInput {
+        let stocks = &state.current_player().stocks;
+        let houses = &state.current_player().houses;
+        match state.phase {
+            TurnPhase::WaitingForSpin if !stocks.is_empty() && rng.chance(20) => TurnInput::SellStock {
+                stock_id: stocks[rng.below(stocks.len())].id.clone(),
+            },
+            TurnPhase::WaitingForSpin if !houses.is_empty() && rng.chance(20) => {
+                let house = &houses[rng.below(houses.len())];
+                if house.mortgaged {
+                    TurnInput::RedeemHouse {
+                        house_id: house.id.clone(),
+                    }
+                } else {
+                    TurnInput::MortgageHouse {
+                        house_id: house.id.clone(),
+                    }
+                }
+            }
+            TurnPhase::ChoosingPath => TurnInput::ChoosePath {
+                index: rng.below(choices.len() + 1),
+            },
+            TurnPhase::ChoosingAction => TurnInput::ChooseAction {
+                id: choices[rng.below(choices.len())].id.clone(),
+            },
+            _ => TurnInput::Spin,
+        }
+    }
+
+    fn is_transfer(reason: &MoneyReason) -> bool {
+        matches!(
+            reason,
+            MoneyReason::GiftPaid { .. }
+                | MoneyReason::GiftReceived { .. }
+                | MoneyReason::LawsuitPaid
+                | MoneyReason::LawsuitReceived
+                | MoneyReason::TreatmentPaid
+                | MoneyReason::TreatmentReceived
+                | MoneyReason::TheftPaid
+                | MoneyReason::TheftReward
+        )
+    }
+
+    /// 1ケース分のゲームを最後まで進め、各操作の後に不変条件を確認する
+    fn check_random_game(case: u64) {
+        let mut rng = TestRng(0x9E37_79B9_7F4A_7C15 ^ (case + 1).wrapping_mul(0xBF58_476D_1CE4_E5B9));
+        let map = random_map(&mut rng);
+        // CheckedEngine で包み、エンジン自身の不変条件も呼び出しごとに確かめる
+        let engine = CheckedEngine::new(ClassicGameEngine::new().with_options(GameOptions {
+            pause_at_branches: rng.chance(50),
+            max_turn_count: rng.chance(20).then(|| 5 + rng.below(50) as u32),
+            global_event_interval: rng.chance(50).then(|| 1 + rng.below(5) as u32),
+            stall_limit: rng.chance(30).then(|| rng.below(3) as u32),
+            ..GameOptions::default()
+        }));
+        let players = (0..2 + rng.below(4))
+            .map(|i| (format!("p{}", i), format!("Player {}", i)))
+            .collect();
+        let mut state = engine.init(players, &map);
+        state.rng_seed = rng.next() | 1;
+        let mut choices: Vec<GameChoice> = Vec::new();
+        let mut ledger = crate::ledger::Ledger::new();
+
+        for _ in 0..10_000 {
+            if engine.is_finished(&state) {
+                return;
+            }
+            let input = random_input(&mut rng, &state, &choices);
+            let (next, events) = match step(&engine, &state, &input) {
+                Ok(result) => result,
+                // 範囲外の道は拒否され、状態はそのまま（選び直せる）
+                Err(_) if matches!(input, TurnInput::ChoosePath { index } if index >= choices.len()) => continue,
+                // 所持金が足りない買い戻しも拒否される
+                Err(_) if matches!(input, TurnInput::RedeemHouse { .. }) => continue,
+                Err(e) => panic!("case {}: {}", case, e),
+            };
+
+            // 所持金の増減はすべて MoneyChanged（株の購入は株価分の支出）として通知される
+            for (before, after) in state.players.iter().zip(&next.players) {
+                let notified: i64 = events
+                    .iter()
+                    .map(|e| match e {
+                        GameEvent::MoneyChanged { player_id, amount, .. } if *player_id == after.id => *amount,
+                        GameEvent::StockPurchased { player_id, price } if *player_id == after.id => -price,
+                        _ => 0,
+                    })
+                    .sum();
+                assert_eq!(after.money - before.money, notified, "case {}: money of {}", case, after.id);
+            }
+            // プレイヤー間のやり取り（ご祝儀・訴訟）は総額を変えない
+            let transferred: i64 = events
+                .iter()
+                .filter_map(|e| match e {
+                    GameEvent::MoneyChanged { amount, reason, .. } if is_transfer(reason) => Some(*amount),
+                    _ => None,
+                })
+                .sum();
+            assert_eq!(transferred, 0, "case {}: transfers are not zero-sum", case);
+
+            // 台帳の最新の残高は実際の所持金と一致する
+            ledger.record(&next, &events, 0);
+            for player in &next.players {
+                if let Some(entry) = ledger.last_for(&player.id) {
+                    assert_eq!(entry.balance, player.money, "case {}: ledger of {}", case, player.id);
+                }
+            }
+
+            for player in &next.players {
+                assert!(next.catalog.board.tile(player.position).is_some(), "case {}: bad position", case);
+            }
+            // ゴールした人の手番は老後の暮らしを選ぶ間だけ
+            if !engine.is_finished(&next) && next.phase != TurnPhase::ChoosingAction {
+                assert!(!next.players[next.current_turn].retired, "case {}: retired player's turn", case);
+            }
+
+            if let Some(GameEvent::ChoiceRequired { choices: c }) =
+                events.iter().rev().find(|e| matches!(e, GameEvent::ChoiceRequired { .. }))
+            {
+                choices = c.clone();
+            }
+            // 選択待ちの間だけ、最後に提示した選択肢が手番プレイヤーのものとして残る
+            let choosing = matches!(next.phase, TurnPhase::ChoosingPath | TurnPhase::ChoosingAction);
+            match &next.pending_choice {
+                Some(pending) => {
+                    assert!(choosing, "case {}: stale pending choice", case);
+                    assert_eq!(pending.player_id, next.current_player().id, "case {}: pending owner", case);
+                    assert_eq!(pending.choices.len(), choices.len(), "case {}: pending choices", case);
+                }
+                None => assert!(!choosing, "case {}: missing pending choice", case),
+            }
+            if next.phase == TurnPhase::ChoosingAction {
+                assert!(!choices.is_empty(), "case {}: action without choices", case);
+                // legal_actions は提示された選択肢と一致する
+                let offered: Vec<PlayerAction> = choices.iter().map(|c| parse_action(&c.id, &next)).collect();
+                assert_eq!(engine.legal_actions(&next), offered, "case {}: legal actions", case);
+            } else {
+                assert!(engine.legal_actions(&next).is_empty(), "case {}: legal actions outside choice", case);
+            }
+            state = next;
+        }
+        panic!("case {}: game did not finish", case);
+    }
+
+    #[test]
+    fn test_random_games_keep_invariants() {
+        for case in 0..300 {
+            check_random_game(case);
+        }
+    }
+
+    #[test]
+    fn test_step_rejects_wrong_phase() {
+        let mut rng = TestRng(1);
+        let map = random_map(&mut rng);
+        let engine = ClassicGameEngine::new();
+        let state = engine.init(vec![("a".to_string(), "A".to_string())], &map);
+        assert!(step(&engine, &state, &TurnInput::ChoosePath { index: 0 }).is_err());
+
+        let (_, events) = step(&engine, &state, &TurnInput::Spin).unwrap();
+        assert!(matches!(events.first(), Some(GameEvent::PlayerMoved { path, .. }) if !path.is_empty()));
+    }
+
+    #[test]
+    fn test_sell_stock_before_spin() {
+        let mut rng = TestRng(1);
+        let map = random_map(&mut rng);
+        let engine = ClassicGameEngine::new();
+        let mut state = engine.init(vec![("a".to_string(), "A".to_string())], &map);
+        state.players[0].stocks.push(Stock {
+            id: "s1".to_string(),
+            name: "株券".to_string(),
+        });
+        state.stock_price = 12_000;
+        let sell = |id: &str| TurnInput::SellStock {
+            stock_id: id.to_string(),
+        };
+        assert!(step(&engine, &state, &sell("missing")).is_err());
+
+        let (sold, events) = step(&engine, &state, &sell("s1")).unwrap();
+        assert_eq!(sold.phase, TurnPhase::WaitingForSpin);
+        assert_eq!(sold.players[0].money, state.players[0].money + 12_000);
+        assert_eq!(sold.players[0].stocks.len(), 0);
+        assert!(matches!(
+            events.as_slice(),
+            [GameEvent::MoneyChanged {
+                reason: MoneyReason::StockSale,
+                ..
+            }]
+        ));
+    }
+
+    #[test]
+    fn test_mortgage_and_redeem_house() {
+        let mut rng = TestRng(1);
+        let map = random_map(&mut rng);
+        let engine = ClassicGameEngine::new();
+        let mut state = engine.init(vec![("a".to_string(), "A".to_string())], &map);
+        state.players[0].houses.push(map.houses[0].clone());
+        state.players[0].money = 0;
+        let mortgage = TurnInput::MortgageHouse {
+            house_id: "house".to_string(),
+        };
+        let redeem = TurnInput::RedeemHouse {
+            house_id: "house".to_string(),
+        };
+        assert!(step(&engine, &state, &redeem).is_err());
+
+        // 売却額 70,000 の半分を受け取り、家は資産に数えなくなる
+        let (mortgaged, _) = step(&engine, &state, &mortgage).unwrap();
+        assert_eq!(mortgaged.phase, TurnPhase::WaitingForSpin);
+        assert_eq!(mortgaged.players[0].money, 35_000);
+        assert_eq!(mortgaged.players[0].asset_breakdown(1.0, STOCK_PRICE).house_value, 0);
+        assert!(step(&engine, &mortgaged, &mortgage).is_err());
+        // 買い戻しには受け取った額の 110% が要る
+        assert!(step(&engine, &mortgaged, &redeem).is_err());
+
+        let mut richer = mortgaged.clone();
+        richer.players[0].money = 40_000;
+        let (redeemed, _) = step(&engine, &richer, &redeem).unwrap();
+        assert_eq!(redeemed.players[0].money, 40_000 - 38_500);
+        assert_eq!(redeemed.players[0].asset_breakdown(1.0, STOCK_PRICE).house_value, 70_000);
+    }
+
+    #[test]
+    fn test_parse_action_prefers_pending_choice() {
+        let mut rng = TestRng(2);
+        let map = random_map(&mut rng);
+        let engine = ClassicGameEngine::new();
+        let players = vec![("a".to_string(), "A".to_string()), ("b".to_string(), "B".to_string())];
+        let mut state = engine.init(players, &map);
+
+        // 選択肢が無ければマスから推測する（スタートマスは SkipAction）
+        assert_eq!(parse_action("b", &state), PlayerAction::SkipAction);
+
+        let sue = PlayerAction::SelectLawsuitTarget {
+            target_id: "b".to_string(),
+        };
+        state.pending_choice = Some(PendingChoice {
+            player_id: "a".to_string(),
+            choices: vec![GameChoice::new(
+                "b",
+                ChoiceText::Sue {
+                    player_name: "B".to_string(),
+                },
+            )
+            .with_action(sue.clone())],
+        });
+        assert_eq!(parse_action("b", &state), sue);
+    }
+}
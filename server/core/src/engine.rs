@@ -0,0 +1,1477 @@
+use std::sync::Arc;
+
+use crate::PlayerId;
+
+use super::events::{ClassicEventResolver, StandardRoulette};
+use super::market;
+use super::state::*;
+use super::text::MoneyReason;
+use super::traits::*;
+
+/// 本家準拠のゲームエンジン実装
+pub struct ClassicGameEngine {
+    event_resolver: Box<dyn EventResolver>,
+    roulette: Box<dyn Roulette>,
+    options: GameOptions,
+}
+
+impl ClassicGameEngine {
+    pub fn new() -> Self {
+        Self {
+            event_resolver: Box::new(ClassicEventResolver),
+            roulette: Box::new(StandardRoulette),
+            options: GameOptions::default(),
+        }
+    }
+
+    pub fn with_components(
+        event_resolver: Box<dyn EventResolver>,
+        roulette: Box<dyn Roulette>,
+    ) -> Self {
+        Self {
+            event_resolver,
+            roulette,
+            options: GameOptions::default(),
+        }
+    }
+
+    pub fn with_options(mut self, options: GameOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// 周回数を進める。規定周回に達したらリタイアさせて true を返す
+    fn complete_lap(state: &mut GameState, player_idx: usize, events: &mut Vec<GameEvent>) -> bool {
+        let finish_after = state.lap_rules.as_ref().and_then(|r| r.finish_after_laps);
+        let player = &mut state.players[player_idx];
+        player.laps += 1;
+        player.paydays_this_lap = 0;
+        events.push(GameEvent::LapCompleted {
+            player_id: player.id.clone(),
+            laps: player.laps,
+        });
+
+        if finish_after.is_some_and(|n| player.laps >= n) {
+            events.extend(state.record_arrival(player_idx));
+            return true;
+        }
+        false
+    }
+
+    /// 短縮ゲーム: 全員を short_start_tile に置き、職業をseedベースで割り当てる
+    fn apply_short_start(state: &mut GameState, map: &MapData) {
+        if let Some(tile_id) = map.short_start_tile {
+            for player in &mut state.players {
+                player.position = tile_id;
+            }
+        }
+        if !state.catalog.careers.is_empty() {
+            for i in 0..state.players.len() {
+                let idx = (state.next_random() as usize) % state.catalog.careers.len();
+                let career = state.catalog.careers[idx].clone();
+                state.players[i].assign_career(career);
+            }
+        }
+        state.payout_multiplier = 2;
+    }
+}
+
+impl Default for ClassicGameEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GameEngine for ClassicGameEngine {
+    fn init(&self, players: Vec<(PlayerId, String)>, map: &MapData) -> GameState {
+        let catalog = Arc::new(GameCatalog::from_map(map));
+
+        // Start tile position (tile id 0 by convention)
+        let start_pos = catalog.board.start_tile_id();
+
+        let player_states: Vec<PlayerState> = players
+            .into_iter()
+            .map(|(id, name)| {
+                let mut ps = PlayerState::new(id, name, map.start_money);
+                ps.position = start_pos;
+                ps
+            })
+            .collect();
+
+        // xorshift は 0 から抜け出せないので 0 は使わない
+        let seed = self.options.seed.filter(|&s| s != 0).unwrap_or(DEFAULT_SEED);
+
+        let mut state = GameState {
+            players: player_states,
+            catalog,
+            current_turn: 0,
+            phase: TurnPhase::WaitingForSpin,
+            rng_seed: seed,
+            loan_unit: map.loan_unit,
+            loan_interest_rate: map.loan_interest_rate,
+            payout_multiplier: 1,
+            turn_number: 1,
+            max_turns: self.options.max_turn_count,
+            lap_rules: map.laps.clone(),
+            pending_steps: 0,
+            chosen_path: None,
+            pending_choice: None,
+            game_started_at: None,
+            tax_cut: false,
+            arrivals: Vec::new(),
+            stock_price: STOCK_PRICE,
+            seed,
+        };
+
+        if self.options.init_mode == InitMode::Short {
+            Self::apply_short_start(&mut state, map);
+        }
+
+        state
+    }
+
+    fn spin(&self, state: &GameState) -> (GameState, SpinResult) {
+        let mut new_state = state.clone();
+        let player = new_state.current_player();
+        let on_speed_tile = new_state
+            .catalog
+            .board
+            .tile(player.position)
+            .is_some_and(|t| t.tile_type == TileType::Speed);
+        let spins: Vec<u32> = (0..if on_speed_tile { 2 } else { 1 })
+            .map(|_| {
+                let value = self.roulette.spin(&new_state);
+                // Advance the rng so next spin is different
+                new_state.next_random();
+                value
+            })
+            .collect();
+        new_state.phase = TurnPhase::Moving;
+
+        let player_id = new_state.players[new_state.current_turn].id.clone();
+        let result = SpinResult {
+            player_id,
+            value: spins.iter().sum(),
+            spins,
+        };
+
+        (new_state, result)
+    }
+
+    fn advance(&self, state: &GameState, steps: u32) -> (GameState, Vec<GameEvent>) {
+        let mut new_state = state.clone();
+        let mut events = Vec::new();
+        let player_idx = new_state.current_turn;
+        let mut remaining = steps;
+        let start_tile = new_state.catalog.board.start_tile_id();
+        let mut finished_laps = false;
+        let mut chosen_path = new_state.chosen_path.take();
+        let mut paused_at: Option<Tile> = None;
+        new_state.pending_steps = 0;
+        let mut path = Vec::new();
+        let mut paydays_passed = 0;
+
+        while remaining > 0 {
+            let current_pos = new_state.players[player_idx].position;
+            let tile = new_state.catalog.board.tile(current_pos).cloned();
+
+            if let Some(tile) = tile {
+                if tile.next.is_empty() {
+                    // Reached the end (Retire tile)
+                    break;
+                }
+
+                // If this tile is a branch and we're not on the last step,
+                // just take the first path. Branch choice only applies at final stop.
+                // (pause_at_branches では止まって道を選ばせ、選んだ道で移動を再開する)
+                let next_tile_id = if tile.next.len() > 1 && self.options.pause_at_branches {
+                    match chosen_path.take().filter(|&i| i < tile.next.len()) {
+                        Some(i) => tile.next[i],
+                        None => {
+                            paused_at = Some(tile);
+                            break;
+                        }
+                    }
+                } else {
+                    tile.next[0]
+                };
+                new_state.players[player_idx].position = next_tile_id;
+                path.push(next_tile_id);
+                remaining -= 1;
+
+                // 周回マップ: スタートマスに戻ったら1周
+                if next_tile_id == start_tile {
+                    finished_laps = Self::complete_lap(&mut new_state, player_idx, &mut events);
+                    if finished_laps {
+                        break;
+                    }
+                }
+
+                // If passing through a Payday tile (not the final stop), collect salary
+                if remaining > 0 {
+                    if let Some(pass_tile) = new_state.catalog.board.tile(next_tile_id).cloned() {
+                        if pass_tile.tile_type == TileType::Payday {
+                            paydays_passed += 1;
+                            let before = new_state.players[player_idx].money;
+                            new_state = self.event_resolver.resolve_payday(&new_state, player_idx);
+                            let salary = new_state.players[player_idx].money - before;
+                            if salary > 0 {
+                                events.push(GameEvent::MoneyChanged {
+                                    player_id: new_state.players[player_idx].id.clone(),
+                                    amount: salary,
+                                    reason: MoneyReason::PaydayPassed,
+                                });
+                            }
+                        }
+                    }
+                }
+            } else {
+                break;
+            }
+        }
+
+        // 通過マスの処理（給料日・周回）より前に移動経路を置く
+        events.insert(
+            0,
+            GameEvent::PlayerMoved {
+                player_id: new_state.players[player_idx].id.clone(),
+                path,
+                paydays_passed,
+            },
+        );
+
+        // 分岐で一時停止: 残り歩数を保存して道の選択を待つ
+        if let Some(branch) = paused_at {
+            new_state.pending_steps = remaining;
+            new_state.phase = TurnPhase::ChoosingPath;
+            let choices = ClassicEventResolver::path_choices(&new_state.catalog.board, &branch);
+            events.push(GameEvent::ChoiceRequired { choices });
+            return (new_state, events);
+        }
+
+        // Resolve event at the tile where the player stopped
+        // (規定周回でゴールした場合はマスのイベントを処理しない)
+        let final_pos = new_state.players[player_idx].position;
+        if let Some(tile) = new_state.catalog.board.tile(final_pos).cloned().filter(|_| !finished_laps) {
+            // 歩数を使い切って止まったときだけ（ゴールで余った場合は除く）
+            if let Some(bonus) = tile.landing_bonus.filter(|_| remaining == 0 && steps > 0) {
+                let player_id = new_state.players[player_idx].id.clone();
+                let bonus = new_state.scaled_payout(bonus);
+                new_state.players[player_idx].money += bonus;
+                events.push(GameEvent::ExactLanding {
+                    player_id: player_id.clone(),
+                    tile_id: tile.id,
+                });
+                events.push(GameEvent::MoneyChanged {
+                    player_id,
+                    amount: bonus,
+                    reason: MoneyReason::LandingBonus,
+                });
+            }
+            let (resolved_state, tile_events) = self.event_resolver.resolve_tile(&new_state, &tile);
+            new_state = resolved_state;
+            events.extend(tile_events);
+        }
+
+        // If phase hasn't been changed by the event (e.g. ChoiceRequired), mark as ResolvingEvent -> TurnEnd
+        if new_state.phase == TurnPhase::Moving {
+            new_state.phase = TurnPhase::TurnEnd;
+        }
+
+        (new_state, events)
+    }
+
+    fn choose_path(&self, state: &GameState, path_index: usize) -> GameState {
+        let mut new_state = state.clone();
+
+        // 移動途中の分岐: 道を記録して Moving に戻す（残り歩数は advance で進める）
+        if new_state.pending_steps > 0 {
+            new_state.chosen_path = Some(path_index);
+            new_state.phase = TurnPhase::Moving;
+            return new_state;
+        }
+
+        let player_idx = new_state.current_turn;
+        let current_pos = new_state.players[player_idx].position;
+
+        if let Some(tile) = new_state.catalog.board.tile(current_pos).cloned() {
+            if path_index < tile.next.len() {
+                new_state.players[player_idx].position = tile.next[path_index];
+            }
+        }
+
+        new_state.phase = TurnPhase::TurnEnd;
+        new_state
+    }
+
+    fn legal_actions(&self, state: &GameState) -> Vec<PlayerAction> {
+        if state.phase != TurnPhase::ChoosingAction {
+            return Vec::new();
+        }
+        let player = state.current_player();
+        match state.catalog.board.tile(player.position).map(|t| &t.tile_type) {
+            Some(TileType::House) => state
+                .catalog
+                .houses_for_sale
+                .iter()
+                .map(|h| PlayerAction::BuyHouse {
+                    house_id: h.id.clone(),
+                })
+                .chain(std::iter::once(PlayerAction::SkipAction))
+                .collect(),
+            Some(TileType::Insurance) => {
+                let mut actions = Vec::new();
+                if !player.life_insurance {
+                    actions.push(PlayerAction::BuyInsurance {
+                        insurance_type: InsuranceType::Life,
+                    });
+                }
+                if !player.auto_insurance {
+                    actions.push(PlayerAction::BuyInsurance {
+                        insurance_type: InsuranceType::Auto,
+                    });
+                }
+                if !player.fire_insurance {
+                    actions.push(PlayerAction::BuyInsurance {
+                        insurance_type: InsuranceType::Fire,
+                    });
+                }
+                actions.push(PlayerAction::SkipAction);
+                actions
+            }
+            Some(TileType::Retire) => vec![
+                PlayerAction::ChooseRetirement {
+                    home: RetirementHome::Luxury,
+                },
+                PlayerAction::ChooseRetirement {
+                    home: RetirementHome::Modest,
+                },
+            ],
+            Some(TileType::Lawsuit) => state
+                .players
+                .iter()
+                .enumerate()
+                .filter(|(i, p)| *i != state.current_turn && !p.retired)
+                .map(|(_, p)| PlayerAction::SelectLawsuitTarget {
+                    target_id: p.id.clone(),
+                })
+                .collect(),
+            Some(TileType::Swap) => state
+                .players
+                .iter()
+                .enumerate()
+                .filter(|(i, p)| *i != state.current_turn && !p.retired)
+                .map(|(_, p)| PlayerAction::SwapPosition {
+                    target_id: p.id.clone(),
+                })
+                .collect(),
+            _ => vec![PlayerAction::SkipAction],
+        }
+    }
+
+    fn resolve_action(&self, state: &GameState, action: PlayerAction) -> (GameState, Vec<GameEvent>) {
+        let mut new_state = state.clone();
+        let mut events = Vec::new();
+        let player_idx = new_state.current_turn;
+        let player_id = new_state.players[player_idx].id.clone();
+
+        // 見送り以外を選んだら引き延ばしの数え直し（ルーレット前の資産の操作は数えない）
+        if !matches!(
+            action,
+            PlayerAction::SkipAction
+                | PlayerAction::SellStock { .. }
+                | PlayerAction::MortgageHouse { .. }
+                | PlayerAction::RedeemHouse { .. }
+        ) {
+            new_state.players[player_idx].consecutive_skips = 0;
+        }
+
+        match action {
+            PlayerAction::BuyHouse { house_id } => {
+                if let Some(house) = new_state.catalog.houses_for_sale.iter().find(|h| h.id == house_id).cloned() {
+                    if new_state.players[player_idx].money >= house.price {
+                        new_state.players[player_idx].money -= house.price;
+                        events.push(GameEvent::MoneyChanged {
+                            player_id: player_id.clone(),
+                            amount: -house.price,
+                            reason: MoneyReason::HousePurchase {
+                                house_name: house.name.clone(),
+                            },
+                        });
+                        events.push(GameEvent::HousePurchased {
+                            player_id,
+                            house: house.clone(),
+                        });
+                        new_state.players[player_idx].houses.push(house);
+                    }
+                }
+                new_state.phase = TurnPhase::TurnEnd;
+            }
+
+            PlayerAction::BuyInsurance { insurance_type } => {
+                match insurance_type {
+                    InsuranceType::Life => {
+                        new_state.players[player_idx].life_insurance = true;
+                        events.push(GameEvent::InsurancePurchased {
+                            player_id,
+                            insurance_type: InsuranceType::Life,
+                        });
+                    }
+                    InsuranceType::Auto => {
+                        new_state.players[player_idx].auto_insurance = true;
+                        events.push(GameEvent::InsurancePurchased {
+                            player_id,
+                            insurance_type: InsuranceType::Auto,
+                        });
+                    }
+                    InsuranceType::Fire => {
+                        new_state.players[player_idx].fire_insurance = true;
+                        events.push(GameEvent::InsurancePurchased {
+                            player_id,
+                            insurance_type: InsuranceType::Fire,
+                        });
+                    }
+                }
+                new_state.phase = TurnPhase::TurnEnd;
+            }
+
+            PlayerAction::SkipAction => {
+                let player = &mut new_state.players[player_idx];
+                player.consecutive_skips += 1;
+                if self.options.stall_limit.is_some_and(|n| player.consecutive_skips > n) {
+                    player.money -= IDLE_TAX;
+                    events.push(GameEvent::MoneyChanged {
+                        player_id,
+                        amount: -IDLE_TAX,
+                        reason: MoneyReason::IdleTax,
+                    });
+                }
+                new_state.phase = TurnPhase::TurnEnd;
+            }
+
+            PlayerAction::ChooseRetirement { home } => {
+                new_state.players[player_idx].retirement_home = Some(home);
+                new_state.phase = TurnPhase::TurnEnd;
+            }
+
+            PlayerAction::SwapPosition { target_id } => {
+                if let Some(target_idx) = new_state.players.iter().position(|p| p.id == target_id) {
+                    let from = new_state.players[player_idx].position;
+                    let to = new_state.players[target_idx].position;
+                    new_state.players[player_idx].position = to;
+                    new_state.players[target_idx].position = from;
+                    let laps = new_state.players[player_idx].laps;
+                    new_state.players[player_idx].laps = new_state.players[target_idx].laps;
+                    new_state.players[target_idx].laps = laps;
+                    events.push(GameEvent::PlayerTeleported { player_id, from, to });
+                    events.push(GameEvent::PlayerTeleported {
+                        player_id: target_id,
+                        from: to,
+                        to: from,
+                    });
+                }
+                new_state.phase = TurnPhase::TurnEnd;
+            }
+
+            PlayerAction::SelectLawsuitTarget { target_id } => {
+                let (lawsuit_state, lawsuit_events) =
+                    self.event_resolver.resolve_lawsuit(&new_state, &target_id);
+                new_state = lawsuit_state;
+                events.extend(lawsuit_events);
+                new_state.phase = TurnPhase::TurnEnd;
+            }
+
+            PlayerAction::RepayDebt => {
+                let loan_unit = new_state.loan_unit;
+                let repay = (loan_unit as f64 * new_state.loan_interest_rate) as i64;
+                if new_state.players[player_idx].debt >= loan_unit
+                    && new_state.players[player_idx].money >= repay
+                {
+                    new_state.players[player_idx].money -= repay;
+                    new_state.players[player_idx].debt -= loan_unit;
+                    events.push(GameEvent::MoneyChanged {
+                        player_id,
+                        amount: -repay,
+                        reason: MoneyReason::DebtRepayment,
+                    });
+                }
+                new_state.phase = TurnPhase::TurnEnd;
+            }
+
+            PlayerAction::BuyStock => {
+                let cost = new_state.stock_price;
+                if new_state.players[player_idx].money >= cost {
+                    new_state.players[player_idx].money -= cost;
+                    let stock_id = format!("stock_{}", new_state.next_random() % 100);
+                    new_state.players[player_idx].stocks.push(Stock {
+                        id: stock_id,
+                        name: "株券".to_string(),
+                    });
+                    events.push(GameEvent::StockPurchased { player_id, price: cost });
+                }
+                new_state.phase = TurnPhase::TurnEnd;
+            }
+
+            PlayerAction::SellStock { stock_id } => {
+                let price = new_state.stock_price;
+                let player = &mut new_state.players[player_idx];
+                if let Some(i) = player.stocks.iter().position(|s| s.id == stock_id) {
+                    player.stocks.remove(i);
+                    player.money += price;
+                    events.push(GameEvent::MoneyChanged {
+                        player_id,
+                        amount: price,
+                        reason: MoneyReason::StockSale,
+                    });
+                }
+            }
+
+            PlayerAction::MortgageHouse { house_id } => {
+                let player = &mut new_state.players[player_idx];
+                if let Some(house) = player.houses.iter_mut().find(|h| h.id == house_id && !h.mortgaged) {
+                    let amount = house.mortgage_value();
+                    house.mortgaged = true;
+                    let house_name = house.name.clone();
+                    player.money += amount;
+                    events.push(GameEvent::MoneyChanged {
+                        player_id,
+                        amount,
+                        reason: MoneyReason::Mortgage { house_name },
+                    });
+                }
+            }
+
+            PlayerAction::RedeemHouse { house_id } => {
+                let player = &mut new_state.players[player_idx];
+                let money = player.money;
+                if let Some(house) = player
+                    .houses
+                    .iter_mut()
+                    .find(|h| h.id == house_id && h.mortgaged && h.redeem_cost() <= money)
+                {
+                    let cost = house.redeem_cost();
+                    house.mortgaged = false;
+                    let house_name = house.name.clone();
+                    player.money -= cost;
+                    events.push(GameEvent::MoneyChanged {
+                        player_id,
+                        amount: -cost,
+                        reason: MoneyReason::MortgageRedemption { house_name },
+                    });
+                }
+            }
+        }
+
+        (new_state, events)
+    }
+
+    fn end_turn(&self, state: &GameState) -> GameState {
+        let mut new_state = state.clone();
+        let player_count = new_state.players.len();
+
+        // 次の手番: 引退しておらず接続中のプレイヤー。全員切断中なら引退していないプレイヤー
+        // （全員引退済みは is_finished で先に終わるので通常は来ない）
+        let current = new_state.current_turn;
+        let order = || (1..=player_count).map(|i| (current + i) % player_count);
+        let players = &new_state.players;
+        let next = order()
+            .find(|&i| !players[i].retired && players[i].connected)
+            .or_else(|| order().find(|&i| !players[i].retired))
+            .unwrap_or((current + 1) % player_count);
+
+        new_state.current_turn = next;
+        new_state.turn_number += 1;
+        new_state.phase = TurnPhase::WaitingForSpin;
+        new_state
+    }
+
+    fn scheduled_events(&self, state: &GameState) -> (GameState, Vec<GameEvent>) {
+        if market::is_due(state, self.options.global_event_interval) {
+            market::trigger(state)
+        } else {
+            (state.clone(), Vec::new())
+        }
+    }
+
+    fn is_finished(&self, state: &GameState) -> bool {
+        // ターン上限: 最終ターンを終えた時点で終了
+        let turn_limit_reached = state
+            .max_turns
+            .is_some_and(|max| state.turn_number >= max && state.phase == TurnPhase::TurnEnd);
+        // 最後にゴールした人が老後の暮らしを選ぶまでは続ける
+        let all_retired = state.players.iter().all(|p| p.retired) && state.phase != TurnPhase::ChoosingAction;
+        turn_limit_reached || all_retired
+    }
+
+    fn rankings(&self, state: &GameState) -> Vec<Ranking> {
+        let mut ranked: Vec<_> = state
+            .players
+            .iter()
+            .map(|p| (p.id.clone(), p.name.clone(), p.asset_breakdown(state.loan_interest_rate, state.stock_price)))
+            .collect();
+
+        // Sort by total_assets descending
+        ranked.sort_by_key(|r| std::cmp::Reverse(r.2.total()));
+
+        ranked
+            .into_iter()
+            .enumerate()
+            .map(|(i, (player_id, player_name, breakdown))| Ranking {
+                arrival_order: state.arrival_order(&player_id),
+                player_id,
+                player_name,
+                total_assets: breakdown.total(),
+                breakdown,
+                rank: (i + 1) as u32,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text::GiftKind;
+
+    fn sample_map() -> MapData {
+        MapData {
+            id: "test".to_string(),
+            name: "Test Map".to_string(),
+            version: "1.0".to_string(),
+            start_money: 10000,
+            loan_unit: 20000,
+            loan_interest_rate: 1.25,
+            short_start_tile: Some(1),
+            laps: None,
+            tiles: vec![
+                TileData {
+                    id: 0,
+                    tile_type: TileType::Start,
+                    position: Position { x: 0.0, y: 0.0 },
+                    next: vec![1],
+                    event: None,
+                    labels: None,
+                    landing_bonus: None,
+                },
+                TileData {
+                    id: 1,
+                    tile_type: TileType::Payday,
+                    position: Position { x: 1.0, y: 0.0 },
+                    next: vec![2],
+                    event: None,
+                    labels: None,
+                    landing_bonus: None,
+                },
+                TileData {
+                    id: 2,
+                    tile_type: TileType::Retire,
+                    position: Position { x: 2.0, y: 0.0 },
+                    next: vec![],
+                    event: None,
+                    labels: None,
+                    landing_bonus: None,
+                },
+            ],
+            careers: vec![Career {
+                id: "test_career".to_string(),
+                name: "Test".to_string(),
+                salary: 10000,
+                pool: "basic".to_string(),
+                abilities: Vec::new(),
+                salary_ladder: Vec::new(),
+            }],
+            houses: vec![House {
+                id: "test_house".to_string(),
+                name: "Test House".to_string(),
+                price: 50000,
+                sell_price: 70000,
+                mortgaged: false,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_init() {
+        let engine = ClassicGameEngine::new();
+        let map = sample_map();
+        let players = vec![
+            ("p1".to_string(), "Alice".to_string()),
+            ("p2".to_string(), "Bob".to_string()),
+        ];
+        let state = engine.init(players, &map);
+
+        assert_eq!(state.players.len(), 2);
+        assert_eq!(state.players[0].money, 10000);
+        assert_eq!(state.players[0].position, 0);
+        assert_eq!(state.current_turn, 0);
+        assert_eq!(state.phase, TurnPhase::WaitingForSpin);
+    }
+
+    #[test]
+    fn test_init_short_game() {
+        let engine = ClassicGameEngine::new().with_options(GameOptions {
+            init_mode: InitMode::Short,
+            ..GameOptions::default()
+        });
+        let map = sample_map();
+        let players = vec![
+            ("p1".to_string(), "Alice".to_string()),
+            ("p2".to_string(), "Bob".to_string()),
+        ];
+        let state = engine.init(players, &map);
+
+        assert_eq!(state.players[0].position, 1);
+        assert!(state.players[0].career.is_some());
+        assert_eq!(state.payday_amount(0), 20000);
+        assert_eq!(state.scaled_payout(5000), 10000);
+        assert_eq!(state.scaled_payout(-5000), -5000);
+    }
+
+    #[test]
+    fn test_spin() {
+        let engine = ClassicGameEngine::new();
+        let map = sample_map();
+        let players = vec![
+            ("p1".to_string(), "Alice".to_string()),
+            ("p2".to_string(), "Bob".to_string()),
+        ];
+        let state = engine.init(players, &map);
+        let (new_state, result) = engine.spin(&state);
+
+        assert!(result.value >= 1 && result.value <= 10);
+        assert_eq!(result.player_id, "p1");
+        assert_eq!(new_state.phase, TurnPhase::Moving);
+        // 盤面などは複製せず共有する
+        assert!(Arc::ptr_eq(&state.catalog, &new_state.catalog));
+    }
+
+    #[test]
+    fn test_landing_bonus_only_on_exact_stop() {
+        let engine = ClassicGameEngine::new();
+        let mut map = sample_map();
+        map.tiles[1].landing_bonus = Some(3000);
+        let state = engine.init(vec![("p1".to_string(), "Alice".to_string())], &map);
+
+        let (landed, events) = engine.advance(&state, 1);
+        assert!(events.iter().any(|e| matches!(e, GameEvent::ExactLanding { tile_id: 1, .. })));
+        assert_eq!(landed.players[0].money, 13000);
+
+        let (passed, events) = engine.advance(&state, 2);
+        assert!(!events.iter().any(|e| matches!(e, GameEvent::ExactLanding { .. })));
+        assert_eq!(passed.players[0].position, 2);
+    }
+
+    #[test]
+    fn test_raise_climbs_salary_ladder_to_cap() {
+        let engine = ClassicGameEngine::new();
+        let mut map = sample_map();
+        map.tiles[1].tile_type = TileType::Raise;
+        let mut state = engine.init(vec![("p1".to_string(), "Alice".to_string())], &map);
+        let mut career = map.careers[0].clone();
+        career.salary_ladder = vec![career.salary + 1000, career.salary + 2000];
+        let top = career.salary + 2000;
+        state.players[0].assign_career(career);
+
+        for level in 1..=3u32 {
+            state.players[0].position = 0;
+            let (after, events) = engine.advance(&state, 1);
+            let raised = events.iter().any(|e| matches!(e, GameEvent::SalaryRaised { .. }));
+            // 上限に達した後は上がらない
+            assert_eq!(raised, level <= 2);
+            assert_eq!(after.players[0].salary_level, level.min(2));
+            state = after;
+        }
+        assert_eq!(state.players[0].salary, top);
+    }
+
+    #[test]
+    fn test_swap_and_overtake_teleport() {
+        let engine = ClassicGameEngine::new();
+        let mut map = sample_map();
+        map.tiles[1].tile_type = TileType::Swap;
+        map.tiles[2].tile_type = TileType::Action;
+        map.tiles[2].next = vec![3];
+        map.tiles.push(TileData {
+            id: 3,
+            tile_type: TileType::Retire,
+            position: Position { x: 3.0, y: 0.0 },
+            next: vec![],
+            event: None,
+            labels: None,
+            landing_bonus: None,
+        });
+        let players = vec![
+            ("p1".to_string(), "Alice".to_string()),
+            ("p2".to_string(), "Bob".to_string()),
+            ("p3".to_string(), "Carol".to_string()),
+        ];
+        let mut state = engine.init(players, &map);
+        state.players[1].position = 2;
+        state.players[2].retired = true;
+
+        // 入れ替えは引退していない相手だけから選ぶ
+        let (landed, _) = engine.advance(&state, 1);
+        assert_eq!(landed.phase, TurnPhase::ChoosingAction);
+        assert_eq!(
+            engine.legal_actions(&landed),
+            vec![PlayerAction::SwapPosition {
+                target_id: "p2".to_string()
+            }]
+        );
+        let (swapped, events) = engine.resolve_action(&landed, engine.legal_actions(&landed).remove(0));
+        assert_eq!((swapped.players[0].position, swapped.players[1].position), (2, 1));
+        assert_eq!(
+            events
+                .iter()
+                .filter(|e| matches!(e, GameEvent::PlayerTeleported { .. }))
+                .count(),
+            2
+        );
+
+        // 追いつきは自分より先にいるプレイヤーの位置へ（効果は無し）
+        map.tiles[1].tile_type = TileType::Overtake;
+        let mut state = engine.init(
+            vec![("p1".to_string(), "Alice".to_string()), ("p2".to_string(), "Bob".to_string())],
+            &map,
+        );
+        state.players[1].position = 2;
+        let (after, events) = engine.advance(&state, 1);
+        assert_eq!(after.players[0].position, 2);
+        assert!(matches!(
+            events.last(),
+            Some(GameEvent::PlayerTeleported { from: 1, to: 2, .. })
+        ));
+        state.players[1].position = 0;
+        let (after, _) = engine.advance(&state, 1);
+        assert_eq!(after.players[0].position, 1);
+    }
+
+    #[test]
+    fn test_lawsuit_amount_per_tile_and_capped() {
+        let engine = ClassicGameEngine::new();
+        let mut map = sample_map();
+        map.tiles[1].tile_type = TileType::Lawsuit;
+        map.tiles[1].event = Some(TileEvent::Lawsuit { amount: 30_000 });
+        let players = vec![
+            ("p1".to_string(), "Alice".to_string()),
+            ("p2".to_string(), "Bob".to_string()),
+        ];
+        let mut state = engine.init(players, &map);
+        state.players[0].position = 1;
+
+        // 手持ち 10,000 で足りない分は 20,000 借りて払う
+        let (after, _) = ClassicEventResolver.resolve_lawsuit(&state, &"p2".to_string());
+        assert_eq!(after.players[0].money, 40_000);
+        assert_eq!(after.players[1].money, 0);
+        assert_eq!(after.players[1].debt, 20_000);
+
+        // 手持ちと借入の上限（2口）を超える分は請求されない
+        state.catalog = Arc::new({
+            let mut catalog = (*state.catalog).clone();
+            catalog.board.tiles[1].event = Some(TileEvent::Lawsuit { amount: 500_000 });
+            catalog
+        });
+        let (after, _) = ClassicEventResolver.resolve_lawsuit(&state, &"p2".to_string());
+        assert_eq!(after.players[0].money, 10_000 + 50_000);
+        assert_eq!(after.players[1].money, 0);
+        assert_eq!(after.players[1].debt, 40_000);
+    }
+
+    #[test]
+    fn test_idle_tax_after_repeated_skips() {
+        let engine = ClassicGameEngine::new().with_options(GameOptions {
+            stall_limit: Some(1),
+            ..GameOptions::default()
+        });
+        let mut state = engine.init(vec![("p1".to_string(), "Alice".to_string())], &sample_map());
+        state.phase = TurnPhase::ChoosingAction;
+
+        let (once, events) = engine.resolve_action(&state, PlayerAction::SkipAction);
+        assert!(events.is_empty());
+        let (twice, events) = engine.resolve_action(&once, PlayerAction::SkipAction);
+        assert_eq!(twice.players[0].money, 10000 - IDLE_TAX);
+        assert!(matches!(
+            events.as_slice(),
+            [GameEvent::MoneyChanged {
+                reason: MoneyReason::IdleTax,
+                ..
+            }]
+        ));
+
+        // 何かを選ぶと数え直す
+        let (bought, _) = engine.resolve_action(&twice, PlayerAction::BuyStock);
+        assert_eq!(bought.players[0].consecutive_skips, 0);
+        let (_, events) = engine.resolve_action(&bought, PlayerAction::SkipAction);
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn test_seed_option_reproduces_game() {
+        let players = || vec![("p1".to_string(), "Alice".to_string()), ("p2".to_string(), "Bob".to_string())];
+        let spins = |seed: Option<u64>| {
+            let engine = ClassicGameEngine::new().with_options(GameOptions {
+                seed,
+                ..GameOptions::default()
+            });
+            let mut state = engine.init(players(), &sample_map());
+            assert_eq!(state.seed, seed.filter(|&s| s != 0).unwrap_or(DEFAULT_SEED));
+            (0..20)
+                .map(|_| {
+                    let (next, result) = engine.spin(&state);
+                    state = next;
+                    result.value
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(spins(Some(7)), spins(Some(7)));
+        assert_ne!(spins(Some(7)), spins(Some(8)));
+        assert_eq!(spins(Some(0)), spins(None));
+    }
+
+    #[test]
+    fn test_spin_twice_from_speed_tile() {
+        let engine = ClassicGameEngine::new();
+        let mut map = sample_map();
+        map.tiles[1].tile_type = TileType::Speed;
+        let mut state = engine.init(vec![("p1".to_string(), "Alice".to_string())], &map);
+        state.players[0].position = 1;
+
+        let (_, result) = engine.spin(&state);
+        assert_eq!(result.spins.len(), 2);
+        assert_eq!(result.value, result.spins.iter().sum::<u32>());
+        assert_eq!(result.spins[0], StandardRoulette.spin(&state));
+    }
+
+    #[test]
+    fn test_state_serializes_catalog_flat() {
+        let engine = ClassicGameEngine::new();
+        let state = engine.init(vec![("p1".to_string(), "Alice".to_string())], &sample_map());
+        let json = serde_json::to_value(&state).unwrap();
+        assert_eq!(json["board"]["tiles"].as_array().unwrap().len(), 3);
+        assert_eq!(json["houses_for_sale"][0]["id"], "test_house");
+        assert!(json.get("catalog").is_none());
+
+        let restored: GameState = serde_json::from_value(json).unwrap();
+        assert_eq!(restored.catalog.careers.len(), 1);
+    }
+
+    #[test]
+    fn test_advance_and_retire() {
+        let engine = ClassicGameEngine::new();
+        let map = sample_map();
+        let players = vec![
+            ("p1".to_string(), "Alice".to_string()),
+            ("p2".to_string(), "Bob".to_string()),
+        ];
+        let mut state = engine.init(players, &map);
+        state.players[0].salary = 10000;
+
+        // Advance 2 steps: Start(0) -> Payday(1) -> Retire(2)
+        let (new_state, events) = engine.advance(&state, 2);
+        assert_eq!(new_state.players[0].position, 2);
+        assert!(new_state.players[0].retired);
+        match &events[0] {
+            GameEvent::PlayerMoved { path, paydays_passed, .. } => {
+                assert_eq!(path, &vec![1, 2]);
+                assert_eq!(*paydays_passed, 1);
+            }
+            other => panic!("unexpected first event: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_laps_on_cyclic_board() {
+        let engine = ClassicGameEngine::new();
+        let mut map = sample_map();
+        // 0 -> 1(給料日) -> 2 -> 0 のループ
+        map.tiles[2].tile_type = TileType::Action;
+        map.tiles[2].next = vec![0];
+        map.laps = Some(LapRules {
+            max_paydays_per_lap: Some(1),
+            finish_after_laps: Some(2),
+        });
+        let players = vec![
+            ("p1".to_string(), "Alice".to_string()),
+            ("p2".to_string(), "Bob".to_string()),
+        ];
+        let mut state = engine.init(players, &map);
+        state.players[0].salary = 1000;
+
+        // 4歩: 1(給料通過) -> 2 -> 0(1周) -> 1(給料日に停止)
+        let (state, events) = engine.advance(&state, 4);
+        assert_eq!(state.players[0].laps, 1);
+        assert_eq!(state.players[0].position, 1);
+        assert_eq!(state.players[0].money, 12000);
+        assert!(events
+            .iter()
+            .any(|e| matches!(e, GameEvent::LapCompleted { laps: 1, .. })));
+
+        // 同じ周回では上限の1回を超えて受け取れない
+        let mut again = state.clone();
+        again.players[0].position = 0;
+        let (again, _) = engine.advance(&again, 1);
+        assert_eq!(again.players[0].money, 12000);
+
+        // 2周目の完了でゴール（残りの歩数は進まない）
+        let (state, _) = engine.advance(&state, 5);
+        assert_eq!(state.players[0].laps, 2);
+        assert_eq!(state.players[0].position, 0);
+        assert!(state.players[0].retired);
+    }
+
+    #[test]
+    fn test_branch_choices_include_preview() {
+        let engine = ClassicGameEngine::new();
+        let mut map = sample_map();
+        map.tiles[0].next = vec![1, 2];
+        let players = vec![
+            ("p1".to_string(), "Alice".to_string()),
+            ("p2".to_string(), "Bob".to_string()),
+        ];
+        let state = engine.init(players, &map);
+        let start = state.catalog.board.tile(0).cloned().unwrap();
+
+        let (state, events) = ClassicEventResolver.resolve_tile(&state, &start);
+        assert_eq!(state.phase, TurnPhase::ChoosingPath);
+        let Some(GameEvent::ChoiceRequired { choices }) = events.first() else {
+            panic!("expected ChoiceRequired: {:?}", events);
+        };
+        let preview: Vec<Vec<(usize, TileType)>> = choices
+            .iter()
+            .map(|c| c.preview.iter().map(|p| (p.tile_id, p.tile_type.clone())).collect())
+            .collect();
+        assert_eq!(
+            preview,
+            vec![
+                vec![(1, TileType::Payday), (2, TileType::Retire)],
+                vec![(2, TileType::Retire)],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pause_at_branch_mid_move() {
+        let engine = ClassicGameEngine::new().with_options(GameOptions {
+            pause_at_branches: true,
+            ..GameOptions::default()
+        });
+        let mut map = sample_map();
+        map.tiles[0].next = vec![1, 2];
+        let players = vec![
+            ("p1".to_string(), "Alice".to_string()),
+            ("p2".to_string(), "Bob".to_string()),
+        ];
+        let mut state = engine.init(players, &map);
+        state.players[0].salary = 1000;
+
+        // スタートの分岐で止まり、残り3歩を保存する
+        let (state, events) = engine.advance(&state, 3);
+        assert_eq!(state.phase, TurnPhase::ChoosingPath);
+        assert_eq!(state.pending_steps, 3);
+        assert_eq!(state.players[0].position, 0);
+        assert!(events
+            .iter()
+            .any(|e| matches!(e, GameEvent::ChoiceRequired { choices } if choices.len() == 2)));
+
+        // 道を選ぶと Moving に戻り、残りの歩数で移動を再開する
+        let state = engine.choose_path(&state, 0);
+        assert_eq!(state.phase, TurnPhase::Moving);
+        let (state, events) = engine.advance(&state, state.pending_steps);
+        assert!(matches!(&events[0], GameEvent::PlayerMoved { path, .. } if path == &vec![1, 2]));
+        assert_eq!(state.players[0].money, 11000 + RETIREMENT_BONUSES[0]);
+        assert!(state.players[0].retired);
+        assert_eq!(state.pending_steps, 0);
+    }
+
+    #[test]
+    fn test_end_turn_skips_retired() {
+        let engine = ClassicGameEngine::new();
+        let map = sample_map();
+        let players = vec![
+            ("p1".to_string(), "Alice".to_string()),
+            ("p2".to_string(), "Bob".to_string()),
+            ("p3".to_string(), "Charlie".to_string()),
+        ];
+        let mut state = engine.init(players, &map);
+        state.players[1].retired = true; // Bob is retired
+
+        // Turn 0 (Alice) -> end_turn -> should skip Bob (retired) -> Charlie (turn 2)
+        let new_state = engine.end_turn(&state);
+        assert_eq!(new_state.current_turn, 2);
+    }
+
+    #[test]
+    fn test_end_turn_skips_disconnected() {
+        let engine = ClassicGameEngine::new();
+        let map = sample_map();
+        let players = vec![
+            ("p1".to_string(), "Alice".to_string()),
+            ("p2".to_string(), "Bob".to_string()),
+            ("p3".to_string(), "Charlie".to_string()),
+        ];
+        let mut state = engine.init(players, &map);
+        state.players[1].connected = false;
+        assert_eq!(engine.end_turn(&state).current_turn, 2);
+
+        // 接続中のプレイヤーが引退済みしかいなければ、切断中でも引退していないプレイヤーの手番
+        state.players[0].retired = true;
+        state.players[2].connected = false;
+        assert_eq!(engine.end_turn(&state).current_turn, 1);
+    }
+
+    #[test]
+    fn test_is_finished() {
+        let engine = ClassicGameEngine::new();
+        let map = sample_map();
+        let players = vec![
+            ("p1".to_string(), "Alice".to_string()),
+            ("p2".to_string(), "Bob".to_string()),
+        ];
+        let mut state = engine.init(players, &map);
+
+        assert!(!engine.is_finished(&state));
+
+        state.players[0].retired = true;
+        state.players[1].retired = true;
+        assert!(engine.is_finished(&state));
+    }
+
+    #[test]
+    fn test_is_finished_by_turn_limit() {
+        let engine = ClassicGameEngine::new().with_options(GameOptions {
+            max_turn_count: Some(3),
+            ..GameOptions::default()
+        });
+        let map = sample_map();
+        let players = vec![
+            ("p1".to_string(), "Alice".to_string()),
+            ("p2".to_string(), "Bob".to_string()),
+        ];
+        let mut state = engine.init(players, &map);
+        assert_eq!(state.turn_number, 1);
+        assert_eq!(state.max_turns, Some(3));
+
+        for _ in 0..2 {
+            state.phase = TurnPhase::TurnEnd;
+            assert!(!engine.is_finished(&state));
+            state = engine.end_turn(&state);
+        }
+        assert_eq!(state.turn_number, 3);
+        assert!(!engine.is_finished(&state));
+
+        state.phase = TurnPhase::TurnEnd;
+        assert!(engine.is_finished(&state));
+    }
+
+    #[test]
+    fn test_rankings() {
+        let engine = ClassicGameEngine::new();
+        let map = sample_map();
+        let players = vec![
+            ("p1".to_string(), "Alice".to_string()),
+            ("p2".to_string(), "Bob".to_string()),
+        ];
+        let mut state = engine.init(players, &map);
+        state.players[0].money = 50000;
+        state.players[1].money = 100000;
+        state.players[1].debt = 20000;
+
+        let rankings = engine.rankings(&state);
+        assert_eq!(rankings[0].player_id, "p2"); // Bob has more money
+        assert_eq!(rankings[0].rank, 1);
+        assert_eq!(rankings[0].breakdown.debt, 25000);
+        assert_eq!(rankings[0].total_assets, 75000);
+        assert_eq!(rankings[1].player_id, "p1");
+        assert_eq!(rankings[1].rank, 2);
+    }
+
+    #[test]
+    fn test_rankings_value_stocks_at_stock_price() {
+        let engine = ClassicGameEngine::new();
+        let map = sample_map();
+        let players = vec![
+            ("p1".to_string(), "Alice".to_string()),
+            ("p2".to_string(), "Bob".to_string()),
+        ];
+        let mut state = engine.init(players, &map);
+        state.players[0].money = 30000;
+        state.players[1].money = 50000;
+        for i in 0..2 {
+            state.players[0].stocks.push(Stock {
+                id: format!("s{}", i),
+                name: "株券".to_string(),
+            });
+        }
+
+        // 株価 15000 なら株2枚で Bob を上回る
+        state.stock_price = 15000;
+        let rankings = engine.rankings(&state);
+        assert_eq!(rankings[0].player_id, "p1");
+        assert_eq!(rankings[0].breakdown.stock_value, 30000);
+        assert_eq!(rankings[0].total_assets, 60000);
+
+        // 暴落して株価 5000 なら逆転する
+        state.stock_price = 5000;
+        let rankings = engine.rankings(&state);
+        assert_eq!(rankings[0].player_id, "p2");
+        assert_eq!(rankings[1].breakdown.stock_value, 10000);
+        assert_eq!(rankings[1].total_assets, 40000);
+    }
+
+    #[test]
+    fn test_buy_stock_at_stock_price() {
+        let engine = ClassicGameEngine::new();
+        let mut state = engine.init(vec![("p1".to_string(), "Alice".to_string())], &sample_map());
+        state.phase = TurnPhase::ChoosingAction;
+        state.stock_price = 7000;
+
+        let (bought, events) = engine.resolve_action(&state, PlayerAction::BuyStock);
+        assert_eq!(bought.players[0].money, 10000 - 7000);
+        assert_eq!(bought.players[0].stocks.len(), 1);
+        assert!(matches!(events.as_slice(), [GameEvent::StockPurchased { price: 7000, .. }]));
+    }
+
+    #[test]
+    fn test_retirement_choice_adjusts_assets() {
+        let engine = ClassicGameEngine::new();
+        let map = sample_map();
+        let mut state = engine.init(vec![("p1".to_string(), "Alice".to_string())], &map);
+        state.players[0].money = 460_000;
+
+        let (state, events) = engine.advance(&state, 2);
+        assert_eq!(state.phase, TurnPhase::ChoosingAction);
+        assert!(!engine.is_finished(&state));
+        assert!(events
+            .iter()
+            .any(|e| matches!(e, GameEvent::ChoiceRequired { choices } if choices.len() == 2)));
+
+        let luxury = PlayerAction::ChooseRetirement {
+            home: RetirementHome::Luxury,
+        };
+        assert!(engine.legal_actions(&state).contains(&luxury));
+        let (state, _) = engine.resolve_action(&state, luxury);
+        assert!(engine.is_finished(&state));
+        // 460000 + 1着の退職金 40000 = 500000 の 20% から入居費を引く
+        let breakdown = state.players[0].asset_breakdown(state.loan_interest_rate, state.stock_price);
+        assert_eq!(breakdown.retirement, 50_000);
+        assert_eq!(breakdown.total(), 550_000);
+    }
+
+    #[test]
+    fn test_retirement_bonus_by_arrival_order() {
+        let engine = ClassicGameEngine::new();
+        let map = sample_map();
+        let players = vec![
+            ("p1".to_string(), "Alice".to_string()),
+            ("p2".to_string(), "Bob".to_string()),
+        ];
+        let mut state = engine.init(players, &map);
+
+        state.current_turn = 1;
+        let (state, _) = engine.advance(&state, 2);
+        let state = engine.end_turn(&state);
+        let (state, events) = engine.advance(&state, 2);
+        assert!(events.iter().any(|e| matches!(
+            e,
+            GameEvent::MoneyChanged { reason: MoneyReason::RetirementBonus { order: 2 }, .. }
+        )));
+
+        assert_eq!(state.arrivals, vec!["p2".to_string(), "p1".to_string()]);
+        assert_eq!(state.players[1].money, 10000 + RETIREMENT_BONUSES[0]);
+        assert_eq!(state.players[0].money, 10000 + RETIREMENT_BONUSES[1]);
+        let rankings = engine.rankings(&state);
+        assert_eq!(rankings[0].player_id, "p2");
+        assert_eq!(rankings[0].arrival_order, Some(1));
+        assert_eq!(rankings[1].arrival_order, Some(2));
+    }
+
+    #[test]
+    fn test_gift_borrows_when_short() {
+        let engine = ClassicGameEngine::new();
+        let map = sample_map();
+        let players = vec![
+            ("p1".to_string(), "Alice".to_string()),
+            ("p2".to_string(), "Bob".to_string()),
+            ("p3".to_string(), "Carol".to_string()),
+        ];
+        let mut state = engine.init(players, &map);
+        state.players[1].money = 2000;
+        let tile = Tile {
+            id: 1,
+            tile_type: TileType::Marry,
+            position: Position { x: 1.0, y: 0.0 },
+            next: vec![2],
+            event: None,
+            labels: None,
+            landing_bonus: None,
+        };
+
+        let (state, events) = ClassicEventResolver.resolve_tile(&state, &tile);
+        // Bob は不足分 3000 を借入単位（20000）で借りてから払う
+        assert_eq!(state.players[1].debt, 20000);
+        assert_eq!(state.players[1].money, 17000);
+        assert_eq!(state.players[2].debt, 0);
+        assert_eq!(state.players[2].money, 5000);
+        assert_eq!(state.players[0].money, 20000);
+        assert!(events.iter().any(|e| matches!(
+            e,
+            GameEvent::MoneyChanged { player_id, amount: 20000, reason: MoneyReason::Loan } if player_id == "p2"
+        )));
+    }
+
+    #[test]
+    fn test_birthday_collects_from_active_players() {
+        let engine = ClassicGameEngine::new();
+        let map = sample_map();
+        let players = vec![
+            ("p1".to_string(), "Alice".to_string()),
+            ("p2".to_string(), "Bob".to_string()),
+            ("p3".to_string(), "Carol".to_string()),
+        ];
+        let mut state = engine.init(players, &map);
+        state.current_turn = 1;
+        state.players[0].money = 0;
+        state.players[2].retired = true;
+        let tile = Tile {
+            id: 1,
+            tile_type: TileType::Birthday,
+            position: Position { x: 1.0, y: 0.0 },
+            next: vec![2],
+            event: None,
+            labels: None,
+            landing_bonus: None,
+        };
+
+        let (state, events) = ClassicEventResolver.resolve_tile(&state, &tile);
+        assert_eq!(state.players[1].money, 11000);
+        assert_eq!(state.players[0].money, 19000);
+        assert_eq!(state.players[0].debt, 20000);
+        assert_eq!(state.players[2].money, 10000);
+        assert!(matches!(
+            events.last(),
+            Some(GameEvent::MoneyChanged {
+                amount: 1000,
+                reason: MoneyReason::GiftReceived { kind: GiftKind::Birthday },
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_twins_scale_gift_and_respect_cap() {
+        let engine = ClassicGameEngine::new();
+        let map = sample_map();
+        let players = vec![
+            ("p1".to_string(), "Alice".to_string()),
+            ("p2".to_string(), "Bob".to_string()),
+        ];
+        let state = engine.init(players, &map);
+        let tile = Tile {
+            id: 1,
+            tile_type: TileType::Baby,
+            position: Position { x: 1.0, y: 0.0 },
+            next: vec![2],
+            event: None,
+            labels: None,
+            landing_bonus: None,
+        };
+
+        let mut twins_seen = false;
+        for seed in 1..200 {
+            let mut state = state.clone();
+            state.rng_seed = seed;
+            let (after, events) = ClassicEventResolver.resolve_tile(&state, &tile);
+            let count = events
+                .iter()
+                .find_map(|e| match e {
+                    GameEvent::BabyBorn { count, .. } => Some(*count),
+                    _ => None,
+                })
+                .unwrap();
+            assert_eq!(after.players[0].children, count);
+            assert_eq!(after.players[0].money, 10000 + 5000 * count as i64);
+            twins_seen |= count == 2;
+
+            // 上限の1人手前では双子でも1人だけ
+            state.players[0].children = MAX_CHILDREN - 1;
+            let (after, _) = ClassicEventResolver.resolve_tile(&state, &tile);
+            assert_eq!(after.players[0].children, MAX_CHILDREN);
+            assert_eq!(after.players[1].money, 5000);
+        }
+        assert!(twins_seen);
+    }
+
+    #[test]
+    fn test_hospital_fee_goes_to_doctor() {
+        let engine = ClassicGameEngine::new();
+        let map = sample_map();
+        let players = vec![
+            ("p1".to_string(), "Alice".to_string()),
+            ("p2".to_string(), "Bob".to_string()),
+        ];
+        let mut state = engine.init(players, &map);
+        let tile = Tile {
+            id: 1,
+            tile_type: TileType::Hospital,
+            position: Position { x: 1.0, y: 0.0 },
+            next: vec![2],
+            event: None,
+            labels: None,
+            landing_bonus: None,
+        };
+
+        // 医者がいなければ銀行に払う
+        let (paid, _) = ClassicEventResolver.resolve_tile(&state, &tile);
+        assert_eq!(paid.players[0].money, 10000 - HOSPITAL_FEE);
+        assert_eq!(paid.players[1].money, 10000);
+
+        let mut doctor = map.careers[0].clone();
+        doctor.abilities = vec![CareerAbility::Doctor];
+        state.players[1].career = Some(doctor.clone());
+        let (paid, _) = ClassicEventResolver.resolve_tile(&state, &tile);
+        assert_eq!(paid.players[0].money, 10000 - HOSPITAL_FEE);
+        assert_eq!(paid.players[1].money, 10000 + HOSPITAL_FEE);
+
+        // 自分が医者なら払わない
+        state.players[0].career = Some(doctor);
+        let (free, events) = ClassicEventResolver.resolve_tile(&state, &tile);
+        assert_eq!(free.players[0].money, 10000);
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn test_fire_insurance_and_police() {
+        let engine = ClassicGameEngine::new();
+        let map = sample_map();
+        let players = vec![
+            ("p1".to_string(), "Alice".to_string()),
+            ("p2".to_string(), "Bob".to_string()),
+        ];
+        let mut state = engine.init(players, &map);
+        let action = |event| Tile {
+            id: 1,
+            tile_type: TileType::Action,
+            position: Position { x: 1.0, y: 0.0 },
+            next: vec![2],
+            event: Some(event),
+            labels: None,
+            landing_bonus: None,
+        };
+
+        let fire = action(TileEvent::Fire { amount: 4000 });
+        let (burned, _) = ClassicEventResolver.resolve_tile(&state, &fire);
+        assert_eq!(burned.players[0].money, 6000);
+        state.players[0].fire_insurance = true;
+        let (insured, events) = ClassicEventResolver.resolve_tile(&state, &fire);
+        assert_eq!(insured.players[0].money, 10000);
+        assert_eq!(events.len(), 2);
+
+        let theft = action(TileEvent::Theft { amount: 3000 });
+        let mut police = map.careers[0].clone();
+        police.abilities = vec![CareerAbility::Police];
+        state.players[1].career = Some(police);
+        let (robbed, _) = ClassicEventResolver.resolve_tile(&state, &theft);
+        assert_eq!(robbed.players[0].money, 7000);
+        assert_eq!(robbed.players[1].money, 13000);
+    }
+}
@@ -0,0 +1,597 @@
+use crate::PlayerId;
+
+use super::script;
+use super::state::*;
+use super::text::{ChoiceText, GiftKind, MoneyReason};
+use super::traits::{EventResolver, Roulette};
+
+// ============================================================
+// StandardRoulette - xorshift64ベースの1-10乱数
+// ============================================================
+
+pub struct StandardRoulette;
+
+impl Roulette for StandardRoulette {
+    fn spin(&self, state: &GameState) -> u32 {
+        // Use seed to derive a value 1-10 without mutating state
+        let mut x = state.rng_seed;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        (x % 10 + 1) as u32
+    }
+}
+
+// ============================================================
+// SpeedRoulette - 出目5-10のスピードルール用
+// ============================================================
+
+pub struct SpeedRoulette;
+
+impl Roulette for SpeedRoulette {
+    fn spin(&self, state: &GameState) -> u32 {
+        let mut x = state.rng_seed;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        (x % 6 + 5) as u32
+    }
+}
+
+// ============================================================
+// ClassicEventResolver - 本家準拠イベント処理
+// ============================================================
+
+pub struct ClassicEventResolver;
+
+/// 分岐の選択肢に含める先読みマス数
+const PATH_PREVIEW_LENGTH: usize = 5;
+
+/// 誕生日マスで1人から受け取る金額
+const BIRTHDAY_GIFT: i64 = 1000;
+
+/// 出産祝いで1人から受け取る金額（生まれた子ども1人あたり）
+const BIRTH_GIFT: i64 = 5000;
+
+impl ClassicEventResolver {
+    /// 分岐マスの道ごとの選択肢（ラベルと先のマスの概要）
+    pub(crate) fn path_choices(board: &Board, tile: &Tile) -> Vec<GameChoice> {
+        let labels = tile.labels.clone().unwrap_or_default();
+        tile.next
+            .iter()
+            .enumerate()
+            .map(|(i, &next)| GameChoice {
+                preview: board.preview_path(next, PATH_PREVIEW_LENGTH),
+                ..GameChoice::new(
+                    i.to_string(),
+                    ChoiceText::Path {
+                        index: i,
+                        label: labels.get(i).cloned(),
+                    },
+                )
+            })
+            .collect()
+    }
+
+    /// 受け取る人の次の手番から順に、ゴールしていない全員から祝い金を集める
+    /// 所持金で払えない人は、不足分を loan_unit 単位で自動的に借りてから払う
+    fn collect_from_others(state: &GameState, recipient_idx: usize, amount: i64, kind: GiftKind) -> (GameState, Vec<GameEvent>) {
+        let mut new_state = state.clone();
+        let mut events = Vec::new();
+        let recipient_id = new_state.players[recipient_idx].id.clone();
+        let mut received = 0;
+        let count = new_state.players.len();
+
+        for i in (1..count).map(|offset| (recipient_idx + offset) % count) {
+            if new_state.players[i].retired {
+                continue;
+            }
+            let giver_id = new_state.players[i].id.clone();
+            events.extend(new_state.borrow_to_cover(i, amount));
+            new_state.players[i].money -= amount;
+            new_state.players[recipient_idx].money += amount;
+            received += amount;
+
+            events.push(GameEvent::MoneyChanged {
+                player_id: giver_id,
+                amount: -amount,
+                reason: MoneyReason::GiftPaid { kind },
+            });
+        }
+
+        events.push(GameEvent::MoneyChanged {
+            player_id: recipient_id,
+            amount: received,
+            reason: MoneyReason::GiftReceived { kind },
+        });
+
+        (new_state, events)
+    }
+}
+
+impl EventResolver for ClassicEventResolver {
+    fn resolve_tile(&self, state: &GameState, tile: &Tile) -> (GameState, Vec<GameEvent>) {
+        let mut new_state = state.clone();
+        let mut events = Vec::new();
+        let player_idx = new_state.current_turn;
+        let player_id = new_state.players[player_idx].id.clone();
+
+        match tile.tile_type {
+            TileType::Payday => {
+                let before = new_state.players[player_idx].money;
+                new_state = self.resolve_payday(&new_state, player_idx);
+                let salary = new_state.players[player_idx].money - before;
+                if salary > 0 {
+                    events.push(GameEvent::MoneyChanged {
+                        player_id,
+                        amount: salary,
+                        reason: MoneyReason::Payday,
+                    });
+                }
+            }
+
+            TileType::Action => match tile.event {
+                Some(TileEvent::Money { amount, ref text }) => {
+                    let amount = new_state.scaled_payout(amount);
+                    new_state.players[player_idx].money += amount;
+                    events.push(GameEvent::MoneyChanged {
+                        player_id,
+                        amount,
+                        reason: MoneyReason::TileEvent { text: text.clone() },
+                    });
+                }
+                Some(TileEvent::Fire { amount }) => {
+                    new_state.players[player_idx].money -= amount;
+                    events.push(GameEvent::MoneyChanged {
+                        player_id: player_id.clone(),
+                        amount: -amount,
+                        reason: MoneyReason::FireDamage,
+                    });
+                    if new_state.players[player_idx].fire_insurance {
+                        new_state.players[player_idx].money += amount;
+                        events.push(GameEvent::MoneyChanged {
+                            player_id,
+                            amount,
+                            reason: MoneyReason::InsuranceClaim,
+                        });
+                    }
+                }
+                Some(TileEvent::Theft { amount }) => {
+                    let is_police = new_state.players[player_idx]
+                        .career
+                        .as_ref()
+                        .is_some_and(|c| c.has_ability(CareerAbility::Police));
+                    if !is_police {
+                        new_state.players[player_idx].money -= amount;
+                        match new_state.next_player_with_ability(player_idx, CareerAbility::Police) {
+                            Some(police_idx) => {
+                                new_state.players[police_idx].money += amount;
+                                events.push(GameEvent::MoneyChanged {
+                                    player_id,
+                                    amount: -amount,
+                                    reason: MoneyReason::TheftPaid,
+                                });
+                                events.push(GameEvent::MoneyChanged {
+                                    player_id: new_state.players[police_idx].id.clone(),
+                                    amount,
+                                    reason: MoneyReason::TheftReward,
+                                });
+                            }
+                            None => events.push(GameEvent::MoneyChanged {
+                                player_id,
+                                amount: -amount,
+                                reason: MoneyReason::Theft,
+                            }),
+                        }
+                    }
+                }
+                _ => {}
+            },
+
+            TileType::Career => {
+                // seedベースで職業割り当て
+                let pool = match &tile.event {
+                    Some(TileEvent::DrawCareer { pool }) => pool.clone(),
+                    _ => "basic".to_string(),
+                };
+                let available: Vec<Career> = new_state
+                    .catalog
+                    .careers
+                    .iter()
+                    .filter(|c| c.pool == pool)
+                    .cloned()
+                    .collect();
+                if !available.is_empty() {
+                    let idx = (new_state.next_random() as usize) % available.len();
+                    let career = available[idx].clone();
+                    new_state.players[player_idx].assign_career(career.clone());
+                    events.push(GameEvent::CareerAssigned {
+                        player_id,
+                        career,
+                    });
+                }
+            }
+
+            TileType::House => {
+                // 家の選択肢を提示
+                let choices: Vec<GameChoice> = new_state
+                    .catalog
+                    .houses_for_sale
+                    .iter()
+                    .map(|h| {
+                        GameChoice::new(
+                            h.id.clone(),
+                            ChoiceText::BuyHouse {
+                                house_name: h.name.clone(),
+                                price: h.price,
+                                sell_price: h.sell_price,
+                            },
+                        )
+                        .with_action(PlayerAction::BuyHouse {
+                            house_id: h.id.clone(),
+                        })
+                    })
+                    .chain(std::iter::once(
+                        GameChoice::new("skip", ChoiceText::SkipHouse).with_action(PlayerAction::SkipAction),
+                    ))
+                    .collect();
+                new_state.phase = TurnPhase::ChoosingAction;
+                events.push(GameEvent::ChoiceRequired { choices });
+            }
+
+            TileType::Marry => {
+                if !new_state.players[player_idx].married {
+                    new_state.players[player_idx].married = true;
+                    events.push(GameEvent::Married {
+                        player_id: player_id.clone(),
+                    });
+                    // ご祝儀
+                    let (gift_state, gift_events) =
+                        Self::collect_from_others(&new_state, player_idx, 5000, GiftKind::Wedding);
+                    new_state = gift_state;
+                    events.extend(gift_events);
+                }
+            }
+
+            TileType::Baby => {
+                let room = MAX_CHILDREN.saturating_sub(new_state.players[player_idx].children);
+                if room > 0 {
+                    let twins = new_state.next_random() % 100 < TWINS_PERCENT;
+                    let count = if twins { 2 } else { 1 }.min(room);
+                    new_state.players[player_idx].children += count;
+                    let children = new_state.players[player_idx].children;
+                    events.push(GameEvent::BabyBorn {
+                        player_id: player_id.clone(),
+                        children,
+                        count,
+                    });
+                    // お祝い金（生まれた人数分）
+                    let (gift_state, gift_events) = Self::collect_from_others(
+                        &new_state,
+                        player_idx,
+                        BIRTH_GIFT * count as i64,
+                        GiftKind::Birth,
+                    );
+                    new_state = gift_state;
+                    events.extend(gift_events);
+                }
+            }
+
+            TileType::Stock => {
+                let cost = new_state.stock_price;
+                if new_state.players[player_idx].money >= cost {
+                    new_state.players[player_idx].money -= cost;
+                    let stock_id = format!("stock_{}", new_state.next_random() % 100);
+                    new_state.players[player_idx].stocks.push(Stock {
+                        id: stock_id,
+                        name: "株券".to_string(),
+                    });
+                    events.push(GameEvent::StockPurchased {
+                        player_id,
+                        price: cost,
+                    });
+                }
+            }
+
+            TileType::Insurance => {
+                let mut choices = Vec::new();
+                if !new_state.players[player_idx].life_insurance {
+                    choices.push(GameChoice::new("life", ChoiceText::LifeInsurance).with_action(
+                        PlayerAction::BuyInsurance {
+                            insurance_type: InsuranceType::Life,
+                        },
+                    ));
+                }
+                if !new_state.players[player_idx].auto_insurance {
+                    choices.push(GameChoice::new("auto", ChoiceText::AutoInsurance).with_action(
+                        PlayerAction::BuyInsurance {
+                            insurance_type: InsuranceType::Auto,
+                        },
+                    ));
+                }
+                if !new_state.players[player_idx].fire_insurance {
+                    choices.push(GameChoice::new("fire", ChoiceText::FireInsurance).with_action(
+                        PlayerAction::BuyInsurance {
+                            insurance_type: InsuranceType::Fire,
+                        },
+                    ));
+                }
+                choices.push(GameChoice::new("skip", ChoiceText::SkipInsurance).with_action(PlayerAction::SkipAction));
+                new_state.phase = TurnPhase::ChoosingAction;
+                events.push(GameEvent::ChoiceRequired { choices });
+            }
+
+            TileType::Tax => {
+                let tax = (new_state.players[player_idx].salary as f64 * 0.1) as i64;
+                let tax = if tax > 0 { tax } else { 5000 };
+                let tax = if new_state.tax_cut { tax / 2 } else { tax };
+                new_state.players[player_idx].money -= tax;
+                events.push(GameEvent::MoneyChanged {
+                    player_id,
+                    amount: -tax,
+                    reason: MoneyReason::Tax,
+                });
+            }
+
+            TileType::Birthday => {
+                let (gift_state, gift_events) =
+                    Self::collect_from_others(&new_state, player_idx, BIRTHDAY_GIFT, GiftKind::Birthday);
+                new_state = gift_state;
+                events.extend(gift_events);
+            }
+
+            TileType::Hospital => {
+                // 医者がいればその人に払う。自分が医者なら払わない
+                let is_doctor = new_state.players[player_idx]
+                    .career
+                    .as_ref()
+                    .is_some_and(|c| c.has_ability(CareerAbility::Doctor));
+                if !is_doctor {
+                    new_state.players[player_idx].money -= HOSPITAL_FEE;
+                    match new_state.next_player_with_ability(player_idx, CareerAbility::Doctor) {
+                        Some(doctor_idx) => {
+                            new_state.players[doctor_idx].money += HOSPITAL_FEE;
+                            events.push(GameEvent::MoneyChanged {
+                                player_id,
+                                amount: -HOSPITAL_FEE,
+                                reason: MoneyReason::TreatmentPaid,
+                            });
+                            events.push(GameEvent::MoneyChanged {
+                                player_id: new_state.players[doctor_idx].id.clone(),
+                                amount: HOSPITAL_FEE,
+                                reason: MoneyReason::TreatmentReceived,
+                            });
+                        }
+                        None => events.push(GameEvent::MoneyChanged {
+                            player_id,
+                            amount: -HOSPITAL_FEE,
+                            reason: MoneyReason::TreatmentFee,
+                        }),
+                    }
+                }
+            }
+
+            TileType::Lawsuit => {
+                // 他プレイヤー選択
+                let choices: Vec<GameChoice> = new_state
+                    .players
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, p)| *i != player_idx && !p.retired)
+                    .map(|(_, p)| {
+                        GameChoice::new(
+                            p.id.clone(),
+                            ChoiceText::Sue {
+                                player_name: p.name.clone(),
+                            },
+                        )
+                        .with_action(PlayerAction::SelectLawsuitTarget {
+                            target_id: p.id.clone(),
+                        })
+                    })
+                    .collect();
+                if !choices.is_empty() {
+                    new_state.phase = TurnPhase::ChoosingAction;
+                    events.push(GameEvent::ChoiceRequired { choices });
+                }
+            }
+
+            TileType::Swap => {
+                // 入れ替える相手を選択
+                let choices: Vec<GameChoice> = new_state
+                    .players
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, p)| *i != player_idx && !p.retired)
+                    .map(|(_, p)| {
+                        GameChoice::new(
+                            p.id.clone(),
+                            ChoiceText::SwapWith {
+                                player_name: p.name.clone(),
+                            },
+                        )
+                        .with_action(PlayerAction::SwapPosition {
+                            target_id: p.id.clone(),
+                        })
+                    })
+                    .collect();
+                if !choices.is_empty() {
+                    new_state.phase = TurnPhase::ChoosingAction;
+                    events.push(GameEvent::ChoiceRequired { choices });
+                }
+            }
+
+            TileType::Overtake => {
+                // 自分より先にいる一番先のプレイヤーに追いつく
+                let leader = (0..new_state.players.len())
+                    .filter(|&i| i != player_idx && !new_state.players[i].retired)
+                    .max_by_key(|&i| new_state.progress(i))
+                    .filter(|&i| new_state.progress(i) > new_state.progress(player_idx));
+                if let Some(leader) = leader {
+                    let from = new_state.players[player_idx].position;
+                    let to = new_state.players[leader].position;
+                    // 周回マップでは周回数も揃える
+                    new_state.players[player_idx].laps = new_state.players[leader].laps;
+                    new_state.players[player_idx].position = to;
+                    events.push(GameEvent::PlayerTeleported { player_id, from, to });
+                }
+            }
+
+            TileType::Branch => {
+                // 分岐マス: path選択フェーズへ
+                new_state.phase = TurnPhase::ChoosingPath;
+                let choices = Self::path_choices(&new_state.catalog.board, tile);
+                events.push(GameEvent::ChoiceRequired { choices });
+            }
+
+            TileType::Retire => {
+                events.extend(new_state.record_arrival(player_idx));
+                // 老後の暮らしを選ぶ
+                let choices = vec![
+                    GameChoice::new("luxury", ChoiceText::LuxuryRetirement).with_action(
+                        PlayerAction::ChooseRetirement {
+                            home: RetirementHome::Luxury,
+                        },
+                    ),
+                    GameChoice::new("modest", ChoiceText::ModestRetirement).with_action(
+                        PlayerAction::ChooseRetirement {
+                            home: RetirementHome::Modest,
+                        },
+                    ),
+                ];
+                new_state.phase = TurnPhase::ChoosingAction;
+                events.push(GameEvent::ChoiceRequired { choices });
+            }
+
+            TileType::Raise => {
+                let player = &mut new_state.players[player_idx];
+                if let Some(salary) = player.promote() {
+                    events.push(GameEvent::SalaryRaised {
+                        player_id,
+                        salary,
+                        level: player.salary_level,
+                    });
+                }
+            }
+
+            TileType::Speed => {
+                // 止まった時点では何も起こらない（次の手番の spin で2回回す）
+            }
+
+            TileType::Start => {
+                // Startマスに止まっても何もしない (ゲーム開始時に分岐選択)
+                // ただしnextが複数あれば分岐として扱う
+                if tile.next.len() > 1 {
+                    new_state.phase = TurnPhase::ChoosingPath;
+                    let choices = Self::path_choices(&new_state.catalog.board, tile);
+                    events.push(GameEvent::ChoiceRequired { choices });
+                }
+            }
+        }
+
+        // マスのスクリプト（種別ごとの処理の後に実行。エラー時は何もしない）
+        if let Some(TileEvent::Script { source }) = &tile.event {
+            match script::run(source, &new_state, player_idx) {
+                Ok((script_state, script_events)) => {
+                    new_state = script_state;
+                    events.extend(script_events);
+                }
+                Err(e) => eprintln!("tile {} script error: {}", tile.id, e),
+            }
+        }
+
+        (new_state, events)
+    }
+
+    fn resolve_payday(&self, state: &GameState, player_index: usize) -> GameState {
+        let mut new_state = state.clone();
+        // 周回マップでは1周あたりの回数に上限がある
+        if new_state.payday_capped(player_index) {
+            return new_state;
+        }
+        let salary = new_state.payday_amount(player_index);
+        new_state.players[player_index].money += salary;
+        new_state.players[player_index].paydays_this_lap += 1;
+        new_state
+    }
+
+    fn resolve_lawsuit(&self, state: &GameState, target: &PlayerId) -> (GameState, Vec<GameEvent>) {
+        let mut new_state = state.clone();
+        let mut events = Vec::new();
+        let current_id = new_state.players[new_state.current_turn].id.clone();
+        // 請求額は止まった訴訟マスごと
+        let claim = match new_state
+            .catalog
+            .board
+            .tile(new_state.current_player().position)
+            .and_then(|t| t.event.as_ref())
+        {
+            Some(TileEvent::Lawsuit { amount }) => *amount,
+            _ => LAWSUIT_AMOUNT,
+        };
+
+        if let Some(target_idx) = new_state.players.iter().position(|p| &p.id == target) {
+            // 1回の訴訟で勝負が決まらないよう、払える額（借入の上限まで）で打ち切る
+            let lawsuit_amount = new_state.lawsuit_payment(target_idx, claim);
+            events.extend(new_state.borrow_to_cover(target_idx, lawsuit_amount));
+            new_state.players[target_idx].money -= lawsuit_amount;
+            new_state.players[new_state.current_turn].money += lawsuit_amount;
+
+            events.push(GameEvent::MoneyChanged {
+                player_id: target.clone(),
+                amount: -lawsuit_amount,
+                reason: MoneyReason::LawsuitPaid,
+            });
+            events.push(GameEvent::MoneyChanged {
+                player_id: current_id,
+                amount: lawsuit_amount,
+                reason: MoneyReason::LawsuitReceived,
+            });
+        }
+
+        (new_state, events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::ClassicGameEngine;
+    use crate::traits::GameEngine;
+
+    #[test]
+    fn test_gift_skips_retired_players() {
+        let map: MapData = serde_json::from_value(serde_json::json!({
+            "id": "m", "name": "m", "version": "1.0",
+            "start_money": 10000, "loan_unit": 20000, "loan_interest_rate": 1.25,
+            "tiles": [
+                { "id": 0, "type": "Start", "position": { "x": 0, "y": 0 }, "next": [] }
+            ],
+            "careers": [], "houses": []
+        }))
+        .unwrap();
+        let mut state = ClassicGameEngine::new().init(
+            vec![
+                ("p1".to_string(), "Alice".to_string()),
+                ("p2".to_string(), "Bob".to_string()),
+                ("p3".to_string(), "Carol".to_string()),
+            ],
+            &map,
+        );
+        state.players[2].retired = true;
+
+        let (after, events) = ClassicEventResolver::collect_from_others(&state, 0, 5000, GiftKind::Wedding);
+        // ゴール済みの Carol は払わず、受け取るのは Bob の分だけ
+        assert_eq!(after.players[0].money, 15000);
+        assert_eq!(after.players[1].money, 5000);
+        assert_eq!(after.players[2].money, 10000);
+        assert!(matches!(
+            events.last(),
+            Some(GameEvent::MoneyChanged {
+                amount: 5000,
+                reason: MoneyReason::GiftReceived { .. },
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,168 @@
+//! 所持金の台帳
+//!
+//! エンジンが返したイベントから所持金の増減を1件ずつ追記する（削除・書き換えはしない）。
+//! 株の購入（StockPurchased）も買ったときの株価分の支出として記録する。
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::PlayerId;
+
+use super::state::{GameEvent, GameState};
+use super::text::MoneyReason;
+
+/// 台帳の1行
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct LedgerEntry {
+    /// ゲーム内の通し番号（0始まり）
+    pub seq: usize,
+    /// 記録時刻（UNIX ミリ秒）
+    #[ts(type = "number")]
+    pub timestamp_ms: u64,
+    pub turn_number: u32,
+    pub player_id: PlayerId,
+    #[ts(type = "number")]
+    pub delta: i64,
+    pub reason: MoneyReason,
+    /// この増減の後の所持金
+    #[ts(type = "number")]
+    pub balance: i64,
+}
+
+/// 1ゲーム分の台帳
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Ledger {
+    entries: Vec<LedgerEntry>,
+}
+
+impl Ledger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 1操作分のイベントを追記する。after は操作後の状態
+    /// 残高は操作後の所持金から逆算するので、同じ操作で複数回増減しても各行の時点の値になる
+    pub fn record(&mut self, after: &GameState, events: &[GameEvent], timestamp_ms: u64) {
+        let changes: Vec<(&PlayerId, i64, MoneyReason)> = events
+            .iter()
+            .filter_map(|e| match e {
+                GameEvent::MoneyChanged { player_id, amount, reason } => Some((player_id, *amount, reason.clone())),
+                GameEvent::StockPurchased { player_id, price } => {
+                    Some((player_id, -price, MoneyReason::StockPurchase))
+                }
+                _ => None,
+            })
+            .collect();
+
+        for (i, (player_id, delta, reason)) in changes.iter().enumerate() {
+            let Some(player) = after.player_by_id(player_id) else {
+                continue;
+            };
+            let later: i64 = changes[i + 1..]
+                .iter()
+                .filter(|(id, _, _)| id == player_id)
+                .map(|(_, d, _)| d)
+                .sum();
+            self.entries.push(LedgerEntry {
+                seq: self.entries.len(),
+                timestamp_ms,
+                turn_number: after.turn_number,
+                player_id: (*player_id).clone(),
+                delta: *delta,
+                reason: reason.clone(),
+                balance: player.money - later,
+            });
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// offset 件目から最大 limit 件
+    pub fn page(&self, offset: usize, limit: usize) -> &[LedgerEntry] {
+        let start = offset.min(self.entries.len());
+        let end = start.saturating_add(limit).min(self.entries.len());
+        &self.entries[start..end]
+    }
+
+    /// プレイヤーごとの最新の行
+    pub fn last_for(&self, player_id: &str) -> Option<&LedgerEntry> {
+        self.entries.iter().rev().find(|e| e.player_id == player_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::ClassicGameEngine;
+    use crate::state::*;
+    use crate::text::GiftKind;
+    use crate::traits::GameEngine;
+
+    fn two_player_state() -> GameState {
+        let map: MapData = serde_json::from_value(serde_json::json!({
+            "id": "m", "name": "m", "version": "1.0",
+            "start_money": 10000, "loan_unit": 20000, "loan_interest_rate": 1.25,
+            "tiles": [
+                { "id": 0, "type": "Start", "position": { "x": 0, "y": 0 }, "next": [1] },
+                { "id": 1, "type": "Retire", "position": { "x": 1, "y": 0 }, "next": [] }
+            ],
+            "careers": [], "houses": []
+        }))
+        .unwrap();
+        ClassicGameEngine::new().init(
+            vec![("p1".to_string(), "Alice".to_string()), ("p2".to_string(), "Bob".to_string())],
+            &map,
+        )
+    }
+
+    #[test]
+    fn test_record_running_balances() {
+        let mut state = two_player_state();
+        // p1 が +5000 と -2000 と株価 12000 での株購入、p2 が -1000
+        state.players[0].money = 10000 + 5000 - 2000 - 12_000;
+        state.players[1].money = 10000 - 1000;
+        let events = vec![
+            GameEvent::MoneyChanged {
+                player_id: "p1".to_string(),
+                amount: 5000,
+                reason: MoneyReason::Payday,
+            },
+            GameEvent::MoneyChanged {
+                player_id: "p2".to_string(),
+                amount: -1000,
+                reason: MoneyReason::GiftPaid { kind: GiftKind::Wedding },
+            },
+            GameEvent::MoneyChanged {
+                player_id: "p1".to_string(),
+                amount: -2000,
+                reason: MoneyReason::Tax,
+            },
+            GameEvent::StockPurchased {
+                player_id: "p1".to_string(),
+                price: 12_000,
+            },
+        ];
+
+        let mut ledger = Ledger::new();
+        ledger.record(&state, &events, 1_000);
+        let balances: Vec<(&str, i64)> = ledger
+            .page(0, 10)
+            .iter()
+            .map(|e| (e.player_id.as_str(), e.balance))
+            .collect();
+        assert_eq!(
+            balances,
+            vec![("p1", 15000), ("p2", 9000), ("p1", 13000), ("p1", 1000)]
+        );
+        assert_eq!(ledger.page(3, 10)[0].reason, MoneyReason::StockPurchase);
+        assert_eq!(ledger.page(3, 10)[0].seq, 3);
+        assert!(ledger.page(10, 10).is_empty());
+    }
+}
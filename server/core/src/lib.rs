@@ -0,0 +1,39 @@
+//! 9-life のルールエンジン
+//!
+//! 盤面・プレイヤー状態（state）、エンジン（engine / traits）、マスのイベント処理（events）などを持つ。
+//! tokio や axum には依存しないため、サーバー以外（ツール・ボット・WASM）にも組み込める。
+
+pub mod achievements;
+pub mod checked;
+pub mod driver;
+pub mod engine;
+pub mod events;
+pub mod ledger;
+pub mod market;
+pub mod narration;
+pub mod persist;
+pub mod plugin;
+pub mod predict;
+pub mod recap;
+pub mod registry;
+pub mod script;
+pub mod simulate;
+pub mod state;
+pub mod text;
+pub mod trace;
+pub mod traits;
+
+pub use achievements::{Achievement, AchievementAward, AchievementTracker};
+pub use engine::ClassicGameEngine;
+pub use events::{ClassicEventResolver, SpeedRoulette, StandardRoulette};
+pub use ledger::{Ledger, LedgerEntry};
+pub use market::GlobalEventKind;
+pub use recap::{PlayerRecap, RecapTracker};
+pub use registry::{EngineRegistry, DEFAULT_RULES_VARIANT};
+pub use simulate::{simulate, BotStrategy, SimulationReport};
+pub use state::*;
+pub use text::{ChoiceText, GiftKind, MoneyReason, SystemText};
+pub use traits::*;
+
+/// プレイヤーID（サーバーでは認証済みユーザーIDか UUID）
+pub type PlayerId = String;
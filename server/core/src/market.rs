@@ -0,0 +1,127 @@
+//! 全員に影響する出来事（好景気・株価暴落・減税・インフレ）
+//!
+//! GameOptions::global_event_interval ターンごとに、end_turn の後で GameEngine::scheduled_events から1つ起こす。
+//! どれが起こるかは GameState の seed から引くため、同じ入力列からは常に同じ出来事になる。
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use super::state::{GameEvent, GameState};
+
+/// 好景気・株価暴落で株価が上下する金額
+pub const STOCK_SWING: i64 = 5_000;
+
+/// インフレで上がる給料の割合（%）
+pub const INFLATION_PERCENT: u32 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum GlobalEventKind {
+    /// 株価が STOCK_SWING 上がる（持ち主は売ったときに差額を受け取る）
+    StockBoom,
+    /// 株価が STOCK_SWING 下がる（0 未満にはならない）
+    StockCrash,
+    /// 次の出来事まで税金マスの税額が半分になる
+    TaxCut,
+    /// 全員の給料が INFLATION_PERCENT 上がる
+    Inflation,
+}
+
+impl GlobalEventKind {
+    const ALL: [GlobalEventKind; 4] = [
+        GlobalEventKind::StockBoom,
+        GlobalEventKind::StockCrash,
+        GlobalEventKind::TaxCut,
+        GlobalEventKind::Inflation,
+    ];
+
+    pub fn fallback_text(&self) -> &'static str {
+        match self {
+            GlobalEventKind::StockBoom => "株価が急上昇！株価が$5,000アップ",
+            GlobalEventKind::StockCrash => "株価が暴落！株価が$5,000ダウン",
+            GlobalEventKind::TaxCut => "減税！次の出来事まで税金が半額",
+            GlobalEventKind::Inflation => "インフレ！全員の給料が10%アップ",
+        }
+    }
+}
+
+/// 操作後のターン番号で出来事を起こすか（interval ターンごと。1ターン目は除く）
+pub fn is_due(state: &GameState, interval: Option<u32>) -> bool {
+    interval.is_some_and(|n| n > 0 && state.turn_number > 1 && (state.turn_number - 1).is_multiple_of(n))
+}
+
+/// seed から出来事を1つ選んで全員に適用する
+pub fn trigger(state: &GameState) -> (GameState, Vec<GameEvent>) {
+    let mut new_state = state.clone();
+    let kind = GlobalEventKind::ALL[(new_state.next_random() % GlobalEventKind::ALL.len() as u64) as usize];
+    let events = vec![GameEvent::GlobalEvent { kind }];
+
+    // 減税は次の出来事まで
+    new_state.tax_cut = false;
+    match kind {
+        GlobalEventKind::StockBoom | GlobalEventKind::StockCrash => {
+            let sign = if kind == GlobalEventKind::StockBoom { 1 } else { -1 };
+            new_state.stock_price = (new_state.stock_price + sign * STOCK_SWING).max(0);
+        }
+        GlobalEventKind::TaxCut => new_state.tax_cut = true,
+        GlobalEventKind::Inflation => {
+            for player in new_state.players.iter_mut() {
+                player.salary += player.salary * INFLATION_PERCENT / 100;
+            }
+        }
+    }
+    (new_state, events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::ClassicGameEngine;
+    use crate::state::{MapData, Stock};
+    use crate::traits::GameEngine;
+
+    #[test]
+    fn test_schedule_and_effects() {
+        let map: MapData = serde_json::from_value(serde_json::json!({
+            "id": "m", "name": "m", "version": "1.0",
+            "start_money": 10000, "loan_unit": 20000, "loan_interest_rate": 1.25,
+            "tiles": [
+                { "id": 0, "type": "Start", "position": { "x": 0, "y": 0 }, "next": [] }
+            ],
+            "careers": [], "houses": []
+        }))
+        .unwrap();
+        let mut state = ClassicGameEngine::new().init(
+            vec![("p1".to_string(), "Alice".to_string()), ("p2".to_string(), "Bob".to_string())],
+            &map,
+        );
+        assert!(!is_due(&state, Some(3)));
+        state.turn_number = 4;
+        assert!(is_due(&state, Some(3)));
+        assert!(!is_due(&state, None));
+
+        state.players[0].salary = 20000;
+        state.players[0].stocks.push(Stock {
+            id: "s".to_string(),
+            name: "s".to_string(),
+        });
+        for seed in 1..64 {
+            state.rng_seed = seed;
+            let (after, events) = trigger(&state);
+            let Some(GameEvent::GlobalEvent { kind }) = events.first() else {
+                panic!("expected GlobalEvent: {:?}", events);
+            };
+            match kind {
+                // 株価が動くだけで、所持金は売るまで変わらない
+                GlobalEventKind::StockBoom => assert_eq!(after.stock_price, 15000),
+                GlobalEventKind::StockCrash => assert_eq!(after.stock_price, 5000),
+                GlobalEventKind::TaxCut => assert!(after.tax_cut),
+                GlobalEventKind::Inflation => assert_eq!(after.players[0].salary, 22000),
+            }
+            assert_eq!(after.players[0].money, 10000);
+            assert_eq!(after.players[1].money, 10000);
+            assert_eq!(events.len(), 1);
+        }
+    }
+}
@@ -0,0 +1,174 @@
+//! ゲームの出来事をチャット欄向けの文言にする
+//!
+//! GameEvent を表示しないクライアントでも、チャット欄を見れば主な出来事（結婚・出産・訴訟・借入と返済・昇給・ゴール・全員向けの出来事）が分かるようにする。
+
+use super::state::{GameEvent, GameState};
+use super::text::{MoneyReason, SystemText};
+
+/// 1操作分のイベントから SystemText を作る。after は操作後の状態（プレイヤー名の解決に使う）
+pub fn narrate(after: &GameState, events: &[GameEvent]) -> Vec<SystemText> {
+    let name = |id: &str| {
+        after
+            .player_by_id(id)
+            .map(|p| p.name.clone())
+            .unwrap_or_else(|| id.to_string())
+    };
+    // 訴訟は支払った側の MoneyChanged と組にする
+    let defendant = events.iter().find_map(|e| match e {
+        GameEvent::MoneyChanged {
+            player_id,
+            reason: MoneyReason::LawsuitPaid,
+            ..
+        } => Some(player_id.as_str()),
+        _ => None,
+    });
+
+    events
+        .iter()
+        .filter_map(|e| match e {
+            GameEvent::Married { player_id } => Some(SystemText::Married {
+                player_name: name(player_id),
+            }),
+            GameEvent::BabyBorn {
+                player_id,
+                children,
+                count,
+            } => Some(SystemText::BabyBorn {
+                player_name: name(player_id),
+                children: *children,
+                count: *count,
+            }),
+            GameEvent::MoneyChanged {
+                player_id,
+                amount,
+                reason: MoneyReason::LawsuitReceived,
+            } => Some(SystemText::Lawsuit {
+                plaintiff: name(player_id),
+                defendant: defendant.map(name).unwrap_or_default(),
+                amount: *amount,
+            }),
+            GameEvent::MoneyChanged {
+                player_id,
+                amount,
+                reason: MoneyReason::DebtRepayment,
+            } => Some(SystemText::LoanRepaid {
+                player_name: name(player_id),
+                amount: -*amount,
+            }),
+            GameEvent::MoneyChanged {
+                player_id,
+                amount,
+                reason: MoneyReason::Loan,
+            } => Some(SystemText::LoanTaken {
+                player_name: name(player_id),
+                amount: *amount,
+            }),
+            GameEvent::PlayerRetired { player_id } => Some(SystemText::Retired {
+                player_name: name(player_id),
+            }),
+            GameEvent::MoneyChanged {
+                player_id,
+                amount,
+                reason: MoneyReason::LandingBonus,
+            } => Some(SystemText::LandingBonus {
+                player_name: name(player_id),
+                amount: *amount,
+            }),
+            GameEvent::GlobalEvent { kind } => Some(SystemText::GlobalEvent { kind: *kind }),
+            GameEvent::MoneyChanged {
+                player_id,
+                amount,
+                reason: MoneyReason::StockSale,
+            } => Some(SystemText::StockSold {
+                player_name: name(player_id),
+                amount: *amount,
+            }),
+            GameEvent::MoneyChanged {
+                player_id,
+                amount,
+                reason: MoneyReason::Mortgage { house_name },
+            } => Some(SystemText::HouseMortgaged {
+                player_name: name(player_id),
+                house_name: house_name.clone(),
+                amount: *amount,
+            }),
+            GameEvent::MoneyChanged {
+                player_id,
+                amount,
+                reason: MoneyReason::MortgageRedemption { house_name },
+            } => Some(SystemText::HouseRedeemed {
+                player_name: name(player_id),
+                house_name: house_name.clone(),
+                amount: -*amount,
+            }),
+            GameEvent::SalaryRaised {
+                player_id, salary, ..
+            } => Some(SystemText::SalaryRaised {
+                player_name: name(player_id),
+                salary: *salary,
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::ClassicGameEngine;
+    use crate::state::MapData;
+    use crate::traits::GameEngine;
+
+    #[test]
+    fn test_narrate_lawsuit_and_marriage() {
+        let map: MapData = serde_json::from_value(serde_json::json!({
+            "id": "m", "name": "m", "version": "1.0",
+            "start_money": 10000, "loan_unit": 20000, "loan_interest_rate": 1.25,
+            "tiles": [
+                { "id": 0, "type": "Start", "position": { "x": 0, "y": 0 }, "next": [] }
+            ],
+            "careers": [], "houses": []
+        }))
+        .unwrap();
+        let state = ClassicGameEngine::new().init(
+            vec![("p1".to_string(), "Alice".to_string()), ("p2".to_string(), "Bob".to_string())],
+            &map,
+        );
+        let events = vec![
+            GameEvent::Married {
+                player_id: "p1".to_string(),
+            },
+            GameEvent::MoneyChanged {
+                player_id: "p2".to_string(),
+                amount: -5000,
+                reason: MoneyReason::LawsuitPaid,
+            },
+            GameEvent::MoneyChanged {
+                player_id: "p1".to_string(),
+                amount: 5000,
+                reason: MoneyReason::LawsuitReceived,
+            },
+            GameEvent::MoneyChanged {
+                player_id: "p1".to_string(),
+                amount: 1000,
+                reason: MoneyReason::Payday,
+            },
+        ];
+
+        let texts = narrate(&state, &events);
+        assert_eq!(
+            texts,
+            vec![
+                SystemText::Married {
+                    player_name: "Alice".to_string()
+                },
+                SystemText::Lawsuit {
+                    plaintiff: "Alice".to_string(),
+                    defendant: "Bob".to_string(),
+                    amount: 5000
+                },
+            ]
+        );
+        assert_eq!(texts[1].fallback_text(), "AliceがBobを訴えて$5000を受け取りました");
+    }
+}
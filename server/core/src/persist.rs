@@ -0,0 +1,131 @@
+//! 保存したゲーム状態（スナップショット・リプレイ）の版管理
+//!
+//! 保存するときは GameState の JSON に version を付け、読み込むときは古い版から1版ずつ移行してから GameState に戻す。
+//! GameState のフィールドを変えるときは STATE_VERSION を上げ、1つ前の版からの移行関数を MIGRATIONS の末尾に足す。
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value};
+
+use super::state::{GameState, STOCK_PRICE};
+
+/// 現在の保存形式の版
+pub const STATE_VERSION: u32 = 2;
+
+/// version の無いデータの版（版管理を始める前に保存されたもの）
+const UNVERSIONED: u32 = 1;
+
+/// MIGRATIONS[i] は版 i + 1 から版 i + 2 への移行
+const MIGRATIONS: [fn(&mut Map<String, Value>); (STATE_VERSION - 1) as usize] = [v1_to_v2];
+
+/// 現在の版の version を付けた JSON にする
+pub fn to_value(state: &GameState) -> Result<Value, String> {
+    let mut value = serde_json::to_value(state).map_err(|e| e.to_string())?;
+    let Value::Object(fields) = &mut value else {
+        return Err("game state must be a JSON object".to_string());
+    };
+    fields.insert("version".to_string(), STATE_VERSION.into());
+    Ok(value)
+}
+
+/// 保存された JSON を現在の版まで移行して読み込む
+pub fn from_value(value: Value) -> Result<GameState, String> {
+    let Value::Object(mut fields) = value else {
+        return Err("game state must be a JSON object".to_string());
+    };
+    let version = match fields.remove("version") {
+        None => UNVERSIONED,
+        Some(v) => v
+            .as_u64()
+            .and_then(|n| u32::try_from(n).ok())
+            .ok_or_else(|| format!("invalid game state version: {}", v))?,
+    };
+    if version == 0 || version > STATE_VERSION {
+        return Err(format!(
+            "unsupported game state version: {} (supported: 1..={})",
+            version, STATE_VERSION
+        ));
+    }
+    for migrate in &MIGRATIONS[(version - 1) as usize..] {
+        migrate(&mut fields);
+    }
+    serde_json::from_value(Value::Object(fields)).map_err(|e| e.to_string())
+}
+
+/// `#[serde(with = "persist")]` で GameState のフィールドを版付きで読み書きする
+pub fn serialize<S: Serializer>(state: &GameState, serializer: S) -> Result<S::Ok, S::Error> {
+    to_value(state)
+        .map_err(serde::ser::Error::custom)?
+        .serialize(serializer)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<GameState, D::Error> {
+    from_value(Value::deserialize(deserializer)?).map_err(serde::de::Error::custom)
+}
+
+/// v1 → v2: 着順（arrivals）・株価（stock_price）・開始時の seed が無い
+/// 着順はゴール済みのプレイヤーを手番順に並べて補う（v1 には実際の着順が残っていない）。seed は不明なので 0
+fn v1_to_v2(fields: &mut Map<String, Value>) {
+    if !fields.contains_key("arrivals") {
+        let arrivals: Vec<Value> = fields
+            .get("players")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter(|p| p.get("retired").and_then(Value::as_bool).unwrap_or(false))
+            .filter_map(|p| p.get("id").cloned())
+            .collect();
+        fields.insert("arrivals".to_string(), Value::Array(arrivals));
+    }
+    fields.entry("stock_price").or_insert(STOCK_PRICE.into());
+    fields.entry("seed").or_insert(0.into());
+    fields.entry("tax_cut").or_insert(false.into());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::ClassicGameEngine;
+    use crate::state::MapData;
+    use crate::traits::GameEngine;
+
+    #[test]
+    fn test_round_trip_and_migrate_v1() {
+        let map: MapData = serde_json::from_value(serde_json::json!({
+            "id": "m", "name": "m", "version": "1.0",
+            "start_money": 10000, "loan_unit": 20000, "loan_interest_rate": 1.25,
+            "tiles": [
+                { "id": 0, "type": "Start", "position": { "x": 0, "y": 0 }, "next": [] }
+            ],
+            "careers": [], "houses": []
+        }))
+        .unwrap();
+        let mut state = ClassicGameEngine::new().init(
+            vec![("p1".to_string(), "Alice".to_string()), ("p2".to_string(), "Bob".to_string())],
+            &map,
+        );
+        state.players[1].retired = true;
+        state.arrivals = vec!["p2".to_string()];
+        state.stock_price = 15000;
+
+        let saved = to_value(&state).unwrap();
+        assert_eq!(saved["version"], STATE_VERSION);
+        let loaded = from_value(saved.clone()).unwrap();
+        assert_eq!(loaded.arrivals, state.arrivals);
+        assert_eq!(loaded.stock_price, 15000);
+        assert_eq!(loaded.seed, state.seed);
+
+        // 版管理を始める前の形式
+        let mut v1 = saved;
+        let fields = v1.as_object_mut().unwrap();
+        for key in ["version", "arrivals", "stock_price", "seed", "tax_cut"] {
+            fields.remove(key);
+        }
+        let migrated = from_value(v1.clone()).unwrap();
+        assert_eq!(migrated.arrivals, vec!["p2".to_string()]);
+        assert_eq!(migrated.stock_price, STOCK_PRICE);
+        assert_eq!(migrated.seed, 0);
+
+        v1["version"] = (STATE_VERSION + 1).into();
+        assert!(from_value(v1).is_err());
+    }
+}
@@ -0,0 +1,210 @@
+//! 外部プラグイン（WASM モジュール）による EventResolver
+//!
+//! ## ABI
+//!
+//! プラグインは以下をエクスポートする:
+//!
+//! - `memory`: 線形メモリ
+//! - `alloc(len: i32) -> i32`: ホストが入力を書き込む領域を確保する
+//! - `resolve_tile(ptr: i32, len: i32) -> i64`: 入力 JSON（[`TileRequest`]）を受け取り、
+//!   出力 JSON（[`TileResponse`]）の位置を `(ptr << 32) | len` で返す
+//!
+//! 入出力はすべて UTF-8 の JSON。`handled: false` を返したマスは組み込みの
+//! ClassicEventResolver で処理する。給料日・訴訟は常に組み込み処理を使う。
+//!
+//! モジュールのロードとメモリの受け渡しは [`PluginModule`] の実装（WASM ランタイム）が担う。
+//! ランタイムが未導入のため、まだどのルールにも登録していない（docs/plans/2026-10-15-wasm-plugins.md）。
+
+use serde::{Deserialize, Serialize};
+
+use crate::PlayerId;
+
+use super::events::ClassicEventResolver;
+use super::state::{GameEvent, GameState, Tile};
+use super::traits::EventResolver;
+
+/// ABI のバージョン（リクエストに含めて渡し、プラグイン側で互換性を確認する）
+/// 2: GameEvent::MoneyChanged の reason が構造化された理由コードになった
+pub const PLUGIN_ABI_VERSION: u32 = 2;
+
+/// `resolve_tile` への入力
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TileRequest {
+    pub abi_version: u32,
+    pub state: GameState,
+    pub tile: Tile,
+}
+
+/// `resolve_tile` の出力
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TileResponse {
+    /// false ならホスト側の組み込み処理を使う
+    pub handled: bool,
+    #[serde(default)]
+    pub state: Option<GameState>,
+    #[serde(default)]
+    pub events: Vec<GameEvent>,
+}
+
+/// ロード済みのプラグインモジュール
+/// `call` はエクスポート関数に JSON を渡し、返された JSON を読み出す
+pub trait PluginModule: Send + Sync {
+    fn call(&self, export: &str, input: &[u8]) -> Result<Vec<u8>, String>;
+}
+
+/// プラグインに resolve_tile を委譲する EventResolver
+pub struct PluginEventResolver {
+    module: Box<dyn PluginModule>,
+    fallback: ClassicEventResolver,
+}
+
+impl PluginEventResolver {
+    pub fn new(module: Box<dyn PluginModule>) -> Self {
+        Self {
+            module,
+            fallback: ClassicEventResolver,
+        }
+    }
+
+    fn call_resolve_tile(&self, state: &GameState, tile: &Tile) -> Result<TileResponse, String> {
+        let request = TileRequest {
+            abi_version: PLUGIN_ABI_VERSION,
+            state: state.clone(),
+            tile: tile.clone(),
+        };
+        let input = serde_json::to_vec(&request).map_err(|e| e.to_string())?;
+        let output = self.module.call("resolve_tile", &input)?;
+        let response: TileResponse =
+            serde_json::from_slice(&output).map_err(|e| format!("invalid plugin response: {}", e))?;
+
+        // プレイヤー構成を変えるような応答は受け付けない
+        if let Some(new_state) = &response.state {
+            let same_players = new_state.players.len() == state.players.len()
+                && new_state
+                    .players
+                    .iter()
+                    .zip(&state.players)
+                    .all(|(a, b)| a.id == b.id);
+            if !same_players {
+                return Err("plugin changed the player list".to_string());
+            }
+        }
+        Ok(response)
+    }
+}
+
+impl EventResolver for PluginEventResolver {
+    fn resolve_tile(&self, state: &GameState, tile: &Tile) -> (GameState, Vec<GameEvent>) {
+        match self.call_resolve_tile(state, tile) {
+            Ok(TileResponse {
+                handled: true,
+                state: new_state,
+                events,
+            }) => (new_state.unwrap_or_else(|| state.clone()), events),
+            Ok(_) => self.fallback.resolve_tile(state, tile),
+            Err(e) => {
+                eprintln!("plugin resolve_tile failed on tile {}: {}", tile.id, e);
+                self.fallback.resolve_tile(state, tile)
+            }
+        }
+    }
+
+    fn resolve_payday(&self, state: &GameState, player_index: usize) -> GameState {
+        self.fallback.resolve_payday(state, player_index)
+    }
+
+    fn resolve_lawsuit(&self, state: &GameState, target: &PlayerId) -> (GameState, Vec<GameEvent>) {
+        self.fallback.resolve_lawsuit(state, target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{Board, GameCatalog, PlayerState, Position, TileType, TurnPhase, STOCK_PRICE};
+    use std::sync::Arc;
+
+    /// Action マスなら全員に $1 を配るテスト用プラグイン
+    struct MockModule;
+
+    impl PluginModule for MockModule {
+        fn call(&self, export: &str, input: &[u8]) -> Result<Vec<u8>, String> {
+            assert_eq!(export, "resolve_tile");
+            let request: TileRequest = serde_json::from_slice(input).unwrap();
+            let response = if request.tile.tile_type == TileType::Action {
+                let mut state = request.state;
+                for p in &mut state.players {
+                    p.money += 1;
+                }
+                TileResponse {
+                    handled: true,
+                    state: Some(state),
+                    events: vec![],
+                }
+            } else {
+                TileResponse {
+                    handled: false,
+                    state: None,
+                    events: vec![],
+                }
+            };
+            Ok(serde_json::to_vec(&response).unwrap())
+        }
+    }
+
+    fn sample_state() -> GameState {
+        let mut player = PlayerState::new("p1".to_string(), "Alice".to_string(), 100);
+        player.salary = 10;
+        GameState {
+            players: vec![player, PlayerState::new("p2".to_string(), "Bob".to_string(), 100)],
+            catalog: Arc::new(GameCatalog {
+                board: Board { tiles: vec![] },
+                careers: vec![],
+                houses_for_sale: vec![],
+            }),
+            current_turn: 0,
+            phase: TurnPhase::Moving,
+            rng_seed: 42,
+            loan_unit: 20000,
+            loan_interest_rate: 1.25,
+            payout_multiplier: 1,
+            turn_number: 1,
+            max_turns: None,
+            lap_rules: None,
+            pending_steps: 0,
+            chosen_path: None,
+            pending_choice: None,
+            game_started_at: None,
+            tax_cut: false,
+            arrivals: Vec::new(),
+            stock_price: STOCK_PRICE,
+            seed: 42,
+        }
+    }
+
+    fn tile(tile_type: TileType) -> Tile {
+        Tile {
+            id: 0,
+            tile_type,
+            position: Position { x: 0.0, y: 0.0 },
+            next: vec![],
+            event: None,
+            labels: None,
+            landing_bonus: None,
+        }
+    }
+
+    #[test]
+    fn test_plugin_handles_or_falls_back() {
+        let resolver = PluginEventResolver::new(Box::new(MockModule));
+        let state = sample_state();
+
+        let (handled, _) = resolver.resolve_tile(&state, &tile(TileType::Action));
+        assert_eq!(handled.players[0].money, 101);
+        assert_eq!(handled.players[1].money, 101);
+
+        // 未処理のマスは組み込みの給料日処理
+        let (fallback, _) = resolver.resolve_tile(&state, &tile(TileType::Payday));
+        assert_eq!(fallback.players[0].money, 110);
+    }
+}
@@ -0,0 +1,203 @@
+//! クライアント側予測用のファサード
+//!
+//! Web クライアントが移動やお金の増減を先行して表示できるよう、エンジンを
+//! init / spin / advance / resolve_action の4操作と、権威ある GameSync への巻き戻し（reconcile）に絞って公開する。
+//! 入出力は JSON 文字列のみなので、WASM 向けにはこの関数をそのまま `#[wasm_bindgen]` で包めばよい
+//! （未着手の部分は docs/plans/2026-10-15-wasm-prediction.md）。
+
+use serde::{Deserialize, Serialize};
+
+use crate::PlayerId;
+
+use super::registry::EngineRegistry;
+use super::state::*;
+use super::traits::GameEngine;
+
+/// 予測中の状態。操作ごとに JSON でクライアントとやり取りする
+pub struct Predictor {
+    engine: Box<dyn GameEngine>,
+    state: GameState,
+}
+
+/// 操作結果（盤面などの不変部分は返さず、表示に必要なプレイヤー状態とイベントのみ）
+#[derive(Debug, Serialize)]
+struct Prediction<'a> {
+    players: &'a [PlayerState],
+    current_turn: usize,
+    phase: TurnPhase,
+    events: Vec<GameEvent>,
+}
+
+/// GameSync のうち巻き戻しに使う項目
+#[derive(Debug, Deserialize)]
+struct SyncPayload {
+    players: Vec<PlayerState>,
+    current_turn: usize,
+    phase: TurnPhase,
+    turn_number: u32,
+    #[serde(default)]
+    pending_choice: Option<PendingChoice>,
+    stock_price: i64,
+    arrivals: Vec<PlayerId>,
+    tax_cut: bool,
+}
+
+impl Predictor {
+    /// マップ JSON と GameStarted の players から予測用の状態を作る
+    pub fn init(rules_variant: &str, map_json: &str, players_json: &str) -> Result<Self, String> {
+        let map: MapData = serde_json::from_str(map_json).map_err(|e| format!("invalid map: {}", e))?;
+        let players: Vec<PlayerState> =
+            serde_json::from_str(players_json).map_err(|e| format!("invalid players: {}", e))?;
+        let engine = EngineRegistry::with_builtin().create(rules_variant, GameOptions::default())?;
+        let ids = players.iter().map(|p| (p.id.clone(), p.name.clone())).collect();
+        let mut state = engine.init(ids, &map);
+        // 職業や所持金はサーバーで決まっているので、開始時点の状態で置き換える
+        state.players = players;
+        Ok(Self { engine, state })
+    }
+
+    /// ルーレットを回したことにする。値はサーバーの RouletteResult を使うので、ここでは移動待ちにするだけ
+    pub fn spin(&mut self) -> Result<String, String> {
+        if self.state.phase != TurnPhase::WaitingForSpin {
+            return Err(format!("cannot spin in {:?}", self.state.phase));
+        }
+        let (state, _) = self.engine.spin(&self.state);
+        self.state = state;
+        self.prediction(Vec::new())
+    }
+
+    /// RouletteResult の出目で移動を予測する
+    pub fn advance(&mut self, steps: u32) -> Result<String, String> {
+        if self.state.phase != TurnPhase::Moving {
+            return Err(format!("cannot advance in {:?}", self.state.phase));
+        }
+        let (mut state, events) = self.engine.advance(&self.state, steps);
+        state.update_pending_choice(&events);
+        self.state = state;
+        self.prediction(events)
+    }
+
+    /// ChoiceRequired の選択肢IDで行動の結果を予測する
+    pub fn resolve_action(&mut self, action_id: &str) -> Result<String, String> {
+        if self.state.phase != TurnPhase::ChoosingAction {
+            return Err(format!("cannot resolve action in {:?}", self.state.phase));
+        }
+        let action = super::driver::parse_action(action_id, &self.state);
+        let (mut state, events) = self.engine.resolve_action(&self.state, action);
+        state.update_pending_choice(&events);
+        self.state = state;
+        self.prediction(events)
+    }
+
+    /// 権威ある GameSync で予測を上書きする。予測が一致していたかを返す
+    pub fn reconcile(&mut self, sync_json: &str) -> Result<bool, String> {
+        let sync: SyncPayload =
+            serde_json::from_str(sync_json).map_err(|e| format!("invalid GameSync: {}", e))?;
+        let matched = sync.current_turn == self.state.current_turn
+            && sync.phase == self.state.phase
+            && sync.stock_price == self.state.stock_price
+            && sync.arrivals == self.state.arrivals
+            && sync.tax_cut == self.state.tax_cut
+            && serde_json::to_value(&sync.players).ok() == serde_json::to_value(&self.state.players).ok();
+        self.state.players = sync.players;
+        self.state.current_turn = sync.current_turn;
+        self.state.phase = sync.phase;
+        self.state.turn_number = sync.turn_number;
+        self.state.pending_choice = sync.pending_choice;
+        self.state.stock_price = sync.stock_price;
+        self.state.arrivals = sync.arrivals;
+        self.state.tax_cut = sync.tax_cut;
+        self.state.pending_steps = 0;
+        self.state.chosen_path = None;
+        Ok(matched)
+    }
+
+    pub fn state(&self) -> &GameState {
+        &self.state
+    }
+
+    fn prediction(&self, events: Vec<GameEvent>) -> Result<String, String> {
+        serde_json::to_string(&Prediction {
+            players: &self.state.players,
+            current_turn: self.state.current_turn,
+            phase: self.state.phase,
+            events,
+        })
+        .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::DEFAULT_RULES_VARIANT;
+
+    const MAP_JSON: &str = r#"{
+        "id": "line", "name": "Line", "version": "1.0",
+        "start_money": 10000, "loan_unit": 20000, "loan_interest_rate": 1.25,
+        "tiles": [
+            { "id": 0, "type": "Start", "position": { "x": 0, "y": 0 }, "next": [1] },
+            { "id": 1, "type": "Action", "position": { "x": 1, "y": 0 }, "next": [2],
+              "event": { "type": "money", "amount": 5000, "text": "bonus" } },
+            { "id": 2, "type": "Action", "position": { "x": 2, "y": 0 }, "next": [3],
+              "event": { "type": "money", "amount": -3000, "text": "fine" } },
+            { "id": 3, "type": "Action", "position": { "x": 3, "y": 0 }, "next": [4],
+              "event": { "type": "money", "amount": 1000, "text": "tip" } },
+            { "id": 4, "type": "Retire", "position": { "x": 4, "y": 0 }, "next": [] }
+        ],
+        "careers": [], "houses": []
+    }"#;
+
+    #[test]
+    fn test_predict_and_reconcile() {
+        let map: MapData = serde_json::from_str(MAP_JSON).unwrap();
+        let engine = EngineRegistry::with_builtin()
+            .create(DEFAULT_RULES_VARIANT, GameOptions::default())
+            .unwrap();
+        let server = engine.init(
+            vec![("p1".to_string(), "Alice".to_string()), ("p2".to_string(), "Bob".to_string())],
+            &map,
+        );
+        let players_json = serde_json::to_string(&server.players).unwrap();
+        let mut predictor = Predictor::init(DEFAULT_RULES_VARIANT, MAP_JSON, &players_json).unwrap();
+
+        // サーバーと同じ出目なら同じ結果になる
+        let (spun, result) = engine.spin(&server);
+        let (authoritative, _) = engine.advance(&spun, result.value);
+        predictor.spin().unwrap();
+        let prediction: serde_json::Value =
+            serde_json::from_str(&predictor.advance(result.value).unwrap()).unwrap();
+        assert_eq!(
+            prediction["players"][0]["position"],
+            serde_json::json!(authoritative.players[0].position)
+        );
+        assert!(predictor.advance(1).is_err());
+
+        let sync = serde_json::json!({
+            "players": authoritative.players,
+            "current_turn": authoritative.current_turn,
+            "phase": authoritative.phase,
+            "turn_number": authoritative.turn_number,
+            "stock_price": authoritative.stock_price,
+            "arrivals": authoritative.arrivals,
+            "tax_cut": authoritative.tax_cut,
+        });
+        assert!(predictor.reconcile(&sync.to_string()).unwrap());
+
+        // ずれていた場合もサーバーの値で上書きされる
+        let mut sync = sync;
+        sync["players"][0]["money"] = serde_json::json!(1);
+        assert!(!predictor.reconcile(&sync.to_string()).unwrap());
+        assert_eq!(predictor.state().players[0].money, 1);
+
+        // 全員に影響する出来事・ゴールの着順もサーバーの値に合わせる
+        sync["stock_price"] = serde_json::json!(15000);
+        sync["arrivals"] = serde_json::json!(["p2"]);
+        sync["tax_cut"] = serde_json::json!(true);
+        assert!(!predictor.reconcile(&sync.to_string()).unwrap());
+        assert_eq!(predictor.state().stock_price, 15000);
+        assert_eq!(predictor.state().arrivals, vec!["p2".to_string()]);
+        assert!(predictor.state().tax_cut);
+        assert!(predictor.reconcile(&sync.to_string()).unwrap());
+    }
+}
@@ -0,0 +1,180 @@
+//! ゲーム終了時の振り返り（プレイヤーごとの人生の記録）
+//!
+//! 操作ごとのイベント列（RecapTracker::observe）から集め、ゲーム終了時に recaps で最終状態と合わせて確定する。
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use super::state::{GameEvent, GameState};
+use super::text::MoneyReason;
+use super::PlayerId;
+
+/// 1人分の振り返り
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct PlayerRecap {
+    pub player_id: PlayerId,
+    pub player_name: String,
+    /// 就いた職業の名前（就いた順）
+    pub careers: Vec<String>,
+    /// 結婚したターン番号（未婚なら null）
+    pub married_on_turn: Option<u32>,
+    pub children: u8,
+    /// 買った家の名前（買った順）
+    pub houses: Vec<String>,
+    /// 訴えて受け取った回数・訴えられて支払った回数
+    pub lawsuits_won: u32,
+    pub lawsuits_lost: u32,
+    #[ts(type = "number")]
+    pub final_money: i64,
+    pub retired: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Story {
+    careers: Vec<String>,
+    married_on_turn: Option<u32>,
+    houses: Vec<String>,
+    lawsuits_won: u32,
+    lawsuits_lost: u32,
+}
+
+/// 振り返りの集計
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RecapTracker {
+    stories: HashMap<PlayerId, Story>,
+}
+
+impl RecapTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 1操作分のイベントを集計する。after は操作後の状態
+    pub fn observe(&mut self, after: &GameState, events: &[GameEvent]) {
+        for event in events {
+            match event {
+                GameEvent::CareerAssigned { player_id, career } => {
+                    self.story(player_id).careers.push(career.name.clone());
+                }
+                GameEvent::Married { player_id } => {
+                    self.story(player_id)
+                        .married_on_turn
+                        .get_or_insert(after.turn_number);
+                }
+                GameEvent::HousePurchased { player_id, house } => {
+                    self.story(player_id).houses.push(house.name.clone());
+                }
+                GameEvent::MoneyChanged {
+                    player_id,
+                    reason: MoneyReason::LawsuitReceived,
+                    ..
+                } => self.story(player_id).lawsuits_won += 1,
+                GameEvent::MoneyChanged {
+                    player_id,
+                    reason: MoneyReason::LawsuitPaid,
+                    ..
+                } => self.story(player_id).lawsuits_lost += 1,
+                _ => {}
+            }
+        }
+    }
+
+    fn story(&mut self, player_id: &str) -> &mut Story {
+        self.stories.entry(player_id.to_string()).or_default()
+    }
+
+    /// ゲーム終了時の振り返り（プレイヤー順）
+    pub fn recaps(&self, final_state: &GameState) -> Vec<PlayerRecap> {
+        final_state
+            .players
+            .iter()
+            .map(|player| {
+                let mut story = self.stories.get(&player.id).cloned().unwrap_or_default();
+                // 短縮ゲームなど、開始時から就いている職業はイベントが無い
+                if story.careers.is_empty() {
+                    story.careers.extend(player.career.as_ref().map(|c| c.name.clone()));
+                }
+                PlayerRecap {
+                    player_id: player.id.clone(),
+                    player_name: player.name.clone(),
+                    careers: story.careers,
+                    married_on_turn: story.married_on_turn,
+                    children: player.children,
+                    houses: story.houses,
+                    lawsuits_won: story.lawsuits_won,
+                    lawsuits_lost: story.lawsuits_lost,
+                    final_money: player.money,
+                    retired: player.retired,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::ClassicGameEngine;
+    use crate::state::{Career, MapData};
+    use crate::traits::GameEngine;
+
+    #[test]
+    fn test_recap_collects_life_events() {
+        let map: MapData = serde_json::from_value(serde_json::json!({
+            "id": "m", "name": "m", "version": "1.0",
+            "start_money": 10000, "loan_unit": 20000, "loan_interest_rate": 1.25,
+            "tiles": [
+                { "id": 0, "type": "Start", "position": { "x": 0, "y": 0 }, "next": [] }
+            ],
+            "careers": [], "houses": []
+        }))
+        .unwrap();
+        let mut state = ClassicGameEngine::new().init(
+            vec![("p1".to_string(), "Alice".to_string()), ("p2".to_string(), "Bob".to_string())],
+            &map,
+        );
+        let mut tracker = RecapTracker::new();
+
+        state.turn_number = 3;
+        tracker.observe(
+            &state,
+            &[
+                GameEvent::CareerAssigned {
+                    player_id: "p1".to_string(),
+                    career: Career {
+                        id: "doctor".to_string(),
+                        name: "医者".to_string(),
+                        salary: 20000,
+                        pool: "college".to_string(),
+                        abilities: Vec::new(),
+                        salary_ladder: Vec::new(),
+                    },
+                },
+                GameEvent::Married {
+                    player_id: "p1".to_string(),
+                },
+                GameEvent::MoneyChanged {
+                    player_id: "p1".to_string(),
+                    amount: 5000,
+                    reason: MoneyReason::LawsuitReceived,
+                },
+                GameEvent::MoneyChanged {
+                    player_id: "p2".to_string(),
+                    amount: -5000,
+                    reason: MoneyReason::LawsuitPaid,
+                },
+            ],
+        );
+
+        let recaps = tracker.recaps(&state);
+        assert_eq!(recaps[0].careers, vec!["医者".to_string()]);
+        assert_eq!(recaps[0].married_on_turn, Some(3));
+        assert_eq!(recaps[0].lawsuits_won, 1);
+        assert_eq!(recaps[1].lawsuits_lost, 1);
+        assert_eq!(recaps[1].married_on_turn, None);
+        assert_eq!(recaps[1].final_money, state.players[1].money);
+    }
+}
@@ -0,0 +1,90 @@
+use std::collections::BTreeMap;
+
+#[cfg(feature = "checked-engine")]
+use super::checked::CheckedEngine;
+use super::engine::ClassicGameEngine;
+use super::events::{ClassicEventResolver, SpeedRoulette};
+use super::state::GameOptions;
+use super::traits::GameEngine;
+
+/// ルール名の既定値
+pub const DEFAULT_RULES_VARIANT: &str = "classic";
+
+/// ハウスルールからエンジンを生成する関数
+pub type EngineFactory = fn(GameOptions) -> Box<dyn GameEngine>;
+
+/// ルール名 -> エンジン生成関数の対応表
+/// 部屋作成時の rules_variant でエンジンを選択する
+pub struct EngineRegistry {
+    factories: BTreeMap<String, EngineFactory>,
+}
+
+impl EngineRegistry {
+    /// 空のレジストリ
+    pub fn new() -> Self {
+        Self {
+            factories: BTreeMap::new(),
+        }
+    }
+
+    /// 組み込みのルール（classic / speed）を登録済みのレジストリ
+    pub fn with_builtin() -> Self {
+        let mut registry = Self::new();
+        registry.register(DEFAULT_RULES_VARIANT, |options| {
+            Box::new(ClassicGameEngine::new().with_options(options))
+        });
+        registry.register("speed", |options| {
+            Box::new(
+                ClassicGameEngine::with_components(
+                    Box::new(ClassicEventResolver),
+                    Box::new(SpeedRoulette),
+                )
+                .with_options(options),
+            )
+        });
+        registry
+    }
+
+    pub fn register(&mut self, name: &str, factory: EngineFactory) {
+        self.factories.insert(name.to_string(), factory);
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.factories.contains_key(name)
+    }
+
+    /// 登録済みのルール名（名前順）
+    pub fn names(&self) -> Vec<&str> {
+        self.factories.keys().map(String::as_str).collect()
+    }
+
+    pub fn create(&self, name: &str, options: GameOptions) -> Result<Box<dyn GameEngine>, String> {
+        let factory = self
+            .factories
+            .get(name)
+            .ok_or_else(|| format!("unknown rules variant: {}", name))?;
+        #[cfg(feature = "checked-engine")]
+        return Ok(Box::new(CheckedEngine::new(factory(options))));
+        #[cfg(not(feature = "checked-engine"))]
+        Ok(factory(options))
+    }
+}
+
+impl Default for EngineRegistry {
+    fn default() -> Self {
+        Self::with_builtin()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_variants() {
+        let registry = EngineRegistry::with_builtin();
+        assert_eq!(registry.names(), vec!["classic", "speed"]);
+        assert!(registry.create("speed", GameOptions::default()).is_ok());
+        assert!(registry.create("unknown", GameOptions::default()).is_err());
+    }
+}
@@ -0,0 +1,691 @@
+//! マスに埋め込む小さなスクリプト言語
+//!
+//! コミュニティマップがサーバーを再ビルドせずに独自のマス効果を定義できるようにする。
+//! Rhai 風の構文のサブセットで、ループは無く、命令数にも上限を設けている。
+//!
+//! ```text
+//! let bonus = salary / 2;
+//! if children > 0 {
+//!     money += bonus * children;
+//!     emit("子育て手当を受け取った");
+//! } else {
+//!     position = 12;
+//! }
+//! ```
+//!
+//! 読み書きできる変数: `money`, `position`（存在するマスIDのみ）
+//! 読み取り専用の変数: `salary`, `children`, `married`, `player_count`, `turn_number`
+//! 組み込み関数: `random(n)`（0..n の乱数）, `min(a, b)`, `max(a, b)`, `emit(text)`
+
+use std::collections::HashMap;
+
+use super::state::{GameEvent, GameState};
+use super::text::MoneyReason;
+
+/// スクリプト本文の最大長
+const MAX_SOURCE_LEN: usize = 4096;
+/// 1回の実行で評価できる式・文の最大数
+const MAX_STEPS: usize = 10_000;
+/// 式・ブロックの入れ子の最大の深さ（構文解析・評価の再帰でスタックを使い切らないように）
+const MAX_DEPTH: usize = 64;
+
+/// スクリプトを実行する。エラー時は状態を変更せず Err を返す
+pub fn run(
+    source: &str,
+    state: &GameState,
+    player_idx: usize,
+) -> Result<(GameState, Vec<GameEvent>), String> {
+    let program = parse(source)?;
+    let mut interp = Interpreter {
+        state: state.clone(),
+        player_idx,
+        locals: HashMap::new(),
+        events: Vec::new(),
+        steps: 0,
+    };
+    let money_before = interp.state.players[player_idx].money;
+    let mut messages = Vec::new();
+    interp.exec_block(&program, &mut messages)?;
+
+    let mut events = interp.events;
+    let player_id = interp.state.players[player_idx].id.clone();
+    let diff = interp.state.players[player_idx].money - money_before;
+    if diff != 0 {
+        events.push(GameEvent::MoneyChanged {
+            player_id,
+            amount: diff,
+            reason: MoneyReason::Script {
+                text: messages.last().cloned(),
+            },
+        });
+    }
+    Ok((interp.state, events))
+}
+
+/// 構文チェックのみ行う（マップ読み込み時の検証用）
+pub fn validate(source: &str) -> Result<(), String> {
+    parse(source).map(|_| ())
+}
+
+// ============================================================
+// Lexer
+// ============================================================
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Int(i64),
+    Str(String),
+    Ident(String),
+    Let,
+    If,
+    Else,
+    True,
+    False,
+    Symbol(&'static str),
+}
+
+const SYMBOLS: &[&str] = &[
+    "+=", "-=", "==", "!=", "<=", ">=", "&&", "||", "+", "-", "*", "/", "%", "(", ")", "{", "}",
+    ";", ",", "=", "<", ">", "!",
+];
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    if source.len() > MAX_SOURCE_LEN {
+        return Err(format!("script too long (max {} bytes)", MAX_SOURCE_LEN));
+    }
+
+    let mut tokens = Vec::new();
+    let mut rest = source;
+    while let Some(c) = rest.chars().next() {
+        if c.is_whitespace() {
+            rest = &rest[c.len_utf8()..];
+        } else if rest.starts_with("//") {
+            rest = rest.find('\n').map_or("", |i| &rest[i..]);
+        } else if c.is_ascii_digit() {
+            let end = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+            let value = rest[..end]
+                .parse()
+                .map_err(|_| format!("integer literal too large: {}", &rest[..end]))?;
+            tokens.push(Token::Int(value));
+            rest = &rest[end..];
+        } else if c == '"' {
+            let end = rest[1..]
+                .find('"')
+                .ok_or_else(|| "unterminated string literal".to_string())?;
+            tokens.push(Token::Str(rest[1..1 + end].to_string()));
+            rest = &rest[end + 2..];
+        } else if c.is_alphabetic() || c == '_' {
+            let end = rest
+                .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .unwrap_or(rest.len());
+            tokens.push(match &rest[..end] {
+                "let" => Token::Let,
+                "if" => Token::If,
+                "else" => Token::Else,
+                "true" => Token::True,
+                "false" => Token::False,
+                ident => Token::Ident(ident.to_string()),
+            });
+            rest = &rest[end..];
+        } else {
+            let symbol = SYMBOLS
+                .iter()
+                .find(|s| rest.starts_with(**s))
+                .ok_or_else(|| format!("unexpected character: {}", c))?;
+            tokens.push(Token::Symbol(symbol));
+            rest = &rest[symbol.len()..];
+        }
+    }
+    Ok(tokens)
+}
+
+// ============================================================
+// Parser
+// ============================================================
+
+#[derive(Debug, Clone)]
+enum Expr {
+    Int(i64),
+    Str(String),
+    Bool(bool),
+    Var(String),
+    Unary(&'static str, Box<Expr>),
+    Binary(&'static str, Box<Expr>, Box<Expr>),
+    Call(String, Vec<Expr>),
+}
+
+#[derive(Debug, Clone)]
+enum Stmt {
+    Let(String, Expr),
+    Assign(String, &'static str, Expr),
+    If(Expr, Vec<Stmt>, Vec<Stmt>),
+    Expr(Expr),
+}
+
+/// 二項演算子の優先順位（低い順）
+const BINARY_PRECEDENCE: &[&[&str]] = &[
+    &["||"],
+    &["&&"],
+    &["==", "!="],
+    &["<", "<=", ">", ">="],
+    &["+", "-"],
+    &["*", "/", "%"],
+];
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    /// 今の入れ子の深さ
+    depth: usize,
+}
+
+fn parse(source: &str) -> Result<Vec<Stmt>, String> {
+    let mut parser = Parser {
+        tokens: tokenize(source)?,
+        pos: 0,
+        depth: 0,
+    };
+    let mut stmts = Vec::new();
+    while parser.peek().is_some() {
+        stmts.push(parser.statement()?);
+    }
+    Ok(stmts)
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn eat_symbol(&mut self, symbol: &str) -> bool {
+        if self.peek() == Some(&Token::Symbol(symbol_ref(symbol))) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_symbol(&mut self, symbol: &str) -> Result<(), String> {
+        if self.eat_symbol(symbol) {
+            Ok(())
+        } else {
+            Err(format!("expected '{}' but found {:?}", symbol, self.peek()))
+        }
+    }
+
+    /// 入れ子を1段深くして f を解析する（MAX_DEPTH を超えたらエラー）
+    fn nested<T>(&mut self, f: impl FnOnce(&mut Self) -> Result<T, String>) -> Result<T, String> {
+        if self.depth >= MAX_DEPTH {
+            return Err(format!("nested too deeply (max {})", MAX_DEPTH));
+        }
+        self.depth += 1;
+        let result = f(self);
+        self.depth -= 1;
+        result
+    }
+
+    fn block(&mut self) -> Result<Vec<Stmt>, String> {
+        self.expect_symbol("{")?;
+        self.nested(|p| {
+            let mut stmts = Vec::new();
+            while !p.eat_symbol("}") {
+                if p.peek().is_none() {
+                    return Err("unterminated block".to_string());
+                }
+                stmts.push(p.statement()?);
+            }
+            Ok(stmts)
+        })
+    }
+
+    fn statement(&mut self) -> Result<Stmt, String> {
+        match self.peek() {
+            Some(Token::Let) => {
+                self.pos += 1;
+                let name = match self.next() {
+                    Some(Token::Ident(name)) => name,
+                    other => return Err(format!("expected variable name but found {:?}", other)),
+                };
+                self.expect_symbol("=")?;
+                let value = self.expression(0)?;
+                self.expect_symbol(";")?;
+                Ok(Stmt::Let(name, value))
+            }
+            Some(Token::If) => {
+                self.pos += 1;
+                let cond = self.expression(0)?;
+                let then = self.block()?;
+                let otherwise = if self.peek() == Some(&Token::Else) {
+                    self.pos += 1;
+                    if self.peek() == Some(&Token::If) {
+                        vec![self.statement()?]
+                    } else {
+                        self.block()?
+                    }
+                } else {
+                    Vec::new()
+                };
+                Ok(Stmt::If(cond, then, otherwise))
+            }
+            Some(Token::Ident(name)) => {
+                let name = name.clone();
+                let op = match self.tokens.get(self.pos + 1) {
+                    Some(Token::Symbol(op @ ("=" | "+=" | "-="))) => Some(*op),
+                    _ => None,
+                };
+                let stmt = if let Some(op) = op {
+                    self.pos += 2;
+                    Stmt::Assign(name, op, self.expression(0)?)
+                } else {
+                    Stmt::Expr(self.expression(0)?)
+                };
+                self.expect_symbol(";")?;
+                Ok(stmt)
+            }
+            _ => {
+                let expr = self.expression(0)?;
+                self.expect_symbol(";")?;
+                Ok(Stmt::Expr(expr))
+            }
+        }
+    }
+
+    fn expression(&mut self, level: usize) -> Result<Expr, String> {
+        if level == BINARY_PRECEDENCE.len() {
+            return self.unary();
+        }
+        let mut lhs = self.expression(level + 1)?;
+        loop {
+            let op = match self.peek() {
+                Some(Token::Symbol(op)) if BINARY_PRECEDENCE[level].contains(op) => *op,
+                _ => break,
+            };
+            self.pos += 1;
+            let rhs = self.expression(level + 1)?;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        self.nested(|p| {
+            for op in ["-", "!"] {
+                if p.eat_symbol(op) {
+                    return Ok(Expr::Unary(symbol_ref(op), Box::new(p.unary()?)));
+                }
+            }
+            p.primary()
+        })
+    }
+
+    fn primary(&mut self) -> Result<Expr, String> {
+        match self.next() {
+            Some(Token::Int(v)) => Ok(Expr::Int(v)),
+            Some(Token::Str(s)) => Ok(Expr::Str(s)),
+            Some(Token::True) => Ok(Expr::Bool(true)),
+            Some(Token::False) => Ok(Expr::Bool(false)),
+            Some(Token::Ident(name)) => {
+                if !self.eat_symbol("(") {
+                    return Ok(Expr::Var(name));
+                }
+                let mut args = Vec::new();
+                if !self.eat_symbol(")") {
+                    loop {
+                        args.push(self.expression(0)?);
+                        if self.eat_symbol(")") {
+                            break;
+                        }
+                        self.expect_symbol(",")?;
+                    }
+                }
+                Ok(Expr::Call(name, args))
+            }
+            Some(Token::Symbol("(")) => {
+                let expr = self.expression(0)?;
+                self.expect_symbol(")")?;
+                Ok(expr)
+            }
+            other => Err(format!("unexpected token: {:?}", other)),
+        }
+    }
+}
+
+/// SYMBOLS 内の &'static str を返す（AST に演算子を保持するため）
+fn symbol_ref(symbol: &str) -> &'static str {
+    SYMBOLS
+        .iter()
+        .find(|s| **s == symbol)
+        .expect("known symbol")
+}
+
+// ============================================================
+// Interpreter
+// ============================================================
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Int(i64),
+    Str(String),
+    Bool(bool),
+}
+
+impl Value {
+    fn as_int(&self) -> Result<i64, String> {
+        match self {
+            Value::Int(v) => Ok(*v),
+            other => Err(format!("expected integer but found {:?}", other)),
+        }
+    }
+
+    fn as_bool(&self) -> Result<bool, String> {
+        match self {
+            Value::Bool(v) => Ok(*v),
+            other => Err(format!("expected bool but found {:?}", other)),
+        }
+    }
+}
+
+struct Interpreter {
+    state: GameState,
+    player_idx: usize,
+    locals: HashMap<String, Value>,
+    events: Vec<GameEvent>,
+    steps: usize,
+}
+
+impl Interpreter {
+    fn step(&mut self) -> Result<(), String> {
+        self.steps += 1;
+        if self.steps > MAX_STEPS {
+            return Err("script step limit exceeded".to_string());
+        }
+        Ok(())
+    }
+
+    fn exec_block(&mut self, stmts: &[Stmt], messages: &mut Vec<String>) -> Result<(), String> {
+        for stmt in stmts {
+            self.step()?;
+            match stmt {
+                Stmt::Let(name, expr) => {
+                    if self.read_player_var(name).is_some() {
+                        return Err(format!("cannot shadow builtin variable: {}", name));
+                    }
+                    let value = self.eval(expr, messages)?;
+                    self.locals.insert(name.clone(), value);
+                }
+                Stmt::Assign(name, op, expr) => {
+                    let rhs = self.eval(expr, messages)?;
+                    let value = match *op {
+                        "=" => rhs,
+                        _ => {
+                            let current = self.lookup(name)?.as_int()?;
+                            let rhs = rhs.as_int()?;
+                            let result = if *op == "+=" {
+                                current.checked_add(rhs)
+                            } else {
+                                current.checked_sub(rhs)
+                            };
+                            Value::Int(result.ok_or("integer overflow")?)
+                        }
+                    };
+                    self.assign(name, value)?;
+                }
+                Stmt::If(cond, then, otherwise) => {
+                    if self.eval(cond, messages)?.as_bool()? {
+                        self.exec_block(then, messages)?;
+                    } else {
+                        self.exec_block(otherwise, messages)?;
+                    }
+                }
+                Stmt::Expr(expr) => {
+                    self.eval(expr, messages)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn read_player_var(&self, name: &str) -> Option<Value> {
+        let player = &self.state.players[self.player_idx];
+        Some(match name {
+            "money" => Value::Int(player.money),
+            "position" => Value::Int(player.position as i64),
+            "salary" => Value::Int(player.salary as i64),
+            "children" => Value::Int(player.children as i64),
+            "married" => Value::Bool(player.married),
+            "player_count" => Value::Int(self.state.players.len() as i64),
+            "turn_number" => Value::Int(self.state.turn_number as i64),
+            _ => return None,
+        })
+    }
+
+    fn lookup(&self, name: &str) -> Result<Value, String> {
+        self.read_player_var(name)
+            .or_else(|| self.locals.get(name).cloned())
+            .ok_or_else(|| format!("unknown variable: {}", name))
+    }
+
+    fn assign(&mut self, name: &str, value: Value) -> Result<(), String> {
+        match name {
+            "money" => {
+                self.state.players[self.player_idx].money = value.as_int()?;
+            }
+            "position" => {
+                let tile_id = usize::try_from(value.as_int()?).map_err(|_| "invalid tile id")?;
+                if self.state.catalog.board.tile(tile_id).is_none() {
+                    return Err(format!("no such tile: {}", tile_id));
+                }
+                self.state.players[self.player_idx].position = tile_id;
+            }
+            _ if self.read_player_var(name).is_some() => {
+                return Err(format!("variable is read-only: {}", name));
+            }
+            _ => {
+                let local = self
+                    .locals
+                    .get_mut(name)
+                    .ok_or_else(|| format!("unknown variable: {}", name))?;
+                *local = value;
+            }
+        }
+        Ok(())
+    }
+
+    fn eval(&mut self, expr: &Expr, messages: &mut Vec<String>) -> Result<Value, String> {
+        self.step()?;
+        match expr {
+            Expr::Int(v) => Ok(Value::Int(*v)),
+            Expr::Str(s) => Ok(Value::Str(s.clone())),
+            Expr::Bool(b) => Ok(Value::Bool(*b)),
+            Expr::Var(name) => self.lookup(name),
+            Expr::Unary(op, operand) => {
+                let value = self.eval(operand, messages)?;
+                match *op {
+                    "-" => Ok(Value::Int(
+                        value.as_int()?.checked_neg().ok_or("integer overflow")?,
+                    )),
+                    _ => Ok(Value::Bool(!value.as_bool()?)),
+                }
+            }
+            Expr::Binary(op, lhs, rhs) => {
+                // && と || は短絡評価
+                if *op == "&&" || *op == "||" {
+                    let left = self.eval(lhs, messages)?.as_bool()?;
+                    if (*op == "&&") != left {
+                        return Ok(Value::Bool(left));
+                    }
+                    return Ok(Value::Bool(self.eval(rhs, messages)?.as_bool()?));
+                }
+                let left = self.eval(lhs, messages)?;
+                let right = self.eval(rhs, messages)?;
+                match *op {
+                    "==" => return Ok(Value::Bool(left == right)),
+                    "!=" => return Ok(Value::Bool(left != right)),
+                    _ => {}
+                }
+                let (a, b) = (left.as_int()?, right.as_int()?);
+                let result = match *op {
+                    "+" => a.checked_add(b),
+                    "-" => a.checked_sub(b),
+                    "*" => a.checked_mul(b),
+                    "/" => a.checked_div(b),
+                    "%" => a.checked_rem(b),
+                    "<" => return Ok(Value::Bool(a < b)),
+                    "<=" => return Ok(Value::Bool(a <= b)),
+                    ">" => return Ok(Value::Bool(a > b)),
+                    _ => return Ok(Value::Bool(a >= b)),
+                };
+                result
+                    .map(Value::Int)
+                    .ok_or_else(|| "arithmetic error (overflow or division by zero)".to_string())
+            }
+            Expr::Call(name, args) => {
+                let args = args
+                    .iter()
+                    .map(|a| self.eval(a, messages))
+                    .collect::<Result<Vec<_>, _>>()?;
+                self.call(name, args, messages)
+            }
+        }
+    }
+
+    fn call(
+        &mut self,
+        name: &str,
+        args: Vec<Value>,
+        messages: &mut Vec<String>,
+    ) -> Result<Value, String> {
+        match (name, args.as_slice()) {
+            ("random", [n]) => {
+                let n = n.as_int()?;
+                if n <= 0 {
+                    return Err("random(n) requires n > 0".to_string());
+                }
+                Ok(Value::Int((self.state.next_random() % n as u64) as i64))
+            }
+            ("min", [a, b]) => Ok(Value::Int(a.as_int()?.min(b.as_int()?))),
+            ("max", [a, b]) => Ok(Value::Int(a.as_int()?.max(b.as_int()?))),
+            ("emit", [Value::Str(text)]) => {
+                self.events.push(GameEvent::ScriptMessage {
+                    player_id: self.state.players[self.player_idx].id.clone(),
+                    text: text.clone(),
+                });
+                messages.push(text.clone());
+                Ok(Value::Bool(true))
+            }
+            _ => Err(format!(
+                "unknown function or wrong arguments: {}({} args)",
+                name,
+                args.len()
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{Board, GameCatalog, Position, Tile, TileType, TurnPhase, STOCK_PRICE};
+    use std::sync::Arc;
+    use crate::PlayerState;
+
+    fn sample_state() -> GameState {
+        let tile = |id| Tile {
+            id,
+            tile_type: TileType::Action,
+            position: Position { x: 0.0, y: 0.0 },
+            next: vec![],
+            event: None,
+            labels: None,
+            landing_bonus: None,
+        };
+        let mut player = PlayerState::new("p1".to_string(), "Alice".to_string(), 10000);
+        player.salary = 20000;
+        player.children = 2;
+        GameState {
+            players: vec![
+                player,
+                PlayerState::new("p2".to_string(), "Bob".to_string(), 10000),
+            ],
+            catalog: Arc::new(GameCatalog {
+                board: Board {
+                    tiles: vec![tile(0), tile(1), tile(2)],
+                },
+                careers: vec![],
+                houses_for_sale: vec![],
+            }),
+            current_turn: 0,
+            phase: TurnPhase::Moving,
+            rng_seed: 42,
+            loan_unit: 20000,
+            loan_interest_rate: 1.25,
+            payout_multiplier: 1,
+            turn_number: 1,
+            max_turns: None,
+            lap_rules: None,
+            pending_steps: 0,
+            chosen_path: None,
+            pending_choice: None,
+            game_started_at: None,
+            tax_cut: false,
+            arrivals: Vec::new(),
+            stock_price: STOCK_PRICE,
+            seed: 42,
+        }
+    }
+
+    #[test]
+    fn test_script_modifies_money_and_position() {
+        let source = r#"
+            // 子供の数に応じた手当
+            let bonus = salary / 2;
+            if children > 0 && !married {
+                money += bonus * children;
+                emit("子育て手当");
+            } else {
+                money -= 1;
+            }
+            position = 2;
+        "#;
+        let (state, events) = run(source, &sample_state(), 0).unwrap();
+        assert_eq!(state.players[0].money, 30000);
+        assert_eq!(state.players[0].position, 2);
+        assert!(matches!(
+            events.last(),
+            Some(GameEvent::MoneyChanged { amount: 20000, reason: MoneyReason::Script { text: Some(text) }, .. }) if text == "子育て手当"
+        ));
+    }
+
+    #[test]
+    fn test_script_errors_are_rejected() {
+        let state = sample_state();
+        assert!(validate("money += ;").is_err());
+        assert!(validate("if true { money = 1;").is_err());
+        assert!(run("salary = 1;", &state, 0).is_err());
+        assert!(run("position = 99;", &state, 0).is_err());
+        assert!(run("money = 1 / 0;", &state, 0).is_err());
+        assert!(run("let money = 1;", &state, 0).is_err());
+        assert!(run("unknown();", &state, 0).is_err());
+    }
+
+    #[test]
+    fn test_deeply_nested_script_is_rejected() {
+        let parens = format!("money = {}1{};", "(".repeat(1000), ")".repeat(1000));
+        assert!(validate(&parens).unwrap_err().contains("nested too deeply"));
+        let negations = format!("money = {}1;", "-".repeat(2000));
+        assert!(validate(&negations).unwrap_err().contains("nested too deeply"));
+        let blocks = format!("{}money = 1;{}", "if true { ".repeat(300), "}".repeat(300));
+        assert!(validate(&blocks).unwrap_err().contains("nested too deeply"));
+
+        // 上限内の入れ子はそのまま動く
+        let shallow = format!("money = {}1{};", "(".repeat(20), ")".repeat(20));
+        assert_eq!(run(&shallow, &sample_state(), 0).unwrap().0.players[0].money, 1);
+    }
+}
@@ -0,0 +1,256 @@
+//! バランス調整用のヘッドレス対戦
+//!
+//! ボット同士のゲームを driver::step で最後まで進め、手番順ごとの勝率・平均ターン数・最終資産の分布を集計する。
+//! 新しいマスやハウスルールの影響を、サーバーを立てずに数千ゲーム分まとめて確かめるために使う。
+//! ゲームごとの seed は基準の seed から順に取るため、同じ引数からは常に同じ集計になる。
+
+use std::str::FromStr;
+
+use serde::Serialize;
+
+use super::driver::{self, TurnInput};
+use super::engine::ClassicGameEngine;
+use super::state::*;
+use super::traits::GameEngine;
+
+/// 1ゲームあたりの操作数の上限（これを超えたゲームは未完了として集計から外す）
+pub const MAX_STEPS_PER_GAME: usize = 10_000;
+
+/// ボットの選び方（分岐の道はどれもランダム）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BotStrategy {
+    /// 提示された選択肢からランダムに選ぶ
+    Random,
+    /// 見送り以外を選ぶ（老後は高級老人ホーム）
+    Greedy,
+    /// 見送れるなら見送る（老後は質素に）
+    Cautious,
+}
+
+impl FromStr for BotStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "random" => Ok(BotStrategy::Random),
+            "greedy" => Ok(BotStrategy::Greedy),
+            "cautious" => Ok(BotStrategy::Cautious),
+            _ => Err(format!("unknown bot strategy: {} (available: random, greedy, cautious)", s)),
+        }
+    }
+}
+
+/// 最終資産の分布
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct MoneyDistribution {
+    pub min: i64,
+    pub p10: i64,
+    pub median: i64,
+    pub p90: i64,
+    pub max: i64,
+    pub mean: f64,
+}
+
+impl MoneyDistribution {
+    fn from_samples(mut samples: Vec<i64>) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+        samples.sort_unstable();
+        let at = |percent: usize| samples[(samples.len() - 1) * percent / 100];
+        Self {
+            min: samples[0],
+            p10: at(10),
+            median: at(50),
+            p90: at(90),
+            max: samples[samples.len() - 1],
+            mean: samples.iter().sum::<i64>() as f64 / samples.len() as f64,
+        }
+    }
+}
+
+/// simulate の集計結果
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SimulationReport {
+    pub games: u32,
+    /// MAX_STEPS_PER_GAME 以内に終わらなかったゲーム数（以下の集計には含めない）
+    pub unfinished: u32,
+    /// 手番順（0 が先手）ごとの1位の回数。同率1位はそれぞれ数える
+    pub wins_by_seat: Vec<u32>,
+    /// 手番順ごとの勝率（終わったゲームに対する割合）
+    pub win_rate_by_seat: Vec<f64>,
+    /// 終了時のターン番号（全プレイヤー通算）の平均
+    pub average_turns: f64,
+    /// 全プレイヤーの最終資産の分布
+    pub final_assets: MoneyDistribution,
+}
+
+/// 標準ルールで simulate_with_options を呼ぶ
+pub fn simulate(
+    map: &MapData,
+    seed: u64,
+    bots: &[BotStrategy],
+    n_games: u32,
+) -> Result<SimulationReport, String> {
+    simulate_with_options(map, GameOptions::default(), seed, bots, n_games)
+}
+
+/// bots の人数でゲームを n_games 回行って集計する（options の seed は無視し、ゲームごとに seed, seed + 1, ... を使う）
+pub fn simulate_with_options(
+    map: &MapData,
+    options: GameOptions,
+    seed: u64,
+    bots: &[BotStrategy],
+    n_games: u32,
+) -> Result<SimulationReport, String> {
+    if bots.len() < 2 {
+        return Err("at least 2 bots are required".to_string());
+    }
+    let mut wins_by_seat = vec![0u32; bots.len()];
+    let mut unfinished = 0;
+    let mut turns = Vec::new();
+    let mut assets = Vec::new();
+
+    for game in 0..n_games {
+        let game_seed = seed.wrapping_add(game as u64).max(1);
+        let engine = ClassicGameEngine::new().with_options(GameOptions {
+            seed: Some(game_seed),
+            ..options
+        });
+        let Some(state) = play(&engine, map, bots, game_seed)? else {
+            unfinished += 1;
+            continue;
+        };
+        for ranking in engine.rankings(&state) {
+            if let Some(seat) = state.players.iter().position(|p| p.id == ranking.player_id) {
+                if ranking.rank == 1 {
+                    wins_by_seat[seat] += 1;
+                }
+            }
+            assets.push(ranking.total_assets);
+        }
+        turns.push(state.turn_number);
+    }
+
+    let finished = turns.len();
+    Ok(SimulationReport {
+        games: n_games,
+        unfinished,
+        win_rate_by_seat: wins_by_seat
+            .iter()
+            .map(|&w| if finished == 0 { 0.0 } else { w as f64 / finished as f64 })
+            .collect(),
+        wins_by_seat,
+        average_turns: if finished == 0 {
+            0.0
+        } else {
+            turns.iter().map(|&t| t as f64).sum::<f64>() / finished as f64
+        },
+        final_assets: MoneyDistribution::from_samples(assets),
+    })
+}
+
+/// 1ゲームを最後まで進める（上限までに終わらなければ None）
+fn play(
+    engine: &dyn GameEngine,
+    map: &MapData,
+    bots: &[BotStrategy],
+    seed: u64,
+) -> Result<Option<GameState>, String> {
+    let players = (0..bots.len())
+        .map(|i| (format!("bot{}", i), format!("Bot {}", i)))
+        .collect();
+    let mut state = engine.init(players, map);
+    // ボットの選択用の乱数（ゲームの乱数とは別に持ち、ゲームの展開を変えない）
+    let mut rng = seed ^ 0x9E37_79B9_7F4A_7C15;
+
+    for _ in 0..MAX_STEPS_PER_GAME {
+        if engine.is_finished(&state) {
+            return Ok(Some(state));
+        }
+        let input = choose(bots[state.current_turn], &state, &mut rng);
+        let (next, _) = driver::step(engine, &state, &input)?;
+        state = next;
+    }
+    Ok(None)
+}
+
+fn next_random(rng: &mut u64) -> u64 {
+    *rng ^= *rng << 13;
+    *rng ^= *rng >> 7;
+    *rng ^= *rng << 17;
+    *rng
+}
+
+/// 手番のボットの操作を決める
+fn choose(bot: BotStrategy, state: &GameState, rng: &mut u64) -> TurnInput {
+    let choices = state.pending_choice.as_ref().map(|p| p.choices.as_slice()).unwrap_or_default();
+    let random = |rng: &mut u64| (next_random(rng) % choices.len().max(1) as u64) as usize;
+    match state.phase {
+        TurnPhase::ChoosingPath => TurnInput::ChoosePath { index: random(rng) },
+        TurnPhase::ChoosingAction if !choices.is_empty() => {
+            let index = match bot {
+                BotStrategy::Random => random(rng),
+                BotStrategy::Greedy | BotStrategy::Cautious => {
+                    let wants_skip = bot == BotStrategy::Cautious;
+                    choices
+                        .iter()
+                        .position(|c| {
+                            let action = driver::parse_action(&c.id, state);
+                            match action {
+                                PlayerAction::SkipAction => wants_skip,
+                                PlayerAction::ChooseRetirement { home } => {
+                                    (home == RetirementHome::Modest) == wants_skip
+                                }
+                                _ => !wants_skip,
+                            }
+                        })
+                        .unwrap_or(0)
+                }
+            };
+            TurnInput::ChooseAction {
+                id: choices[index].id.clone(),
+            }
+        }
+        _ => TurnInput::Spin,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map() -> MapData {
+        serde_json::from_value(serde_json::json!({
+            "id": "m", "name": "m", "version": "1.0",
+            "start_money": 10000, "loan_unit": 20000, "loan_interest_rate": 1.25,
+            "tiles": [
+                { "id": 0, "type": "Start", "position": { "x": 0, "y": 0 }, "next": [1] },
+                { "id": 1, "type": "House", "position": { "x": 1, "y": 0 }, "next": [2] },
+                { "id": 2, "type": "Payday", "position": { "x": 2, "y": 0 }, "next": [3] },
+                { "id": 3, "type": "Insurance", "position": { "x": 3, "y": 0 }, "next": [4] },
+                { "id": 4, "type": "Retire", "position": { "x": 4, "y": 0 }, "next": [] }
+            ],
+            "careers": [],
+            "houses": [{ "id": "h", "name": "h", "price": 5000, "sell_price": 8000 }]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_simulate_is_reproducible() {
+        let bots = [BotStrategy::Greedy, BotStrategy::Cautious, BotStrategy::Random];
+        let report = simulate(&map(), 1, &bots, 50).unwrap();
+        assert_eq!(report, simulate(&map(), 1, &bots, 50).unwrap());
+        assert_eq!(report.unfinished, 0);
+        assert_eq!(report.wins_by_seat.len(), 3);
+        assert!(report.wins_by_seat.iter().sum::<u32>() >= 50);
+        assert!(report.average_turns >= 3.0);
+        assert!(report.final_assets.min <= report.final_assets.median);
+        assert!(report.final_assets.median <= report.final_assets.max);
+
+        assert!(simulate(&map(), 1, &bots[..1], 1).is_err());
+        assert_eq!("greedy".parse::<BotStrategy>(), Ok(BotStrategy::Greedy));
+    }
+}
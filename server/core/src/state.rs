@@ -0,0 +1,976 @@
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::PlayerId;
+
+use super::market::GlobalEventKind;
+use super::text::{ChoiceText, MoneyReason};
+
+// ============================================================
+// Map data types (loaded from JSON)
+// ============================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct MapData {
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    #[ts(type = "number")]
+    pub start_money: i64,
+    #[ts(type = "number")]
+    pub loan_unit: u64,
+    pub loan_interest_rate: f64,
+    /// 短縮ゲームの開始マス（未指定のマップは短縮ゲーム非対応）
+    #[serde(default)]
+    #[ts(optional)]
+    pub short_start_tile: Option<usize>,
+    /// 周回マップのルール（スタートマスに戻るループを持つマップ用）
+    #[serde(default)]
+    #[ts(optional)]
+    pub laps: Option<LapRules>,
+    pub tiles: Vec<TileData>,
+    pub careers: Vec<Career>,
+    pub houses: Vec<House>,
+}
+
+/// 周回マップのルール
+/// スタートマスを通過・到着するたびに1周と数える
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct LapRules {
+    /// 1周あたりに受け取れる給料日の回数
+    #[serde(default)]
+    #[ts(optional)]
+    pub max_paydays_per_lap: Option<u32>,
+    /// この周回数に到達したプレイヤーはゴール（リタイア）となる
+    #[serde(default)]
+    #[ts(optional)]
+    pub finish_after_laps: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct TileData {
+    pub id: usize,
+    #[serde(rename = "type")]
+    pub tile_type: TileType,
+    pub position: Position,
+    pub next: Vec<usize>,
+    pub event: Option<TileEvent>,
+    pub labels: Option<Vec<String>>,
+    /// ちょうど止まったとき（通過では無し）にもらえるボーナス
+    #[serde(default)]
+    #[ts(type = "number | null")]
+    pub landing_bonus: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct Position {
+    pub x: f64,
+    pub y: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[ts(export)]
+pub enum TileType {
+    Start,
+    Payday,
+    Action,
+    Career,
+    House,
+    Marry,
+    Baby,
+    Stock,
+    Insurance,
+    Tax,
+    Lawsuit,
+    Branch,
+    Retire,
+    /// 治療費を払う（医者がいればその人が受け取る）
+    Hospital,
+    /// 他の全員から誕生日祝いをもらう
+    Birthday,
+    /// 次の手番はルーレットを2回回し、合計だけ進む
+    Speed,
+    /// 職業の給料の段階を1つ上げる
+    Raise,
+    /// 他のプレイヤーを1人選んで位置（周回数も）を入れ替える（移動先のマスの効果は無し）
+    Swap,
+    /// 一番先に進んでいるプレイヤーの位置（周回数も）まで移動する（移動先のマスの効果は無し）
+    Overtake,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(tag = "type")]
+pub enum TileEvent {
+    #[serde(rename = "money")]
+    Money {
+        #[ts(type = "number")]
+        amount: i64,
+        text: String,
+    },
+    #[serde(rename = "draw_career")]
+    DrawCareer { pool: String },
+    /// マスに止まった時に実行するスクリプト（game::script の構文）
+    #[serde(rename = "script")]
+    Script { source: String },
+    /// 火事で amount を失う（火災保険に入っていれば同額が支払われる）
+    #[serde(rename = "fire")]
+    Fire {
+        #[ts(type = "number")]
+        amount: i64,
+    },
+    /// 泥棒に amount を盗まれる（警察官がいれば捕まえ、盗まれた分は警察官のものになる）
+    #[serde(rename = "theft")]
+    Theft {
+        #[ts(type = "number")]
+        amount: i64,
+    },
+    /// 訴訟マスで請求する金額（未指定の訴訟マスは LAWSUIT_AMOUNT）
+    #[serde(rename = "lawsuit")]
+    Lawsuit {
+        #[ts(type = "number")]
+        amount: i64,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct Career {
+    pub id: String,
+    pub name: String,
+    pub salary: u32,
+    pub pool: String,
+    /// 他のマスの結果を変える職業の能力
+    #[serde(default)]
+    pub abilities: Vec<CareerAbility>,
+    /// 昇給マスで上がっていく給料（salary の次の段階から順に。最後の段階がこの職業の上限）
+    #[serde(default)]
+    pub salary_ladder: Vec<u32>,
+}
+
+impl Career {
+    pub fn has_ability(&self, ability: CareerAbility) -> bool {
+        self.abilities.contains(&ability)
+    }
+
+    /// 段階 level（0 が初任給）の給料。上限を超える段階なら None
+    pub fn salary_at(&self, level: u32) -> Option<u32> {
+        match level {
+            0 => Some(self.salary),
+            n => self.salary_ladder.get(n as usize - 1).copied(),
+        }
+    }
+}
+
+/// 職業の能力
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum CareerAbility {
+    /// 病院マスの治療費を受け取る（自分は払わない）
+    Doctor,
+    /// 泥棒を捕まえて盗まれたお金を受け取る（自分は盗まれない）
+    Police,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct House {
+    pub id: String,
+    pub name: String,
+    #[ts(type = "number")]
+    pub price: i64,
+    #[ts(type = "number")]
+    pub sell_price: i64,
+    /// 抵当に入っている（買い戻すまで資産に数えない）
+    #[serde(default)]
+    pub mortgaged: bool,
+}
+
+impl House {
+    /// 抵当に入れて受け取る金額
+    pub fn mortgage_value(&self) -> i64 {
+        self.sell_price * MORTGAGE_PERCENT / 100
+    }
+
+    /// 抵当から買い戻す金額
+    pub fn redeem_cost(&self) -> i64 {
+        self.mortgage_value() * MORTGAGE_REDEEM_PERCENT / 100
+    }
+}
+
+/// 家を抵当に入れて受け取る割合（売却額に対する%）
+pub const MORTGAGE_PERCENT: i64 = 50;
+
+/// 抵当から買い戻すときに払う割合（受け取った額に対する%）
+pub const MORTGAGE_REDEEM_PERCENT: i64 = 110;
+
+// ============================================================
+// Game state
+// ============================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct Board {
+    pub tiles: Vec<Tile>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct Tile {
+    pub id: usize,
+    #[serde(rename = "type")]
+    pub tile_type: TileType,
+    pub position: Position,
+    pub next: Vec<usize>,
+    pub event: Option<TileEvent>,
+    pub labels: Option<Vec<String>>,
+    /// ちょうど止まったとき（通過では無し）にもらえるボーナス
+    #[serde(default)]
+    #[ts(type = "number | null")]
+    pub landing_bonus: Option<i64>,
+}
+
+impl Board {
+    pub fn from_map(map: &MapData) -> Self {
+        let tiles = map
+            .tiles
+            .iter()
+            .map(|td| Tile {
+                id: td.id,
+                tile_type: td.tile_type.clone(),
+                position: td.position.clone(),
+                next: td.next.clone(),
+                event: td.event.clone(),
+                labels: td.labels.clone(),
+                landing_bonus: td.landing_bonus,
+            })
+            .collect();
+        Board { tiles }
+    }
+
+    /// first から道なりに最大 len マス先までの概要（次の分岐・行き止まりで打ち切る）
+    pub fn preview_path(&self, first: usize, len: usize) -> Vec<TilePreview> {
+        let mut preview = Vec::new();
+        let mut id = first;
+        while preview.len() < len {
+            let Some(tile) = self.tile(id) else { break };
+            preview.push(TilePreview {
+                tile_id: tile.id,
+                tile_type: tile.tile_type.clone(),
+            });
+            match tile.next.as_slice() {
+                [next] if *next != first => id = *next,
+                _ => break,
+            }
+        }
+        preview
+    }
+
+    /// スタートマス（先頭のマス）のID
+    pub fn start_tile_id(&self) -> usize {
+        self.tiles.first().map(|t| t.id).unwrap_or(0)
+    }
+
+    pub fn tile(&self, id: usize) -> Option<&Tile> {
+        self.tiles.iter().find(|t| t.id == id)
+    }
+
+    /// Find the tile index in the tiles vec by tile id
+    pub fn tile_index(&self, id: usize) -> Option<usize> {
+        self.tiles.iter().position(|t| t.id == id)
+    }
+
+    /// スタートマスから id までの最短のマス数（たどり着けないマスは None）
+    pub fn distance_from_start(&self, id: usize) -> Option<u32> {
+        let start = self.start_tile_id();
+        let mut seen = vec![start];
+        let mut frontier = vec![start];
+        let mut distance = 0;
+        while !frontier.is_empty() {
+            if frontier.contains(&id) {
+                return Some(distance);
+            }
+            let mut next = Vec::new();
+            for tile in frontier.iter().filter_map(|t| self.tile(*t)) {
+                for n in &tile.next {
+                    if !seen.contains(n) {
+                        seen.push(*n);
+                        next.push(*n);
+                    }
+                }
+            }
+            frontier = next;
+            distance += 1;
+        }
+        None
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct Stock {
+    pub id: String,
+    pub name: String,
+}
+
+/// 株券1枚の購入価格
+pub const STOCK_PRICE: i64 = 10_000;
+
+/// 訴訟マスの請求額（マスに lawsuit イベントが無いとき）
+pub const LAWSUIT_AMOUNT: i64 = 100_000;
+
+/// 訴えられた側が手持ちで払いきれないときに借りてでも払う上限（loan_unit の口数）
+pub const LAWSUIT_LOAN_UNITS: u64 = 2;
+
+/// 選択肢を見送り続けたときの放置税（GameOptions::stall_limit）
+pub const IDLE_TAX: i64 = 5_000;
+
+/// 病院マスの治療費
+pub const HOSPITAL_FEE: i64 = 10_000;
+
+/// 子どもの人数の上限
+pub const MAX_CHILDREN: u8 = 6;
+
+/// 出産マスで双子が生まれる確率（%）
+pub const TWINS_PERCENT: u64 = 10;
+
+/// ゴールした順の退職金（1番目から。これより後は無し）
+pub const RETIREMENT_BONUSES: [i64; 4] = [40_000, 30_000, 20_000, 10_000];
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct PromissoryNote {
+    pub id: String,
+    #[ts(type = "number")]
+    pub amount: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct PlayerState {
+    pub id: PlayerId,
+    pub name: String,
+    #[ts(type = "number")]
+    pub money: i64,
+    pub career: Option<Career>,
+    pub salary: u32,
+    /// 職業の給料の段階（0 が初任給。職業が変わると 0 に戻る）
+    #[serde(default)]
+    pub salary_level: u32,
+    /// 続けて選択肢を見送った回数（何かを選ぶと 0 に戻る）
+    #[serde(default)]
+    pub consecutive_skips: u32,
+    pub married: bool,
+    pub children: u8,
+    pub life_insurance: bool,
+    pub auto_insurance: bool,
+    #[serde(default)]
+    pub fire_insurance: bool,
+    pub stocks: Vec<Stock>,
+    pub houses: Vec<House>,
+    #[ts(type = "number")]
+    pub debt: u64,
+    pub promissory_notes: Vec<PromissoryNote>,
+    pub position: usize,
+    pub retired: bool,
+    /// ゴールで選んだ老後の暮らし（選ぶ前やマスを通らずにゴールした場合は None）
+    #[serde(default)]
+    pub retirement_home: Option<RetirementHome>,
+    /// 周回数（スタートマスを通過した回数）
+    #[serde(default)]
+    pub laps: u32,
+    /// 現在の周回で受け取った給料日の回数
+    #[serde(default)]
+    pub paydays_this_lap: u32,
+    /// 接続中か（切断中のプレイヤーは手番を飛ばす）
+    #[serde(default = "default_connected")]
+    pub connected: bool,
+}
+
+impl PlayerState {
+    pub fn new(id: PlayerId, name: String, start_money: i64) -> Self {
+        Self {
+            id,
+            name,
+            money: start_money,
+            career: None,
+            salary: 0,
+            salary_level: 0,
+            consecutive_skips: 0,
+            married: false,
+            children: 0,
+            life_insurance: false,
+            auto_insurance: false,
+            fire_insurance: false,
+            stocks: Vec::new(),
+            houses: Vec::new(),
+            debt: 0,
+            promissory_notes: Vec::new(),
+            position: 0,
+            retired: false,
+            retirement_home: None,
+            laps: 0,
+            paydays_this_lap: 0,
+            connected: true,
+        }
+    }
+
+    /// 職業に就く（給料は初任給から）
+    pub fn assign_career(&mut self, career: Career) {
+        self.salary = career.salary;
+        self.salary_level = 0;
+        self.career = Some(career);
+    }
+
+    /// 給料を次の段階に上げる。上げられたら新しい給料を返す（職業の上限なら None）
+    pub fn promote(&mut self) -> Option<u32> {
+        let salary = self.career.as_ref()?.salary_at(self.salary_level + 1)?;
+        self.salary_level += 1;
+        // インフレで段階の給料を上回っていたら下げない
+        self.salary = self.salary.max(salary);
+        Some(self.salary)
+    }
+
+    /// Total assets for ranking: money + house sell prices + stocks at stock_price + promissory notes - debt with interest
+    pub fn total_assets(&self, interest_rate: f64, stock_price: i64) -> i64 {
+        self.asset_breakdown(interest_rate, stock_price).total()
+    }
+
+    /// total_assets の内訳
+    pub fn asset_breakdown(&self, interest_rate: f64, stock_price: i64) -> AssetBreakdown {
+        let mut breakdown = AssetBreakdown {
+            cash: self.money,
+            house_value: self.houses.iter().filter(|h| !h.mortgaged).map(|h| h.sell_price).sum(),
+            stock_value: self.stocks.len() as i64 * stock_price,
+            promissory_notes: self.promissory_notes.iter().map(|n| n.amount).sum(),
+            children_bonus: 0,
+            debt: (self.debt as f64 * interest_rate) as i64,
+            retirement: 0,
+        };
+        if let Some(home) = self.retirement_home {
+            breakdown.retirement = home.adjustment(breakdown.total());
+        }
+        breakdown
+    }
+}
+
+/// 資産の内訳（順位の根拠。結果画面で勝因を説明する）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct AssetBreakdown {
+    #[ts(type = "number")]
+    pub cash: i64,
+    /// 家の売却額の合計（抵当に入っている家は除く）
+    #[ts(type = "number")]
+    pub house_value: i64,
+    /// 株券の評価額（枚数 × その時点の株価）
+    #[ts(type = "number")]
+    pub stock_value: i64,
+    /// 約束手形の額面の合計
+    #[ts(type = "number")]
+    pub promissory_notes: i64,
+    /// 子どもの人数による加算（現行ルールでは無いので 0）
+    #[ts(type = "number")]
+    pub children_bonus: i64,
+    /// 利子込みの借金（合計から差し引く）
+    #[ts(type = "number")]
+    pub debt: i64,
+    /// ゴールで選んだ老後の暮らしによる増減
+    #[ts(type = "number")]
+    pub retirement: i64,
+}
+
+impl AssetBreakdown {
+    pub fn total(&self) -> i64 {
+        self.cash + self.house_value + self.stock_value + self.promissory_notes + self.children_bonus - self.debt
+            + self.retirement
+    }
+}
+
+/// 高級老人ホームで資産に加わる割合（%）と入居費
+pub const LUXURY_RETIREMENT_PERCENT: i64 = 20;
+pub const LUXURY_RETIREMENT_COST: i64 = 50_000;
+
+/// 質素な老後で資産に加わる金額
+pub const MODEST_RETIREMENT_BONUS: i64 = 10_000;
+
+/// ゴールで選ぶ老後の暮らし
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub enum RetirementHome {
+    /// 資産の LUXURY_RETIREMENT_PERCENT % が加わるが入居費を払う（資産が多いほど得）
+    Luxury,
+    /// MODEST_RETIREMENT_BONUS が加わる
+    Modest,
+}
+
+impl RetirementHome {
+    /// 老後の暮らしを除いた資産 total に対する増減
+    pub fn adjustment(self, total: i64) -> i64 {
+        match self {
+            RetirementHome::Luxury => total.max(0) * LUXURY_RETIREMENT_PERCENT / 100 - LUXURY_RETIREMENT_COST,
+            RetirementHome::Modest => MODEST_RETIREMENT_BONUS,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[ts(export)]
+pub enum TurnPhase {
+    WaitingForSpin,
+    Spinning,
+    Moving,
+    ResolvingEvent,
+    ChoosingPath,
+    ChoosingAction,
+    TurnEnd,
+}
+
+/// ゲーム開始時の初期化モード
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InitMode {
+    /// スタートマスから開始
+    #[default]
+    Standard,
+    /// 短縮ゲーム: マップの short_start_tile から職業付きで開始し、収入が2倍
+    Short,
+}
+
+/// 部屋作成時にホストが選ぶハウスルール
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameOptions {
+    pub init_mode: InitMode,
+    /// 全プレイヤー通算のターン数上限。到達したら現時点の資産で順位を決める
+    pub max_turn_count: Option<u32>,
+    /// 移動の途中でも分岐に着いたら止まって道を選ばせる
+    pub pause_at_branches: bool,
+    /// このターン数ごとに全員に影響する出来事（market）を起こす
+    pub global_event_interval: Option<u32>,
+    /// 選択肢を続けてこの回数より多く見送ると、見送るたびに IDLE_TAX を払う（引き延ばし対策）
+    pub stall_limit: Option<u32>,
+    /// 乱数の seed（同じ seed・マップ・参加者なら同じ操作に同じ結果になる）。None か 0 なら DEFAULT_SEED
+    pub seed: Option<u64>,
+}
+
+/// GameOptions::seed が無いときの seed
+pub const DEFAULT_SEED: u64 = 42;
+
+fn default_payout_multiplier() -> i64 {
+    1
+}
+
+/// next_random の1ステップ（Simple xorshift64）
+pub fn xorshift(mut x: u64) -> u64 {
+    x ^= x << 13;
+    x ^= x >> 7;
+    x ^= x << 17;
+    x
+}
+
+fn default_stock_price() -> i64 {
+    STOCK_PRICE
+}
+
+fn default_connected() -> bool {
+    true
+}
+
+/// ゲーム中に変化しない部分（盤面・職業・売り出し中の家）
+/// 操作のたびに GameState を複製しても共有されるよう Arc で持つ
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameCatalog {
+    pub board: Board,
+    pub careers: Vec<Career>,
+    pub houses_for_sale: Vec<House>,
+}
+
+impl GameCatalog {
+    pub fn from_map(map: &MapData) -> Self {
+        Self {
+            board: Board::from_map(map),
+            careers: map.careers.clone(),
+            houses_for_sale: map.houses.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameState {
+    pub players: Vec<PlayerState>,
+    /// シリアライズ時は従来どおり board / careers / houses_for_sale を GameState 直下に展開する
+    #[serde(flatten)]
+    pub catalog: Arc<GameCatalog>,
+    pub current_turn: usize,
+    pub phase: TurnPhase,
+    pub rng_seed: u64,
+    /// Reference to map data for interest rate, loan unit etc.
+    pub loan_unit: u64,
+    pub loan_interest_rate: f64,
+    /// 給料日・臨時収入の倍率（短縮ゲームでは2）
+    #[serde(default = "default_payout_multiplier")]
+    pub payout_multiplier: i64,
+    /// 現在のターン番号（全プレイヤー通算、1始まり）
+    #[serde(default)]
+    pub turn_number: u32,
+    #[serde(default)]
+    pub max_turns: Option<u32>,
+    #[serde(default)]
+    pub lap_rules: Option<LapRules>,
+    /// 分岐で止まった時点の残り歩数（ChoosingPath 中のみ）
+    #[serde(default)]
+    pub pending_steps: u32,
+    /// 移動再開時に分岐で進む道
+    #[serde(default)]
+    pub chosen_path: Option<usize>,
+    /// 提示中の選択肢（ChoosingPath / ChoosingAction 中のみ）
+    #[serde(default)]
+    pub pending_choice: Option<PendingChoice>,
+    /// ゲーム開始時刻（UNIX ミリ秒）。エンジンは時計を持たないので、ゲームを動かす側が設定する
+    #[serde(default)]
+    pub game_started_at: Option<u64>,
+    /// 減税中（次の全員向けの出来事まで税金マスの税額が半分）
+    #[serde(default)]
+    pub tax_cut: bool,
+    /// ゴールした順のプレイヤーID
+    #[serde(default)]
+    pub arrivals: Vec<PlayerId>,
+    /// 株券1枚の売却価格（好景気・株価暴落で上下する）
+    #[serde(default = "default_stock_price")]
+    pub stock_price: i64,
+    /// ゲーム開始時の seed（rng_seed は乱数を引くたびに変わる）
+    #[serde(default)]
+    pub seed: u64,
+}
+
+impl GameState {
+    pub fn current_player(&self) -> &PlayerState {
+        &self.players[self.current_turn]
+    }
+
+    pub fn current_player_mut(&mut self) -> &mut PlayerState {
+        &mut self.players[self.current_turn]
+    }
+
+    pub fn player_by_id(&self, id: &str) -> Option<&PlayerState> {
+        self.players.iter().find(|p| p.id == id)
+    }
+
+    pub fn player_by_id_mut(&mut self, id: &str) -> Option<&mut PlayerState> {
+        self.players.iter_mut().find(|p| p.id == id)
+    }
+
+    /// player_index の次の手番から順に見て、最初に能力を持つ職業に就いているプレイヤー
+    pub fn next_player_with_ability(&self, player_index: usize, ability: CareerAbility) -> Option<usize> {
+        let count = self.players.len();
+        (1..count)
+            .map(|offset| (player_index + offset) % count)
+            .find(|&i| self.players[i].career.as_ref().is_some_and(|c| c.has_ability(ability)))
+    }
+
+    /// 提示中の選択肢に id があるか。無ければ選べる ID の一覧を返す
+    /// （提示中の選択肢を持たない古い状態からの復元では検証しない）
+    pub fn check_choice(&self, id: &str) -> Result<(), Vec<String>> {
+        match &self.pending_choice {
+            Some(pending) if !pending.choices.iter().any(|c| c.id == id) => {
+                Err(pending.choices.iter().map(|c| c.id.clone()).collect())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// 操作で発生したイベントから pending_choice を更新する
+    /// 選択肢が提示されたら手番プレイヤーの選択として残し、選択待ちでなくなったら消す
+    pub fn update_pending_choice(&mut self, events: &[GameEvent]) {
+        let choices = events.iter().rev().find_map(|e| match e {
+            GameEvent::ChoiceRequired { choices } => Some(choices),
+            _ => None,
+        });
+        match choices {
+            Some(choices) => {
+                self.pending_choice = Some(PendingChoice {
+                    player_id: self.current_player().id.clone(),
+                    choices: choices.clone(),
+                })
+            }
+            None if !matches!(self.phase, TurnPhase::ChoosingPath | TurnPhase::ChoosingAction) => {
+                self.pending_choice = None
+            }
+            None => {}
+        }
+    }
+
+    /// amount を払うのに所持金が足りなければ、不足分を loan_unit 単位で借りる
+    /// 借りた場合はその MoneyChanged を返す
+    pub fn borrow_to_cover(&mut self, player_index: usize, amount: i64) -> Option<GameEvent> {
+        let shortfall = amount - self.players[player_index].money;
+        if shortfall <= 0 || self.loan_unit == 0 {
+            return None;
+        }
+        let loan = (shortfall as u64).div_ceil(self.loan_unit) * self.loan_unit;
+        let player = &mut self.players[player_index];
+        player.debt += loan;
+        player.money += loan as i64;
+        Some(GameEvent::MoneyChanged {
+            player_id: player.id.clone(),
+            amount: loan as i64,
+            reason: MoneyReason::Loan,
+        })
+    }
+
+    /// 訴訟で target が払う金額（手持ちの現金と、借りられる上限 LAWSUIT_LOAN_UNITS 口までが上限）
+    pub fn lawsuit_payment(&self, target_index: usize, amount: i64) -> i64 {
+        let limit = (self.loan_unit * LAWSUIT_LOAN_UNITS) as i64;
+        amount.min(self.players[target_index].money.max(0) + limit).max(0)
+    }
+
+    /// ゴールを記録し、着順に応じた退職金を渡す（退職金が無い着順なら MoneyChanged は無し）
+    pub fn record_arrival(&mut self, player_index: usize) -> Vec<GameEvent> {
+        let player = &mut self.players[player_index];
+        player.retired = true;
+        let mut events = vec![GameEvent::PlayerRetired {
+            player_id: player.id.clone(),
+        }];
+        self.arrivals.push(player.id.clone());
+        let order = self.arrivals.len();
+        if let Some(&bonus) = RETIREMENT_BONUSES.get(order - 1) {
+            player.money += bonus;
+            events.push(GameEvent::MoneyChanged {
+                player_id: player.id.clone(),
+                amount: bonus,
+                reason: MoneyReason::RetirementBonus { order: order as u32 },
+            });
+        }
+        events
+    }
+
+    /// 盤面上の進み具合（周回数、スタートからのマス数）。大きいほど先にいる
+    pub fn progress(&self, idx: usize) -> (u32, u32) {
+        let player = &self.players[idx];
+        let distance = self.catalog.board.distance_from_start(player.position).unwrap_or(0);
+        (player.laps, distance)
+    }
+
+    /// ゴールした順番（1始まり。未ゴールなら None）
+    pub fn arrival_order(&self, player_id: &str) -> Option<u32> {
+        self.arrivals.iter().position(|id| id == player_id).map(|i| i as u32 + 1)
+    }
+
+    /// 給料日に受け取る金額
+    pub fn payday_amount(&self, player_index: usize) -> i64 {
+        self.players[player_index].salary as i64 * self.payout_multiplier
+    }
+
+    /// 周回ルールの給料日上限に達しているか
+    pub fn payday_capped(&self, player_index: usize) -> bool {
+        self.lap_rules
+            .as_ref()
+            .and_then(|r| r.max_paydays_per_lap)
+            .is_some_and(|max| self.players[player_index].paydays_this_lap >= max)
+    }
+
+    /// マスのイベント金額（収入のみ倍率を掛ける）
+    pub fn scaled_payout(&self, amount: i64) -> i64 {
+        if amount > 0 {
+            amount * self.payout_multiplier
+        } else {
+            amount
+        }
+    }
+
+    pub fn active_player_count(&self) -> usize {
+        self.players.iter().filter(|p| !p.retired).count()
+    }
+
+    /// Advance the RNG seed and return a pseudo-random u64
+    pub fn next_random(&mut self) -> u64 {
+        self.rng_seed = xorshift(self.rng_seed);
+        self.rng_seed
+    }
+}
+
+// ============================================================
+// Action & Event types
+// ============================================================
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub enum PlayerAction {
+    BuyHouse { house_id: String },
+    BuyInsurance { insurance_type: InsuranceType },
+    SkipAction,
+    SelectLawsuitTarget { target_id: PlayerId },
+    RepayDebt,
+    BuyStock,
+    ChooseRetirement { home: RetirementHome },
+    /// 持っている株券を stock_price で売る（ルーレット前にいつでも。フェーズは変わらない）
+    SellStock { stock_id: String },
+    /// target と位置を入れ替える（入れ替えマスで選んだ相手）
+    SwapPosition { target_id: PlayerId },
+    /// 持っている家を抵当に入れる（ルーレット前にいつでも。フェーズは変わらない）
+    MortgageHouse { house_id: String },
+    /// 抵当に入れた家を買い戻す（ルーレット前にいつでも。フェーズは変わらない）
+    RedeemHouse { house_id: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub enum InsuranceType {
+    Life,
+    Auto,
+    Fire,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum GameEvent {
+    MoneyChanged {
+        player_id: PlayerId,
+        amount: i64,
+        reason: MoneyReason,
+    },
+    CareerAssigned {
+        player_id: PlayerId,
+        career: Career,
+    },
+    /// 昇給マスで給料の段階が上がった
+    SalaryRaised {
+        player_id: PlayerId,
+        salary: u32,
+        level: u32,
+    },
+    Married {
+        player_id: PlayerId,
+    },
+    BabyBorn {
+        player_id: PlayerId,
+        /// 生まれた後の子どもの人数
+        children: u8,
+        /// 今回生まれた人数（双子なら 2）
+        count: u8,
+    },
+    HousePurchased {
+        player_id: PlayerId,
+        house: House,
+    },
+    InsurancePurchased {
+        player_id: PlayerId,
+        insurance_type: InsuranceType,
+    },
+    StockPurchased {
+        player_id: PlayerId,
+        /// 買ったときの株価（stock_price）
+        price: i64,
+    },
+    PlayerRetired {
+        player_id: PlayerId,
+    },
+    /// 移動経路（出発マスを除き、通過・停止したマスIDを順に並べたもの）
+    PlayerMoved {
+        player_id: PlayerId,
+        path: Vec<usize>,
+        /// 通過した（停止マスを除く）給料日マスの数
+        paydays_passed: u32,
+    },
+    LapCompleted {
+        player_id: PlayerId,
+        laps: u32,
+    },
+    /// マスをたどらずに位置が変わった（入れ替え・追いつき）
+    PlayerTeleported {
+        player_id: PlayerId,
+        from: usize,
+        to: usize,
+    },
+    ChoiceRequired {
+        choices: Vec<GameChoice>,
+    },
+    /// landing_bonus のあるマスにちょうど止まった（続く MoneyChanged でボーナスを受け取る）
+    ExactLanding {
+        player_id: PlayerId,
+        tile_id: usize,
+    },
+    /// マスのスクリプトが emit() したメッセージ
+    ScriptMessage {
+        player_id: PlayerId,
+        text: String,
+    },
+    /// 全員に影響する出来事（お金の増減は続く MoneyChanged で通知する）
+    GlobalEvent {
+        kind: GlobalEventKind,
+    },
+}
+
+/// 選択待ちの内容（誰の選択か）
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct PendingChoice {
+    pub player_id: PlayerId,
+    pub choices: Vec<GameChoice>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct GameChoice {
+    pub id: String,
+    /// text の日本語表記（ローカライズ未対応のクライアント向け）
+    pub label: String,
+    pub text: ChoiceText,
+    /// 分岐の選択肢の場合、その道の先のマス
+    #[serde(default)]
+    pub preview: Vec<TilePreview>,
+    /// 選んだときに行う行動（分岐の道は null）
+    #[serde(default)]
+    pub action: Option<PlayerAction>,
+}
+
+impl GameChoice {
+    pub fn new(id: impl Into<String>, text: ChoiceText) -> Self {
+        Self {
+            id: id.into(),
+            label: text.fallback_text(),
+            text,
+            preview: Vec::new(),
+            action: None,
+        }
+    }
+
+    pub fn with_action(mut self, action: PlayerAction) -> Self {
+        self.action = Some(action);
+        self
+    }
+}
+
+/// 分岐の先にあるマスの概要
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct TilePreview {
+    pub tile_id: usize,
+    #[serde(rename = "type")]
+    pub tile_type: TileType,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpinResult {
+    pub player_id: PlayerId,
+    /// 進む歩数（spins の合計）
+    pub value: u32,
+    /// 各回の出目（スピードマスからは2回）
+    pub spins: Vec<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Ranking {
+    pub player_id: PlayerId,
+    pub player_name: String,
+    pub total_assets: i64,
+    pub breakdown: AssetBreakdown,
+    pub rank: u32,
+    /// ゴールした順番（未ゴールなら None）
+    pub arrival_order: Option<u32>,
+}
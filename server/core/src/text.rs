@@ -0,0 +1,291 @@
+//! クライアントでローカライズするための構造化メッセージ
+//!
+//! 理由や選択肢の文言はコードとパラメータで送り、表示はクライアント側で組み立てる。
+//! `fallback_text()` はローカライズ未対応のクライアント向けの日本語表記。
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use super::market::GlobalEventKind;
+
+/// お祝い金の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum GiftKind {
+    Wedding,
+    Birth,
+    Birthday,
+}
+
+impl GiftKind {
+    fn fallback_text(self) -> &'static str {
+        match self {
+            GiftKind::Wedding => "ご祝儀",
+            GiftKind::Birth => "出産祝い",
+            GiftKind::Birthday => "誕生日祝い",
+        }
+    }
+}
+
+/// 所持金が変動した理由
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(tag = "code", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum MoneyReason {
+    Payday,
+    PaydayPassed,
+    /// マップのマスに書かれた文言
+    TileEvent { text: String },
+    GiftPaid { kind: GiftKind },
+    GiftReceived { kind: GiftKind },
+    Tax,
+    LawsuitPaid,
+    LawsuitReceived,
+    HousePurchase { house_name: String },
+    DebtRepayment,
+    /// 病院マスの治療費（医者がいない場合は銀行へ）
+    TreatmentFee,
+    TreatmentPaid,
+    TreatmentReceived,
+    FireDamage,
+    /// 火災保険の支払い
+    InsuranceClaim,
+    /// 盗まれた（警察官がいない場合）
+    Theft,
+    /// 盗まれたお金を警察官が受け取った
+    TheftPaid,
+    TheftReward,
+    /// 支払いに足りない分を銀行から借りた
+    Loan,
+    /// landing_bonus のあるマスにちょうど止まった
+    LandingBonus,
+    /// ゴールした順（1始まり）に応じた退職金
+    RetirementBonus { order: u32 },
+    StockPurchase,
+    StockSale,
+    /// 選択肢を見送り続けた（GameOptions::stall_limit）
+    IdleTax,
+    /// 家を抵当に入れた
+    Mortgage { house_name: String },
+    /// 抵当に入れた家を買い戻した
+    MortgageRedemption { house_name: String },
+    /// マスのスクリプト（emit() したメッセージがあればその最後のもの）
+    Script { text: Option<String> },
+}
+
+impl MoneyReason {
+    pub fn fallback_text(&self) -> String {
+        match self {
+            MoneyReason::Payday => "給料日".to_string(),
+            MoneyReason::PaydayPassed => "給料日(通過)".to_string(),
+            MoneyReason::TileEvent { text } => text.clone(),
+            MoneyReason::GiftPaid { kind } => kind.fallback_text().to_string(),
+            MoneyReason::GiftReceived { kind } => format!("{}(受取)", kind.fallback_text()),
+            MoneyReason::Tax => "税金".to_string(),
+            MoneyReason::LawsuitPaid => "訴訟(支払い)".to_string(),
+            MoneyReason::LawsuitReceived => "訴訟(受取)".to_string(),
+            MoneyReason::HousePurchase { house_name } => format!("{}購入", house_name),
+            MoneyReason::DebtRepayment => "借金返済".to_string(),
+            MoneyReason::Loan => "借入".to_string(),
+            MoneyReason::LandingBonus => "ぴったりボーナス".to_string(),
+            MoneyReason::RetirementBonus { order } => format!("退職金({}着)", order),
+            MoneyReason::TreatmentFee | MoneyReason::TreatmentPaid => "治療費".to_string(),
+            MoneyReason::TreatmentReceived => "治療費(受取)".to_string(),
+            MoneyReason::FireDamage => "火事".to_string(),
+            MoneyReason::InsuranceClaim => "保険金".to_string(),
+            MoneyReason::Theft | MoneyReason::TheftPaid => "盗難".to_string(),
+            MoneyReason::TheftReward => "泥棒の逮捕".to_string(),
+            MoneyReason::StockPurchase => "株の購入".to_string(),
+            MoneyReason::StockSale => "株の売却".to_string(),
+            MoneyReason::IdleTax => "放置税".to_string(),
+            MoneyReason::Mortgage { house_name } => format!("{}を抵当に", house_name),
+            MoneyReason::MortgageRedemption { house_name } => format!("{}の買い戻し", house_name),
+            MoneyReason::Script { text } => text.clone().unwrap_or_else(|| "イベント".to_string()),
+        }
+    }
+}
+
+/// 選択肢の文言
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(tag = "code", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ChoiceText {
+    /// 分岐の道（index は0始まり。label はマップが付けた名前）
+    Path { index: usize, label: Option<String> },
+    BuyHouse {
+        house_name: String,
+        #[ts(type = "number")]
+        price: i64,
+        #[ts(type = "number")]
+        sell_price: i64,
+    },
+    SkipHouse,
+    LifeInsurance,
+    AutoInsurance,
+    FireInsurance,
+    SkipInsurance,
+    LuxuryRetirement,
+    ModestRetirement,
+    Sue { player_name: String },
+    SwapWith { player_name: String },
+}
+
+impl ChoiceText {
+    pub fn fallback_text(&self) -> String {
+        match self {
+            ChoiceText::Path { label: Some(label), .. } => label.clone(),
+            ChoiceText::Path { index, label: None } => format!("道 {}", index + 1),
+            ChoiceText::BuyHouse {
+                house_name,
+                price,
+                sell_price,
+            } => format!("{} (${} / 売却${})", house_name, price, sell_price),
+            ChoiceText::SkipHouse => "購入しない".to_string(),
+            ChoiceText::LifeInsurance => "生命保険に加入".to_string(),
+            ChoiceText::AutoInsurance => "自動車保険に加入".to_string(),
+            ChoiceText::FireInsurance => "火災保険に加入".to_string(),
+            ChoiceText::LuxuryRetirement => "高級老人ホームに入る".to_string(),
+            ChoiceText::ModestRetirement => "質素に暮らす".to_string(),
+            ChoiceText::SkipInsurance => "加入しない".to_string(),
+            ChoiceText::Sue { player_name } => format!("{}を訴える", player_name),
+            ChoiceText::SwapWith { player_name } => format!("{}と入れ替わる", player_name),
+        }
+    }
+}
+
+/// チャット欄に流すゲームの出来事（SystemMessage）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(tag = "code", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SystemText {
+    Married { player_name: String },
+    BabyBorn {
+        player_name: String,
+        children: u8,
+        /// 今回生まれた人数（双子なら 2）
+        count: u8,
+    },
+    /// plaintiff が defendant を訴えて amount を受け取った
+    Lawsuit {
+        plaintiff: String,
+        defendant: String,
+        #[ts(type = "number")]
+        amount: i64,
+    },
+    LoanRepaid {
+        player_name: String,
+        #[ts(type = "number")]
+        amount: i64,
+    },
+    LoanTaken {
+        player_name: String,
+        #[ts(type = "number")]
+        amount: i64,
+    },
+    Retired { player_name: String },
+    /// landing_bonus のあるマスにちょうど止まった
+    LandingBonus {
+        player_name: String,
+        #[ts(type = "number")]
+        amount: i64,
+    },
+    GlobalEvent { kind: GlobalEventKind },
+    /// 昇給マスで給料が上がった
+    SalaryRaised { player_name: String, salary: u32 },
+    /// 株券を1枚売った
+    StockSold {
+        player_name: String,
+        #[ts(type = "number")]
+        amount: i64,
+    },
+    /// 家を抵当に入れた
+    HouseMortgaged {
+        player_name: String,
+        house_name: String,
+        #[ts(type = "number")]
+        amount: i64,
+    },
+    /// 抵当に入れた家を買い戻した
+    HouseRedeemed {
+        player_name: String,
+        house_name: String,
+        #[ts(type = "number")]
+        amount: i64,
+    },
+}
+
+impl SystemText {
+    pub fn fallback_text(&self) -> String {
+        match self {
+            SystemText::Married { player_name } => format!("{}が結婚しました", player_name),
+            SystemText::BabyBorn {
+                player_name,
+                children,
+                count,
+            } => {
+                if *count > 1 {
+                    format!("{}に双子が生まれました（{}人目）", player_name, children)
+                } else {
+                    format!("{}に子どもが生まれました（{}人目）", player_name, children)
+                }
+            }
+            SystemText::Lawsuit {
+                plaintiff,
+                defendant,
+                amount,
+            } => format!("{}が{}を訴えて${}を受け取りました", plaintiff, defendant, amount),
+            SystemText::LoanRepaid { player_name, amount } => {
+                format!("{}が借金を返済しました（${}）", player_name, amount)
+            }
+            SystemText::LoanTaken { player_name, amount } => {
+                format!("{}が${}を借りました", player_name, amount)
+            }
+            SystemText::Retired { player_name } => format!("{}がゴールしました", player_name),
+            SystemText::LandingBonus { player_name, amount } => {
+                format!("{}がぴったり止まってボーナス${}を獲得しました", player_name, amount)
+            }
+            SystemText::GlobalEvent { kind } => kind.fallback_text().to_string(),
+            SystemText::SalaryRaised { player_name, salary } => {
+                format!("{}の給料が${}に上がりました", player_name, salary)
+            }
+            SystemText::StockSold { player_name, amount } => {
+                format!("{}が株券を${}で売りました", player_name, amount)
+            }
+            SystemText::HouseMortgaged {
+                player_name,
+                house_name,
+                amount,
+            } => format!("{}が{}を抵当に入れて${}を受け取りました", player_name, house_name, amount),
+            SystemText::HouseRedeemed {
+                player_name,
+                house_name,
+                amount,
+            } => format!("{}が{}を${}で買い戻しました", player_name, house_name, amount),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_codes_and_fallback_text() {
+        let reason = MoneyReason::GiftReceived {
+            kind: GiftKind::Wedding,
+        };
+        assert_eq!(
+            serde_json::to_value(&reason).unwrap(),
+            serde_json::json!({ "code": "GIFT_RECEIVED", "kind": "WEDDING" })
+        );
+        assert_eq!(reason.fallback_text(), "ご祝儀(受取)");
+
+        let choice = ChoiceText::Path { index: 1, label: None };
+        assert_eq!(
+            serde_json::to_value(&choice).unwrap(),
+            serde_json::json!({ "code": "PATH", "index": 1, "label": null })
+        );
+        assert_eq!(choice.fallback_text(), "道 2");
+    }
+}
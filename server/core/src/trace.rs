@@ -0,0 +1,270 @@
+//! 状態遷移のデバッグログ
+//!
+//! TracingEngine は別のエンジンを包み、状態を変える呼び出し（init / spin / advance / choose_path / resolve_action /
+//! end_turn / scheduled_events）ごとに、入力・消費した乱数・状態の差分・結果を1行の JSON（NDJSON）で書き出す。
+//! 「お金が消えた」のような報告を、どの操作でどの値が変わったかまで追えるようにするためのもので、通常は使わない。
+
+use std::io::Write;
+use std::sync::Mutex;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use super::state::*;
+use super::traits::GameEngine;
+use crate::PlayerId;
+
+/// 消費した乱数を辿る上限（これを超えたら乱数は不明として記録する）
+pub const MAX_TRACED_DRAWS: usize = 10_000;
+
+/// 状態の1か所の変化（path は "players/0/money" のような / 区切り）
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StateChange {
+    pub path: String,
+    pub before: Value,
+    pub after: Value,
+}
+
+/// ログの1行
+#[derive(Debug, Clone, Serialize)]
+pub struct TraceRecord {
+    /// 呼び出したエンジンのメソッド名
+    pub call: &'static str,
+    /// 呼び出し前のターン番号と手番のプレイヤー（init では None）
+    pub turn_number: Option<u32>,
+    pub player_id: Option<PlayerId>,
+    /// 引数（歩数・分岐の番号・行動など）
+    pub input: Value,
+    /// 消費した乱数（引いた順。辿れなければ None）
+    pub rng: Option<Vec<u64>>,
+    pub diff: Vec<StateChange>,
+    /// 戻り値のうち状態以外（ルーレットの結果・イベント）
+    pub output: Value,
+}
+
+/// 状態を変える呼び出しを out に記録するエンジン
+pub struct TracingEngine {
+    inner: Box<dyn GameEngine>,
+    out: Mutex<Box<dyn Write + Send>>,
+}
+
+impl TracingEngine {
+    pub fn new(inner: Box<dyn GameEngine>, out: Box<dyn Write + Send>) -> Self {
+        Self {
+            inner,
+            out: Mutex::new(out),
+        }
+    }
+
+    fn record(
+        &self,
+        call: &'static str,
+        before: Option<&GameState>,
+        after: &GameState,
+        input: Value,
+        output: Value,
+    ) {
+        let record = TraceRecord {
+            call,
+            turn_number: before.map(|s| s.turn_number),
+            player_id: before.and_then(|s| s.players.get(s.current_turn)).map(|p| p.id.clone()),
+            input,
+            rng: before.and_then(|s| rng_draws(s.rng_seed, after.rng_seed)),
+            diff: diff_states(before, after),
+            output,
+        };
+        let Ok(line) = serde_json::to_string(&record) else {
+            return;
+        };
+        let mut out = self.out.lock().unwrap_or_else(|e| e.into_inner());
+        if let Err(e) = writeln!(out, "{}", line).and_then(|_| out.flush()) {
+            eprintln!("failed to write debug log: {}", e);
+        }
+    }
+}
+
+fn to_json<T: Serialize>(value: &T) -> Value {
+    serde_json::to_value(value).unwrap_or(Value::Null)
+}
+
+impl GameEngine for TracingEngine {
+    fn init(&self, players: Vec<(PlayerId, String)>, map: &MapData) -> GameState {
+        let input = serde_json::json!({ "players": players, "map_id": map.id });
+        let state = self.inner.init(players, map);
+        self.record("init", None, &state, input, Value::Null);
+        state
+    }
+
+    fn spin(&self, state: &GameState) -> (GameState, SpinResult) {
+        let (new_state, result) = self.inner.spin(state);
+        self.record("spin", Some(state), &new_state, Value::Null, to_json(&result));
+        (new_state, result)
+    }
+
+    fn advance(&self, state: &GameState, steps: u32) -> (GameState, Vec<GameEvent>) {
+        let (new_state, events) = self.inner.advance(state, steps);
+        self.record("advance", Some(state), &new_state, steps.into(), to_json(&events));
+        (new_state, events)
+    }
+
+    fn choose_path(&self, state: &GameState, path_index: usize) -> GameState {
+        let new_state = self.inner.choose_path(state, path_index);
+        self.record("choose_path", Some(state), &new_state, path_index.into(), Value::Null);
+        new_state
+    }
+
+    fn legal_actions(&self, state: &GameState) -> Vec<PlayerAction> {
+        self.inner.legal_actions(state)
+    }
+
+    fn resolve_action(&self, state: &GameState, action: PlayerAction) -> (GameState, Vec<GameEvent>) {
+        let input = to_json(&action);
+        let (new_state, events) = self.inner.resolve_action(state, action);
+        self.record("resolve_action", Some(state), &new_state, input, to_json(&events));
+        (new_state, events)
+    }
+
+    fn end_turn(&self, state: &GameState) -> GameState {
+        let new_state = self.inner.end_turn(state);
+        self.record("end_turn", Some(state), &new_state, Value::Null, Value::Null);
+        new_state
+    }
+
+    fn scheduled_events(&self, state: &GameState) -> (GameState, Vec<GameEvent>) {
+        let (new_state, events) = self.inner.scheduled_events(state);
+        // 何も起こらなかった呼び出しは記録しない
+        if !events.is_empty() {
+            self.record("scheduled_events", Some(state), &new_state, Value::Null, to_json(&events));
+        }
+        (new_state, events)
+    }
+
+    fn is_finished(&self, state: &GameState) -> bool {
+        self.inner.is_finished(state)
+    }
+
+    fn rankings(&self, state: &GameState) -> Vec<Ranking> {
+        self.inner.rankings(state)
+    }
+}
+
+/// rng_seed が before から after になるまでに引いた乱数（GameState::next_random と同じ xorshift を辿る）
+pub fn rng_draws(before: u64, after: u64) -> Option<Vec<u64>> {
+    let mut seed = before;
+    let mut draws = Vec::new();
+    while seed != after {
+        if draws.len() >= MAX_TRACED_DRAWS {
+            return None;
+        }
+        seed = xorshift(seed);
+        draws.push(seed);
+    }
+    Some(draws)
+}
+
+/// 2つの状態の JSON の差分（値が変わった葉だけ。before が無ければ after 全体を1件として返す）
+pub fn diff_states(before: Option<&GameState>, after: &GameState) -> Vec<StateChange> {
+    let mut changes = Vec::new();
+    let before = before.map(to_json).unwrap_or(Value::Null);
+    diff_values(String::new(), &before, &to_json(after), &mut changes);
+    changes
+}
+
+fn diff_values(path: String, before: &Value, after: &Value, changes: &mut Vec<StateChange>) {
+    if before == after {
+        return;
+    }
+    let child = |key: &str| {
+        if path.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}/{}", path, key)
+        }
+    };
+    match (before, after) {
+        (Value::Object(b), Value::Object(a)) => {
+            for (key, value) in a {
+                diff_values(child(key), b.get(key).unwrap_or(&Value::Null), value, changes);
+            }
+            for (key, value) in b.iter().filter(|(key, _)| !a.contains_key(*key)) {
+                diff_values(child(key), value, &Value::Null, changes);
+            }
+        }
+        (Value::Array(b), Value::Array(a)) => {
+            for i in 0..b.len().max(a.len()) {
+                diff_values(
+                    child(&i.to_string()),
+                    b.get(i).unwrap_or(&Value::Null),
+                    a.get(i).unwrap_or(&Value::Null),
+                    changes,
+                );
+            }
+        }
+        _ => changes.push(StateChange {
+            path,
+            before: before.clone(),
+            after: after.clone(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::ClassicGameEngine;
+    use std::sync::Arc;
+
+    /// テスト用に書き出した内容を取り出せる Write
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_trace_records_rng_and_diff() {
+        let map: MapData = serde_json::from_value(serde_json::json!({
+            "id": "m", "name": "m", "version": "1.0",
+            "start_money": 10000, "loan_unit": 20000, "loan_interest_rate": 1.25,
+            "tiles": [
+                { "id": 0, "type": "Start", "position": { "x": 0, "y": 0 }, "next": [1] },
+                { "id": 1, "type": "Payday", "position": { "x": 1, "y": 0 }, "next": [2] },
+                { "id": 2, "type": "Retire", "position": { "x": 2, "y": 0 }, "next": [] }
+            ],
+            "careers": [], "houses": []
+        }))
+        .unwrap();
+        let buffer = SharedBuffer::default();
+        let engine = TracingEngine::new(Box::new(ClassicGameEngine::new()), Box::new(buffer.clone()));
+        let state = engine.init(
+            vec![("p1".to_string(), "Alice".to_string()), ("p2".to_string(), "Bob".to_string())],
+            &map,
+        );
+        let (state, result) = engine.spin(&state);
+        engine.advance(&state, result.value);
+
+        let log = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<Value> = log.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0]["call"], "init");
+        assert_eq!(lines[1]["call"], "spin");
+        assert_eq!(lines[1]["player_id"], "p1");
+        assert_eq!(lines[1]["rng"].as_array().unwrap().len(), 1);
+        assert_eq!(lines[2]["call"], "advance");
+        assert!(lines[2]["diff"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|c| c["path"] == "players/0/position"));
+
+        assert_eq!(rng_draws(7, 7), Some(Vec::new()));
+        assert_eq!(rng_draws(7, 8), None);
+    }
+}
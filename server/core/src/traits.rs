@@ -1,4 +1,4 @@
-use crate::protocol::PlayerId;
+use crate::PlayerId;
 
 use super::state::*;
 
@@ -17,12 +17,19 @@ pub trait GameEngine: Send + Sync {
     /// 分岐マスでの選択を処理
     fn choose_path(&self, state: &GameState, path_index: usize) -> GameState;
 
+    /// 手番プレイヤーが今選べる行動（ChoosingAction 以外では空）
+    /// 直前の ChoiceRequired で提示した選択肢と1対1に対応する
+    fn legal_actions(&self, state: &GameState) -> Vec<PlayerAction>;
+
     /// イベント選択（家購入、保険加入など）を処理
     fn resolve_action(&self, state: &GameState, action: PlayerAction) -> (GameState, Vec<GameEvent>);
 
     /// ターン終了処理（次のプレイヤーへ）
     fn end_turn(&self, state: &GameState) -> GameState;
 
+    /// end_turn の後に起こる、全員に影響する出来事（起こらなければイベントは空）
+    fn scheduled_events(&self, state: &GameState) -> (GameState, Vec<GameEvent>);
+
     /// ゲーム終了判定
     fn is_finished(&self, state: &GameState) -> bool;
 
@@ -30,6 +37,49 @@ pub trait GameEngine: Send + Sync {
     fn rankings(&self, state: &GameState) -> Vec<Ranking>;
 }
 
+/// Box に入れたエンジンもそのまま GameEngine として包めるようにする（CheckedEngine など）
+impl<E: GameEngine + ?Sized> GameEngine for Box<E> {
+    fn init(&self, players: Vec<(PlayerId, String)>, map: &MapData) -> GameState {
+        (**self).init(players, map)
+    }
+
+    fn spin(&self, state: &GameState) -> (GameState, SpinResult) {
+        (**self).spin(state)
+    }
+
+    fn advance(&self, state: &GameState, steps: u32) -> (GameState, Vec<GameEvent>) {
+        (**self).advance(state, steps)
+    }
+
+    fn choose_path(&self, state: &GameState, path_index: usize) -> GameState {
+        (**self).choose_path(state, path_index)
+    }
+
+    fn legal_actions(&self, state: &GameState) -> Vec<PlayerAction> {
+        (**self).legal_actions(state)
+    }
+
+    fn resolve_action(&self, state: &GameState, action: PlayerAction) -> (GameState, Vec<GameEvent>) {
+        (**self).resolve_action(state, action)
+    }
+
+    fn end_turn(&self, state: &GameState) -> GameState {
+        (**self).end_turn(state)
+    }
+
+    fn scheduled_events(&self, state: &GameState) -> (GameState, Vec<GameEvent>) {
+        (**self).scheduled_events(state)
+    }
+
+    fn is_finished(&self, state: &GameState) -> bool {
+        (**self).is_finished(state)
+    }
+
+    fn rankings(&self, state: &GameState) -> Vec<Ranking> {
+        (**self).rankings(state)
+    }
+}
+
 /// イベント処理の拡張トレイト
 pub trait EventResolver: Send + Sync {
     /// マスに止まった時のイベントを解決
@@ -0,0 +1,404 @@
+//! 負荷試験用シミュレーター
+//!
+//! 実際の WebSocket 接続で部屋を作成し、各プレイヤーがランダムな合法手でゲームを最後まで進める。
+//! 操作を送ってから次の GameSync を受け取るまでの時間を計測し、スループットとレイテンシを出力する。
+//!
+//! ```text
+//! cargo run --release --bin simulator -- --url ws://localhost:3000/ws --rooms 50 --players 4
+//! ```
+//!
+//! `balance` サブコマンドはサーバーを使わずにボット同士のゲームを回し（game::simulate）、
+//! 手番順ごとの勝率・平均ターン数・最終資産の分布を出力する。--map は組み込みマップのIDかマップファイルのパス。
+//!
+//! ```text
+//! cargo run --release --bin simulator -- balance --map classic --games 5000 --bots greedy,cautious,random
+//! ```
+
+use std::time::{Duration, Instant};
+
+use futures_util::{SinkExt, StreamExt};
+use rand::RngExt;
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
+
+use nine_life_server::game::simulate::{simulate_with_options, BotStrategy};
+use nine_life_server::game::{GameOptions, TurnPhase};
+use nine_life_server::map::loader::load_file;
+use nine_life_server::map::MapCatalog;
+use nine_life_server::protocol::{Choice, ClientMessage, ServerMessage};
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+struct Options {
+    url: String,
+    rooms: usize,
+    players: usize,
+    /// 1部屋あたりの制限時間
+    timeout: Duration,
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut options = Options {
+        url: "ws://localhost:3000/ws".to_string(),
+        rooms: 10,
+        players: 4,
+        timeout: Duration::from_secs(120),
+    };
+    let mut args = std::env::args().skip(1);
+    while let Some(flag) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| format!("missing value for {}", flag))?;
+        let number = || {
+            value
+                .parse::<usize>()
+                .map_err(|_| format!("invalid value for {}: {}", flag, value))
+        };
+        match flag.as_str() {
+            "--url" => options.url = value.clone(),
+            "--rooms" => options.rooms = number()?,
+            "--players" => options.players = number()?.max(2),
+            "--timeout" => options.timeout = Duration::from_secs(number()? as u64),
+            _ => return Err(format!("unknown option: {}", flag)),
+        }
+    }
+    Ok(options)
+}
+
+async fn connect(url: &str) -> Result<Socket, String> {
+    connect_async(url)
+        .await
+        .map(|(socket, _)| socket)
+        .map_err(|e| format!("connect failed: {}", e))
+}
+
+async fn send(socket: &mut Socket, msg: &ClientMessage) -> Result<(), String> {
+    let text = serde_json::to_string(msg).map_err(|e| e.to_string())?;
+    socket
+        .send(Message::Text(text.into()))
+        .await
+        .map_err(|e| format!("send failed: {}", e))
+}
+
+/// 次のサーバーメッセージを受信する（テキスト以外は読み飛ばす）
+async fn recv(socket: &mut Socket) -> Result<ServerMessage, String> {
+    loop {
+        match socket.next().await {
+            Some(Ok(Message::Text(text))) => {
+                return serde_json::from_str(&text)
+                    .map_err(|e| format!("invalid message: {}: {}", e, text));
+            }
+            Some(Ok(Message::Close(_))) | None => return Err("connection closed".to_string()),
+            Some(Ok(_)) => continue,
+            Some(Err(e)) => return Err(format!("receive failed: {}", e)),
+        }
+    }
+}
+
+fn random_index(len: usize) -> usize {
+    rand::rng().random_range(0..len.max(1))
+}
+
+#[derive(Default)]
+struct Stats {
+    actions: usize,
+    latencies: Vec<Duration>,
+}
+
+/// 自分の手番ではランダムな合法手を送り、ゲーム終了まで進める
+async fn play(mut socket: Socket, player_id: String) -> Result<Stats, String> {
+    let mut stats = Stats::default();
+    let mut choices: Vec<Choice> = Vec::new();
+    let mut sent_at: Option<Instant> = None;
+
+    loop {
+        match recv(&mut socket).await? {
+            ServerMessage::ChoiceRequired { choices: c } => choices = c,
+            ServerMessage::GameEnded { .. } => return Ok(stats),
+            ServerMessage::Error { code, message, .. } => return Err(format!("{}: {}", code, message)),
+            ServerMessage::GameSync {
+                players,
+                current_turn,
+                phase,
+                ..
+            } => {
+                if let Some(sent) = sent_at.take() {
+                    stats.latencies.push(sent.elapsed());
+                }
+                if players.get(current_turn).map(|p| p.id.as_str()) != Some(player_id.as_str()) {
+                    continue;
+                }
+                let action = match phase {
+                    TurnPhase::WaitingForSpin => ClientMessage::SpinRoulette { action_token: None },
+                    TurnPhase::ChoosingPath => ClientMessage::ChoicePath {
+                        path_index: random_index(choices.len()),
+                        action_token: None,
+                    },
+                    TurnPhase::ChoosingAction if !choices.is_empty() => {
+                        ClientMessage::ChoiceAction {
+                            action_id: choices[random_index(choices.len())].id.clone(),
+                            action_token: None,
+                        }
+                    }
+                    _ => continue,
+                };
+                send(&mut socket, &action).await?;
+                stats.actions += 1;
+                sent_at = Some(Instant::now());
+            }
+            _ => {}
+        }
+    }
+}
+
+/// 部屋を作成して全員を参加させ、ゲームを1回最後まで行う
+async fn run_room(url: String, players: usize) -> Result<Stats, String> {
+    let mut host = connect(&url).await?;
+    send(
+        &mut host,
+        &ClientMessage::CreateRoom {
+            player_name: "sim-host".to_string(),
+            map_id: "classic".to_string(),
+            private: false,
+            max_players: Some(players),
+            series_games: None,
+            short_game: false,
+            max_turn_count: None,
+            pause_at_branches: false,
+            rules_variant: None,
+            spectator_delay_secs: None,
+            global_event_interval: None,
+            stall_limit: None,
+            seed: None,
+            title: None,
+            tags: Vec::new(),
+            language: None,
+        },
+    )
+    .await?;
+    let (room_id, host_id) = loop {
+        if let ServerMessage::RoomCreated {
+            room_id, player_id, ..
+        } = recv(&mut host).await?
+        {
+            break (room_id, player_id);
+        }
+    };
+
+    let mut clients = vec![(host, host_id)];
+    for i in 1..players {
+        let mut guest = connect(&url).await?;
+        send(
+            &mut guest,
+            &ClientMessage::JoinRoom {
+                room_id: room_id.clone(),
+                player_name: format!("sim-{}", i),
+                token: None,
+                spectate: false,
+            },
+        )
+        .await?;
+        let player_id = loop {
+            match recv(&mut guest).await? {
+                ServerMessage::RoomState { player_id, .. } => break player_id,
+                ServerMessage::Error { code, message, .. } => {
+                    return Err(format!("{}: {}", code, message))
+                }
+                _ => {}
+            }
+        };
+        clients.push((guest, player_id));
+    }
+
+    send(&mut clients[0].0, &ClientMessage::StartGame).await?;
+
+    let results = futures_util::future::join_all(
+        clients
+            .into_iter()
+            .map(|(socket, player_id)| play(socket, player_id)),
+    )
+    .await;
+
+    let mut stats = Stats::default();
+    for result in results {
+        let player = result?;
+        stats.actions += player.actions;
+        stats.latencies.extend(player.latencies);
+    }
+    Ok(stats)
+}
+
+fn percentile(sorted: &[Duration], p: f64) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let idx = ((sorted.len() - 1) as f64 * p).round() as usize;
+    sorted[idx]
+}
+
+struct BalanceOptions {
+    map: String,
+    games: u32,
+    seed: u64,
+    bots: Vec<BotStrategy>,
+    game_options: GameOptions,
+}
+
+fn parse_balance_args(args: impl Iterator<Item = String>) -> Result<BalanceOptions, String> {
+    let mut options = BalanceOptions {
+        map: "classic".to_string(),
+        games: 1000,
+        seed: 1,
+        bots: vec![BotStrategy::Random; 4],
+        game_options: GameOptions::default(),
+    };
+    let mut args = args;
+    while let Some(flag) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| format!("missing value for {}", flag))?;
+        let number = || {
+            value
+                .parse::<u64>()
+                .map_err(|_| format!("invalid value for {}: {}", flag, value))
+        };
+        match flag.as_str() {
+            "--map" => options.map = value.clone(),
+            "--games" => options.games = number()? as u32,
+            "--seed" => options.seed = number()?,
+            "--bots" => {
+                options.bots = value
+                    .split(',')
+                    .map(|s| s.trim().parse())
+                    .collect::<Result<_, _>>()?
+            }
+            "--max-turns" => options.game_options.max_turn_count = Some(number()? as u32),
+            "--global-events" => options.game_options.global_event_interval = Some(number()? as u32),
+            "--stall-limit" => options.game_options.stall_limit = Some(number()? as u32),
+            _ => return Err(format!("unknown option: {}", flag)),
+        }
+    }
+    Ok(options)
+}
+
+/// balance サブコマンド: ボット同士のゲームを回して集計を出力する
+fn run_balance(options: BalanceOptions) -> Result<(), String> {
+    let map = match MapCatalog::builtin().get(&options.map) {
+        Some(map) => map.clone(),
+        None => load_file(std::path::Path::new(&options.map))?,
+    };
+    println!(
+        "simulating {} game(s) on {} with {:?} (seed {})",
+        options.games, map.id, options.bots, options.seed
+    );
+    let started = Instant::now();
+    let report = simulate_with_options(
+        &map,
+        options.game_options,
+        options.seed,
+        &options.bots,
+        options.games,
+    )?;
+    println!(
+        "games: {} finished, {} unfinished in {:.2?}",
+        report.games - report.unfinished,
+        report.unfinished,
+        started.elapsed()
+    );
+    for (seat, (wins, rate)) in report.wins_by_seat.iter().zip(&report.win_rate_by_seat).enumerate() {
+        println!("seat {} ({:?}): {} win(s), {:.1}%", seat, options.bots[seat], wins, rate * 100.0);
+    }
+    println!("average turns: {:.1}", report.average_turns);
+    let assets = &report.final_assets;
+    println!(
+        "final assets: min {} / p10 {} / median {} / p90 {} / max {} / mean {:.0}",
+        assets.min, assets.p10, assets.median, assets.p90, assets.max, assets.mean
+    );
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() {
+    let mut args = std::env::args().skip(1).peekable();
+    if args.peek().map(String::as_str) == Some("balance") {
+        args.next();
+        let result = parse_balance_args(args).and_then(run_balance);
+        if let Err(e) = result {
+            eprintln!("{}", e);
+            eprintln!(
+                "usage: simulator balance [--map ID|PATH] [--games N] [--seed N] [--bots random,greedy,cautious] \
+[--max-turns N] [--global-events N] [--stall-limit N]"
+            );
+            std::process::exit(2);
+        }
+        return;
+    }
+
+    let options = match parse_args() {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}", e);
+            eprintln!(
+                "usage: simulator [--url ws://host/ws] [--rooms N] [--players N] [--timeout SECS]"
+            );
+            std::process::exit(2);
+        }
+    };
+
+    println!(
+        "simulating {} room(s) x {} player(s) against {}",
+        options.rooms, options.players, options.url
+    );
+    let started = Instant::now();
+    let handles: Vec<_> = (0..options.rooms)
+        .map(|_| {
+            let url = options.url.clone();
+            let (players, timeout) = (options.players, options.timeout);
+            tokio::spawn(async move {
+                tokio::time::timeout(timeout, run_room(url, players))
+                    .await
+                    .unwrap_or_else(|_| Err("timed out".to_string()))
+            })
+        })
+        .collect();
+
+    let mut total = Stats::default();
+    let mut failed = 0;
+    for (i, handle) in handles.into_iter().enumerate() {
+        match handle.await.map_err(|e| e.to_string()).and_then(|r| r) {
+            Ok(stats) => {
+                total.actions += stats.actions;
+                total.latencies.extend(stats.latencies);
+            }
+            Err(e) => {
+                failed += 1;
+                eprintln!("room #{} failed: {}", i, e);
+            }
+        }
+    }
+    let elapsed = started.elapsed();
+
+    total.latencies.sort();
+    println!(
+        "rooms: {} completed, {} failed in {:.2}s",
+        options.rooms - failed,
+        failed,
+        elapsed.as_secs_f64()
+    );
+    println!(
+        "actions: {} ({:.1}/s)",
+        total.actions,
+        total.actions as f64 / elapsed.as_secs_f64()
+    );
+    println!(
+        "latency: p50 {:?} / p90 {:?} / p99 {:?} / max {:?}",
+        percentile(&total.latencies, 0.50),
+        percentile(&total.latencies, 0.90),
+        percentile(&total.latencies, 0.99),
+        total.latencies.last().copied().unwrap_or_default()
+    );
+    if failed > 0 {
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,34 @@
+//! 複数ノード間のブロードキャスト
+//!
+//! RoomManager::broadcast は自ノードの接続に配ったうえで、同じメッセージを Broadcaster にも渡す。
+//! 既定の LocalBroadcaster は何もしない（単一ノード）。BROADCAST_URL を設定すると RedisBroadcaster が
+//! 部屋ごとのチャンネルに流し、他のノードは受け取ったメッセージを自ノードにいる同じ部屋の接続に配る。
+//! 部屋の状態はまだ各ノードが別々に持つため、ロードバランサーの背後で複数台を動かすための最初の一歩に当たる。
+
+pub mod redis;
+
+use crate::protocol::RoomId;
+use crate::transport::traits::EncodedMessage;
+
+pub use redis::RedisBroadcaster;
+
+/// 他のノードから届いた部屋宛てのメッセージ
+#[derive(Debug, Clone)]
+pub struct RemoteMessage {
+    pub room_id: RoomId,
+    pub msg: EncodedMessage,
+}
+
+/// 部屋宛てのメッセージを他のノードに流す
+/// 送信は呼び出し元を待たせない（失敗しても自ノードへの配信には影響しない）
+pub trait Broadcaster: Send + Sync {
+    fn publish(&self, room_id: &str, msg: &EncodedMessage);
+}
+
+/// 単一ノード用（他のノードには何も流さない）
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LocalBroadcaster;
+
+impl Broadcaster for LocalBroadcaster {
+    fn publish(&self, _room_id: &str, _msg: &EncodedMessage) {}
+}
@@ -0,0 +1,380 @@
+//! Redis の Pub/Sub を使う Broadcaster
+//!
+//! RESP（Redis のプロトコル）を直接話す最小限のクライアントで、使うのは AUTH / PUBLISH / PSUBSCRIBE だけ。
+//! 部屋ごとに CHANNEL_PREFIX + 部屋ID のチャンネルへ流し、受信側は CHANNEL_PREFIX* をまとめて購読する。
+//! 流す内容は「送信元ノードID 空白 GameSyncなら1/それ以外0 改行 エンコード済みの JSON」。
+
+use std::time::Duration;
+
+use bytes::Bytes;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+
+use super::{Broadcaster, RemoteMessage};
+use crate::transport::traits::EncodedMessage;
+
+/// 部屋ごとのチャンネル名の接頭辞
+pub const CHANNEL_PREFIX: &str = "nine-life:room:";
+
+/// 送信待ちの上限（Redis が詰まったら超えた分は捨てる）
+const PUBLISH_QUEUE: usize = 1024;
+
+/// 受信したメッセージを RoomManager に渡すまでの待ち行列の長さ
+const RECEIVE_QUEUE: usize = 1024;
+
+/// 接続・コマンドの応答待ちのタイムアウト
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 購読が切れてから繋ぎ直すまでの待ち時間
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// 応答の1行（型と長さ）の上限
+const MAX_LINE_LEN: u64 = 1024;
+
+/// 受け取るバルク文字列の上限
+const MAX_BULK_LEN: usize = 16 * 1024 * 1024;
+
+/// 接続先（redis://[[ユーザー]:パスワード@]ホスト[:ポート]）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RedisUrl {
+    pub host: String,
+    pub port: u16,
+    pub username: Option<String>,
+    pub password: Option<String>,
+}
+
+impl RedisUrl {
+    pub fn parse(url: &str) -> Result<Self, String> {
+        let rest = url
+            .strip_prefix("redis://")
+            .ok_or_else(|| format!("unsupported url (redis:// only): {}", url))?
+            .trim_end_matches('/');
+        let (userinfo, authority) = match rest.rsplit_once('@') {
+            Some((userinfo, authority)) => (Some(userinfo), authority),
+            None => (None, rest),
+        };
+        let (username, password) = match userinfo.map(|u| u.split_once(':').unwrap_or(("", u))) {
+            Some((user, password)) => (
+                Some(user.to_string()).filter(|u| !u.is_empty()),
+                Some(password.to_string()).filter(|p| !p.is_empty()),
+            ),
+            None => (None, None),
+        };
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => (
+                host,
+                port.parse()
+                    .map_err(|_| format!("invalid port in url: {}", url))?,
+            ),
+            None => (authority, 6379),
+        };
+        if host.is_empty() {
+            return Err(format!("missing host in url: {}", url));
+        }
+        Ok(Self {
+            host: host.to_string(),
+            port,
+            username,
+            password,
+        })
+    }
+}
+
+/// Redis の応答
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reply {
+    Status(String),
+    Error(String),
+    Integer(i64),
+    Bulk(Vec<u8>),
+    Nil,
+    Array(Vec<Reply>),
+}
+
+/// コマンドを RESP の配列にする
+pub fn encode_command(args: &[&[u8]]) -> Vec<u8> {
+    let mut out = format!("*{}\r\n", args.len()).into_bytes();
+    for arg in args {
+        out.extend_from_slice(format!("${}\r\n", arg.len()).as_bytes());
+        out.extend_from_slice(arg);
+        out.extend_from_slice(b"\r\n");
+    }
+    out
+}
+
+/// 応答を1つ読む（Pub/Sub の応答は入れ子にならないので、配列の中の配列は扱わない）
+pub async fn read_reply<R: AsyncBufRead + Unpin>(reader: &mut R) -> Result<Reply, String> {
+    let (kind, line) = read_line(reader).await?;
+    if kind != b'*' {
+        return read_scalar(reader, kind, line).await;
+    }
+    let len: i64 = line.parse().map_err(|_| format!("invalid array length: {}", line))?;
+    if len < 0 {
+        return Ok(Reply::Nil);
+    }
+    let mut items = Vec::new();
+    for _ in 0..len {
+        let (kind, line) = read_line(reader).await?;
+        if kind == b'*' {
+            return Err("nested arrays are not supported".to_string());
+        }
+        items.push(read_scalar(reader, kind, line).await?);
+    }
+    Ok(Reply::Array(items))
+}
+
+/// 型を表す1文字と、行の残り
+async fn read_line<R: AsyncBufRead + Unpin>(reader: &mut R) -> Result<(u8, String), String> {
+    let mut line = Vec::new();
+    (&mut *reader)
+        .take(MAX_LINE_LEN)
+        .read_until(b'\n', &mut line)
+        .await
+        .map_err(|e| e.to_string())?;
+    if line.is_empty() {
+        return Err("connection closed".to_string());
+    }
+    let Some(line) = line.strip_suffix(b"\r\n") else {
+        return Err("reply line too long or truncated".to_string());
+    };
+    let Some((&kind, rest)) = line.split_first() else {
+        return Err("empty reply line".to_string());
+    };
+    Ok((kind, String::from_utf8_lossy(rest).into_owned()))
+}
+
+async fn read_scalar<R: AsyncBufRead + Unpin>(reader: &mut R, kind: u8, line: String) -> Result<Reply, String> {
+    match kind {
+        b'+' => Ok(Reply::Status(line)),
+        b'-' => Ok(Reply::Error(line)),
+        b':' => line
+            .parse()
+            .map(Reply::Integer)
+            .map_err(|_| format!("invalid integer reply: {}", line)),
+        b'$' => {
+            let len: i64 = line.parse().map_err(|_| format!("invalid bulk length: {}", line))?;
+            if len < 0 {
+                return Ok(Reply::Nil);
+            }
+            let len = len as usize;
+            if len > MAX_BULK_LEN {
+                return Err(format!("bulk reply too large: {} bytes", len));
+            }
+            let mut buf = vec![0u8; len + 2];
+            reader.read_exact(&mut buf).await.map_err(|e| e.to_string())?;
+            if !buf.ends_with(b"\r\n") {
+                return Err("bulk reply not terminated".to_string());
+            }
+            buf.truncate(len);
+            Ok(Reply::Bulk(buf))
+        }
+        _ => Err(format!("unexpected reply type: {}", kind as char)),
+    }
+}
+
+/// チャンネルに流す内容
+fn encode_payload(node_id: &str, msg: &EncodedMessage) -> Vec<u8> {
+    let mut out = format!("{} {}\n", node_id, u8::from(msg.is_game_sync())).into_bytes();
+    out.extend_from_slice(msg.json());
+    out
+}
+
+/// 送信元ノードIDとメッセージ
+fn decode_payload(payload: &[u8]) -> Option<(&str, EncodedMessage)> {
+    let newline = payload.iter().position(|&b| b == b'\n')?;
+    let header = std::str::from_utf8(&payload[..newline]).ok()?;
+    let (node_id, game_sync) = header.split_once(' ')?;
+    let json = Bytes::copy_from_slice(&payload[newline + 1..]);
+    Some((node_id, EncodedMessage::from_json(json, game_sync == "1")))
+}
+
+/// 購読中に届いた pmessage を RemoteMessage にする（自ノードが流したもの・読めないものは None）
+fn remote_message(reply: Reply, node_id: &str) -> Option<RemoteMessage> {
+    let Reply::Array(items) = reply else {
+        return None;
+    };
+    let [Reply::Bulk(kind), _, Reply::Bulk(channel), Reply::Bulk(payload)] = items.as_slice() else {
+        return None;
+    };
+    if kind != b"pmessage" {
+        return None;
+    }
+    let room_id = std::str::from_utf8(channel).ok()?.strip_prefix(CHANNEL_PREFIX)?;
+    let (origin, msg) = decode_payload(payload)?;
+    (origin != node_id).then(|| RemoteMessage {
+        room_id: room_id.to_string(),
+        msg,
+    })
+}
+
+async fn connect(url: &RedisUrl) -> Result<BufReader<TcpStream>, String> {
+    let stream = tokio::time::timeout(TIMEOUT, TcpStream::connect((url.host.as_str(), url.port)))
+        .await
+        .map_err(|_| "timed out".to_string())?
+        .map_err(|e| e.to_string())?;
+    let mut conn = BufReader::new(stream);
+    if let Some(password) = &url.password {
+        let mut args: Vec<&[u8]> = vec![b"AUTH"];
+        if let Some(username) = &url.username {
+            args.push(username.as_bytes());
+        }
+        args.push(password.as_bytes());
+        command(&mut conn, &args).await?;
+    }
+    Ok(conn)
+}
+
+/// コマンドを送って応答を1つ読む（エラー応答は Err）
+async fn command(conn: &mut BufReader<TcpStream>, args: &[&[u8]]) -> Result<Reply, String> {
+    conn.get_mut()
+        .write_all(&encode_command(args))
+        .await
+        .map_err(|e| e.to_string())?;
+    let reply = tokio::time::timeout(TIMEOUT, read_reply(conn))
+        .await
+        .map_err(|_| "timed out".to_string())??;
+    match reply {
+        Reply::Error(e) => Err(e),
+        reply => Ok(reply),
+    }
+}
+
+/// Redis の Pub/Sub で他のノードとメッセージをやり取りする
+/// 送信用の接続はバックグラウンドタスクが持ち、切れたら次の送信時に繋ぎ直す
+pub struct RedisBroadcaster {
+    url: RedisUrl,
+    /// このノードの識別子（自分が流したメッセージを受信側で読み飛ばす）
+    node_id: String,
+    queue: mpsc::Sender<(String, Vec<u8>)>,
+}
+
+impl RedisBroadcaster {
+    /// URL を確かめて送信タスクを起動する（接続は最初の送信時）
+    pub fn new(url: &str) -> Result<Self, String> {
+        let url = RedisUrl::parse(url)?;
+        let (queue, rx) = mpsc::channel(PUBLISH_QUEUE);
+        tokio::spawn(run_publisher(url.clone(), rx));
+        Ok(Self {
+            url,
+            node_id: uuid::Uuid::new_v4().simple().to_string(),
+            queue,
+        })
+    }
+
+    pub fn node_id(&self) -> &str {
+        &self.node_id
+    }
+
+    /// 他のノードが流したメッセージの受信を始める（切れたら RECONNECT_DELAY 後に繋ぎ直す）
+    pub fn subscribe(&self) -> mpsc::Receiver<RemoteMessage> {
+        let (tx, rx) = mpsc::channel(RECEIVE_QUEUE);
+        tokio::spawn(run_subscriber(self.url.clone(), self.node_id.clone(), tx));
+        rx
+    }
+}
+
+impl Broadcaster for RedisBroadcaster {
+    fn publish(&self, room_id: &str, msg: &EncodedMessage) {
+        let channel = format!("{}{}", CHANNEL_PREFIX, room_id);
+        if let Err(mpsc::error::TrySendError::Full(_)) = self.queue.try_send((channel, encode_payload(&self.node_id, msg))) {
+            eprintln!("broadcast queue is full, dropped a message for room {}", room_id);
+        }
+    }
+}
+
+async fn run_publisher(url: RedisUrl, mut rx: mpsc::Receiver<(String, Vec<u8>)>) {
+    let mut conn = None;
+    while let Some((channel, payload)) = rx.recv().await {
+        if conn.is_none() {
+            match connect(&url).await {
+                Ok(c) => conn = Some(c),
+                Err(e) => {
+                    eprintln!("broadcast publish to {}:{} failed: {}", url.host, url.port, e);
+                    continue;
+                }
+            }
+        }
+        let Some(c) = conn.as_mut() else {
+            continue;
+        };
+        if let Err(e) = command(c, &[b"PUBLISH", channel.as_bytes(), &payload]).await {
+            eprintln!("broadcast publish to {}:{} failed: {}", url.host, url.port, e);
+            conn = None;
+        }
+    }
+}
+
+async fn run_subscriber(url: RedisUrl, node_id: String, tx: mpsc::Sender<RemoteMessage>) {
+    loop {
+        match subscribe_once(&url, &node_id, &tx).await {
+            // 受け取り側が無くなった
+            Ok(()) => return,
+            Err(e) => eprintln!("broadcast subscription to {}:{} lost: {}", url.host, url.port, e),
+        }
+        tokio::time::sleep(RECONNECT_DELAY).await;
+    }
+}
+
+async fn subscribe_once(url: &RedisUrl, node_id: &str, tx: &mpsc::Sender<RemoteMessage>) -> Result<(), String> {
+    let mut conn = connect(url).await?;
+    let pattern = format!("{}*", CHANNEL_PREFIX);
+    command(&mut conn, &[b"PSUBSCRIBE", pattern.as_bytes()]).await?;
+    loop {
+        // 購読中は次のメッセージまで待ち続ける（タイムアウトしない）
+        let reply = read_reply(&mut conn).await?;
+        let Some(message) = remote_message(reply, node_id) else {
+            continue;
+        };
+        if tx.send(message).await.is_err() {
+            return Ok(());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::ServerMessage;
+
+    #[tokio::test]
+    async fn test_resp_and_remote_message() {
+        let url = RedisUrl::parse("redis://:secret@cache.local:6380/").unwrap();
+        assert_eq!(url.host, "cache.local");
+        assert_eq!(url.port, 6380);
+        assert_eq!(url.username, None);
+        assert_eq!(url.password.as_deref(), Some("secret"));
+        assert_eq!(RedisUrl::parse("redis://cache.local").unwrap().port, 6379);
+        assert!(RedisUrl::parse("http://cache.local").is_err());
+
+        assert_eq!(
+            encode_command(&[b"PUBLISH", b"ch", b"hi"]),
+            b"*3\r\n$7\r\nPUBLISH\r\n$2\r\nch\r\n$2\r\nhi\r\n"
+        );
+
+        let msg = EncodedMessage::encode(&ServerMessage::Pong { client_ts: 1 }).unwrap();
+        let payload = encode_payload("other", &msg);
+        let mut wire = format!(
+            "+OK\r\n:1\r\n*4\r\n$8\r\npmessage\r\n$1\r\n*\r\n${}\r\n{}ABC123\r\n${}\r\n",
+            CHANNEL_PREFIX.len() + 6,
+            CHANNEL_PREFIX,
+            payload.len()
+        )
+        .into_bytes();
+        wire.extend_from_slice(&payload);
+        wire.extend_from_slice(b"\r\n-ERR bad\r\n$99999999999\r\n");
+        let mut reader = BufReader::new(std::io::Cursor::new(wire));
+
+        assert_eq!(read_reply(&mut reader).await, Ok(Reply::Status("OK".to_string())));
+        assert_eq!(read_reply(&mut reader).await, Ok(Reply::Integer(1)));
+        let reply = read_reply(&mut reader).await.unwrap();
+        assert!(remote_message(reply.clone(), "self-node").is_some_and(|m| {
+            m.room_id == "ABC123" && m.msg.json() == msg.json() && !m.msg.is_game_sync()
+        }));
+        // 自ノードが流したものは読み飛ばす
+        assert!(remote_message(reply, "other").is_none());
+        assert_eq!(read_reply(&mut reader).await, Ok(Reply::Error("ERR bad".to_string())));
+        assert!(read_reply(&mut reader).await.is_err());
+        assert!(read_reply(&mut reader).await.is_err());
+    }
+}
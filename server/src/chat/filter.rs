@@ -0,0 +1,230 @@
+//! チャットのモデレーション
+//!
+//! `handle_chat` がブロードキャスト前に `ChatFilter` を呼ぶ。既定は何もしない（PassthroughFilter）。
+//! 公開サーバー向けに、禁止語を伏せ字にする WordListFilter と、外部の判定サービスに問い合わせる HttpChatFilter を用意している。
+
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::webhook::client::{self, HttpUrl};
+
+/// 外部判定サービスの応答待ちの上限（超えたら素通しする）
+const MODERATION_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// 判定対象のメッセージの送り主
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct ChatContext<'a> {
+    pub room_id: &'a str,
+    pub player_id: &'a str,
+    pub player_name: &'a str,
+}
+
+/// チャットのフィルター
+#[async_trait]
+pub trait ChatFilter: Send + Sync {
+    /// 送るテキストを返す（伏せ字にしてもよい）。None ならメッセージを捨てる
+    async fn filter(&self, ctx: ChatContext<'_>, text: String) -> Option<String>;
+}
+
+/// 何もしないフィルター
+pub struct PassthroughFilter;
+
+#[async_trait]
+impl ChatFilter for PassthroughFilter {
+    async fn filter(&self, _ctx: ChatContext<'_>, text: String) -> Option<String> {
+        Some(text)
+    }
+}
+
+/// 禁止語（大文字小文字を区別しない）を同じ文字数の * に置き換える
+pub struct WordListFilter {
+    words: Vec<Vec<char>>,
+}
+
+impl WordListFilter {
+    pub fn new(words: &[String]) -> Self {
+        Self {
+            words: words
+                .iter()
+                .map(|w| w.trim().chars().flat_map(char::to_lowercase).collect::<Vec<_>>())
+                .filter(|w| !w.is_empty())
+                .collect(),
+        }
+    }
+
+    fn mask(&self, text: &str) -> String {
+        let mut chars: Vec<char> = text.chars().collect();
+        let lower: Vec<char> = chars
+            .iter()
+            .map(|c| c.to_lowercase().next().unwrap_or(*c))
+            .collect();
+        let mut masked = vec![false; chars.len()];
+        for word in &self.words {
+            if word.len() > lower.len() {
+                continue;
+            }
+            for start in 0..=lower.len() - word.len() {
+                if lower[start..start + word.len()] == word[..] {
+                    masked[start..start + word.len()].fill(true);
+                }
+            }
+        }
+        for (c, m) in chars.iter_mut().zip(&masked) {
+            if *m {
+                *c = '*';
+            }
+        }
+        chars.into_iter().collect()
+    }
+}
+
+#[async_trait]
+impl ChatFilter for WordListFilter {
+    async fn filter(&self, _ctx: ChatContext<'_>, text: String) -> Option<String> {
+        Some(self.mask(&text))
+    }
+}
+
+/// 外部判定サービスへのリクエスト（POST、JSON）
+#[derive(Debug, Serialize)]
+struct ModerationRequest<'a> {
+    #[serde(flatten)]
+    ctx: ChatContext<'a>,
+    text: &'a str,
+}
+
+/// 外部判定サービスの応答
+#[derive(Debug, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+enum ModerationResponse {
+    Allow,
+    /// text に置き換えて送る
+    Replace { text: String },
+    Drop,
+}
+
+/// 外部の判定サービスに問い合わせるフィルター（http:// のみ）
+/// 応答が不正・タイムアウトした場合は素通しする（判定サービスの障害でチャットを止めない）
+pub struct HttpChatFilter {
+    url: HttpUrl,
+}
+
+impl HttpChatFilter {
+    pub fn new(url: &str) -> Result<Self, String> {
+        Ok(Self {
+            url: HttpUrl::parse(url)?,
+        })
+    }
+}
+
+#[async_trait]
+impl ChatFilter for HttpChatFilter {
+    async fn filter(&self, ctx: ChatContext<'_>, text: String) -> Option<String> {
+        let body = serde_json::to_string(&ModerationRequest { ctx, text: &text }).ok()?;
+        let response = match client::post_json_for_body(&self.url, &body, MODERATION_TIMEOUT).await {
+            Ok((status, body)) if (200..300).contains(&status) => serde_json::from_str(&body).map_err(|e| e.to_string()),
+            Ok((status, _)) => Err(format!("returned {}", status)),
+            Err(e) => Err(e),
+        };
+        match response {
+            Ok(ModerationResponse::Allow) => Some(text),
+            Ok(ModerationResponse::Replace { text }) => Some(text),
+            Ok(ModerationResponse::Drop) => None,
+            Err(e) => {
+                eprintln!("chat moderation {}{} failed: {}", self.url.host, self.url.path, e);
+                Some(text)
+            }
+        }
+    }
+}
+
+/// 複数のフィルターを順にかける（どれかが捨てたらそこで終わり）
+pub struct FilterChain {
+    filters: Vec<Box<dyn ChatFilter>>,
+}
+
+#[async_trait]
+impl ChatFilter for FilterChain {
+    async fn filter(&self, ctx: ChatContext<'_>, mut text: String) -> Option<String> {
+        for filter in &self.filters {
+            text = filter.filter(ctx, text).await?;
+        }
+        Some(text)
+    }
+}
+
+/// 設定の再読み込みで中身を差し替えられるフィルター（差し替えは次のメッセージから効く）
+pub struct SwappableChatFilter {
+    current: RwLock<Arc<dyn ChatFilter>>,
+}
+
+impl SwappableChatFilter {
+    pub fn new(filter: Arc<dyn ChatFilter>) -> Self {
+        Self {
+            current: RwLock::new(filter),
+        }
+    }
+
+    pub fn replace(&self, filter: Arc<dyn ChatFilter>) {
+        *self.current.write().unwrap_or_else(|e| e.into_inner()) = filter;
+    }
+}
+
+#[async_trait]
+impl ChatFilter for SwappableChatFilter {
+    async fn filter(&self, ctx: ChatContext<'_>, text: String) -> Option<String> {
+        let current = self.current.read().unwrap_or_else(|e| e.into_inner()).clone();
+        current.filter(ctx, text).await
+    }
+}
+
+/// 設定からフィルターを組み立てる（禁止語 → 外部判定の順）。どちらも無ければ PassthroughFilter
+pub fn build(blocked_words: &[String], moderation_url: Option<&str>) -> Arc<dyn ChatFilter> {
+    let mut filters: Vec<Box<dyn ChatFilter>> = Vec::new();
+    if !blocked_words.is_empty() {
+        filters.push(Box::new(WordListFilter::new(blocked_words)));
+    }
+    if let Some(url) = moderation_url {
+        match HttpChatFilter::new(url) {
+            Ok(filter) => filters.push(Box::new(filter)),
+            Err(e) => eprintln!("chat moderation disabled: {}", e),
+        }
+    }
+    if filters.is_empty() {
+        Arc::new(PassthroughFilter)
+    } else {
+        Arc::new(FilterChain { filters })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CTX: ChatContext<'static> = ChatContext {
+        room_id: "ROOM01",
+        player_id: "p1",
+        player_name: "Alice",
+    };
+
+    #[tokio::test]
+    async fn test_word_list_masks_case_insensitively() {
+        let filter = WordListFilter::new(&["bad".to_string(), "バカ".to_string()]);
+        assert_eq!(
+            filter.filter(CTX, "so BAD, バカ!".to_string()).await.unwrap(),
+            "so ***, **!"
+        );
+        assert_eq!(filter.filter(CTX, "good".to_string()).await.unwrap(), "good");
+    }
+
+    #[test]
+    fn test_moderation_response() {
+        let drop: ModerationResponse = serde_json::from_str(r#"{"action":"drop"}"#).unwrap();
+        assert!(matches!(drop, ModerationResponse::Drop));
+        let replace: ModerationResponse = serde_json::from_str(r#"{"action":"replace","text":"***"}"#).unwrap();
+        assert!(matches!(replace, ModerationResponse::Replace { text } if text == "***"));
+    }
+}
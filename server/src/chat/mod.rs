@@ -1,18 +1,423 @@
-use crate::protocol::ServerMessage;
-use crate::room::RoomManager;
+pub mod filter;
 
-/// チャットメッセージを処理し、同一部屋内にブロードキャストする
+use std::time::{Duration, Instant};
+
+use thiserror::Error;
+
+use crate::protocol::{ChatLine, ServerMessage};
+use filter::{ChatContext, ChatFilter};
+use crate::room::{RoomError, RoomManager};
+
+/// Reaction で送れるスタンプ（クライアントは emote_id に対応する絵を表示する）
+pub const EMOTES: &[&str] = &[
+    "clap", "laugh", "wow", "cry", "angry", "thumbs_up", "heart", "party",
+];
+
+/// 同じプレイヤーの PlayerTyping を配信する最短間隔
+const TYPING_INTERVAL: Duration = Duration::from_secs(3);
+
+/// PlayerTyping の表示を消すまでの時間（TYPING_INTERVAL より長くして、入力中は途切れないようにする）
+const TYPING_EXPIRY: Duration = Duration::from_secs(5);
+
+/// チャットの制限（文字数と連投）
+#[derive(Debug, Clone, Copy)]
+pub struct ChatPolicy {
+    /// 1メッセージの最大文字数
+    pub max_len: usize,
+    /// 最大文字数を超えたメッセージを切り詰めるか（false なら拒否）
+    pub truncate: bool,
+    /// 連続して送れる件数
+    pub burst: u32,
+    /// 送れる件数が1件回復するまでの時間
+    pub refill: Duration,
+}
+
+impl Default for ChatPolicy {
+    fn default() -> Self {
+        Self {
+            max_len: 200,
+            truncate: true,
+            burst: 5,
+            refill: Duration::from_secs(2),
+        }
+    }
+}
+
+/// チャットを受け付けなかった理由
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ChatError {
+    #[error("sending chat messages too fast")]
+    RateLimited,
+    #[error("chat message exceeds {0} characters")]
+    TooLong(usize),
+    #[error("muted by the host")]
+    Muted,
+    #[error("unknown emote: {0}")]
+    UnknownEmote(String),
+    #[error("reaction target is not in the room")]
+    UnknownTarget,
+}
+
+impl ChatError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            ChatError::RateLimited => "CHAT_RATE_LIMITED",
+            ChatError::TooLong(_) => "CHAT_TOO_LONG",
+            ChatError::Muted => "CHAT_MUTED",
+            ChatError::UnknownEmote(_) => "UNKNOWN_EMOTE",
+            ChatError::UnknownTarget => "UNKNOWN_TARGET",
+        }
+    }
+}
+
+impl From<ChatError> for ServerMessage {
+    fn from(e: ChatError) -> Self {
+        ServerMessage::Error {
+            code: e.code().to_string(),
+            message: e.to_string(),
+            valid_choices: None,
+        }
+    }
+}
+
+/// 接続（プレイヤー）ごとの連投制限（トークンバケット）と入力中通知の間引き
+#[derive(Debug)]
+pub struct ChatLimiter {
+    policy: ChatPolicy,
+    tokens: u32,
+    last_refill: Instant,
+    typing: TypingThrottle,
+}
+
+impl ChatLimiter {
+    pub fn new(policy: ChatPolicy) -> Self {
+        Self {
+            policy,
+            tokens: policy.burst,
+            last_refill: Instant::now(),
+            typing: TypingThrottle::default(),
+        }
+    }
+
+    /// 1件送れるなら消費して true
+    fn try_acquire(&mut self, now: Instant) -> bool {
+        if !self.policy.refill.is_zero() {
+            let elapsed = now.saturating_duration_since(self.last_refill);
+            let refilled = (elapsed.as_nanos() / self.policy.refill.as_nanos()).min(u32::MAX as u128) as u32;
+            if refilled > 0 {
+                self.tokens = self.tokens.saturating_add(refilled).min(self.policy.burst);
+                self.last_refill += self.policy.refill * refilled;
+            }
+        }
+        if self.tokens == 0 {
+            return false;
+        }
+        self.tokens -= 1;
+        true
+    }
+}
+
+/// 入力中通知の間引き
+#[derive(Debug, Default)]
+struct TypingThrottle {
+    last_sent: Option<Instant>,
+}
+
+impl TypingThrottle {
+    /// 前回の配信から TYPING_INTERVAL 経っていれば true
+    fn try_send(&mut self, now: Instant) -> bool {
+        if self
+            .last_sent
+            .is_some_and(|last| now.saturating_duration_since(last) < TYPING_INTERVAL)
+        {
+            return false;
+        }
+        self.last_sent = Some(now);
+        true
+    }
+
+    /// チャットを送ったら次の入力ですぐ通知できるようにする
+    fn reset(&mut self) {
+        self.last_sent = None;
+    }
+}
+
+/// 制御文字を取り除いて前後の空白を削り、文字数の上限を適用する
+fn sanitize(text: &str, policy: &ChatPolicy) -> Result<String, ChatError> {
+    let cleaned: String = text.chars().filter(|c| !c.is_control()).collect();
+    let cleaned = cleaned.trim();
+    if cleaned.chars().count() <= policy.max_len {
+        return Ok(cleaned.to_string());
+    }
+    if policy.truncate {
+        Ok(cleaned.chars().take(policy.max_len).collect())
+    } else {
+        Err(ChatError::TooLong(policy.max_len))
+    }
+}
+
+/// 文字数・連投の制限とフィルターを適用する。送らないメッセージなら None
+async fn prepare(
+    limiter: &mut ChatLimiter,
+    filter: &dyn ChatFilter,
+    ctx: ChatContext<'_>,
+    text: String,
+) -> Result<Option<String>, ChatError> {
+    let text = sanitize(&text, &limiter.policy)?;
+    if text.is_empty() {
+        return Ok(None);
+    }
+    if !limiter.try_acquire(Instant::now()) {
+        return Err(ChatError::RateLimited);
+    }
+    Ok(filter.filter(ctx, text).await)
+}
+
+/// チャットメッセージを処理し、フィルターを通して履歴に残し、同一部屋内にブロードキャストする
+/// （ロビー・ゲーム中・終了後のいずれでも送れる。空のメッセージとフィルターが捨てたものは送らない）
 pub async fn handle_chat(
     room_manager: &RoomManager,
+    limiter: &mut ChatLimiter,
+    filter: &dyn ChatFilter,
     room_id: &str,
     player_id: &str,
     player_name: &str,
     text: String,
-) {
-    let msg = ServerMessage::ChatBroadcast {
+) -> Result<(), ChatError> {
+    // ミュート中の発言は連投制限の回数にも数えず、フィルターにも渡さない
+    if let Err(RoomError::Muted) = room_manager.check_reaction(room_id, player_id, None).await {
+        return Err(ChatError::Muted);
+    }
+    let ctx = ChatContext {
+        room_id,
+        player_id,
+        player_name,
+    };
+    let Some(text) = prepare(limiter, filter, ctx, text).await? else {
+        return Ok(());
+    };
+
+    let line = ChatLine {
         player_id: player_id.to_string(),
         player_name: player_name.to_string(),
         text,
     };
+    match room_manager.record_chat(room_id, line.clone()).await {
+        Ok(()) => {}
+        Err(RoomError::Muted) => return Err(ChatError::Muted),
+        Err(_) => return Ok(()),
+    }
+    limiter.typing.reset();
+    let msg = ServerMessage::ChatBroadcast {
+        player_id: line.player_id,
+        player_name: line.player_name,
+        text: line.text,
+    };
     room_manager.broadcast(room_id, &msg).await;
+    Ok(())
+}
+
+/// 観戦者のチャットを観戦者だけに送る（プレイヤーの指南にならないよう、プレイヤーには届けない。履歴にも残さない）
+pub async fn handle_spectator_chat(
+    room_manager: &RoomManager,
+    limiter: &mut ChatLimiter,
+    filter: &dyn ChatFilter,
+    room_id: &str,
+    spectator_id: &str,
+    name: &str,
+    text: String,
+) -> Result<(), ChatError> {
+    let ctx = ChatContext {
+        room_id,
+        player_id: spectator_id,
+        player_name: name,
+    };
+    let Some(text) = prepare(limiter, filter, ctx, text).await? else {
+        return Ok(());
+    };
+    let msg = ServerMessage::SpectatorChatBroadcast {
+        spectator_id: spectator_id.to_string(),
+        name: name.to_string(),
+        text,
+    };
+    room_manager.broadcast_spectators(room_id, &msg).await;
+    Ok(())
+}
+
+/// 入力中を本人以外に知らせる（間引いた分とミュート中のプレイヤーの通知は黙って捨てる）
+pub async fn handle_typing(room_manager: &RoomManager, limiter: &mut ChatLimiter, room_id: &str, player_id: &str) {
+    if !limiter.typing.try_send(Instant::now()) {
+        return;
+    }
+    if room_manager.check_reaction(room_id, player_id, None).await.is_err() {
+        return;
+    }
+    let msg = ServerMessage::PlayerTyping {
+        player_id: player_id.to_string(),
+        expires_in_ms: TYPING_EXPIRY.as_millis() as u64,
+    };
+    room_manager.broadcast_except(room_id, player_id, &msg).await;
+}
+
+/// スタンプを検証して同一部屋内にブロードキャストする（チャットと同じ連投制限・ミュートに従う）
+pub async fn handle_reaction(
+    room_manager: &RoomManager,
+    limiter: &mut ChatLimiter,
+    room_id: &str,
+    player_id: &str,
+    emote_id: String,
+    target: Option<String>,
+) -> Result<(), ChatError> {
+    if !EMOTES.contains(&emote_id.as_str()) {
+        return Err(ChatError::UnknownEmote(emote_id));
+    }
+    match room_manager.check_reaction(room_id, player_id, target.as_deref()).await {
+        Ok(()) => {}
+        Err(RoomError::Muted) => return Err(ChatError::Muted),
+        Err(RoomError::PlayerNotFound) => return Err(ChatError::UnknownTarget),
+        Err(_) => return Ok(()),
+    }
+    if !limiter.try_acquire(Instant::now()) {
+        return Err(ChatError::RateLimited);
+    }
+    let msg = ServerMessage::ReactionBroadcast {
+        player_id: player_id.to_string(),
+        emote_id,
+        target,
+    };
+    room_manager.broadcast(room_id, &msg).await;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::map::MapCatalog;
+    use crate::profile::ProfileStore;
+    use crate::protocol::ClientMessage;
+    use crate::room::models::RoomOptions;
+    use crate::storage::MemoryStorage;
+    use crate::transport::traits::{EncodedMessage, Result as TransportResult, Transport};
+    use crate::webhook::WebhookNotifier;
+    use async_trait::async_trait;
+
+    struct NullTransport;
+
+    #[async_trait]
+    impl Transport for NullTransport {
+        async fn send_raw(&self, _msg: EncodedMessage) -> TransportResult<()> {
+            Ok(())
+        }
+        async fn recv(&mut self) -> TransportResult<ClientMessage> {
+            Err("closed".into())
+        }
+        async fn close(&self) -> TransportResult<()> {
+            Ok(())
+        }
+    }
+
+    /// 呼ばれたら失敗するフィルター
+    struct UnreachableFilter;
+
+    #[async_trait]
+    impl ChatFilter for UnreachableFilter {
+        async fn filter(&self, _ctx: ChatContext<'_>, _text: String) -> Option<String> {
+            panic!("muted chat reached the filter");
+        }
+    }
+
+    #[test]
+    fn test_sanitize() {
+        let policy = ChatPolicy {
+            max_len: 5,
+            ..ChatPolicy::default()
+        };
+        assert_eq!(sanitize(" he\u{7}llo\n ", &policy).unwrap(), "hello");
+        assert_eq!(sanitize("こんにちは世界", &policy).unwrap(), "こんにちは");
+
+        let strict = ChatPolicy {
+            truncate: false,
+            ..policy
+        };
+        assert_eq!(sanitize("too long", &strict), Err(ChatError::TooLong(5)));
+    }
+
+    #[test]
+    fn test_typing_throttle() {
+        let mut typing = TypingThrottle::default();
+        let start = Instant::now();
+        assert!(typing.try_send(start));
+        assert!(!typing.try_send(start + Duration::from_secs(1)));
+        assert!(typing.try_send(start + TYPING_INTERVAL));
+        typing.reset();
+        assert!(typing.try_send(start + TYPING_INTERVAL + Duration::from_millis(1)));
+    }
+
+    #[test]
+    fn test_limiter_refills() {
+        let policy = ChatPolicy {
+            burst: 2,
+            refill: Duration::from_secs(1),
+            ..ChatPolicy::default()
+        };
+        let mut limiter = ChatLimiter::new(policy);
+        let start = limiter.last_refill;
+        assert!(limiter.try_acquire(start));
+        assert!(limiter.try_acquire(start));
+        assert!(!limiter.try_acquire(start));
+        assert!(!limiter.try_acquire(start + Duration::from_millis(900)));
+        assert!(limiter.try_acquire(start + Duration::from_millis(1100)));
+        assert!(!limiter.try_acquire(start + Duration::from_millis(1200)));
+        // 長く空いても burst までしか貯まらない
+        let later = start + Duration::from_secs(60);
+        assert!(limiter.try_acquire(later));
+        assert!(limiter.try_acquire(later));
+        assert!(!limiter.try_acquire(later));
+    }
+
+    #[tokio::test]
+    async fn test_muted_chat_is_rejected_first() {
+        let manager = RoomManager::new(
+            6,
+            WebhookNotifier::new(&[]),
+            ProfileStore::new(Arc::new(MemoryStorage::new())),
+            MapCatalog::builtin(),
+            Duration::ZERO,
+        );
+        let (room_id, host, _) = manager
+            .create_room(
+                "Alice".to_string(),
+                "classic".to_string(),
+                RoomOptions::default(),
+                None,
+                Arc::new(NullTransport),
+            )
+            .await
+            .unwrap();
+        let (guest, _) = manager
+            .join_room(&room_id, "Bob".to_string(), None, None, None, Arc::new(NullTransport))
+            .await
+            .unwrap();
+        manager.set_muted(&room_id, &host, &guest, true).await.unwrap();
+
+        let mut limiter = ChatLimiter::new(ChatPolicy {
+            burst: 1,
+            ..ChatPolicy::default()
+        });
+        let result = handle_chat(
+            &manager,
+            &mut limiter,
+            &UnreachableFilter,
+            &room_id,
+            &guest,
+            "Bob",
+            "hi".to_string(),
+        )
+        .await;
+        assert_eq!(result, Err(ChatError::Muted));
+        // 連投制限の回数は減っていない
+        assert_eq!(limiter.tokens, 1);
+    }
 }
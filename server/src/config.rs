@@ -1,9 +1,61 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use serde_json::Value;
+
+use crate::chat::filter::{self, SwappableChatFilter};
+use crate::chat::ChatPolicy;
+use crate::room::RoomManager;
+use crate::transport::BackpressurePolicy;
+
 /// サーバー設定
 pub struct ServerConfig {
     pub host: String,
     pub port: u16,
     pub max_players_per_room: usize,
     pub max_rooms: usize,
+    /// 管理API (/admin) の Bearer トークン。未設定なら管理APIは無効
+    pub admin_token: Option<String>,
+    /// クライアントのビルド成果物（client/dist）を配信するディレクトリ
+    /// 同一オリジン配信時はクライアントを VITE_WS_URL 指定でビルドする
+    pub static_dir: Option<PathBuf>,
+    /// 部屋のライフサイクルを通知する Webhook の送信先（http:// のみ）
+    pub webhook_urls: Vec<String>,
+    /// プレイヤー認証トークン（JWT / HS256）の署名鍵。未設定なら匿名のみ
+    pub jwt_secret: Option<String>,
+    /// 永続データ（プロフィール等）の保存先。未設定ならメモリ上のみ
+    pub data_dir: Option<PathBuf>,
+    /// 追加マップ（*.json / *.toml）を置くディレクトリ
+    pub map_dir: Option<PathBuf>,
+    /// 遅いクライアントへの送信ポリシー
+    pub backpressure: BackpressurePolicy,
+    /// チャットの文字数・連投の制限
+    pub chat: ChatPolicy,
+    /// 伏せ字にするチャットの禁止語
+    pub chat_blocked_words: Vec<String>,
+    /// チャットを判定する外部サービス（http:// のみ）
+    pub chat_moderation_url: Option<String>,
+    /// ゲーム中に全員の接続が切れた部屋を一時停止して残しておく時間（0 なら即座に削除）
+    pub abandoned_game_grace: Duration,
+    /// CORS で許可するオリジン。空なら全オリジンを許可
+    pub cors_origins: Vec<String>,
+    /// 送信元IPを入れるプロキシのヘッダー（Fly.io なら Fly-Client-IP）。未設定なら接続元のアドレスを使う
+    pub client_ip_header: Option<String>,
+    /// 設定ファイル（TOML）。環境変数と同じ名前を小文字にしたキーで書き、環境変数より優先する
+    pub config_file: Option<PathBuf>,
+    /// 部屋ごとの状態遷移のデバッグログ（NDJSON）を書くディレクトリ。未設定なら書かない
+    pub debug_log_dir: Option<PathBuf>,
+    /// ブロードキャストを他のノードと共有する Pub/Sub（redis:// のみ）。未設定なら単一ノード
+    pub broadcast_url: Option<String>,
+    /// このノードの WebSocket の URL（例: wss://node-a.example.com/ws）。設定すると部屋のリースを DATA_DIR に書き、
+    /// 部屋を他のノードに移せるようになる（DATA_DIR は全ノードで共有する）
+    pub node_url: Option<String>,
+    /// 部屋のリースの期限（期限の3分の1ごとに更新する）
+    pub room_lease_ttl: Duration,
+    /// ゲーム操作を処理するワーカーのタスク数（WebSocket の接続数とは別に増減できる）
+    pub room_workers: usize,
 }
 
 impl Default for ServerConfig {
@@ -13,12 +65,266 @@ impl Default for ServerConfig {
             port: 3000,
             max_players_per_room: 6,
             max_rooms: 100,
+            admin_token: None,
+            static_dir: None,
+            webhook_urls: Vec::new(),
+            jwt_secret: None,
+            data_dir: None,
+            map_dir: None,
+            backpressure: BackpressurePolicy::default(),
+            chat: ChatPolicy::default(),
+            chat_blocked_words: Vec::new(),
+            chat_moderation_url: None,
+            abandoned_game_grace: Duration::from_secs(300),
+            cors_origins: Vec::new(),
+            client_ip_header: None,
+            config_file: None,
+            debug_log_dir: None,
+            broadcast_url: None,
+            node_url: None,
+            room_lease_ttl: Duration::from_secs(30),
+            room_workers: 16,
         }
     }
 }
 
 impl ServerConfig {
+    /// 既定値を環境変数で上書きした設定
+    pub fn from_env() -> Self {
+        Self::from_source(&ConfigSource::default())
+    }
+
+    /// 既定値を環境変数で上書きし、CONFIG_FILE があればさらにその内容で上書きした設定
+    pub fn load() -> Result<Self, String> {
+        Ok(Self::from_source(&ConfigSource::load()?))
+    }
+
+    fn from_source(source: &ConfigSource) -> Self {
+        Self {
+            admin_token: source.var("ADMIN_TOKEN").filter(|t| !t.is_empty()),
+            static_dir: source.var("STATIC_DIR").map(PathBuf::from),
+            webhook_urls: source.list("WEBHOOK_URLS"),
+            jwt_secret: source.var("JWT_SECRET").filter(|s| !s.is_empty()),
+            data_dir: source.var("DATA_DIR").map(PathBuf::from),
+            map_dir: source.var("MAP_DIR").map(PathBuf::from),
+            debug_log_dir: source.var("DEBUG_LOG_DIR").map(PathBuf::from),
+            broadcast_url: source.var("BROADCAST_URL").filter(|u| !u.is_empty()),
+            node_url: source.var("NODE_URL").filter(|u| !u.is_empty()),
+            room_lease_ttl: source
+                .number("ROOM_LEASE_TTL_SECS")
+                .filter(|&n| n > 0)
+                .map(Duration::from_secs)
+                .unwrap_or(Self::default().room_lease_ttl),
+            room_workers: source
+                .number("ROOM_WORKERS")
+                .filter(|&n| n > 0)
+                .map(|n| n as usize)
+                .unwrap_or(Self::default().room_workers),
+            backpressure: Self::backpressure_from(source),
+            chat: Self::chat_from(source),
+            chat_blocked_words: source.list("CHAT_BLOCKED_WORDS"),
+            chat_moderation_url: source.var("CHAT_MODERATION_URL").filter(|s| !s.is_empty()),
+            abandoned_game_grace: source
+                .number("ABANDONED_GAME_GRACE_SECS")
+                .map(Duration::from_secs)
+                .unwrap_or(Self::default().abandoned_game_grace),
+            cors_origins: source.list("CORS_ORIGINS"),
+            client_ip_header: source.var("CLIENT_IP_HEADER").filter(|h| !h.is_empty()),
+            config_file: source.file.clone(),
+            ..Self::default()
+        }
+    }
+
+    /// SEND_QUEUE_CAPACITY / COALESCE_GAME_SYNC（"0" か "false" で無効）/ SLOW_CLIENT_TIMEOUT_SECS
+    fn backpressure_from(source: &ConfigSource) -> BackpressurePolicy {
+        let default = BackpressurePolicy::default();
+        BackpressurePolicy {
+            queue_capacity: source
+                .number("SEND_QUEUE_CAPACITY")
+                .map(|n| n as usize)
+                .unwrap_or(default.queue_capacity),
+            coalesce_game_sync: source.flag("COALESCE_GAME_SYNC").unwrap_or(default.coalesce_game_sync),
+            disconnect_after: source
+                .number("SLOW_CLIENT_TIMEOUT_SECS")
+                .map(Duration::from_secs)
+                .unwrap_or(default.disconnect_after),
+        }
+    }
+
+    /// CHAT_MAX_LEN / CHAT_TRUNCATE（"0" か "false" で超過を拒否）/ CHAT_BURST / CHAT_REFILL_MS
+    fn chat_from(source: &ConfigSource) -> ChatPolicy {
+        let default = ChatPolicy::default();
+        ChatPolicy {
+            max_len: source
+                .number("CHAT_MAX_LEN")
+                .map(|n| n as usize)
+                .unwrap_or(default.max_len),
+            truncate: source.flag("CHAT_TRUNCATE").unwrap_or(default.truncate),
+            burst: source.number("CHAT_BURST").map(|n| n as u32).unwrap_or(default.burst),
+            refill: source
+                .number("CHAT_REFILL_MS")
+                .map(Duration::from_millis)
+                .unwrap_or(default.refill),
+        }
+    }
+
     pub fn addr(&self) -> String {
         format!("{}:{}", self.host, self.port)
     }
 }
+
+/// 設定値の読み元（設定ファイルの値を環境変数より優先する）
+#[derive(Debug, Default)]
+struct ConfigSource {
+    file: Option<PathBuf>,
+    /// 設定ファイルの値（キーは小文字）
+    values: HashMap<String, String>,
+}
+
+impl ConfigSource {
+    /// CONFIG_FILE があれば読む
+    fn load() -> Result<Self, String> {
+        let Some(path) = std::env::var_os("CONFIG_FILE").map(PathBuf::from) else {
+            return Ok(Self::default());
+        };
+        let values = read_config_file(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Ok(Self {
+            file: Some(path),
+            values,
+        })
+    }
+
+    fn var(&self, name: &str) -> Option<String> {
+        self.values
+            .get(&name.to_ascii_lowercase())
+            .cloned()
+            .or_else(|| std::env::var(name).ok())
+    }
+
+    /// カンマ区切りの値
+    fn list(&self, name: &str) -> Vec<String> {
+        self.var(name)
+            .map(|v| {
+                v.split(',')
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn number(&self, name: &str) -> Option<u64> {
+        self.var(name).and_then(|v| v.parse().ok())
+    }
+
+    /// "0" か "false" なら false
+    fn flag(&self, name: &str) -> Option<bool> {
+        self.var(name).map(|v| v != "0" && v != "false")
+    }
+}
+
+/// 設定ファイル（TOML）のトップレベルの値を環境変数と同じ形の文字列にする（配列はカンマ区切り）
+fn read_config_file(path: &Path) -> Result<HashMap<String, String>, String> {
+    let source = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    parse_config(&source)
+}
+
+fn parse_config(source: &str) -> Result<HashMap<String, String>, String> {
+    let Value::Object(table) = crate::map::toml::parse(source)? else {
+        return Err("config must be a table".to_string());
+    };
+    let scalar = |key: &str, value: &Value| match value {
+        Value::String(s) => Ok(s.clone()),
+        Value::Number(n) => Ok(n.to_string()),
+        Value::Bool(b) => Ok(b.to_string()),
+        _ => Err(format!("{}: unsupported value", key)),
+    };
+    table
+        .iter()
+        .map(|(key, value)| {
+            let text = match value {
+                Value::Array(items) => items
+                    .iter()
+                    .map(|item| scalar(key, item))
+                    .collect::<Result<Vec<_>, _>>()?
+                    .join(","),
+                _ => scalar(key, value)?,
+            };
+            Ok((key.to_ascii_lowercase(), text))
+        })
+        .collect()
+}
+
+/// 再起動せずに反映できる設定の反映先（SIGHUP か POST /admin/reload-config で設定を読み直す）
+/// 対象は部屋の猶予時間・チャットの制限とフィルター・CORS のオリジン。それ以外は再起動まで変わらない
+pub struct ConfigReloader {
+    room_manager: Arc<RoomManager>,
+    chat: RwLock<ChatPolicy>,
+    chat_filter: Arc<SwappableChatFilter>,
+    cors_origins: RwLock<Vec<String>>,
+}
+
+impl ConfigReloader {
+    pub fn new(config: &ServerConfig, room_manager: Arc<RoomManager>) -> Self {
+        let reloader = Self {
+            room_manager,
+            chat: RwLock::new(config.chat),
+            chat_filter: Arc::new(SwappableChatFilter::new(filter::build(
+                &config.chat_blocked_words,
+                config.chat_moderation_url.as_deref(),
+            ))),
+            cors_origins: RwLock::new(config.cors_origins.clone()),
+        };
+        reloader.room_manager.set_abandoned_grace(config.abandoned_game_grace);
+        reloader
+    }
+
+    /// 設定を読み直して反映する（読めなければ何も変えない）
+    pub fn reload(&self) -> Result<(), String> {
+        let config = ServerConfig::load()?;
+        self.room_manager.set_abandoned_grace(config.abandoned_game_grace);
+        *self.chat.write().unwrap_or_else(|e| e.into_inner()) = config.chat;
+        self.chat_filter.replace(filter::build(
+            &config.chat_blocked_words,
+            config.chat_moderation_url.as_deref(),
+        ));
+        *self.cors_origins.write().unwrap_or_else(|e| e.into_inner()) = config.cors_origins;
+        Ok(())
+    }
+
+    /// 新しい接続に使うチャットの制限（接続中のプレイヤーは接続時の制限のまま）
+    pub fn chat_policy(&self) -> ChatPolicy {
+        *self.chat.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// チャットのフィルター（再読み込みすると接続中のプレイヤーにもすぐ反映される）
+    pub fn chat_filter(&self) -> Arc<SwappableChatFilter> {
+        self.chat_filter.clone()
+    }
+
+    /// CORS で許可するオリジンか（CORS_ORIGINS が空なら全て許可）
+    pub fn allows_origin(&self, origin: &str) -> bool {
+        let origins = self.cors_origins.read().unwrap_or_else(|e| e.into_inner());
+        origins.is_empty() || origins.iter().any(|o| o == origin)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_file_values_override_env() {
+        let values = parse_config(
+            "abandoned_game_grace_secs = 60\nCHAT_TRUNCATE = false\ncors_origins = [\"https://a.example\", \"https://b.example\"]\n",
+        )
+        .unwrap();
+        let source = ConfigSource { file: None, values };
+        let config = ServerConfig::from_source(&source);
+        assert_eq!(config.abandoned_game_grace, Duration::from_secs(60));
+        assert!(!config.chat.truncate);
+        assert_eq!(config.cors_origins, vec!["https://a.example", "https://b.example"]);
+
+        assert!(parse_config("chat = { max_len = 10 }").is_err());
+    }
+}
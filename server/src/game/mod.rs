@@ -1,9 +1,3 @@
-pub mod engine;
-pub mod events;
-pub mod state;
-pub mod traits;
+//! ゲームエンジン（nine-life-core クレート）の再エクスポート
 
-pub use engine::ClassicGameEngine;
-pub use events::{ClassicEventResolver, StandardRoulette};
-pub use state::*;
-pub use traits::*;
+pub use nine_life_core::*;
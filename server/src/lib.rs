@@ -0,0 +1,15 @@
+pub mod auth;
+pub mod broadcast;
+pub mod chat;
+pub mod config;
+pub mod game;
+pub mod map;
+pub mod metrics;
+pub mod profile;
+pub mod protocol;
+pub mod room;
+pub mod storage;
+pub mod transport;
+pub mod web;
+pub mod webhook;
+pub mod worker;
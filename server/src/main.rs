@@ -1,78 +1,385 @@
-mod chat;
-mod config;
-mod game;
-mod protocol;
-mod room;
-mod transport;
-mod web;
-
+use std::net::{IpAddr, SocketAddr};
 use std::sync::Arc;
 
 use axum::extract::ws::WebSocket;
-use axum::extract::{State, WebSocketUpgrade};
-use axum::response::IntoResponse;
+use axum::extract::{ConnectInfo, FromRef, Query, State, WebSocketUpgrade};
+use axum::http::{HeaderMap, HeaderValue, StatusCode, Uri};
+use axum::response::{IntoResponse, Response};
 use axum::routing::get;
 use axum::Router;
-use tower_http::cors::{Any, CorsLayer};
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
+
+use nine_life_server::auth::{AuthIdentity, JwtKey};
+use nine_life_server::broadcast::{Broadcaster, LocalBroadcaster, RedisBroadcaster};
+use nine_life_server::chat::filter::ChatFilter;
+use nine_life_server::chat::{ChatLimiter, ChatPolicy};
+use nine_life_server::config::{ConfigReloader, ServerConfig};
+use nine_life_server::map::MapCatalog;
+use nine_life_server::metrics::{ConnectionFailure, METRICS};
+use nine_life_server::profile::ProfileStore;
+use nine_life_server::protocol::{ClientMessage, ServerMessage};
+use nine_life_server::room::lease::LeaseStore;
+use nine_life_server::room::{RoomError, RoomManager, RoomOptions};
+use nine_life_server::storage::{FileStorage, MemoryStorage, Storage};
+use nine_life_server::transport::{split_websocket, BackpressurePolicy, Transport};
+use nine_life_server::webhook::WebhookNotifier;
+use nine_life_server::worker::{LocalWorker, WorkerLink, WorkerRequest};
+use nine_life_server::{chat, web};
 
-use crate::config::ServerConfig;
-use crate::protocol::{ClientMessage, ServerMessage};
-use crate::room::RoomManager;
-use crate::transport::{split_websocket, Transport};
+#[derive(Clone)]
+struct AppState {
+    room_manager: Arc<RoomManager>,
+    /// 部屋に入った後のゲーム操作の送り先
+    worker: Arc<dyn WorkerLink>,
+    profiles: ProfileStore,
+    jwt_key: Option<Arc<JwtKey>>,
+    backpressure: BackpressurePolicy,
+    /// チャットの制限・フィルターなど再読み込みできる設定
+    reloader: Arc<ConfigReloader>,
+    /// 送信元IPを入れるプロキシのヘッダー
+    client_ip_header: Option<Arc<str>>,
+}
 
-type AppState = Arc<RoomManager>;
+impl FromRef<AppState> for Arc<RoomManager> {
+    fn from_ref(state: &AppState) -> Self {
+        state.room_manager.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<ConfigReloader> {
+    fn from_ref(state: &AppState) -> Self {
+        state.reloader.clone()
+    }
+}
+
+impl FromRef<AppState> for ProfileStore {
+    fn from_ref(state: &AppState) -> Self {
+        state.profiles.clone()
+    }
+}
+
+/// WebSocket 接続時のクエリ（/ws?auth=<token>）
+#[derive(Debug, serde::Deserialize)]
+struct WsQuery {
+    auth: Option<String>,
+    /// 別のノードから移った部屋の引き継ぎ用の合言葉（Reconnect の URL に付く）
+    handoff: Option<String>,
+}
+
+/// 接続してきたクライアント
+struct Client {
+    ip: IpAddr,
+    identity: Option<AuthIdentity>,
+    handoff: Option<String>,
+}
 
 #[tokio::main]
 async fn main() {
-    let config = ServerConfig::default();
-    let room_manager = Arc::new(RoomManager::new(config.max_players_per_room));
+    let config = match ServerConfig::load() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("failed to load config {}", e);
+            std::process::exit(1);
+        }
+    };
+    let webhooks = WebhookNotifier::new(&config.webhook_urls);
+    let storage: Arc<dyn Storage> = match &config.data_dir {
+        Some(dir) => Arc::new(FileStorage::new(dir.clone())),
+        None => Arc::new(MemoryStorage::new()),
+    };
+    let profiles = ProfileStore::new(storage.clone());
+    let leases = config
+        .node_url
+        .clone()
+        .map(|url| LeaseStore::new(storage, url, config.room_lease_ttl));
+    let mut maps = MapCatalog::builtin();
+    if let Some(map_dir) = &config.map_dir {
+        match maps.load_dir(map_dir) {
+            Ok(count) => println!("loaded {} map(s) from {}", count, map_dir.display()),
+            Err(e) => eprintln!("failed to read map directory {}", e),
+        }
+    }
+    // BROADCAST_URL があれば他のノードとブロードキャストを共有する
+    let (broadcaster, remote): (Arc<dyn Broadcaster>, _) = match &config.broadcast_url {
+        Some(url) => match RedisBroadcaster::new(url) {
+            Ok(redis) => {
+                println!("sharing broadcasts as node {}", redis.node_id());
+                let remote = redis.subscribe();
+                (Arc::new(redis), Some(remote))
+            }
+            Err(e) => {
+                eprintln!("failed to start broadcast backend {}", e);
+                std::process::exit(1);
+            }
+        },
+        None => (Arc::new(LocalBroadcaster), None),
+    };
+    let room_manager = Arc::new(
+        RoomManager::new(
+            config.max_players_per_room,
+            webhooks,
+            profiles.clone(),
+            maps,
+            config.abandoned_game_grace,
+        )
+        .with_debug_log_dir(config.debug_log_dir.clone())
+        .with_broadcaster(broadcaster)
+        .with_leases(leases),
+    );
+    if let Some(interval) = room_manager.lease_renew_interval() {
+        let room_manager = room_manager.clone();
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                room_manager.renew_leases().await;
+            }
+        });
+    }
+    if let Some(mut remote) = remote {
+        let room_manager = room_manager.clone();
+        tokio::spawn(async move {
+            while let Some(message) = remote.recv().await {
+                room_manager.deliver(&message.room_id, message.msg).await;
+            }
+        });
+    }
+    let reloader = Arc::new(ConfigReloader::new(&config, room_manager.clone()));
+    #[cfg(unix)]
+    spawn_reload_on_hangup(reloader.clone());
 
+    let cors_reloader = reloader.clone();
     let cors = CorsLayer::new()
-        .allow_origin(Any)
+        .allow_origin(AllowOrigin::predicate(move |origin: &HeaderValue, _| {
+            origin.to_str().is_ok_and(|o| cors_reloader.allows_origin(o))
+        }))
         .allow_methods(Any)
         .allow_headers(Any);
 
-    let app = Router::new()
+    let mut app = Router::new()
         .route("/room/{id}", get(web::invite_page))
+        .route("/room/{id}/qr.svg", get(web::invite_qr))
+        .route("/api/rooms", get(web::room_list))
         .route("/api/room/{id}", get(web::room_info))
-        .route("/ws", get(ws_upgrade))
-        .layer(cors)
-        .with_state(room_manager);
+        .route("/api/room/{id}/state", get(web::room_state))
+        .route("/api/room/{id}/ledger", get(web::room_ledger))
+        .route("/api/room/{id}/events", get(web::room_events))
+        .route("/api/room/{id}/export", get(web::export::room_export))
+        .route("/api/room/{id}/result-card", get(web::room_result_card))
+        .route("/api/leaderboard", get(web::leaderboard))
+        .route("/api/maps/{id}/analysis", get(web::map_analysis))
+        .route("/api/schema/{name}", get(web::json_schema))
+        .route("/api/openapi.json", get(web::openapi_spec))
+        .route("/api/docs", get(web::api_docs))
+        .route("/ws", get(ws_upgrade));
+
+    // 管理APIはトークン設定時のみ有効
+    if let Some(admin_token) = config.admin_token.clone() {
+        app = app.nest("/admin", web::admin::router(admin_token));
+    }
+
+    // クライアントを同一バイナリから配信（未定義パスは SPA にフォールバック）
+    if let Some(static_dir) = config.static_dir.clone() {
+        let static_dir = Arc::new(static_dir);
+        app = app.fallback(move |uri: Uri| {
+            let static_dir = static_dir.clone();
+            async move { web::spa::serve(&static_dir, uri).await }
+        });
+    }
+
+    let worker: Arc<dyn WorkerLink> = Arc::new(LocalWorker::spawn(room_manager.clone(), config.room_workers));
+    let state = AppState {
+        room_manager,
+        worker,
+        profiles,
+        jwt_key: config.jwt_secret.as_deref().map(|s| Arc::new(JwtKey::new(s))),
+        backpressure: config.backpressure,
+        reloader,
+        client_ip_header: config.client_ip_header.as_deref().map(Arc::from),
+    };
+    let app = app.layer(cors).with_state(state);
 
     let addr = config.addr();
     println!("9-life server listening on {}", addr);
 
     let listener = tokio::net::TcpListener::bind(&addr).await.unwrap();
-    axum::serve(listener, app).await.unwrap();
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
+        .await
+        .unwrap();
+}
+
+/// SIGHUP で設定を読み直す
+#[cfg(unix)]
+fn spawn_reload_on_hangup(reloader: Arc<ConfigReloader>) {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    tokio::spawn(async move {
+        let mut hangup = match signal(SignalKind::hangup()) {
+            Ok(hangup) => hangup,
+            Err(e) => {
+                eprintln!("failed to listen for SIGHUP {}", e);
+                return;
+            }
+        };
+        while hangup.recv().await.is_some() {
+            match reloader.reload() {
+                Ok(()) => println!("reloaded config"),
+                Err(e) => eprintln!("failed to reload config {}", e),
+            }
+        }
+    });
+}
+
+/// 送信元IP（ヘッダーが設定されていて読めればその値、無ければ接続元のアドレス）
+fn client_ip(peer: SocketAddr, headers: &HeaderMap, header: Option<&str>) -> IpAddr {
+    header
+        .and_then(|name| headers.get(name))
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.split(',').next())
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or(peer.ip())
 }
 
 async fn ws_upgrade(
     ws: WebSocketUpgrade,
-    State(room_manager): State<AppState>,
-) -> impl IntoResponse {
-    ws.on_upgrade(move |socket| handle_socket(socket, room_manager))
+    Query(query): Query<WsQuery>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    State(state): State<AppState>,
+) -> Response {
+    let ip = client_ip(peer, &headers, state.client_ip_header.as_deref());
+    println!("ws upgrade from {}", ip);
+    METRICS.connections.record_upgrade(ip);
+
+    // 認証が有効でトークンが提示された場合のみ検証（トークンなしは匿名）
+    let identity = match (&state.jwt_key, query.auth) {
+        (Some(key), Some(token)) => match key.verify(&token) {
+            Ok(identity) => Some(identity),
+            Err(e) => {
+                record_failure(ip, ConnectionFailure::Unauthorized);
+                return (StatusCode::UNAUTHORIZED, e).into_response();
+            }
+        },
+        _ => None,
+    };
+    let room_manager = state.room_manager;
+    let worker = state.worker;
+    let backpressure = state.backpressure;
+    let chat = state.reloader.chat_policy();
+    let chat_filter: Arc<dyn ChatFilter> = state.reloader.chat_filter();
+    let client = Client {
+        ip,
+        identity,
+        handoff: query.handoff,
+    };
+    ws.on_upgrade(move |socket| {
+        handle_socket(socket, client, room_manager, worker, backpressure, chat, chat_filter)
+    })
+}
+
+/// 部屋に入るまでの失敗を数えて記録する（送信元IPごとの失敗数は GET /admin/offenders で見られる）
+fn record_failure(ip: IpAddr, failure: ConnectionFailure) {
+    eprintln!("ws connection from {} failed: {:?}", ip, failure);
+    METRICS.connections.record_failure(ip, failure);
 }
 
-async fn handle_socket(socket: WebSocket, room_manager: AppState) {
-    let (sender, mut receiver) = split_websocket(socket);
+/// 参加しようとした部屋が他のノードにあれば、そのノードに繋ぎ直すよう Reconnect を送る（送ったら true）
+async fn redirect_to_owner(
+    room_manager: &RoomManager,
+    sender: &impl Transport,
+    room_id: &str,
+    error: &RoomError,
+) -> bool {
+    if *error != RoomError::RoomNotFound {
+        return false;
+    }
+    let Some(url) = room_manager.locate(room_id).await else {
+        return false;
+    };
+    let _ = sender.send(ServerMessage::Reconnect { url }).await;
+    true
+}
+
+async fn handle_socket(
+    socket: WebSocket,
+    client: Client,
+    room_manager: Arc<RoomManager>,
+    worker: Arc<dyn WorkerLink>,
+    backpressure: BackpressurePolicy,
+    chat_policy: ChatPolicy,
+    chat_filter: Arc<dyn ChatFilter>,
+) {
+    let (sender, mut receiver) = split_websocket(socket, backpressure);
+    let mut chat_limiter = ChatLimiter::new(chat_policy);
 
     // 最初のメッセージで CreateRoom か JoinRoom を待つ
     let (room_id, player_id, player_name) = match receiver.recv().await {
         Ok(ClientMessage::CreateRoom {
             player_name,
             map_id,
+            private,
+            max_players,
+            series_games,
+            short_game,
+            max_turn_count,
+            pause_at_branches,
+            rules_variant,
+            spectator_delay_secs,
+            global_event_interval,
+            stall_limit,
+            seed,
+            title,
+            tags,
+            language,
         }) => {
             let sender_clone = sender.clone();
             let transport_arc: Arc<dyn Transport> = Arc::new(sender_clone);
-            let (room_id, player_id) = room_manager
-                .create_room(player_name.clone(), map_id, transport_arc)
+            let created = room_manager
+                .create_room(
+                    player_name.clone(),
+                    map_id,
+                    RoomOptions {
+                        private,
+                        max_players,
+                        series_games,
+                        short_game,
+                        max_turn_count,
+                        pause_at_branches,
+                        rules_variant,
+                        spectator_delay_secs,
+                        global_event_interval,
+                        stall_limit,
+                        seed,
+                        title,
+                        tags,
+                        language,
+                    },
+                    client.identity.as_ref(),
+                    transport_arc,
+                )
                 .await;
+            let (room_id, player_id, join_token) = match created {
+                Ok(created) => created,
+                Err(e) => {
+                    record_failure(client.ip, ConnectionFailure::CreateFailed);
+                    let _ = sender.send(ServerMessage::from(e)).await;
+                    return;
+                }
+            };
 
-            let invite_url = format!("/room/{}", room_id);
+            // 非公開部屋は招待URLにトークンを含める
+            let (invite_url, join_token) = if private {
+                (
+                    format!("/room/{}?token={}", room_id, join_token),
+                    Some(join_token),
+                )
+            } else {
+                (format!("/room/{}", room_id), None)
+            };
             let msg = ServerMessage::RoomCreated {
                 room_id: room_id.clone(),
                 invite_url,
                 player_id: player_id.clone(),
+                join_token,
             };
             let _ = sender.send(msg).await;
 
@@ -80,10 +387,11 @@ async fn handle_socket(socket: WebSocket, room_manager: AppState) {
             let room_state = ServerMessage::RoomState {
                 room_id: room_id.clone(),
                 player_id: player_id.clone(),
-                players: vec![crate::protocol::PlayerInfo {
-                    id: player_id.clone(),
-                    name: player_name.clone(),
-                }],
+                players: room_manager
+                    .get_room_info(&room_id)
+                    .await
+                    .map(|info| info.players)
+                    .unwrap_or_default(),
                 status: "Lobby".to_string(),
             };
             let _ = sender.send(room_state).await;
@@ -93,14 +401,88 @@ async fn handle_socket(socket: WebSocket, room_manager: AppState) {
         Ok(ClientMessage::JoinRoom {
             room_id,
             player_name,
+            token,
+            spectate: true,
+        }) => {
+            // 観戦者は観戦者チャットと退出だけを受け付ける
+            let transport_arc: Arc<dyn Transport> = Arc::new(sender.clone());
+            let spectator_id = match room_manager
+                .join_spectator(&room_id, player_name.clone(), token.as_deref(), transport_arc)
+                .await
+            {
+                Ok(spectator_id) => spectator_id,
+                Err(e) => {
+                    if redirect_to_owner(&room_manager, &sender, &room_id, &e).await {
+                        return;
+                    }
+                    record_failure(client.ip, ConnectionFailure::JoinFailed);
+                    let _ = sender.send(ServerMessage::from(e)).await;
+                    return;
+                }
+            };
+            if let Some(info) = room_manager.get_room_info(&room_id).await {
+                let room_state = ServerMessage::RoomState {
+                    room_id: room_id.clone(),
+                    player_id: spectator_id.clone(),
+                    players: info.players,
+                    status: info.status,
+                };
+                let _ = sender.send(room_state).await;
+            }
+            loop {
+                match receiver.recv().await {
+                    Ok(ClientMessage::ChatMessage { text }) => {
+                        if let Err(e) = chat::handle_spectator_chat(
+                            &room_manager,
+                            &mut chat_limiter,
+                            chat_filter.as_ref(),
+                            &room_id,
+                            &spectator_id,
+                            &player_name,
+                            text,
+                        )
+                        .await
+                        {
+                            let _ = sender.send(ServerMessage::from(e)).await;
+                        }
+                    }
+                    Ok(ClientMessage::Ping { client_ts }) => {
+                        let _ = sender.send(ServerMessage::Pong { client_ts }).await;
+                    }
+                    Ok(ClientMessage::LeaveRoom) | Err(_) => break,
+                    Ok(_) => {
+                        let msg = ServerMessage::Error {
+                            code: "SPECTATOR_READ_ONLY".to_string(),
+                            message: "Spectators can only chat".to_string(),
+                            valid_choices: None,
+                        };
+                        let _ = sender.send(msg).await;
+                    }
+                }
+            }
+            room_manager.leave_spectator(&room_id, &spectator_id).await;
+            return;
+        }
+        Ok(ClientMessage::JoinRoom {
+            room_id,
+            player_name,
+            token,
+            ..
         }) => {
             let sender_clone = sender.clone();
             let transport_arc: Arc<dyn Transport> = Arc::new(sender_clone);
             match room_manager
-                .join_room(&room_id, player_name.clone(), transport_arc)
+                .join_room(
+                    &room_id,
+                    player_name.clone(),
+                    token.as_deref(),
+                    client.handoff.as_deref(),
+                    client.identity.as_ref(),
+                    transport_arc,
+                )
                 .await
             {
-                Ok(player_id) => {
+                Ok((player_id, rejoined)) => {
                     // 参加を他のプレイヤーに通知
                     let msg = ServerMessage::PlayerJoined {
                         player_id: player_id.clone(),
@@ -119,141 +501,109 @@ async fn handle_socket(socket: WebSocket, room_manager: AppState) {
                         let _ = sender.send(room_state).await;
                     }
 
+                    let messages = room_manager.chat_history(&room_id).await;
+                    if !messages.is_empty() {
+                        let _ = sender.send(ServerMessage::ChatHistory { messages }).await;
+                    }
+
+                    // ゲーム中への再参加なら盤面と現在の状態を送る
+                    if rejoined {
+                        for msg in room_manager.game_snapshot(&room_id).await.unwrap_or_default() {
+                            let _ = sender.send(msg).await;
+                        }
+                    }
+
                     (room_id, player_id, player_name)
                 }
                 Err(e) => {
-                    let msg = ServerMessage::Error {
-                        code: "JOIN_FAILED".to_string(),
-                        message: e,
-                    };
-                    let _ = sender.send(msg).await;
+                    if redirect_to_owner(&room_manager, &sender, &room_id, &e).await {
+                        return;
+                    }
+                    record_failure(client.ip, ConnectionFailure::JoinFailed);
+                    let _ = sender.send(ServerMessage::from(e)).await;
                     return;
                 }
             }
         }
         Ok(_) => {
+            record_failure(client.ip, ConnectionFailure::InvalidFirstMessage);
             let msg = ServerMessage::Error {
                 code: "INVALID_FIRST_MESSAGE".to_string(),
                 message: "Expected CreateRoom or JoinRoom".to_string(),
+                valid_choices: None,
             };
             let _ = sender.send(msg).await;
             return;
         }
-        Err(_) => return,
+        // 切断は失敗に数えず、JSON として読めなかったものだけ数える
+        Err(e) => {
+            if e.downcast_ref::<serde_json::Error>().is_some() {
+                record_failure(client.ip, ConnectionFailure::MalformedMessage);
+            }
+            return;
+        }
     };
 
     // メッセージループ
     loop {
         match receiver.recv().await {
             Ok(ClientMessage::ChatMessage { text }) => {
-                chat::handle_chat(
+                if let Err(e) = chat::handle_chat(
                     &room_manager,
+                    &mut chat_limiter,
+                    chat_filter.as_ref(),
                     &room_id,
                     &player_id,
                     &player_name,
                     text,
                 )
-                .await;
-            }
-            Ok(ClientMessage::LeaveRoom) => {
-                let _ = room_manager.leave_room(&room_id, &player_id).await;
-                let msg = ServerMessage::PlayerLeft {
-                    player_id: player_id.clone(),
-                };
-                room_manager.broadcast(&room_id, &msg).await;
-                break;
-            }
-            Ok(ClientMessage::StartGame) => {
-                match room_manager.start_game(&room_id, &player_id).await {
-                    Ok(msgs) => {
-                        for msg in msgs {
-                            room_manager.broadcast(&room_id, &msg).await;
-                        }
-                    }
-                    Err(e) => {
-                        let _ = sender
-                            .send(ServerMessage::Error {
-                                code: "GAME_ERROR".to_string(),
-                                message: e,
-                            })
-                            .await;
-                    }
+                .await
+                {
+                    let _ = sender.send(ServerMessage::from(e)).await;
                 }
             }
-            Ok(ClientMessage::SpinRoulette) => {
-                match room_manager.spin_roulette(&room_id, &player_id).await {
-                    Ok(msgs) => {
-                        for msg in msgs {
-                            room_manager.broadcast(&room_id, &msg).await;
-                        }
-                    }
-                    Err(e) => {
-                        let _ = sender
-                            .send(ServerMessage::Error {
-                                code: "GAME_ERROR".to_string(),
-                                message: e,
-                            })
-                            .await;
-                    }
-                }
+            Ok(ClientMessage::Ping { client_ts }) => {
+                let _ = sender.send(ServerMessage::Pong { client_ts }).await;
             }
-            Ok(ClientMessage::ChoicePath { path_index }) => {
-                match room_manager
-                    .choose_path(&room_id, &player_id, path_index)
-                    .await
-                {
-                    Ok(msgs) => {
-                        for msg in msgs {
-                            room_manager.broadcast(&room_id, &msg).await;
-                        }
-                    }
-                    Err(e) => {
-                        let _ = sender
-                            .send(ServerMessage::Error {
-                                code: "GAME_ERROR".to_string(),
-                                message: e,
-                            })
-                            .await;
-                    }
-                }
+            Ok(ClientMessage::Typing) => {
+                chat::handle_typing(&room_manager, &mut chat_limiter, &room_id, &player_id).await;
             }
-            Ok(ClientMessage::ChoiceAction { action_id }) => {
-                match room_manager
-                    .choose_action(&room_id, &player_id, action_id)
-                    .await
+            Ok(ClientMessage::Reaction { emote_id, target }) => {
+                if let Err(e) =
+                    chat::handle_reaction(&room_manager, &mut chat_limiter, &room_id, &player_id, emote_id, target).await
                 {
-                    Ok(msgs) => {
-                        for msg in msgs {
-                            room_manager.broadcast(&room_id, &msg).await;
-                        }
-                    }
-                    Err(e) => {
-                        let _ = sender
-                            .send(ServerMessage::Error {
-                                code: "GAME_ERROR".to_string(),
-                                message: e,
-                            })
-                            .await;
-                    }
+                    let _ = sender.send(ServerMessage::from(e)).await;
                 }
             }
-            Ok(_) => {
-                let _ = sender
-                    .send(ServerMessage::Error {
-                        code: "UNKNOWN_MESSAGE".to_string(),
-                        message: "Unrecognized message type".to_string(),
-                    })
-                    .await;
+            Ok(ClientMessage::LeaveRoom) => {
+                let _ = worker.request(worker_request(&room_id, &player_id, ClientMessage::LeaveRoom)).await;
+                break;
+            }
+            // 部屋に入った後のゲーム操作はワーカーに任せ、本人への返信だけを送る
+            Ok(message) => {
+                for msg in worker.request(worker_request(&room_id, &player_id, message)).await {
+                    let _ = sender.send(msg).await;
+                }
             }
             Err(_) => {
-                // 接続切断時の処理
-                let _ = room_manager.leave_room(&room_id, &player_id).await;
-                let msg = ServerMessage::PlayerLeft {
-                    player_id: player_id.clone(),
-                };
-                room_manager.broadcast(&room_id, &msg).await;
+                if receiver.is_degraded() {
+                    let msg = ServerMessage::PlayerConnectionDegraded {
+                        player_id: player_id.clone(),
+                    };
+                    room_manager.broadcast(&room_id, &msg).await;
+                }
+                // 接続切断時の処理（管理者による切断・部屋削除済みなら通知不要）
+                let _ = worker.request(worker_request(&room_id, &player_id, ClientMessage::LeaveRoom)).await;
                 break;
             }
         }
     }
 }
+
+fn worker_request(room_id: &str, player_id: &str, message: ClientMessage) -> WorkerRequest {
+    WorkerRequest {
+        room_id: room_id.to_string(),
+        player_id: player_id.to_string(),
+        message,
+    }
+}
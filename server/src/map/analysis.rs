@@ -0,0 +1,317 @@
+//! マップのグラフ解析（マップ作者のバランス調整用）
+
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use serde::Serialize;
+
+use crate::game::state::{MapData, TileType};
+
+/// 列挙するルートの上限（分岐の多いマップで爆発しないように）
+const MAX_ROUTES: usize = 256;
+
+/// ルーレットの平均出目（1〜10）
+const AVERAGE_SPIN: f64 = 5.5;
+
+/// 通過するマスID列と、各分岐で選んだ道のラベル
+type Route = (Vec<usize>, Vec<String>);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MapAnalysis {
+    pub map_id: String,
+    pub tile_count: usize,
+    pub start_tile: usize,
+    pub retire_tiles: Vec<usize>,
+    /// スタートからリタイアまでの最短歩数
+    pub shortest_path: Option<usize>,
+    /// 最長歩数（ループがある場合は None）
+    pub longest_path: Option<usize>,
+    pub has_cycle: bool,
+    /// スタートから到達できないマス
+    pub unreachable_tiles: Vec<usize>,
+    /// リタイアに到達できない行き止まりのマス
+    pub dead_ends: Vec<usize>,
+    pub routes: Vec<RouteSummary>,
+    /// ルートが多すぎて列挙を打ち切ったか
+    pub routes_truncated: bool,
+    pub branches: Vec<BranchInfo>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RouteSummary {
+    /// 各分岐で選んだ道のラベル
+    pub choices: Vec<String>,
+    pub tiles: Vec<usize>,
+    pub length: usize,
+    /// ルート上の給料日マスの数
+    pub paydays: usize,
+    /// 平均的な出目で進んだ場合のターン数
+    pub expected_turns: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BranchInfo {
+    pub tile_id: usize,
+    pub fan_out: usize,
+    pub labels: Vec<String>,
+}
+
+pub fn analyze(map: &MapData) -> MapAnalysis {
+    let graph = Graph::new(map);
+    let start = map.tiles.first().map(|t| t.id).unwrap_or(0);
+    let retire_tiles: Vec<usize> = map
+        .tiles
+        .iter()
+        .filter(|t| t.tile_type == TileType::Retire || t.next.is_empty())
+        .map(|t| t.id)
+        .collect();
+
+    let reachable = graph.reachable_from(start);
+    let unreachable_tiles = map
+        .tiles
+        .iter()
+        .map(|t| t.id)
+        .filter(|id| !reachable.contains(id))
+        .collect();
+
+    // 逆向きに辿ってリタイアに到達できるマスを求める
+    // 規定周回でゴールする周回マップではスタートマスもゴールとみなす
+    let mut goals = retire_tiles.clone();
+    if map.laps.as_ref().is_some_and(|r| r.finish_after_laps.is_some()) {
+        goals.push(start);
+    }
+    let can_retire = graph.reaching(&goals);
+    let dead_ends = map
+        .tiles
+        .iter()
+        .map(|t| t.id)
+        .filter(|id| reachable.contains(id) && !can_retire.contains(id))
+        .collect();
+
+    let has_cycle = graph.has_cycle_from(start);
+    let (routes, routes_truncated) = graph.routes(start);
+    let route_summaries: Vec<RouteSummary> = routes
+        .into_iter()
+        .map(|(tiles, choices)| {
+            let length = tiles.len() - 1;
+            let paydays = tiles[1..]
+                .iter()
+                .filter(|id| graph.tile_type(**id) == Some(&TileType::Payday))
+                .count();
+            RouteSummary {
+                choices,
+                length,
+                paydays,
+                expected_turns: length as f64 / AVERAGE_SPIN,
+                tiles,
+            }
+        })
+        .collect();
+
+    let branches = map
+        .tiles
+        .iter()
+        .filter(|t| t.next.len() > 1)
+        .map(|t| BranchInfo {
+            tile_id: t.id,
+            fan_out: t.next.len(),
+            labels: (0..t.next.len()).map(|i| graph.branch_label(t.id, i)).collect(),
+        })
+        .collect();
+
+    MapAnalysis {
+        map_id: map.id.clone(),
+        tile_count: map.tiles.len(),
+        start_tile: start,
+        retire_tiles,
+        shortest_path: graph.shortest_path(start),
+        longest_path: if has_cycle || routes_truncated {
+            None
+        } else {
+            route_summaries.iter().map(|r| r.length).max()
+        },
+        has_cycle,
+        unreachable_tiles,
+        dead_ends,
+        routes: route_summaries,
+        routes_truncated,
+        branches,
+    }
+}
+
+struct Graph<'a> {
+    map: &'a MapData,
+    index: HashMap<usize, usize>,
+}
+
+impl<'a> Graph<'a> {
+    fn new(map: &'a MapData) -> Self {
+        let index = map.tiles.iter().enumerate().map(|(i, t)| (t.id, i)).collect();
+        Self { map, index }
+    }
+
+    fn next(&self, id: usize) -> &[usize] {
+        self.index
+            .get(&id)
+            .map(|&i| self.map.tiles[i].next.as_slice())
+            .unwrap_or(&[])
+    }
+
+    fn tile_type(&self, id: usize) -> Option<&TileType> {
+        self.index.get(&id).map(|&i| &self.map.tiles[i].tile_type)
+    }
+
+    fn branch_label(&self, id: usize, path_index: usize) -> String {
+        self.index
+            .get(&id)
+            .and_then(|&i| self.map.tiles[i].labels.as_ref())
+            .and_then(|labels| labels.get(path_index).cloned())
+            .unwrap_or_else(|| format!("道 {}", path_index + 1))
+    }
+
+    fn reachable_from(&self, start: usize) -> HashSet<usize> {
+        let mut seen = HashSet::from([start]);
+        let mut stack = vec![start];
+        while let Some(id) = stack.pop() {
+            for &next in self.next(id) {
+                if seen.insert(next) {
+                    stack.push(next);
+                }
+            }
+        }
+        seen
+    }
+
+    /// targets のいずれかに到達できるマス
+    fn reaching(&self, targets: &[usize]) -> HashSet<usize> {
+        let mut reverse: HashMap<usize, Vec<usize>> = HashMap::new();
+        for tile in &self.map.tiles {
+            for &next in &tile.next {
+                reverse.entry(next).or_default().push(tile.id);
+            }
+        }
+        let mut seen: HashSet<usize> = targets.iter().copied().collect();
+        let mut stack = targets.to_vec();
+        while let Some(id) = stack.pop() {
+            for &prev in reverse.get(&id).map(Vec::as_slice).unwrap_or(&[]) {
+                if seen.insert(prev) {
+                    stack.push(prev);
+                }
+            }
+        }
+        seen
+    }
+
+    fn has_cycle_from(&self, start: usize) -> bool {
+        // 0: 未訪問, 1: 探索中, 2: 完了
+        let mut state: HashMap<usize, u8> = HashMap::new();
+        let mut stack = vec![(start, 0usize)];
+        state.insert(start, 1);
+        while let Some((id, child)) = stack.pop() {
+            let next = self.next(id);
+            if child < next.len() {
+                stack.push((id, child + 1));
+                let n = next[child];
+                match state.get(&n).copied().unwrap_or(0) {
+                    1 => return true,
+                    0 => {
+                        state.insert(n, 1);
+                        stack.push((n, 0));
+                    }
+                    _ => {}
+                }
+            } else {
+                state.insert(id, 2);
+            }
+        }
+        false
+    }
+
+    /// スタートから行き止まり（next が空）までの単純路を列挙する
+    fn routes(&self, start: usize) -> (Vec<Route>, bool) {
+        let mut routes = Vec::new();
+        let mut path = vec![start];
+        let mut choices = Vec::new();
+        let truncated = self.walk(&mut path, &mut choices, &mut routes);
+        (routes, truncated)
+    }
+
+    fn walk(
+        &self,
+        path: &mut Vec<usize>,
+        choices: &mut Vec<String>,
+        routes: &mut Vec<Route>,
+    ) -> bool {
+        let id = *path.last().expect("path is not empty");
+        let next = self.next(id);
+        if next.is_empty() {
+            routes.push((path.clone(), choices.clone()));
+            return routes.len() >= MAX_ROUTES;
+        }
+        for (i, &n) in next.iter().enumerate() {
+            if path.contains(&n) {
+                continue; // ループは1周で打ち切る
+            }
+            let branched = next.len() > 1;
+            if branched {
+                choices.push(self.branch_label(id, i));
+            }
+            path.push(n);
+            let truncated = self.walk(path, choices, routes);
+            path.pop();
+            if branched {
+                choices.pop();
+            }
+            if truncated {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// 行き止まり（リタイア）までの BFS による最短歩数
+    fn shortest_path(&self, start: usize) -> Option<usize> {
+        let mut dist = HashMap::from([(start, 0usize)]);
+        let mut queue = VecDeque::from([start]);
+        while let Some(id) = queue.pop_front() {
+            let d = dist[&id];
+            if self.next(id).is_empty() {
+                return Some(d);
+            }
+            for &n in self.next(id) {
+                if let Entry::Vacant(e) = dist.entry(n) {
+                    e.insert(d + 1);
+                    queue.push_back(n);
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map::MapCatalog;
+
+    #[test]
+    fn test_analyze_classic_map() {
+        let catalog = MapCatalog::builtin();
+        let analysis = analyze(catalog.get("classic").unwrap());
+
+        assert_eq!(analysis.start_tile, 0);
+        assert_eq!(analysis.retire_tiles, vec![36]);
+        assert!(!analysis.has_cycle);
+        assert!(analysis.unreachable_tiles.is_empty());
+        assert!(analysis.dead_ends.is_empty());
+
+        // 就職コース（0→1→...→4→9）と大学コース（0→5→...→8→9）
+        assert_eq!(analysis.routes.len(), 2);
+        assert_eq!(analysis.routes[0].choices, vec!["就職コース"]);
+        assert_eq!(analysis.shortest_path, Some(32));
+        assert_eq!(analysis.longest_path, Some(32));
+        assert_eq!(analysis.branches.len(), 1);
+        assert_eq!(analysis.branches[0].fan_out, 2);
+        assert!(analysis.routes.iter().all(|r| r.paydays >= 6));
+    }
+}
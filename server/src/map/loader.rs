@@ -0,0 +1,194 @@
+//! マップの読み込み
+//! 組み込みの classic マップに加え、マップディレクトリ内の `*.json` / `*.toml` を拡張子で判別して読み込む
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::game::script;
+use crate::game::state::{MapData, TileEvent};
+
+/// 埋め込みマップデータ
+const CLASSIC_MAP_JSON: &str = include_str!("classic.json");
+
+/// マップの記述形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MapFormat {
+    Json,
+    Toml,
+}
+
+impl MapFormat {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "json" => Some(Self::Json),
+            "toml" => Some(Self::Toml),
+            _ => None,
+        }
+    }
+}
+
+/// マップ定義をパースして検証する
+pub fn parse_map(source: &str, format: MapFormat) -> Result<MapData, String> {
+    let map: MapData = match format {
+        MapFormat::Json => serde_json::from_str(source).map_err(|e| e.to_string())?,
+        MapFormat::Toml => {
+            let value = super::toml::parse(source)?;
+            serde_json::from_value(value).map_err(|e| e.to_string())?
+        }
+    };
+    validate(&map)?;
+    Ok(map)
+}
+
+pub fn load_file(path: &Path) -> Result<MapData, String> {
+    let format = MapFormat::from_path(path)
+        .ok_or_else(|| format!("{}: unsupported map format", path.display()))?;
+    let source =
+        std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    parse_map(&source, format).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// タイルの接続・スクリプトの構文・火事と盗難の金額を検証する
+fn validate(map: &MapData) -> Result<(), String> {
+    if map.tiles.is_empty() {
+        return Err("map has no tiles".to_string());
+    }
+    let exists = |id: usize| map.tiles.iter().any(|t| t.id == id);
+    for tile in &map.tiles {
+        if let Some(next) = tile.next.iter().find(|&&next| !exists(next)) {
+            return Err(format!("tile {} points to missing tile {}", tile.id, next));
+        }
+        match &tile.event {
+            Some(TileEvent::Script { source }) => {
+                script::validate(source).map_err(|e| format!("tile {} script: {}", tile.id, e))?;
+            }
+            Some(TileEvent::Fire { amount } | TileEvent::Theft { amount } | TileEvent::Lawsuit { amount })
+                if *amount < 0 =>
+            {
+                return Err(format!("tile {} event amount must not be negative", tile.id));
+            }
+            _ => {}
+        }
+    }
+    if let Some(short_start) = map.short_start_tile.filter(|&id| !exists(id)) {
+        return Err(format!("short_start_tile {} does not exist", short_start));
+    }
+    if map.laps.as_ref().and_then(|r| r.finish_after_laps) == Some(0) {
+        return Err("laps.finish_after_laps must be at least 1".to_string());
+    }
+    Ok(())
+}
+
+/// 利用可能なマップの一覧（マップID -> マップデータ）
+pub struct MapCatalog {
+    maps: BTreeMap<String, MapData>,
+}
+
+impl MapCatalog {
+    /// 組み込みマップのみ
+    pub fn builtin() -> Self {
+        let classic = parse_map(CLASSIC_MAP_JSON, MapFormat::Json)
+            .expect("embedded classic map must be valid");
+        let mut maps = BTreeMap::new();
+        maps.insert(classic.id.clone(), classic);
+        Self { maps }
+    }
+
+    /// ディレクトリ内のマップを追加する（同じIDは上書き）
+    /// 読み込めなかったファイルはエラーを出力して読み飛ばし、読み込めた数を返す
+    pub fn load_dir(&mut self, dir: &Path) -> Result<usize, String> {
+        let entries = std::fs::read_dir(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+        let mut paths: Vec<_> = entries
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| MapFormat::from_path(p).is_some())
+            .collect();
+        paths.sort();
+
+        let mut loaded = 0;
+        for path in paths {
+            match load_file(&path) {
+                Ok(map) => {
+                    self.maps.insert(map.id.clone(), map);
+                    loaded += 1;
+                }
+                Err(e) => eprintln!("skipping map {}", e),
+            }
+        }
+        Ok(loaded)
+    }
+
+    pub fn get(&self, map_id: &str) -> Option<&MapData> {
+        self.maps.get(map_id)
+    }
+
+    pub fn ids(&self) -> impl Iterator<Item = &str> {
+        self.maps.keys().map(String::as_str)
+    }
+}
+
+impl Default for MapCatalog {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_TOML: &str = r#"
+id = "mini"
+name = "ミニマップ"
+version = "1.0"
+start_money = 10000
+loan_unit = 20000
+loan_interest_rate = 1.25
+careers = []
+houses = []
+
+[[tiles]]
+id = 0
+type = "Start"
+position = { x = 0, y = 0 }
+next = [1]
+
+[[tiles]]
+id = 1
+type = "Action"
+position = { x = 1, y = 0 }
+next = [2]
+event = { type = "money", amount = 500, text = "お小遣い" }
+
+[[tiles]]
+id = 2
+type = "Retire"
+position = { x = 2, y = 0 }
+next = []
+"#;
+
+    #[test]
+    fn test_parse_toml_map() {
+        let map = parse_map(SAMPLE_TOML, MapFormat::Toml).unwrap();
+        assert_eq!(map.id, "mini");
+        assert_eq!(map.tiles.len(), 3);
+        assert!(matches!(map.tiles[1].event, Some(TileEvent::Money { amount: 500, .. })));
+
+        let broken = SAMPLE_TOML.replace("next = [2]", "next = [9]");
+        assert!(parse_map(&broken, MapFormat::Toml).is_err());
+    }
+
+    #[test]
+    fn test_load_dir_detects_format() {
+        let dir = std::env::temp_dir().join(format!("nine-life-maps-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("mini.toml"), SAMPLE_TOML).unwrap();
+        std::fs::write(dir.join("notes.txt"), "ignored").unwrap();
+        std::fs::write(dir.join("broken.json"), "{").unwrap();
+
+        let mut catalog = MapCatalog::builtin();
+        assert_eq!(catalog.load_dir(&dir).unwrap(), 1);
+        assert_eq!(catalog.ids().collect::<Vec<_>>(), vec!["classic", "mini"]);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
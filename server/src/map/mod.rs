@@ -0,0 +1,6 @@
+pub mod analysis;
+pub mod loader;
+pub mod schema;
+pub mod toml;
+
+pub use loader::MapCatalog;
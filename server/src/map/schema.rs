@@ -0,0 +1,46 @@
+//! マップ定義の JSON Schema（エディタでの補完・検証用）
+//!
+//! `MapData` を変更したら schema.json も合わせて更新すること（テストで食い違いを検出する）。
+
+const MAP_SCHEMA_JSON: &str = include_str!("schema.json");
+
+/// 名前に対応する JSON Schema を返す（現在は "map" のみ）
+pub fn schema(name: &str) -> Option<&'static str> {
+    match name {
+        "map" => Some(MAP_SCHEMA_JSON),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map::MapCatalog;
+    use serde_json::Value;
+
+    /// オブジェクトのキーがすべてスキーマの properties にあり、required がすべて揃っているか
+    fn assert_matches(object: &Value, schema: &Value, context: &str) {
+        let properties = schema["properties"].as_object().unwrap();
+        for key in object.as_object().unwrap().keys() {
+            assert!(properties.contains_key(key), "{}: '{}' is missing from schema", context, key);
+        }
+        for key in schema["required"].as_array().unwrap() {
+            let key = key.as_str().unwrap();
+            assert!(object.get(key).is_some(), "{}: required '{}' is not serialized", context, key);
+        }
+    }
+
+    #[test]
+    fn test_map_schema_matches_map_data() {
+        let map_schema: Value = serde_json::from_str(schema("map").unwrap()).unwrap();
+        let catalog = MapCatalog::builtin();
+        let map = serde_json::to_value(catalog.get("classic").unwrap()).unwrap();
+
+        let defs = &map_schema["$defs"];
+        assert_matches(&map, &map_schema, "map");
+        assert_matches(&map["tiles"][0], &defs["tile"], "tile");
+        assert_matches(&map["careers"][0], &defs["career"], "career");
+        assert_matches(&map["houses"][0], &defs["house"], "house");
+        assert!(schema("client-message").is_none());
+    }
+}
@@ -0,0 +1,423 @@
+//! マップ定義用の TOML パーサー（serde_json::Value に変換する）
+//!
+//! 対応している構文:
+//! - `key = value`（裸のキー・引用符付きキー・ドット区切りキー）
+//! - `[table]` / `[[array_of_tables]]`
+//! - 文字列（基本・リテラル・複数行）、整数、浮動小数点数、真偽値、配列、インラインテーブル
+//!
+//! 日時型には対応しない（マップ定義では使わない）。
+
+use serde_json::{Map, Number, Value};
+
+pub fn parse(source: &str) -> Result<Value, String> {
+    let mut parser = Parser {
+        src: source,
+        pos: 0,
+        line: 1,
+    };
+    parser.document().map_err(|e| format!("line {}: {}", parser.line, e))
+}
+
+struct Parser<'a> {
+    src: &'a str,
+    pos: usize,
+    line: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.src[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        if c == '\n' {
+            self.line += 1;
+        }
+        Some(c)
+    }
+
+    fn eat(&mut self, s: &str) -> bool {
+        if self.rest().starts_with(s) {
+            for _ in s.chars() {
+                self.bump();
+            }
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, s: &str) -> Result<(), String> {
+        if self.eat(s) {
+            Ok(())
+        } else {
+            Err(format!("expected '{}'", s))
+        }
+    }
+
+    /// 行内の空白とコメントを読み飛ばす
+    fn skip_inline_ws(&mut self) {
+        while let Some(c) = self.peek() {
+            match c {
+                ' ' | '\t' => {
+                    self.bump();
+                }
+                '#' => {
+                    while !matches!(self.peek(), None | Some('\n')) {
+                        self.bump();
+                    }
+                }
+                _ => break,
+            }
+        }
+    }
+
+    /// 改行を含む空白とコメントを読み飛ばす
+    fn skip_ws(&mut self) {
+        loop {
+            self.skip_inline_ws();
+            match self.peek() {
+                Some('\n') | Some('\r') => {
+                    self.bump();
+                }
+                _ => break,
+            }
+        }
+    }
+
+    fn end_of_line(&mut self) -> Result<(), String> {
+        self.skip_inline_ws();
+        self.eat("\r");
+        match self.peek() {
+            None => Ok(()),
+            Some('\n') => {
+                self.bump();
+                Ok(())
+            }
+            Some(c) => Err(format!("unexpected character after value: {:?}", c)),
+        }
+    }
+
+    fn document(&mut self) -> Result<Value, String> {
+        let mut root = Map::new();
+        // 現在のテーブルへのパス（配列テーブルは最後の要素を指す）
+        let mut current: Vec<String> = Vec::new();
+
+        loop {
+            self.skip_ws();
+            if self.peek().is_none() {
+                break;
+            }
+
+            if self.eat("[[") {
+                let path = self.key_path()?;
+                self.expect("]]")?;
+                self.end_of_line()?;
+                let (last, parent) = path.split_last().expect("key path is not empty");
+                let table = table_at(&mut root, parent)?;
+                let array = table
+                    .entry(last.clone())
+                    .or_insert_with(|| Value::Array(Vec::new()));
+                match array {
+                    Value::Array(items) => items.push(Value::Object(Map::new())),
+                    _ => return Err(format!("'{}' is not an array of tables", last)),
+                }
+                current = path;
+            } else if self.eat("[") {
+                let path = self.key_path()?;
+                self.expect("]")?;
+                self.end_of_line()?;
+                table_at(&mut root, &path)?;
+                current = path;
+            } else {
+                let path = self.key_path()?;
+                self.expect("=")?;
+                self.skip_inline_ws();
+                let value = self.value()?;
+                self.end_of_line()?;
+                let table = table_at(&mut root, &current)?;
+                insert(table, &path, value)?;
+            }
+        }
+        Ok(Value::Object(root))
+    }
+
+    fn key_path(&mut self) -> Result<Vec<String>, String> {
+        let mut path = Vec::new();
+        loop {
+            self.skip_inline_ws();
+            path.push(self.key()?);
+            self.skip_inline_ws();
+            if !self.eat(".") {
+                return Ok(path);
+            }
+        }
+    }
+
+    fn key(&mut self) -> Result<String, String> {
+        match self.peek() {
+            Some('"') => self.basic_string(),
+            Some('\'') => self.literal_string(),
+            _ => {
+                let len = self
+                    .rest()
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '-'))
+                    .unwrap_or(self.rest().len());
+                if len == 0 {
+                    return Err("expected key".to_string());
+                }
+                let key = self.rest()[..len].to_string();
+                self.pos += len;
+                Ok(key)
+            }
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, String> {
+        match self.peek() {
+            Some('"') if self.rest().starts_with("\"\"\"") => {
+                self.multiline_string("\"\"\"", true).map(Value::String)
+            }
+            Some('\'') if self.rest().starts_with("'''") => {
+                self.multiline_string("'''", false).map(Value::String)
+            }
+            Some('"') => self.basic_string().map(Value::String),
+            Some('\'') => self.literal_string().map(Value::String),
+            Some('[') => self.array(),
+            Some('{') => self.inline_table(),
+            Some('t') if self.eat("true") => Ok(Value::Bool(true)),
+            Some('f') if self.eat("false") => Ok(Value::Bool(false)),
+            Some(_) => self.number(),
+            None => Err("expected value".to_string()),
+        }
+    }
+
+    fn basic_string(&mut self) -> Result<String, String> {
+        self.expect("\"")?;
+        let mut s = String::new();
+        loop {
+            if matches!(self.peek(), None | Some('\n')) {
+                return Err("unterminated string".to_string());
+            }
+            match self.bump() {
+                Some('"') => return Ok(s),
+                Some('\\') => s.push(self.escape()?),
+                Some(c) => s.push(c),
+                None => unreachable!("checked above"),
+            }
+        }
+    }
+
+    fn literal_string(&mut self) -> Result<String, String> {
+        self.expect("'")?;
+        let end = self
+            .rest()
+            .find(['\'', '\n'])
+            .filter(|&i| self.rest()[i..].starts_with('\''))
+            .ok_or("unterminated string")?;
+        let s = self.rest()[..end].to_string();
+        self.pos += end + 1;
+        Ok(s)
+    }
+
+    fn multiline_string(&mut self, delim: &str, escapes: bool) -> Result<String, String> {
+        self.expect(delim)?;
+        // 開始デリミタ直後の改行は含めない
+        if !self.eat("\r\n") {
+            self.eat("\n");
+        }
+        let mut s = String::new();
+        loop {
+            if self.eat(delim) {
+                return Ok(s);
+            }
+            match self.bump() {
+                None => return Err("unterminated multi-line string".to_string()),
+                Some('\\') if escapes => {
+                    // 行末のバックスラッシュは改行と続く空白を取り除く
+                    if matches!(self.peek(), Some('\n') | Some('\r')) {
+                        while matches!(self.peek(), Some(c) if c.is_whitespace()) {
+                            self.bump();
+                        }
+                    } else {
+                        s.push(self.escape()?);
+                    }
+                }
+                Some(c) => s.push(c),
+            }
+        }
+    }
+
+    fn escape(&mut self) -> Result<char, String> {
+        Ok(match self.bump() {
+            Some('n') => '\n',
+            Some('t') => '\t',
+            Some('r') => '\r',
+            Some('"') => '"',
+            Some('\\') => '\\',
+            Some('u') => self.unicode_escape(4)?,
+            Some('U') => self.unicode_escape(8)?,
+            other => return Err(format!("invalid escape: {:?}", other)),
+        })
+    }
+
+    fn unicode_escape(&mut self, len: usize) -> Result<char, String> {
+        let hex = self.rest().get(..len).ok_or("invalid unicode escape")?;
+        let code = u32::from_str_radix(hex, 16).map_err(|_| "invalid unicode escape")?;
+        self.pos += len;
+        char::from_u32(code).ok_or_else(|| "invalid unicode escape".to_string())
+    }
+
+    fn number(&mut self) -> Result<Value, String> {
+        let len = self
+            .rest()
+            .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.' | '_')))
+            .unwrap_or(self.rest().len());
+        let raw = &self.rest()[..len];
+        let cleaned = raw.replace('_', "");
+        self.pos += len;
+
+        if let Ok(i) = cleaned.parse::<i64>() {
+            return Ok(Value::Number(i.into()));
+        }
+        cleaned
+            .parse::<f64>()
+            .ok()
+            .and_then(Number::from_f64)
+            .map(Value::Number)
+            .ok_or_else(|| format!("invalid value: {}", raw))
+    }
+
+    fn array(&mut self) -> Result<Value, String> {
+        self.expect("[")?;
+        let mut items = Vec::new();
+        loop {
+            self.skip_ws();
+            if self.eat("]") {
+                return Ok(Value::Array(items));
+            }
+            items.push(self.value()?);
+            self.skip_ws();
+            if !self.eat(",") {
+                self.skip_ws();
+                self.expect("]")?;
+                return Ok(Value::Array(items));
+            }
+        }
+    }
+
+    fn inline_table(&mut self) -> Result<Value, String> {
+        self.expect("{")?;
+        let mut table = Map::new();
+        self.skip_inline_ws();
+        if self.eat("}") {
+            return Ok(Value::Object(table));
+        }
+        loop {
+            let path = self.key_path()?;
+            self.expect("=")?;
+            self.skip_inline_ws();
+            let value = self.value()?;
+            insert(&mut table, &path, value)?;
+            self.skip_inline_ws();
+            if self.eat("}") {
+                return Ok(Value::Object(table));
+            }
+            self.expect(",")?;
+        }
+    }
+}
+
+/// パスのテーブルを返す（無ければ作成。配列テーブルは最後の要素）
+fn table_at<'m>(root: &'m mut Map<String, Value>, path: &[String]) -> Result<&'m mut Map<String, Value>, String> {
+    let mut table = root;
+    for key in path {
+        let entry = table
+            .entry(key.clone())
+            .or_insert_with(|| Value::Object(Map::new()));
+        table = match entry {
+            Value::Object(map) => map,
+            Value::Array(items) => match items.last_mut() {
+                Some(Value::Object(map)) => map,
+                _ => return Err(format!("'{}' is not a table", key)),
+            },
+            _ => return Err(format!("'{}' is not a table", key)),
+        };
+    }
+    Ok(table)
+}
+
+fn insert(table: &mut Map<String, Value>, path: &[String], value: Value) -> Result<(), String> {
+    let (last, parent) = path.split_last().expect("key path is not empty");
+    let table = table_at(table, parent)?;
+    if table.contains_key(last) {
+        return Err(format!("duplicate key: {}", last));
+    }
+    table.insert(last.clone(), value);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_map_like_document() {
+        let source = r#"
+# マップ定義
+id = "loop"
+name = 'ループ盤'
+loan_interest_rate = 1.25
+start_money = 10_000
+meta.author = "rerolab"
+
+[[tiles]]
+id = 0
+type = "Start"
+position = { x = 0, y = -1.5 }
+next = [
+  1,
+  2, # 分岐
+]
+
+[[tiles]]
+id = 1
+type = "Action"
+event = { type = "script", source = """
+money += 100;
+emit("ok!");
+""" }
+
+[extra]
+flag = true
+"#;
+        let value = parse(source).unwrap();
+        assert_eq!(value["id"], json!("loop"));
+        assert_eq!(value["name"], json!("ループ盤"));
+        assert_eq!(value["start_money"], json!(10000));
+        assert_eq!(value["loan_interest_rate"], json!(1.25));
+        assert_eq!(value["meta"]["author"], json!("rerolab"));
+        assert_eq!(value["tiles"][0]["position"], json!({ "x": 0, "y": -1.5 }));
+        assert_eq!(value["tiles"][0]["next"], json!([1, 2]));
+        assert_eq!(
+            value["tiles"][1]["event"]["source"],
+            json!("money += 100;\nemit(\"ok!\");\n")
+        );
+        assert_eq!(value["extra"]["flag"], json!(true));
+    }
+
+    #[test]
+    fn test_parse_errors_report_line() {
+        let err = parse("a = 1\nb = \"unterminated\n").unwrap_err();
+        assert!(err.starts_with("line 2"), "{}", err);
+        assert!(parse("a = 1\na = 2\n").is_err());
+        assert!(parse("a = 1 b\n").is_err());
+    }
+}
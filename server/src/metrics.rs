@@ -0,0 +1,286 @@
+//! 運用向けの計測値（Prometheus のテキスト形式で GET /admin/metrics から返す）
+//!
+//! 部屋の待ち時間やゲームの長さをヒストグラムで集計し、タイムアウトなどの設定を実際の値から決められるようにする。
+//! WebSocket の接続数と失敗数は送信元IPごとにも数え、失敗の多いIPを GET /admin/offenders で確認できる。
+//! 値はプロセス全体で1つ（METRICS）。部屋タスクからも HTTP ハンドラからも引数なしで記録・参照できる。
+
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::net::IpAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+/// 部屋の待ち時間・ゲームの長さのバケット（秒）
+const DURATION_BUCKETS: [u64; 11] = [5, 15, 30, 60, 120, 300, 600, 1200, 1800, 3600, 7200];
+
+/// 送信元IPごとの記録を残す上限（超えたら失敗の少ない・古いIPから忘れる）
+const MAX_TRACKED_IPS: usize = 10_000;
+
+/// サーバー全体の計測値
+pub static METRICS: LazyLock<Metrics> = LazyLock::new(Metrics::new);
+
+pub struct Metrics {
+    /// 部屋の作成（2ゲーム目以降は前のゲームの終了）からゲーム開始まで
+    pub lobby_wait: Histogram,
+    /// ゲーム開始から終了まで
+    pub game_duration: Histogram,
+    /// WebSocket の接続と失敗
+    pub connections: ConnectionStats,
+}
+
+impl Metrics {
+    fn new() -> Self {
+        Self {
+            lobby_wait: Histogram::new(
+                "nine_life_lobby_wait_seconds",
+                "部屋の作成（または前のゲームの終了）からゲーム開始までの時間",
+                &DURATION_BUCKETS,
+            ),
+            game_duration: Histogram::new(
+                "nine_life_game_duration_seconds",
+                "ゲーム開始から終了までの時間",
+                &DURATION_BUCKETS,
+            ),
+            connections: ConnectionStats::new(MAX_TRACKED_IPS),
+        }
+    }
+
+    /// Prometheus のテキスト形式
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        self.lobby_wait.render(&mut out);
+        self.game_duration.render(&mut out);
+        self.connections.render(&mut out);
+        out
+    }
+}
+
+/// 累積バケットのヒストグラム（境界は秒）
+pub struct Histogram {
+    name: &'static str,
+    help: &'static str,
+    bounds: &'static [u64],
+    /// bounds[i] 以下の観測数（最後の要素は +Inf）
+    buckets: Vec<AtomicU64>,
+    sum_ms: AtomicU64,
+    count: AtomicU64,
+}
+
+impl Histogram {
+    pub fn new(name: &'static str, help: &'static str, bounds: &'static [u64]) -> Self {
+        Self {
+            name,
+            help,
+            bounds,
+            buckets: (0..=bounds.len()).map(|_| AtomicU64::new(0)).collect(),
+            sum_ms: AtomicU64::new(0),
+            count: AtomicU64::new(0),
+        }
+    }
+
+    pub fn observe(&self, value: Duration) {
+        let ms = value.as_millis() as u64;
+        let index = self
+            .bounds
+            .iter()
+            .position(|&bound| ms <= bound * 1000)
+            .unwrap_or(self.bounds.len());
+        self.buckets[index].fetch_add(1, Ordering::Relaxed);
+        self.sum_ms.fetch_add(ms, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    fn render(&self, out: &mut String) {
+        let _ = writeln!(out, "# HELP {} {}", self.name, self.help);
+        let _ = writeln!(out, "# TYPE {} histogram", self.name);
+        let mut cumulative = 0;
+        for (i, bucket) in self.buckets.iter().enumerate() {
+            cumulative += bucket.load(Ordering::Relaxed);
+            let le = self.bounds.get(i).map_or("+Inf".to_string(), u64::to_string);
+            let _ = writeln!(out, "{}_bucket{{le=\"{}\"}} {}", self.name, le, cumulative);
+        }
+        let sum = self.sum_ms.load(Ordering::Relaxed) as f64 / 1000.0;
+        let _ = writeln!(out, "{}_sum {}", self.name, sum);
+        let _ = writeln!(out, "{}_count {}", self.name, self.count());
+    }
+}
+
+/// WebSocket 接続の失敗の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConnectionFailure {
+    /// 認証トークンの検証に失敗した
+    Unauthorized,
+    /// 最初のメッセージを JSON として読めなかった
+    MalformedMessage,
+    /// 最初のメッセージが CreateRoom / JoinRoom 以外だった
+    InvalidFirstMessage,
+    /// 部屋を作れなかった
+    CreateFailed,
+    /// 部屋に参加できなかった（観戦を含む）
+    JoinFailed,
+}
+
+impl ConnectionFailure {
+    const ALL: [ConnectionFailure; 5] = [
+        ConnectionFailure::Unauthorized,
+        ConnectionFailure::MalformedMessage,
+        ConnectionFailure::InvalidFirstMessage,
+        ConnectionFailure::CreateFailed,
+        ConnectionFailure::JoinFailed,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            ConnectionFailure::Unauthorized => "unauthorized",
+            ConnectionFailure::MalformedMessage => "malformed_message",
+            ConnectionFailure::InvalidFirstMessage => "invalid_first_message",
+            ConnectionFailure::CreateFailed => "create_failed",
+            ConnectionFailure::JoinFailed => "join_failed",
+        }
+    }
+}
+
+/// 送信元IP1つ分の記録（管理APIの応答）
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Offender {
+    pub ip: IpAddr,
+    pub connections: u64,
+    pub failures: u64,
+    pub last_failure: Option<ConnectionFailure>,
+    /// 最後に接続・失敗した時刻（UNIX ミリ秒）
+    pub last_seen: u64,
+}
+
+/// WebSocket の接続数・失敗数（サーバー全体と送信元IPごと）
+pub struct ConnectionStats {
+    upgrades: AtomicU64,
+    failures: [AtomicU64; ConnectionFailure::ALL.len()],
+    by_ip: Mutex<HashMap<IpAddr, Offender>>,
+    max_ips: usize,
+}
+
+impl ConnectionStats {
+    pub fn new(max_ips: usize) -> Self {
+        Self {
+            upgrades: AtomicU64::new(0),
+            failures: Default::default(),
+            by_ip: Mutex::new(HashMap::new()),
+            max_ips,
+        }
+    }
+
+    pub fn record_upgrade(&self, ip: IpAddr) {
+        self.upgrades.fetch_add(1, Ordering::Relaxed);
+        self.update(ip, |entry| entry.connections += 1);
+    }
+
+    pub fn record_failure(&self, ip: IpAddr, failure: ConnectionFailure) {
+        self.failures[failure as usize].fetch_add(1, Ordering::Relaxed);
+        self.update(ip, |entry| {
+            entry.failures += 1;
+            entry.last_failure = Some(failure);
+        });
+    }
+
+    /// 失敗の多い順（同数なら最近の順）に limit 件。失敗していないIPは含めない
+    pub fn top_offenders(&self, limit: usize) -> Vec<Offender> {
+        let by_ip = self.by_ip.lock().unwrap_or_else(|e| e.into_inner());
+        let mut offenders: Vec<Offender> = by_ip.values().filter(|o| o.failures > 0).cloned().collect();
+        offenders.sort_by_key(|o| (std::cmp::Reverse(o.failures), std::cmp::Reverse(o.last_seen)));
+        offenders.truncate(limit);
+        offenders
+    }
+
+    fn update(&self, ip: IpAddr, apply: impl FnOnce(&mut Offender)) {
+        let mut by_ip = self.by_ip.lock().unwrap_or_else(|e| e.into_inner());
+        if !by_ip.contains_key(&ip) && by_ip.len() >= self.max_ips {
+            let forget = by_ip
+                .values()
+                .min_by_key(|o| (o.failures, o.last_seen))
+                .map(|o| o.ip);
+            if let Some(forget) = forget {
+                by_ip.remove(&forget);
+            }
+        }
+        let entry = by_ip.entry(ip).or_insert(Offender {
+            ip,
+            connections: 0,
+            failures: 0,
+            last_failure: None,
+            last_seen: 0,
+        });
+        apply(entry);
+        entry.last_seen = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+    }
+
+    fn render(&self, out: &mut String) {
+        let _ = writeln!(out, "# HELP nine_life_ws_upgrades_total WebSocket の接続数");
+        let _ = writeln!(out, "# TYPE nine_life_ws_upgrades_total counter");
+        let _ = writeln!(out, "nine_life_ws_upgrades_total {}", self.upgrades.load(Ordering::Relaxed));
+        let _ = writeln!(out, "# HELP nine_life_ws_failures_total 部屋の作成・参加までに失敗した WebSocket 接続の数");
+        let _ = writeln!(out, "# TYPE nine_life_ws_failures_total counter");
+        for failure in ConnectionFailure::ALL {
+            let count = self.failures[failure as usize].load(Ordering::Relaxed);
+            let _ = writeln!(out, "nine_life_ws_failures_total{{reason=\"{}\"}} {}", failure.label(), count);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram_renders_cumulative_buckets() {
+        let histogram = Histogram::new("test_seconds", "テスト", &[1, 10]);
+        histogram.observe(Duration::from_millis(500));
+        histogram.observe(Duration::from_secs(10));
+        histogram.observe(Duration::from_secs(60));
+
+        let mut out = String::new();
+        histogram.render(&mut out);
+        assert!(out.contains("# TYPE test_seconds histogram"));
+        assert!(out.contains("test_seconds_bucket{le=\"1\"} 1\n"));
+        assert!(out.contains("test_seconds_bucket{le=\"10\"} 2\n"));
+        assert!(out.contains("test_seconds_bucket{le=\"+Inf\"} 3\n"));
+        assert!(out.contains("test_seconds_sum 70.5\n"));
+        assert!(out.contains("test_seconds_count 3\n"));
+    }
+
+    #[test]
+    fn test_top_offenders_and_eviction() {
+        let stats = ConnectionStats::new(2);
+        let a: IpAddr = "10.0.0.1".parse().unwrap();
+        let b: IpAddr = "10.0.0.2".parse().unwrap();
+        let c: IpAddr = "10.0.0.3".parse().unwrap();
+        stats.record_upgrade(a);
+        stats.record_failure(a, ConnectionFailure::InvalidFirstMessage);
+        stats.record_failure(a, ConnectionFailure::JoinFailed);
+        stats.record_upgrade(b);
+        // 上限なので失敗の無い b を忘れる
+        stats.record_failure(c, ConnectionFailure::Unauthorized);
+
+        let top = stats.top_offenders(10);
+        assert_eq!(top.iter().map(|o| o.ip).collect::<Vec<_>>(), vec![a, c]);
+        assert_eq!(top[0].connections, 1);
+        assert_eq!(top[0].failures, 2);
+        assert_eq!(top[0].last_failure, Some(ConnectionFailure::JoinFailed));
+        assert_eq!(stats.top_offenders(1).len(), 1);
+
+        let mut out = String::new();
+        stats.render(&mut out);
+        assert!(out.contains("nine_life_ws_upgrades_total 2\n"));
+        assert!(out.contains("nine_life_ws_failures_total{reason=\"join_failed\"} 1\n"));
+    }
+}
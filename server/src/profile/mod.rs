@@ -0,0 +1,266 @@
+//! 認証済みプレイヤーのプロフィール（表示名・戦績・好きな色・レーティング）
+
+pub mod rating;
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+use ts_rs::TS;
+
+use crate::game::Achievement;
+use crate::storage::Storage;
+
+const COLLECTION: &str = "profiles";
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct Profile {
+    pub user_id: String,
+    pub display_name: String,
+    pub games_played: u32,
+    pub wins: u32,
+    /// `#rrggbb` 形式
+    pub favorite_color: Option<String>,
+    #[serde(default = "default_rating")]
+    pub rating: i32,
+    /// 実績ごとの獲得回数
+    #[serde(default)]
+    pub achievements: BTreeMap<Achievement, u32>,
+}
+
+fn default_rating() -> i32 {
+    rating::INITIAL_RATING
+}
+
+/// リーダーボードの1行
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct LeaderboardEntry {
+    pub rank: usize,
+    pub user_id: String,
+    pub display_name: String,
+    pub rating: i32,
+    pub games_played: u32,
+    pub wins: u32,
+}
+
+impl Profile {
+    fn new(user_id: &str, display_name: &str) -> Self {
+        Self {
+            user_id: user_id.to_string(),
+            display_name: display_name.to_string(),
+            games_played: 0,
+            wins: 0,
+            favorite_color: None,
+            rating: rating::INITIAL_RATING,
+            achievements: BTreeMap::new(),
+        }
+    }
+}
+
+/// 1ゲーム分の戦績（認証済みプレイヤー1人分）
+#[derive(Debug, Clone)]
+pub struct GameResult {
+    pub user_id: String,
+    pub rank: u32,
+    pub achievements: Vec<Achievement>,
+}
+
+/// プロフィールの読み書き
+/// 更新は読み込み→変更→保存になるため、ロックで直列化する
+#[derive(Clone)]
+pub struct ProfileStore {
+    storage: Arc<dyn Storage>,
+    update_lock: Arc<Mutex<()>>,
+}
+
+impl ProfileStore {
+    pub fn new(storage: Arc<dyn Storage>) -> Self {
+        Self {
+            storage,
+            update_lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    pub async fn get(&self, user_id: &str) -> Option<Profile> {
+        match self.storage.get(COLLECTION, user_id).await {
+            Ok(value) => value.and_then(|v| serde_json::from_value(v).ok()),
+            Err(e) => {
+                eprintln!("profile load failed ({}): {}", user_id, e);
+                None
+            }
+        }
+    }
+
+    /// 全プロフィール
+    pub async fn list(&self) -> Vec<Profile> {
+        match self.storage.list(COLLECTION).await {
+            Ok(entries) => entries
+                .into_iter()
+                .filter_map(|(_, v)| serde_json::from_value(v).ok())
+                .collect(),
+            Err(e) => {
+                eprintln!("profile list failed: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
+    /// 入室時に呼ぶ。プロフィールが無ければ作成し、表示名を最新にする
+    pub async fn touch(&self, user_id: &str, display_name: &str) -> Option<Profile> {
+        self.update(user_id, Some(display_name), |p| {
+            p.display_name = display_name.to_string();
+        })
+        .await
+    }
+
+    /// 好きな色を設定（不正な形式なら None で解除）
+    pub async fn set_favorite_color(&self, user_id: &str, color: Option<String>) -> Option<Profile> {
+        let color = color.filter(|c| is_hex_color(c));
+        self.update(user_id, None, |p| p.favorite_color = color).await
+    }
+
+    /// ゲーム終了時の戦績・レーティング更新
+    /// プロフィールの無い匿名プレイヤーは無視し、
+    /// レーティングはプロフィールを持つ参加者同士の順位から計算する
+    pub async fn record_game(&self, results: &[GameResult]) {
+        let _guard = self.update_lock.lock().await;
+
+        let mut entries = Vec::new();
+        for result in results {
+            if let Some(profile) = self.get(&result.user_id).await {
+                entries.push((profile, result));
+            }
+        }
+
+        let ratings: Vec<(i32, u32)> = entries.iter().map(|(p, result)| (p.rating, result.rank)).collect();
+        let deltas = rating::rating_deltas(&ratings);
+
+        for ((mut profile, result), delta) in entries.into_iter().zip(deltas) {
+            profile.games_played += 1;
+            if result.rank == 1 {
+                profile.wins += 1;
+            }
+            profile.rating += delta;
+            for achievement in &result.achievements {
+                *profile.achievements.entry(*achievement).or_default() += 1;
+            }
+            self.save(&profile).await;
+        }
+    }
+
+    /// レーティング順の上位 `limit` 件（1局以上プレイしたプレイヤーのみ）
+    pub async fn leaderboard(&self, limit: usize) -> Vec<LeaderboardEntry> {
+        let mut profiles: Vec<Profile> = self
+            .list()
+            .await
+            .into_iter()
+            .filter(|p| p.games_played > 0)
+            .collect();
+        profiles.sort_by(|a, b| {
+            b.rating
+                .cmp(&a.rating)
+                .then(b.wins.cmp(&a.wins))
+                .then(a.user_id.cmp(&b.user_id))
+        });
+
+        profiles
+            .into_iter()
+            .take(limit)
+            .enumerate()
+            .map(|(i, p)| LeaderboardEntry {
+                rank: i + 1,
+                user_id: p.user_id,
+                display_name: p.display_name,
+                rating: p.rating,
+                games_played: p.games_played,
+                wins: p.wins,
+            })
+            .collect()
+    }
+
+    async fn save(&self, profile: &Profile) {
+        let value = match serde_json::to_value(profile) {
+            Ok(value) => value,
+            Err(e) => {
+                eprintln!("profile serialize failed ({}): {}", profile.user_id, e);
+                return;
+            }
+        };
+        if let Err(e) = self.storage.put(COLLECTION, &profile.user_id, value).await {
+            eprintln!("profile save failed ({}): {}", profile.user_id, e);
+        }
+    }
+
+    /// create_with が Some なら存在しない場合に作成する
+    async fn update(
+        &self,
+        user_id: &str,
+        create_with: Option<&str>,
+        f: impl FnOnce(&mut Profile),
+    ) -> Option<Profile> {
+        let _guard = self.update_lock.lock().await;
+        let mut profile = match (self.get(user_id).await, create_with) {
+            (Some(profile), _) => profile,
+            (None, Some(name)) => Profile::new(user_id, name),
+            (None, None) => return None,
+        };
+        f(&mut profile);
+        self.save(&profile).await;
+        Some(profile)
+    }
+}
+
+fn is_hex_color(color: &str) -> bool {
+    color.len() == 7
+        && color.starts_with('#')
+        && color[1..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+
+    #[tokio::test]
+    async fn test_profile_lifecycle() {
+        let store = ProfileStore::new(Arc::new(MemoryStorage::new()));
+
+        store.touch("u1", "Alice").await.unwrap();
+        store.touch("u2", "Bob").await.unwrap();
+        // 匿名プレイヤー（プロフィール無し）は無視される
+        let game = |first: &str, second: &str| {
+            [(first, 1), (second, 2), ("anon", 3)]
+                .into_iter()
+                .map(|(user_id, rank)| GameResult {
+                    user_id: user_id.to_string(),
+                    rank,
+                    achievements: if rank == 1 { vec![Achievement::FirstToMarry] } else { Vec::new() },
+                })
+                .collect::<Vec<_>>()
+        };
+        store.record_game(&game("u1", "u2")).await;
+        store.record_game(&game("u2", "u1")).await;
+        assert!(store.get("anon").await.is_none());
+
+        let profile = store.touch("u1", "Alice2").await.unwrap();
+        assert_eq!(profile.display_name, "Alice2");
+        assert_eq!(profile.games_played, 2);
+        assert_eq!(profile.wins, 1);
+        assert_eq!(profile.achievements.get(&Achievement::FirstToMarry), Some(&1));
+
+        store.record_game(&game("u1", "u2")).await;
+        let board = store.leaderboard(10).await;
+        assert_eq!(board.len(), 2);
+        assert_eq!(board[0].user_id, "u1");
+        assert!(board[0].rating > rating::INITIAL_RATING);
+        assert!(board[1].rating < rating::INITIAL_RATING);
+
+        let profile = store.set_favorite_color("u1", Some("#ff8800".to_string())).await.unwrap();
+        assert_eq!(profile.favorite_color.as_deref(), Some("#ff8800"));
+        let profile = store.set_favorite_color("u1", Some("red".to_string())).await.unwrap();
+        assert_eq!(profile.favorite_color, None);
+    }
+}
@@ -0,0 +1,64 @@
+//! 多人数対局向けの Elo レーティング
+//!
+//! 順位表を全ペアの1対1対局とみなし、各ペアの期待勝率との差分を合計する。
+//! K 係数は対戦相手数で割り、人数によって変動幅が大きくならないようにする。
+
+/// 初期レーティング
+pub const INITIAL_RATING: i32 = 1500;
+
+const K_FACTOR: f64 = 32.0;
+
+/// `players` は (現在のレーティング, 順位) の並び。同順位は引き分け扱い
+/// 戻り値は同じ並びのレーティング変動量
+pub fn rating_deltas(players: &[(i32, u32)]) -> Vec<i32> {
+    let n = players.len();
+    if n < 2 {
+        return vec![0; n];
+    }
+    let k = K_FACTOR / (n - 1) as f64;
+
+    players
+        .iter()
+        .enumerate()
+        .map(|(i, &(rating, rank))| {
+            let delta: f64 = players
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .map(|(_, &(other_rating, other_rank))| {
+                    let expected =
+                        1.0 / (1.0 + 10f64.powf((other_rating - rating) as f64 / 400.0));
+                    let actual = match rank.cmp(&other_rank) {
+                        std::cmp::Ordering::Less => 1.0,
+                        std::cmp::Ordering::Equal => 0.5,
+                        std::cmp::Ordering::Greater => 0.0,
+                    };
+                    k * (actual - expected)
+                })
+                .sum();
+            delta.round() as i32
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rating_deltas() {
+        // 同レーティングの1対1は ±16
+        assert_eq!(rating_deltas(&[(1500, 1), (1500, 2)]), vec![16, -16]);
+
+        // 格上が勝っても変動は小さい
+        let deltas = rating_deltas(&[(1800, 1), (1400, 2)]);
+        assert!(deltas[0] > 0 && deltas[0] < 16);
+
+        // 多人数: 1位が最も上がり、最下位が最も下がる
+        let deltas = rating_deltas(&[(1500, 1), (1500, 2), (1500, 3), (1500, 4)]);
+        assert!(deltas[0] > deltas[1] && deltas[1] > deltas[2] && deltas[2] > deltas[3]);
+        assert_eq!(deltas.iter().sum::<i32>(), 0);
+
+        assert_eq!(rating_deltas(&[(1500, 1)]), vec![0]);
+    }
+}
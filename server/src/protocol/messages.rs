@@ -1,10 +1,15 @@
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
-use crate::game::state::{Board, Career, House, PlayerState, TurnPhase};
+use crate::game::state::{
+    AssetBreakdown, Board, Career, House, PendingChoice, PlayerState, TilePreview, TurnPhase,
+};
+use crate::game::text::{ChoiceText, SystemText};
+use crate::game::{AchievementAward, GlobalEventKind, LedgerEntry, PlayerRecap};
+use crate::profile::Profile;
 
 pub type RoomId = String;
-pub type PlayerId = String;
+pub use nine_life_core::PlayerId;
 
 /// クライアント -> サーバー メッセージ
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -14,23 +19,185 @@ pub enum ClientMessage {
     CreateRoom {
         player_name: String,
         map_id: String,
+        /// 非公開部屋（詳細情報の閲覧・参加に join_token が必要）
+        #[serde(default)]
+        #[ts(as = "Option<bool>", optional)]
+        private: bool,
+        /// 部屋の定員（サーバー上限でクランプ）。未指定ならサーバー上限
+        #[serde(default)]
+        #[ts(optional)]
+        max_players: Option<usize>,
+        /// シリーズ戦のゲーム数（2以上で有効）。終了後にホストが StartGame で次のゲームを開始する
+        #[serde(default)]
+        #[ts(optional)]
+        series_games: Option<u32>,
+        /// 短縮ゲーム（マップ途中から職業付き・収入2倍で開始）
+        #[serde(default)]
+        #[ts(as = "Option<bool>", optional)]
+        short_game: bool,
+        /// ターン数上限（全プレイヤー通算）。到達時点の資産で順位を決める
+        #[serde(default)]
+        #[ts(optional)]
+        max_turn_count: Option<u32>,
+        /// 移動の途中でも分岐で止まって道を選ぶ
+        #[serde(default)]
+        #[ts(as = "Option<bool>", optional)]
+        pause_at_branches: bool,
+        /// ゲームルール（"classic" / "speed"）。未指定なら classic
+        #[serde(default)]
+        #[ts(optional)]
+        rules_variant: Option<String>,
+        /// 観戦者への配信遅延（秒、最大 300）。配信・大会向け
+        #[serde(default)]
+        #[ts(optional)]
+        spectator_delay_secs: Option<u32>,
+        /// このターン数ごとに全員に影響する出来事（好景気・暴落・減税・インフレ）を起こす
+        #[serde(default)]
+        #[ts(optional)]
+        global_event_interval: Option<u32>,
+        /// 選択肢をこの回数より多く続けて見送ると、見送るたびに放置税を取る（引き延ばし対策）
+        #[serde(default)]
+        #[ts(optional)]
+        stall_limit: Option<u32>,
+        /// 乱数の seed（1〜2^53-1）。同じマップ・seed・参加順なら全員が同じルーレットと職業を引く（デイリーチャレンジ向け）
+        /// 未指定ならゲームごとにサーバーが選ぶ
+        #[serde(default)]
+        #[ts(optional, type = "number")]
+        seed: Option<u64>,
+        /// 公開ロビーに表示する部屋名（最大 40 文字）
+        #[serde(default)]
+        #[ts(optional)]
+        title: Option<String>,
+        /// 公開ロビーで絞り込むタグ（"初心者歓迎" など。最大 5 個・各 20 文字）
+        #[serde(default)]
+        #[ts(as = "Option<Vec<String>>", optional)]
+        tags: Vec<String>,
+        /// 部屋の言語・地域（"ja" / "ja-JP" などの BCP 47 の言語タグ）
+        #[serde(default)]
+        #[ts(optional)]
+        language: Option<String>,
     },
     JoinRoom {
         room_id: RoomId,
         player_name: String,
+        /// 非公開部屋の参加トークン
+        #[serde(default)]
+        #[ts(optional)]
+        token: Option<String>,
+        /// 観戦者として参加する（ゲームの進行は部屋の配信遅延だけ遅れて届く。チャットは観戦者同士のみ）
+        #[serde(default)]
+        #[ts(as = "Option<bool>", optional)]
+        spectate: bool,
     },
     LeaveRoom,
     StartGame,
-    SpinRoulette,
+    /// action_token: 再送で同じ操作が二重に適用されないよう、クライアントが操作ごとに付ける一意な値
+    /// 同じプレイヤーが適用済みの値を再送すると、状態は変えずに最初の結果を本人にだけ返す
+    SpinRoulette {
+        #[serde(default)]
+        #[ts(optional)]
+        action_token: Option<String>,
+    },
     ChoicePath {
         path_index: usize,
+        #[serde(default)]
+        #[ts(optional)]
+        action_token: Option<String>,
     },
     ChoiceAction {
         action_id: String,
+        #[serde(default)]
+        #[ts(optional)]
+        action_token: Option<String>,
+    },
+    /// 持っている株券を現在の株価で売る（自分の手番のルーレット前のみ）
+    SellStock {
+        stock_id: String,
+        #[serde(default)]
+        #[ts(optional)]
+        action_token: Option<String>,
+    },
+    /// 持っている家を抵当に入れて売却額の一部を受け取る（自分の手番のルーレット前のみ）
+    MortgageHouse {
+        house_id: String,
+        #[serde(default)]
+        #[ts(optional)]
+        action_token: Option<String>,
+    },
+    /// 抵当に入れた家を買い戻す（自分の手番のルーレット前のみ）
+    RedeemHouse {
+        house_id: String,
+        #[serde(default)]
+        #[ts(optional)]
+        action_token: Option<String>,
     },
     ChatMessage {
         text: String,
     },
+    /// スタンプ（emote_id はサーバーの EMOTES のいずれか）。target は特定のプレイヤーへの反応
+    Reaction {
+        emote_id: String,
+        #[serde(default)]
+        #[ts(optional)]
+        target: Option<PlayerId>,
+    },
+    /// チャット入力中（入力が続く間、クライアントは数秒おきに送ってよい）
+    Typing,
+    /// 遅延測定。client_ts（クライアントの時計、ミリ秒）をそのまま Pong で返す
+    Ping {
+        #[ts(type = "number")]
+        client_ts: u64,
+    },
+    /// 自分のプロフィール更新（認証済みプレイヤーのみ）
+    UpdateProfile {
+        favorite_color: Option<String>,
+    },
+    /// ホストのみ: プレイヤーのチャットを止める・再開する（PlayerMuteChanged が全員に届く）
+    MutePlayer {
+        player_id: PlayerId,
+    },
+    UnmutePlayer {
+        player_id: PlayerId,
+    },
+    /// ホストのみ: プレイヤーを切断し、この部屋への再参加を拒否する
+    BanPlayer {
+        player_id: PlayerId,
+    },
+    /// ホストのみ: ゲームを一時停止する（再開までルーレット・選択を受け付けない）
+    PauseGame,
+    /// ホストのみ: 一時停止したゲームを再開する
+    ResumeGame,
+    /// ホストのみ: 終了したゲームからロビーに戻る（全員に RoomState が届く）
+    ReturnToLobby,
+    /// 反応のないプレイヤー（target）を退出させる投票を始める（ゲーム中のみ。自分は賛成に数える）
+    StartKickVote {
+        target: PlayerId,
+    },
+    /// 進行中の退出投票に賛成・反対する
+    CastKickVote {
+        approve: bool,
+    },
+    /// 盤面と現在の状態を自分にだけ送り直してもらう（GameStarted・GameSync、選択待ちなら ChoiceRequired）
+    RequestGameSync,
+    /// 途中経過の順位（Standings で返る）
+    RequestStandings,
+    /// 直近のゲームの出来事（RecentEvents で返る。別タブから戻ったときの振り返り用）
+    GetRecentEvents {
+        /// 件数（既定 20・最大 100）
+        #[serde(default)]
+        #[ts(optional)]
+        count: Option<usize>,
+    },
+    /// 所持金の台帳（LedgerPage で返る）
+    RequestLedger {
+        #[serde(default)]
+        #[ts(optional)]
+        offset: Option<usize>,
+        /// 件数（既定 50・最大 200）
+        #[serde(default)]
+        #[ts(optional)]
+        limit: Option<usize>,
+    },
 }
 
 /// サーバー -> クライアント メッセージ
@@ -42,6 +209,8 @@ pub enum ServerMessage {
         room_id: RoomId,
         invite_url: String,
         player_id: PlayerId,
+        /// 非公開部屋の場合のみ
+        join_token: Option<String>,
     },
     PlayerJoined {
         player_id: PlayerId,
@@ -50,25 +219,71 @@ pub enum ServerMessage {
     PlayerLeft {
         player_id: PlayerId,
     },
+    /// ゲーム中のプレイヤーの接続が切れた（席は残り、手番は飛ばされる。再参加すると PlayerJoined）
+    PlayerDisconnected {
+        player_id: PlayerId,
+    },
+    /// 通信が追いつかないプレイヤーを切断した（直後に PlayerLeft か PlayerDisconnected が続く）
+    PlayerConnectionDegraded {
+        player_id: PlayerId,
+    },
     GameStarted {
         turn_order: Vec<PlayerId>,
         board: Board,
         players: Vec<PlayerState>,
         careers: Vec<Career>,
         houses: Vec<House>,
+        /// このゲームの seed（CreateRoom の seed に渡すと同じゲームを再現できる）
+        #[ts(type = "number")]
+        seed: u64,
     },
     GameSync {
         players: Vec<PlayerState>,
         current_turn: usize,
         phase: TurnPhase,
+        /// 全プレイヤー通算のターン番号（1始まり）
+        turn_number: u32,
+        /// ターン数上限ルールの上限（無制限なら null）
+        max_turns: Option<u32>,
+        /// 選択待ちなら誰の選択か・選択肢（ChoiceRequired を取りこぼしても分かるように）
+        pending_choice: Option<PendingChoice>,
+        /// 一時停止中か（途中参加・再接続したクライアント向け）
+        paused: bool,
+        /// ゲーム開始時刻（UNIX ミリ秒。フレームの server_ts との差が経過時間）
+        #[ts(type = "number | null")]
+        game_started_at: Option<u64>,
+        /// 今の株価（株券の売買・資産の評価に使う）
+        #[ts(type = "number")]
+        stock_price: i64,
+        /// ゴールした順のプレイヤーID
+        arrivals: Vec<PlayerId>,
+        /// 減税中か（税金マスの税額が半分）
+        tax_cut: bool,
     },
     RouletteResult {
         player_id: PlayerId,
+        /// 進む歩数（spins の合計）
         value: u32,
+        /// 各回の出目（スピードマスからは2回回す）
+        spins: Vec<u32>,
+        /// ホイールが止まるまでの時間（フレームの server_ts から数える。全員の画面で同時に止める）
+        animation_ms: u32,
+        /// 最後の回でホイールが止まる区画（0始まり、区画 i に i + 1 の数字）
+        segment: u32,
     },
     PlayerMoved {
         player_id: PlayerId,
         position: usize,
+        /// 出発マスを除いた移動経路（最後の要素が停止マス）
+        path: Vec<usize>,
+        /// 通過した給料日マスの数
+        paydays_passed: u32,
+    },
+    /// マスをたどらずに位置が変わった（入れ替え・追いつき）。クライアントはワープとして表示する
+    PlayerTeleported {
+        player_id: PlayerId,
+        from: usize,
+        to: usize,
     },
     ChoiceRequired {
         choices: Vec<Choice>,
@@ -77,17 +292,72 @@ pub enum ServerMessage {
         current_turn: usize,
         player_id: PlayerId,
     },
+    /// 全員に影響する出来事（手番が変わった直後。所持金・給料の変化は続く GameSync に反映済み）
+    GlobalEvent {
+        kind: GlobalEventKind,
+        /// kind の日本語表記（ローカライズ未対応のクライアント向け）
+        label: String,
+    },
+    /// 途中経過の順位（手番が変わるたびと RequestStandings への応答。現時点の資産で計算）
+    Standings {
+        rankings: Vec<RankingEntry>,
+    },
     GameEnded {
         rankings: Vec<RankingEntry>,
+        /// このゲームで各プレイヤーが獲得した実績
+        achievements: Vec<AchievementAward>,
+        /// 各プレイヤーの人生の振り返り（結果画面・共有用）
+        recaps: Vec<PlayerRecap>,
+        /// ゲーム開始から終了までの時間（ミリ秒）
+        #[ts(type = "number")]
+        duration_ms: u64,
+        /// ゴールした順のプレイヤーID（ターン上限で終わった場合は未ゴールの人を含まない）
+        arrivals: Vec<PlayerId>,
     },
     ChatBroadcast {
         player_id: PlayerId,
         player_name: String,
         text: String,
     },
+    /// チャット欄に流すゲームの出来事（結婚・出産・訴訟・借金返済・ゴール）
+    SystemMessage {
+        text: SystemText,
+        /// text の日本語表記（ローカライズ未対応のクライアント向け）
+        label: String,
+    },
+    /// Ping への応答。受信時刻 - client_ts が往復時間、フレームの server_ts と合わせてサーバーとの時計のずれが分かる
+    Pong {
+        #[ts(type = "number")]
+        client_ts: u64,
+    },
+    /// 他のプレイヤーがチャット入力中。expires_in_ms の間に次の PlayerTyping か ChatBroadcast が来なければ表示を消す
+    PlayerTyping {
+        player_id: PlayerId,
+        #[ts(type = "number")]
+        expires_in_ms: u64,
+    },
+    /// 観戦者同士のチャット（プレイヤーには届かない）
+    SpectatorChatBroadcast {
+        spectator_id: PlayerId,
+        name: String,
+        text: String,
+    },
+    /// Reaction を部屋の全員に配信する（履歴には残さない）
+    ReactionBroadcast {
+        player_id: PlayerId,
+        emote_id: String,
+        target: Option<PlayerId>,
+    },
+    /// 参加・再参加した本人に送る直近のチャット（古い順）
+    ChatHistory {
+        messages: Vec<ChatLine>,
+    },
     Error {
         code: String,
         message: String,
+        /// INVALID_CHOICE のとき、選べる選択肢の ID（それ以外は null）
+        #[serde(default)]
+        valid_choices: Option<Vec<String>>,
     },
     RoomState {
         room_id: RoomId,
@@ -95,15 +365,116 @@ pub enum ServerMessage {
         players: Vec<PlayerInfo>,
         status: String,
     },
+    /// 部屋が管理者により閉じられた
+    RoomClosed {
+        reason: String,
+    },
+    /// 自分が部屋から切断された
+    Kicked {
+        reason: String,
+    },
+    /// 部屋が別のサーバーに移った。url に繋ぎ直して同じ部屋に JoinRoom する（url に付いた引き継ぎ用の合言葉で元の席に戻る）
+    Reconnect {
+        url: String,
+    },
+    /// シリーズ戦の途中経過（各ゲームの GameEnded の直後に送信）
+    SeriesStandings {
+        games_played: u32,
+        total_games: u32,
+        standings: Vec<SeriesStanding>,
+        /// 全ゲーム終了済みか
+        finished: bool,
+    },
+    PlayerProfileUpdated {
+        player_id: PlayerId,
+        profile: Profile,
+    },
+    /// ホスト（player_id）がゲームを一時停止した
+    GamePaused {
+        player_id: PlayerId,
+    },
+    GameResumed {
+        player_id: PlayerId,
+    },
+    /// 退出投票が始まった（expires_in_ms 以内に required 票の賛成で可決）
+    KickVoteStarted {
+        target: PlayerId,
+        started_by: PlayerId,
+        required: usize,
+        #[ts(type = "number")]
+        expires_in_ms: u64,
+    },
+    KickVoteProgress {
+        approvals: usize,
+        rejections: usize,
+        required: usize,
+    },
+    /// 可決なら直後に PlayerDisconnected が続く
+    KickVoteEnded {
+        target: PlayerId,
+        passed: bool,
+    },
+    /// ホストがプレイヤーをミュート・解除した
+    PlayerMuteChanged {
+        player_id: PlayerId,
+        muted: bool,
+    },
+    /// 直近のゲームの出来事（GetRecentEvents への応答。古い順）
+    RecentEvents {
+        events: Vec<TimelineEntry>,
+    },
+    /// 所持金の台帳の1ページ（RequestLedger への応答）
+    LedgerPage {
+        offset: usize,
+        /// 台帳の全件数
+        total: usize,
+        entries: Vec<LedgerEntry>,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct Choice {
     pub id: String,
+    /// text の日本語表記（ローカライズ未対応のクライアント向け）
+    pub label: String,
+    /// クライアントでローカライズする選択肢の文言
+    pub text: ChoiceText,
+    /// 分岐の選択肢の場合、その道の先のマス（それ以外は空）
+    pub preview: Vec<TilePreview>,
+}
+
+/// 実際に送るフレーム（ServerMessage に送信時刻を添える）
+/// アニメーションの開始時刻をプレイヤー間でそろえるのに使う
+#[derive(Debug, Serialize, TS)]
+#[ts(export)]
+pub struct ServerFrame<'a> {
+    #[serde(flatten)]
+    pub message: &'a ServerMessage,
+    /// サーバーの送信時刻（UNIX ミリ秒）
+    #[ts(type = "number")]
+    pub server_ts: u64,
+}
+
+/// ゲームの出来事の1件（SystemMessage と同じ内容に、起きたターン番号を添えたもの）
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct TimelineEntry {
+    pub turn_number: u32,
+    pub text: SystemText,
+    /// text の日本語表記（ローカライズ未対応のクライアント向け）
     pub label: String,
 }
 
+/// チャット履歴の1件（ChatBroadcast と同じ内容）
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ChatLine {
+    pub player_id: PlayerId,
+    pub player_name: String,
+    pub text: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct RankingEntry {
@@ -111,7 +482,20 @@ pub struct RankingEntry {
     pub player_name: String,
     #[ts(type = "number")]
     pub total_assets: i64,
+    /// total_assets の内訳
+    pub breakdown: AssetBreakdown,
     pub rank: u32,
+    /// ゴールした順番（未ゴールなら null）
+    pub arrival_order: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct SeriesStanding {
+    pub player_id: PlayerId,
+    pub player_name: String,
+    pub points: u32,
+    pub wins: u32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -119,4 +503,28 @@ pub struct RankingEntry {
 pub struct PlayerInfo {
     pub id: PlayerId,
     pub name: String,
+    /// 認証済みプレイヤーのプロフィール
+    pub profile: Option<Profile>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_action_token_is_optional() {
+        let msg: ClientMessage = serde_json::from_str(r#"{"type":"SpinRoulette"}"#).unwrap();
+        assert!(matches!(msg, ClientMessage::SpinRoulette { action_token: None }));
+        let msg: ClientMessage =
+            serde_json::from_str(r#"{"type":"ChoicePath","path_index":1,"action_token":"a1"}"#).unwrap();
+        assert!(matches!(msg, ClientMessage::ChoicePath { path_index: 1, action_token: Some(t) } if t == "a1"));
+    }
+
+    /// `npm run gen:types` 用: ワイヤープロトコル全体を依存する型ごと bindings/ に書き出す
+    #[test]
+    fn export_wire_protocol() {
+        ClientMessage::export_all().unwrap();
+        ServerMessage::export_all().unwrap();
+        ServerFrame::export_all().unwrap();
+    }
 }
@@ -1,3 +1,4 @@
 pub mod messages;
+pub mod schema;
 
 pub use messages::*;
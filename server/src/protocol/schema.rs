@@ -0,0 +1,135 @@
+//! ワイヤープロトコルの JSON Schema（TypeScript 以外のクライアントがバインディングを生成する用）
+//!
+//! `ClientMessage` / `ServerMessage` を変更したら client-message.json / server-message.json も合わせて更新すること
+//! （ts-rs の出力とメッセージの種類・項目が食い違うとテストが失敗する）。
+//! ServerMessage のうち盤面・プレイヤーの状態などゲームの型は object とだけ定め、中身は bindings/ の型を参照する。
+
+const CLIENT_MESSAGE_SCHEMA_JSON: &str = include_str!("client-message.json");
+const SERVER_MESSAGE_SCHEMA_JSON: &str = include_str!("server-message.json");
+
+/// 名前に対応する JSON Schema を返す（"client-message" / "server-message"）
+pub fn schema(name: &str) -> Option<&'static str> {
+    match name {
+        "client-message" => Some(CLIENT_MESSAGE_SCHEMA_JSON),
+        "server-message" => Some(SERVER_MESSAGE_SCHEMA_JSON),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::protocol::{ClientMessage, ServerMessage};
+    use serde_json::Value;
+    use ts_rs::TS;
+
+    /// 種類 → （項目名 → 省略できるか）
+    type Variants = BTreeMap<String, BTreeMap<String, bool>>;
+
+    /// ts-rs のインライン表記（`{ "type": "X", a: T, b?: T, } | ...`）から種類と項目を取り出す
+    fn ts_variants(ts: &str) -> Variants {
+        let mut code = String::new();
+        let mut rest = ts;
+        while let Some(start) = rest.find("/**") {
+            code.push_str(&rest[..start]);
+            rest = &rest[start + rest[start..].find("*/").unwrap() + 2..];
+        }
+        code.push_str(rest);
+
+        let mut variants = Variants::new();
+        for chunk in code.split("{ \"type\": \"").skip(1) {
+            let (name, body) = chunk.split_once('"').unwrap();
+            let mut fields = BTreeMap::new();
+            for part in body.split(", ") {
+                let Some((key, _)) = part.trim().split_once(": ") else {
+                    continue;
+                };
+                let optional = key.ends_with('?');
+                let key = key.trim_end_matches('?');
+                if key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') && !key.is_empty() {
+                    fields.insert(key.to_string(), optional);
+                }
+            }
+            variants.insert(name.to_string(), fields);
+        }
+        variants
+    }
+
+    /// スキーマの oneOf から種類と項目を取り出す（$defs への $ref は展開する）
+    fn schema_variants(schema: &Value) -> Variants {
+        let mut variants = Variants::new();
+        for variant in schema["oneOf"].as_array().unwrap() {
+            let mut parts = vec![variant];
+            if let Some(r) = variant["$ref"].as_str() {
+                parts.push(schema.pointer(r.strip_prefix('#').unwrap()).unwrap());
+            }
+            let mut fields = BTreeMap::new();
+            let mut required = Vec::new();
+            let mut name = None;
+            for part in parts {
+                for (key, property) in part["properties"].as_object().into_iter().flatten() {
+                    if key == "type" {
+                        name = property["const"].as_str();
+                    } else {
+                        fields.insert(key.clone(), true);
+                    }
+                }
+                required.extend(part["required"].as_array().into_iter().flatten().filter_map(Value::as_str));
+            }
+            for key in required.into_iter().filter(|k| *k != "type") {
+                *fields.get_mut(key).unwrap_or_else(|| panic!("required '{}' has no property", key)) = false;
+            }
+            variants.insert(name.unwrap().to_string(), fields);
+        }
+        variants
+    }
+
+    fn assert_no_dangling_refs(schema: &Value, root: &Value) {
+        match schema {
+            Value::Object(map) => {
+                if let Some(Value::String(r)) = map.get("$ref") {
+                    assert!(root.pointer(r.strip_prefix('#').unwrap()).is_some(), "dangling $ref: {}", r);
+                }
+                map.values().for_each(|v| assert_no_dangling_refs(v, root));
+            }
+            Value::Array(items) => items.iter().for_each(|v| assert_no_dangling_refs(v, root)),
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn test_message_schemas_match_protocol() {
+        for (name, ts) in [
+            ("client-message", ClientMessage::inline()),
+            ("server-message", ServerMessage::inline()),
+        ] {
+            let schema: Value = serde_json::from_str(schema(name).unwrap()).unwrap();
+            assert_no_dangling_refs(&schema, &schema);
+            assert_eq!(schema_variants(&schema), ts_variants(&ts), "{}", name);
+        }
+        assert!(schema("map").is_none());
+    }
+
+    #[test]
+    fn test_client_message_schema_accepts_minimal_messages() {
+        let schema: Value = serde_json::from_str(schema("client-message").unwrap()).unwrap();
+        for (name, fields) in schema_variants(&schema) {
+            let mut msg = serde_json::json!({ "type": name });
+            for (key, optional) in fields {
+                if optional {
+                    continue;
+                }
+                let sample = match key.as_str() {
+                    "path_index" | "client_ts" => Value::from(0),
+                    "approve" => Value::from(true),
+                    "favorite_color" => Value::Null,
+                    _ => Value::from("x"),
+                };
+                msg[key] = sample;
+            }
+            serde_json::from_value::<ClientMessage>(msg).unwrap_or_else(|e| panic!("{}: {}", name, e));
+        }
+    }
+}
@@ -0,0 +1,266 @@
+//! 部屋ごとのタスク
+//!
+//! 各部屋は専用の tokio タスクが所有し、操作はコマンドチャネル経由でそのタスク上で実行する。
+//! 部屋をまたいだロック競合がなく、同じ部屋への操作は届いた順に1つずつ処理される。
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot, RwLock};
+use tokio::time::Instant;
+
+use crate::protocol::RoomId;
+use crate::room::error::RoomError;
+use crate::room::models::Room;
+
+/// 部屋タスクのコマンドキューの長さ
+const COMMAND_BUFFER: usize = 64;
+
+type Command = Box<dyn FnOnce(&mut Room) + Send>;
+
+/// 部屋タスクへの送信口
+#[derive(Clone)]
+pub struct RoomHandle {
+    tx: mpsc::Sender<Command>,
+}
+
+impl RoomHandle {
+    /// 部屋タスクを起動する
+    /// 全員が退出（または切断）したらタスクを終了し、rooms から自身を取り除く
+    /// （復元直後のようにまだ誰も参加していない部屋は、最初の参加者を待つ）
+    /// ゲーム中に全員の接続が切れた場合は一時停止し、abandoned_grace の間だけ再接続を待つ
+    pub fn spawn(
+        mut room: Room,
+        rooms: Arc<RwLock<HashMap<RoomId, RoomHandle>>>,
+        abandoned_grace: Duration,
+    ) -> Self {
+        let (tx, mut rx) = mpsc::channel::<Command>(COMMAND_BUFFER);
+        tokio::spawn(async move {
+            let mut occupied = room.has_connected_players();
+            let mut abandoned_until: Option<Instant> = None;
+            loop {
+                let command = match abandoned_until {
+                    Some(deadline) => match tokio::time::timeout_at(deadline, rx.recv()).await {
+                        Ok(command) => command,
+                        Err(_) => break,
+                    },
+                    None => rx.recv().await,
+                };
+                let Some(command) = command else {
+                    break;
+                };
+                command(&mut room);
+                if room.has_connected_players() {
+                    occupied = true;
+                    abandoned_until = None;
+                } else if occupied && abandoned_until.is_none() {
+                    if abandoned_grace.is_zero() || !room.pause_abandoned() {
+                        break;
+                    }
+                    abandoned_until = Some(Instant::now() + abandoned_grace);
+                }
+            }
+            // 以降に届いたコマンドは RoomNotFound になる
+            rx.close();
+            let mut rooms = rooms.write().await;
+            if rooms.get(&room.id).is_some_and(|h| h.tx.is_closed()) {
+                rooms.remove(&room.id);
+            }
+        });
+        Self { tx }
+    }
+
+    /// 部屋タスク上で f を実行して結果を返す（部屋が既に閉じていれば RoomNotFound）
+    pub async fn call<R, F>(&self, f: F) -> Result<R, RoomError>
+    where
+        R: Send + 'static,
+        F: FnOnce(&mut Room) -> R + Send + 'static,
+    {
+        let (reply_tx, reply_rx) = oneshot::channel();
+        self.tx
+            .send(Box::new(move |room| {
+                let _ = reply_tx.send(f(room));
+            }))
+            .await
+            .map_err(|_| RoomError::RoomNotFound)?;
+        reply_rx.await.map_err(|_| RoomError::RoomNotFound)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::ClientMessage;
+    use crate::room::models::{Player, RoomSettings};
+    use crate::transport::traits::{EncodedMessage, Result as TransportResult, Transport};
+    use async_trait::async_trait;
+
+    struct NullTransport;
+
+    #[async_trait]
+    impl Transport for NullTransport {
+        async fn send_raw(&self, _msg: EncodedMessage) -> TransportResult<()> {
+            Ok(())
+        }
+        async fn recv(&mut self) -> TransportResult<ClientMessage> {
+            Err("closed".into())
+        }
+        async fn close(&self) -> TransportResult<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_room_task_stops_when_empty() {
+        let host = Player {
+            id: "p1".to_string(),
+            name: "Alice".to_string(),
+            user_id: None,
+            profile: None,
+            transport: Arc::new(NullTransport),
+            connected: true,
+        };
+        let settings = RoomSettings {
+            private: false,
+            max_players: 4,
+            series_games: None,
+            game_options: Default::default(),
+            rules_variant: "classic".to_string(),
+            spectator_delay: Duration::ZERO,
+            listing: Default::default(),
+        };
+        let room = Room::new("ROOM01".to_string(), host, "classic".to_string(), settings);
+        let rooms = Arc::new(RwLock::new(HashMap::new()));
+        let handle = RoomHandle::spawn(room, rooms.clone(), Duration::ZERO);
+        rooms.write().await.insert("ROOM01".to_string(), handle.clone());
+
+        assert_eq!(handle.call(|room| room.players.len()).await.unwrap(), 1);
+
+        handle.call(|room| room.players.clear()).await.unwrap();
+        assert!(matches!(handle.call(|_| ()).await, Err(RoomError::RoomNotFound)));
+        // タスクの後始末（一覧からの削除）を待つ
+        for _ in 0..100 {
+            if rooms.read().await.is_empty() {
+                return;
+            }
+            tokio::task::yield_now().await;
+        }
+        panic!("room was not removed");
+    }
+
+    #[tokio::test]
+    async fn test_restored_room_waits_for_players() {
+        use crate::game::{EngineRegistry, GameOptions, DEFAULT_RULES_VARIANT};
+        use crate::room::models::RoomSnapshot;
+
+        let engines = EngineRegistry::with_builtin();
+        let map = crate::map::MapCatalog::builtin().get("classic").unwrap().clone();
+        let engine = engines.create(DEFAULT_RULES_VARIANT, GameOptions::default()).unwrap();
+        let state = engine.init(
+            vec![("p1".to_string(), "Alice".to_string()), ("p2".to_string(), "Bob".to_string())],
+            &map,
+        );
+        let snapshot = RoomSnapshot {
+            map_id: "classic".to_string(),
+            rules_variant: DEFAULT_RULES_VARIANT.to_string(),
+            game_options: GameOptions::default(),
+            state,
+        };
+        let room = Room::restore("ROOM02".to_string(), snapshot, &engines, None).unwrap();
+        let rooms = Arc::new(RwLock::new(HashMap::new()));
+        let handle = RoomHandle::spawn(room, rooms.clone(), Duration::ZERO);
+
+        // 参加者がいなくても終了しない
+        assert_eq!(handle.call(|room| room.unclaimed_seat()).await.unwrap(), Some("p1".to_string()));
+        let seat = handle
+            .call(|room| {
+                room.players.push(Player {
+                    id: "p1".to_string(),
+                    name: "Alice".to_string(),
+                    user_id: None,
+                    profile: None,
+                    transport: Arc::new(NullTransport),
+                    connected: true,
+                });
+                room.unclaimed_seat()
+            })
+            .await
+            .unwrap();
+        assert_eq!(seat, Some("p2".to_string()));
+
+        // 追放された席は引き継げない
+        let seat = handle
+            .call(|room| {
+                room.banned.insert("p2".to_string());
+                room.unclaimed_seat()
+            })
+            .await
+            .unwrap();
+        assert_eq!(seat, None);
+    }
+
+    #[tokio::test]
+    async fn test_abandoned_game_is_paused_until_grace_expires() {
+        use crate::room::models::RoomStatus;
+
+        let host = Player {
+            id: "p1".to_string(),
+            name: "Alice".to_string(),
+            user_id: Some("u1".to_string()),
+            profile: None,
+            transport: Arc::new(NullTransport),
+            connected: true,
+        };
+        let settings = RoomSettings {
+            private: false,
+            max_players: 4,
+            series_games: None,
+            game_options: Default::default(),
+            rules_variant: "classic".to_string(),
+            spectator_delay: Duration::ZERO,
+            listing: Default::default(),
+        };
+        let mut room = Room::new("ROOM03".to_string(), host, "classic".to_string(), settings);
+        room.status = RoomStatus::Playing;
+        let rooms = Arc::new(RwLock::new(HashMap::new()));
+        let handle = RoomHandle::spawn(room, rooms.clone(), Duration::from_millis(50));
+
+        // 全員切断しても猶予の間は残り、一時停止している
+        handle.call(|room| room.players[0].connected = false).await.unwrap();
+        assert!(handle.call(|room| room.paused && room.auto_paused).await.unwrap());
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(matches!(handle.call(|_| ()).await, Err(RoomError::RoomNotFound)));
+    }
+
+    #[tokio::test]
+    async fn test_anonymous_game_is_not_kept_when_abandoned() {
+        use crate::room::models::RoomStatus;
+
+        let host = Player {
+            id: "p1".to_string(),
+            name: "Alice".to_string(),
+            user_id: None,
+            profile: None,
+            transport: Arc::new(NullTransport),
+            connected: true,
+        };
+        let settings = RoomSettings {
+            private: false,
+            max_players: 4,
+            series_games: None,
+            game_options: Default::default(),
+            rules_variant: "classic".to_string(),
+            spectator_delay: Duration::ZERO,
+            listing: Default::default(),
+        };
+        let mut room = Room::new("ROOM04".to_string(), host, "classic".to_string(), settings);
+        room.status = RoomStatus::Playing;
+        let rooms = Arc::new(RwLock::new(HashMap::new()));
+        let handle = RoomHandle::spawn(room, rooms.clone(), Duration::from_secs(60));
+
+        // 匿名のプレイヤーは戻れないので、猶予を待たずに閉じる
+        handle.call(|room| room.players[0].connected = false).await.unwrap();
+        assert!(matches!(handle.call(|_| ()).await, Err(RoomError::RoomNotFound)));
+    }
+}
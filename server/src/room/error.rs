@@ -0,0 +1,200 @@
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use thiserror::Error;
+
+use crate::protocol::ServerMessage;
+
+/// 部屋・ゲーム操作のエラー
+/// `code()` はクライアントが分岐・ローカライズに使う安定したコード。`Display` は英語の説明
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum RoomError {
+    #[error("room not found")]
+    RoomNotFound,
+    #[error("player not found in room")]
+    PlayerNotFound,
+    #[error("unknown map: {map_id} (available: {available})")]
+    UnknownMap { map_id: String, available: String },
+    #[error("unknown rules variant: {name} (available: {available})")]
+    UnknownRulesVariant { name: String, available: String },
+    #[error("invalid join token")]
+    InvalidJoinToken,
+    #[error("already in room")]
+    AlreadyInRoom,
+    #[error("room is not in lobby state")]
+    NotInLobby,
+    #[error("room is full")]
+    RoomFull,
+    #[error("only host can start game")]
+    NotHost,
+    #[error("need at least {0} players")]
+    NotEnoughPlayers(usize),
+    #[error("this map does not support short games")]
+    ShortGameUnsupported,
+    #[error("game not started")]
+    GameNotStarted,
+    #[error("game has not finished")]
+    GameNotFinished,
+    /// 提示中の選択肢に無い ID（値は選べる ID）
+    #[error("invalid choice (valid: {})", .0.join(", "))]
+    InvalidChoice(Vec<String>),
+    #[error("not your turn")]
+    NotYourTurn,
+    /// 持っていない株券を売ろうとした
+    #[error("stock not owned: {0}")]
+    StockNotOwned(String),
+    /// 抵当に入れられる（買い戻しなら抵当に入っている）家を持っていない
+    #[error("no house to mortgage or redeem: {0}")]
+    HouseNotOwned(String),
+    /// 所持金が足りない（値は必要な金額）
+    #[error("insufficient funds (need {0})")]
+    InsufficientFunds(i64),
+    #[error("game is paused")]
+    GamePaused,
+    /// 現在のフェーズでは受け付けない操作（値は期待するフェーズ）
+    #[error("not in {0} phase")]
+    WrongPhase(&'static str),
+    #[error("profile requires authentication")]
+    NotAuthenticated,
+    #[error("profile not found")]
+    ProfileNotFound,
+    #[error("banned from this room")]
+    Banned,
+    #[error("muted in this room")]
+    Muted,
+    /// 自分自身を対象にできない操作（ミュート・追放）
+    #[error("cannot target yourself")]
+    CannotTargetSelf,
+    #[error("a kick vote is already in progress")]
+    KickVoteInProgress,
+    #[error("no kick vote in progress")]
+    NoKickVote,
+    #[error("not eligible to vote")]
+    NotEligibleToVote,
+    /// 観戦の配信遅延がある部屋では、遅延なしのゲーム状態を HTTP で公開しない
+    #[error("game state is delayed for spectators")]
+    SpectatorDelayed,
+    #[error("invalid snapshot: {0}")]
+    InvalidSnapshot(String),
+    #[error("engine error: {0}")]
+    Engine(String),
+    /// NODE_URL が未設定で、部屋を他のノードに移せない
+    #[error("room handoff is disabled")]
+    HandoffDisabled,
+    #[error("storage error: {0}")]
+    Storage(String),
+}
+
+impl RoomError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            RoomError::RoomNotFound => "ROOM_NOT_FOUND",
+            RoomError::PlayerNotFound => "PLAYER_NOT_FOUND",
+            RoomError::UnknownMap { .. } => "UNKNOWN_MAP",
+            RoomError::UnknownRulesVariant { .. } => "UNKNOWN_RULES_VARIANT",
+            RoomError::InvalidJoinToken => "INVALID_JOIN_TOKEN",
+            RoomError::AlreadyInRoom => "ALREADY_IN_ROOM",
+            RoomError::NotInLobby => "NOT_IN_LOBBY",
+            RoomError::RoomFull => "ROOM_FULL",
+            RoomError::NotHost => "NOT_HOST",
+            RoomError::NotEnoughPlayers(_) => "NOT_ENOUGH_PLAYERS",
+            RoomError::ShortGameUnsupported => "SHORT_GAME_UNSUPPORTED",
+            RoomError::GameNotStarted => "GAME_NOT_STARTED",
+            RoomError::GameNotFinished => "GAME_NOT_FINISHED",
+            RoomError::NotYourTurn => "NOT_YOUR_TURN",
+            RoomError::StockNotOwned(_) => "STOCK_NOT_OWNED",
+            RoomError::HouseNotOwned(_) => "HOUSE_NOT_OWNED",
+            RoomError::InsufficientFunds(_) => "INSUFFICIENT_FUNDS",
+            RoomError::GamePaused => "GAME_PAUSED",
+            RoomError::InvalidChoice(_) => "INVALID_CHOICE",
+            RoomError::WrongPhase(_) => "WRONG_PHASE",
+            RoomError::NotAuthenticated => "NOT_AUTHENTICATED",
+            RoomError::ProfileNotFound => "PROFILE_NOT_FOUND",
+            RoomError::Banned => "BANNED",
+            RoomError::Muted => "MUTED",
+            RoomError::CannotTargetSelf => "CANNOT_TARGET_SELF",
+            RoomError::KickVoteInProgress => "KICK_VOTE_IN_PROGRESS",
+            RoomError::NoKickVote => "NO_KICK_VOTE",
+            RoomError::NotEligibleToVote => "NOT_ELIGIBLE_TO_VOTE",
+            RoomError::SpectatorDelayed => "SPECTATOR_DELAYED",
+            RoomError::InvalidSnapshot(_) => "INVALID_SNAPSHOT",
+            RoomError::Engine(_) => "ENGINE_ERROR",
+            RoomError::HandoffDisabled => "HANDOFF_DISABLED",
+            RoomError::Storage(_) => "STORAGE_ERROR",
+        }
+    }
+
+    /// HTTP API で返すステータスコード
+    pub fn status(&self) -> StatusCode {
+        match self {
+            RoomError::RoomNotFound
+            | RoomError::PlayerNotFound
+            | RoomError::UnknownMap { .. }
+            | RoomError::ProfileNotFound => StatusCode::NOT_FOUND,
+            RoomError::NotAuthenticated => StatusCode::UNAUTHORIZED,
+            RoomError::InvalidJoinToken
+            | RoomError::NotHost
+            | RoomError::Banned
+            | RoomError::Muted
+            | RoomError::NotEligibleToVote
+            | RoomError::SpectatorDelayed => StatusCode::FORBIDDEN,
+            RoomError::CannotTargetSelf
+            | RoomError::InvalidChoice(_)
+            | RoomError::StockNotOwned(_)
+            | RoomError::HouseNotOwned(_) => StatusCode::BAD_REQUEST,
+            RoomError::UnknownRulesVariant { .. } | RoomError::InvalidSnapshot(_) => {
+                StatusCode::BAD_REQUEST
+            }
+            RoomError::Engine(_) | RoomError::Storage(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            _ => StatusCode::CONFLICT,
+        }
+    }
+}
+
+/// HTTP API では `{ "code": ..., "message": ... }` を返す
+impl IntoResponse for RoomError {
+    fn into_response(self) -> Response {
+        let body = serde_json::json!({
+            "code": self.code(),
+            "message": self.to_string(),
+        });
+        (self.status(), Json(body)).into_response()
+    }
+}
+
+impl From<RoomError> for ServerMessage {
+    fn from(e: RoomError) -> Self {
+        let valid_choices = match &e {
+            RoomError::InvalidChoice(valid) => Some(valid.clone()),
+            _ => None,
+        };
+        ServerMessage::Error {
+            code: e.code().to_string(),
+            message: e.to_string(),
+            valid_choices,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_message_carries_code() {
+        let msg = ServerMessage::from(RoomError::WrongPhase("spin"));
+        match msg {
+            ServerMessage::Error { code, message, .. } => {
+                assert_eq!(code, "WRONG_PHASE");
+                assert_eq!(message, "not in spin phase");
+            }
+            other => panic!("unexpected message: {:?}", other),
+        }
+
+        let msg = ServerMessage::from(RoomError::InvalidChoice(vec!["0".to_string(), "1".to_string()]));
+        assert!(matches!(
+            msg,
+            ServerMessage::Error { code, valid_choices: Some(valid), .. } if code == "INVALID_CHOICE" && valid.len() == 2
+        ));
+    }
+}
@@ -0,0 +1,231 @@
+//! ノード間での部屋の引き継ぎ
+//!
+//! 複数ノードで動かすとき、各部屋は1つのノードだけが持つ。どのノードが持つかはストレージ（DATA_DIR を共有する）の
+//! room_leases に期限付きのリース（持ち主のノードURL）として書き、持ち主は期限が切れる前に更新し続ける。
+//! スケールダウンなどで部屋を別のノードに移すときは、ゲーム状態・部屋の記録（RoomRecords）と席ごとの引き継ぎ用の合言葉を room_handoffs に書き、
+//! リースを移し先に書き換えてから、接続中のプレイヤーに Reconnect { url } で移し先に繋ぎ直してもらう。
+//! 移し先は最初の参加（JoinRoom）が来た時点で room_handoffs から部屋を復元する。
+//! ストレージには比較して書き込む操作が無いため、同じ部屋を2つのノードが同時に取りに行く競合は防げない。
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::game::{AchievementTracker, Ledger, RecapTracker};
+use crate::protocol::{ChatLine, PlayerId, RoomId, TimelineEntry};
+use crate::room::models::{now_ms, Room, RoomListing, RoomSnapshot};
+use crate::room::series::Series;
+use crate::storage::Storage;
+
+const LEASES: &str = "room_leases";
+const HANDOFFS: &str = "room_handoffs";
+
+/// 部屋の持ち主
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoomLease {
+    pub room_id: RoomId,
+    /// 持ち主のノードの WebSocket の URL（NODE_URL）
+    pub node_url: String,
+    /// 期限（UNIX ミリ秒）
+    pub expires_at: u64,
+}
+
+/// 別のノードに移す部屋
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoomHandoff {
+    pub snapshot: RoomSnapshot,
+    pub private: bool,
+    pub join_token: String,
+    #[serde(default)]
+    pub listing: RoomListing,
+    /// 引き継ぎ用の合言葉 → 席（プレイヤーID）。Reconnect の URL に ?handoff=<合言葉> として付ける
+    pub seats: HashMap<String, PlayerId>,
+    #[serde(default)]
+    pub records: RoomRecords,
+}
+
+/// ゲーム状態のほかに引き継ぐ部屋の記録（ホスト・ミュート・追放、台帳・実績・振り返り・出来事、チャット、シリーズ戦）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RoomRecords {
+    pub host: Option<PlayerId>,
+    pub muted: HashSet<PlayerId>,
+    pub banned: HashSet<PlayerId>,
+    /// ホストが一時停止していたか（全員の切断による自動の一時停止は引き継がない）
+    pub paused: bool,
+    pub series: Option<Series>,
+    pub spectator_delay: Duration,
+    pub ledger: Ledger,
+    pub achievements: AchievementTracker,
+    pub recap: RecapTracker,
+    pub event_log: Vec<TimelineEntry>,
+    pub chat_history: VecDeque<ChatLine>,
+}
+
+impl RoomRecords {
+    pub fn of(room: &Room) -> Self {
+        Self {
+            host: Some(room.host.clone()),
+            muted: room.muted.clone(),
+            banned: room.banned.clone(),
+            paused: room.paused && !room.auto_paused,
+            series: room.series.clone(),
+            spectator_delay: room.spectator_delay,
+            ledger: room.ledger.clone(),
+            achievements: room.achievements.clone(),
+            recap: room.recap.clone(),
+            event_log: room.event_log.clone(),
+            chat_history: room.chat_history.clone(),
+        }
+    }
+
+    /// 復元した部屋に書き戻す
+    pub fn apply(self, room: &mut Room) {
+        if let Some(host) = self.host {
+            room.host = host;
+        }
+        room.muted = self.muted;
+        room.banned = self.banned;
+        room.paused = self.paused;
+        room.series = self.series;
+        room.spectator_delay = self.spectator_delay;
+        room.ledger = self.ledger;
+        room.achievements = self.achievements;
+        room.recap = self.recap;
+        room.event_log = self.event_log;
+        room.chat_history = self.chat_history;
+    }
+}
+
+/// 部屋のリースと引き継ぎの読み書き
+pub struct LeaseStore {
+    storage: Arc<dyn Storage>,
+    node_url: String,
+    ttl: Duration,
+}
+
+impl LeaseStore {
+    pub fn new(storage: Arc<dyn Storage>, node_url: String, ttl: Duration) -> Self {
+        Self { storage, node_url, ttl }
+    }
+
+    /// このノードの URL
+    pub fn node_url(&self) -> &str {
+        &self.node_url
+    }
+
+    /// 更新の間隔（期限の3分の1）
+    pub fn renew_interval(&self) -> Duration {
+        self.ttl / 3
+    }
+
+    /// 期限内のリース
+    pub async fn owner(&self, room_id: &str) -> Result<Option<RoomLease>, String> {
+        let Some(value) = self.storage.get(LEASES, room_id).await? else {
+            return Ok(None);
+        };
+        let lease: RoomLease = serde_json::from_value(value).map_err(|e| e.to_string())?;
+        Ok(Some(lease).filter(|l| l.expires_at > now_ms()))
+    }
+
+    /// 空いている（期限切れ・自ノードのものを含む）ならリースを取る。他のノードが持っていれば false
+    pub async fn acquire(&self, room_id: &str) -> Result<bool, String> {
+        if self.owner(room_id).await?.is_some_and(|l| l.node_url != self.node_url) {
+            return Ok(false);
+        }
+        self.write(room_id, &self.node_url).await?;
+        Ok(true)
+    }
+
+    /// このノードが持つ部屋のリースを延ばす（他のノードに移ったものは触らない）
+    pub async fn renew(&self, room_ids: &[RoomId]) {
+        for room_id in room_ids {
+            if let Err(e) = self.acquire(room_id).await {
+                eprintln!("failed to renew lease for room {}: {}", room_id, e);
+            }
+        }
+    }
+
+    /// 引き継ぎを書き、リースを移し先に渡す
+    pub async fn hand_off(&self, room_id: &str, handoff: &RoomHandoff, to_url: &str) -> Result<(), String> {
+        let value = serde_json::to_value(handoff).map_err(|e| e.to_string())?;
+        self.storage.put(HANDOFFS, room_id, value).await?;
+        self.write(room_id, to_url).await
+    }
+
+    /// このノード宛ての引き継ぎを取り出す（取り出したものはストレージから消す）
+    pub async fn take_handoff(&self, room_id: &str) -> Result<Option<RoomHandoff>, String> {
+        if self.owner(room_id).await?.is_none_or(|l| l.node_url != self.node_url) {
+            return Ok(None);
+        }
+        let Some(value) = self.storage.get(HANDOFFS, room_id).await? else {
+            return Ok(None);
+        };
+        self.storage.delete(HANDOFFS, room_id).await?;
+        serde_json::from_value(value).map(Some).map_err(|e| e.to_string())
+    }
+
+    async fn write(&self, room_id: &str, node_url: &str) -> Result<(), String> {
+        let lease = RoomLease {
+            room_id: room_id.to_string(),
+            node_url: node_url.to_string(),
+            expires_at: now_ms() + self.ttl.as_millis() as u64,
+        };
+        let value = serde_json::to_value(&lease).map_err(|e| e.to_string())?;
+        self.storage.put(LEASES, room_id, value).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{EngineRegistry, GameOptions, DEFAULT_RULES_VARIANT};
+    use crate::storage::MemoryStorage;
+
+    #[tokio::test]
+    async fn test_lease_and_handoff() {
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::new());
+        let a = LeaseStore::new(storage.clone(), "ws://a/ws".to_string(), Duration::from_secs(30));
+        let b = LeaseStore::new(storage.clone(), "ws://b/ws".to_string(), Duration::from_secs(30));
+
+        assert!(a.acquire("ROOM01").await.unwrap());
+        assert!(!b.acquire("ROOM01").await.unwrap());
+        assert_eq!(b.owner("ROOM01").await.unwrap().unwrap().node_url, "ws://a/ws");
+
+        let map = crate::map::MapCatalog::builtin().get("classic").unwrap().clone();
+        let engine = EngineRegistry::with_builtin()
+            .create(DEFAULT_RULES_VARIANT, GameOptions::default())
+            .unwrap();
+        let handoff = RoomHandoff {
+            snapshot: RoomSnapshot {
+                map_id: "classic".to_string(),
+                rules_variant: DEFAULT_RULES_VARIANT.to_string(),
+                game_options: GameOptions::default(),
+                state: engine.init(vec![("p1".to_string(), "Alice".to_string())], &map),
+            },
+            private: false,
+            join_token: "token".to_string(),
+            listing: RoomListing::default(),
+            seats: HashMap::from([("secret".to_string(), "p1".to_string())]),
+            records: RoomRecords::default(),
+        };
+        a.hand_off("ROOM01", &handoff, "ws://b/ws").await.unwrap();
+        // 移し元では取り出せない
+        assert!(a.take_handoff("ROOM01").await.unwrap().is_none());
+        let taken = b.take_handoff("ROOM01").await.unwrap().unwrap();
+        assert_eq!(taken.seats["secret"], "p1");
+        assert!(b.take_handoff("ROOM01").await.unwrap().is_none());
+
+        // 移した後は更新しても取り返さない
+        a.renew(&["ROOM01".to_string()]).await;
+        assert_eq!(a.owner("ROOM01").await.unwrap().unwrap().node_url, "ws://b/ws");
+
+        // 期限切れのリースは取れる
+        let expired = LeaseStore::new(storage, "ws://c/ws".to_string(), Duration::ZERO);
+        assert!(expired.acquire("ROOM02").await.unwrap());
+        assert!(expired.owner("ROOM02").await.unwrap().is_none());
+        assert!(a.acquire("ROOM02").await.unwrap());
+    }
+}
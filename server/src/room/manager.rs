@@ -1,31 +1,121 @@
 use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use tokio::sync::RwLock;
-
-use crate::game::state::{GameEvent, GameState, MapData, PlayerAction, TurnPhase};
-use crate::game::GameEngine;
-use crate::protocol::{PlayerId, RoomId, ServerMessage};
-use crate::room::models::{Room, RoomStatus};
-use crate::transport::traits::Transport;
-
-/// 埋め込みマップデータ
-const CLASSIC_MAP_JSON: &str = include_str!("../classic.json");
+use std::time::Duration;
+use tokio::sync::{Mutex, RwLock};
+
+use crate::auth::AuthIdentity;
+use crate::broadcast::{Broadcaster, LocalBroadcaster};
+use crate::game::state::{
+    GameChoice, GameEvent, GameOptions, GameState, InitMode, MapData, PlayerAction, TurnPhase,
+};
+use crate::game::{driver, narration, AchievementAward, EngineRegistry, LedgerEntry, DEFAULT_RULES_VARIANT};
+use crate::map::MapCatalog;
+use crate::profile::{GameResult, Profile, ProfileStore};
+use crate::protocol::{ChatLine, PlayerId, RoomId, ServerMessage, TimelineEntry};
+use crate::room::actor::RoomHandle;
+use crate::room::error::RoomError;
+use crate::room::lease::{LeaseStore, RoomHandoff, RoomRecords};
+use crate::room::spectator::{Spectator, MAX_SPECTATORS, MAX_SPECTATOR_DELAY};
+use crate::room::vote::{KickVote, VoteStatus, KICK_VOTE_TIMEOUT};
+use crate::room::models::{
+    now_ms, Player, Room, RoomFilter, RoomListing, RoomOptions, RoomSettings, RoomSnapshot, RoomStatus,
+    MAX_SEED, MAX_SERIES_GAMES, MAX_TURN_COUNT, MIN_PLAYERS, RECENT_EVENTS_LEN,
+};
+use crate::transport::traits::{EncodedMessage, Transport};
+use crate::webhook::{WebhookEvent, WebhookNotifier};
+
+/// 台帳の1ページの既定の件数
+pub const LEDGER_PAGE_SIZE: usize = 50;
+
+/// 台帳の1ページの最大件数
+pub const MAX_LEDGER_PAGE: usize = 200;
+
+/// 他のノードと重ならない部屋IDを探す回数
+const ROOM_ID_ATTEMPTS: usize = 8;
+
+/// GetRecentEvents の既定の件数
+pub const RECENT_EVENTS_PAGE: usize = 20;
+
+/// ルーレットの盤面の区画数（区画 i に i + 1 の数字）
+pub const ROULETTE_SEGMENTS: u32 = 10;
+
+/// ルーレットが止まるまでのアニメーション時間の範囲（ミリ秒）
+pub const ROULETTE_ANIMATION_MS: std::ops::RangeInclusive<u32> = 2000..=3000;
 
 /// ルームマネージャー
 /// 全ルームの作成・参加・退出を管理する
+/// 各部屋は専用のタスク（room::actor）が所有し、ここでは部屋IDと送信口の対応だけを持つ
 pub struct RoomManager {
-    rooms: Arc<RwLock<HashMap<RoomId, Room>>>,
+    rooms: Arc<RwLock<HashMap<RoomId, RoomHandle>>>,
     max_players_per_room: usize,
+    webhooks: WebhookNotifier,
+    profiles: ProfileStore,
+    engines: Arc<EngineRegistry>,
+    maps: Arc<MapCatalog>,
+    /// ゲーム中に全員の接続が切れた部屋を残しておく時間（ミリ秒。設定の再読み込みで変わる）
+    abandoned_grace_ms: AtomicU64,
+    /// 状態遷移のデバッグログを書くディレクトリ（未設定なら書かない）
+    debug_log_dir: Option<PathBuf>,
+    /// 他のノードへのブロードキャスト（単一ノードなら LocalBroadcaster）
+    broadcaster: Arc<dyn Broadcaster>,
+    /// 部屋のリース（複数ノード構成で NODE_URL を設定したときだけ）
+    leases: Option<Arc<LeaseStore>>,
+    /// 引き継ぎから部屋を復元している間、同じ部屋を二重に復元しないためのロック
+    adopting: Mutex<()>,
 }
 
 impl RoomManager {
-    pub fn new(max_players_per_room: usize) -> Self {
+    pub fn new(
+        max_players_per_room: usize,
+        webhooks: WebhookNotifier,
+        profiles: ProfileStore,
+        maps: MapCatalog,
+        abandoned_grace: Duration,
+    ) -> Self {
         Self {
             rooms: Arc::new(RwLock::new(HashMap::new())),
             max_players_per_room,
+            webhooks,
+            profiles,
+            engines: Arc::new(EngineRegistry::with_builtin()),
+            maps: Arc::new(maps),
+            abandoned_grace_ms: AtomicU64::new(abandoned_grace.as_millis() as u64),
+            debug_log_dir: None,
+            broadcaster: Arc::new(LocalBroadcaster),
+            leases: None,
+            adopting: Mutex::new(()),
         }
     }
 
+    /// 以降に作る部屋の猶予時間を変える（作成済みの部屋は作成時の値のまま）
+    pub fn set_abandoned_grace(&self, grace: Duration) {
+        self.abandoned_grace_ms.store(grace.as_millis() as u64, Ordering::Relaxed);
+    }
+
+    fn abandoned_grace(&self) -> Duration {
+        Duration::from_millis(self.abandoned_grace_ms.load(Ordering::Relaxed))
+    }
+
+    /// 以降に作る部屋で、エンジンの呼び出しごとに状態遷移を <dir>/<部屋ID>.ndjson に書き出す
+    pub fn with_debug_log_dir(mut self, dir: Option<PathBuf>) -> Self {
+        self.debug_log_dir = dir;
+        self
+    }
+
+    /// broadcast するメッセージを他のノードにも流す
+    pub fn with_broadcaster(mut self, broadcaster: Arc<dyn Broadcaster>) -> Self {
+        self.broadcaster = broadcaster;
+        self
+    }
+
+    /// 部屋のリースを取り、部屋を他のノードに移したり他のノードから引き継いだりできるようにする
+    pub fn with_leases(mut self, leases: Option<LeaseStore>) -> Self {
+        self.leases = leases.map(Arc::new);
+        self
+    }
+
     /// 6文字の英数字ルームIDを生成
     fn generate_room_id() -> RoomId {
         use rand::RngExt;
@@ -36,92 +126,456 @@ impl RoomManager {
             .collect()
     }
 
-    /// マップデータをロード
-    pub fn load_map(map_id: &str) -> Result<MapData, String> {
-        match map_id {
-            "classic" => serde_json::from_str(CLASSIC_MAP_JSON)
-                .map_err(|e| format!("failed to parse classic map: {}", e)),
-            _ => Err(format!("unknown map: {}", map_id)),
+    /// ルーレットのアニメーション時間と止まる区画を決める（全員の画面で同じ時刻・同じ数字に止める）
+    fn roulette_animation(value: u32) -> (u32, u32) {
+        use rand::RngExt;
+        let animation_ms = rand::rng().random_range(ROULETTE_ANIMATION_MS);
+        let segment = value.saturating_sub(1) % ROULETTE_SEGMENTS;
+        (animation_ms, segment)
+    }
+
+    /// 認証済みならユーザーID、匿名なら新しいUUIDをプレイヤーIDとする
+    fn player_id_for(identity: Option<&AuthIdentity>) -> PlayerId {
+        identity
+            .map(|i| i.user_id.clone())
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string())
+    }
+
+    /// 認証済みプレイヤーのプロフィールを読み込む（無ければ作成）
+    async fn load_profile(&self, identity: Option<&AuthIdentity>, name: &str) -> Option<Profile> {
+        let identity = identity?;
+        self.profiles.touch(&identity.user_id, name).await
+    }
+
+    /// 部屋タスクの送信口
+    async fn room(&self, room_id: &str) -> Result<RoomHandle, RoomError> {
+        self.rooms
+            .read()
+            .await
+            .get(room_id)
+            .cloned()
+            .ok_or(RoomError::RoomNotFound)
+    }
+
+    /// 部屋タスクの送信口。このノードに無ければ、このノード宛ての引き継ぎがあればそこから部屋を復元する
+    async fn room_or_adopt(&self, room_id: &str) -> Result<RoomHandle, RoomError> {
+        if let Ok(room) = self.room(room_id).await {
+            return Ok(room);
+        }
+        let Some(leases) = &self.leases else {
+            return Err(RoomError::RoomNotFound);
+        };
+        let _adopting = self.adopting.lock().await;
+        if let Ok(room) = self.room(room_id).await {
+            return Ok(room);
+        }
+        let handoff = leases
+            .take_handoff(room_id)
+            .await
+            .map_err(RoomError::Storage)?
+            .ok_or(RoomError::RoomNotFound)?;
+        let map = self.load_map(&handoff.snapshot.map_id).ok();
+        let mut room = Room::restore(room_id.to_string(), handoff.snapshot, &self.engines, map)?;
+        room.private = handoff.private;
+        room.join_token = handoff.join_token;
+        room.listing = handoff.listing;
+        room.handoff_seats = handoff.seats;
+        handoff.records.apply(&mut room);
+        // 席は合言葉か認証済みのユーザーIDでだけ取り戻せる（空いている席を誰でも引き継げる復元とは違う）
+        room.restored = false;
+        if let Some(dir) = self.debug_log_dir.clone() {
+            room.enable_debug_log(dir);
+        }
+        let handle = RoomHandle::spawn(room, self.rooms.clone(), self.abandoned_grace());
+        self.rooms.write().await.insert(room_id.to_string(), handle.clone());
+        Ok(handle)
+    }
+
+    /// 新しい部屋ID（リースがあれば他のノードの部屋と重ならないものを取る）
+    async fn claim_room_id(&self) -> Result<RoomId, RoomError> {
+        let Some(leases) = &self.leases else {
+            return Ok(Self::generate_room_id());
+        };
+        for _ in 0..ROOM_ID_ATTEMPTS {
+            let room_id = Self::generate_room_id();
+            if leases.acquire(&room_id).await.map_err(RoomError::Storage)? {
+                return Ok(room_id);
+            }
+        }
+        Err(RoomError::Storage("no free room id".to_string()))
+    }
+
+    /// 他のノードが持っている部屋なら、そのノードの URL
+    pub async fn locate(&self, room_id: &str) -> Option<String> {
+        let leases = self.leases.as_ref()?;
+        let lease = leases.owner(room_id).await.ok()??;
+        (lease.node_url != leases.node_url()).then_some(lease.node_url)
+    }
+
+    /// リースを更新する間隔（リースを使わないなら None）
+    pub fn lease_renew_interval(&self) -> Option<Duration> {
+        self.leases.as_ref().map(|l| l.renew_interval())
+    }
+
+    /// このノードにある部屋のリースを延ばす
+    pub async fn renew_leases(&self) {
+        let Some(leases) = &self.leases else {
+            return;
+        };
+        let room_ids: Vec<RoomId> = self.rooms.read().await.keys().cloned().collect();
+        leases.renew(&room_ids).await;
+    }
+
+    /// 進行中の部屋を別のノード（to_url）に移す
+    /// ゲーム状態と席ごとの合言葉を書いてリースを渡し、接続中のプレイヤーと観戦者に Reconnect を送って切断する
+    pub async fn hand_off(&self, room_id: &str, to_url: &str) -> Result<(), RoomError> {
+        let leases = self.leases.as_ref().ok_or(RoomError::HandoffDisabled)?;
+        // 一覧から外してから状態を取り出し、以降の操作が届かないようにする
+        let room = self
+            .rooms
+            .write()
+            .await
+            .remove(room_id)
+            .ok_or(RoomError::RoomNotFound)?;
+        let prepared = room
+            .call(|room| {
+                let state = room
+                    .game_state
+                    .clone()
+                    .filter(|_| room.status == RoomStatus::Playing)
+                    .ok_or(RoomError::GameNotStarted)?;
+                let seats = room
+                    .players
+                    .iter()
+                    .filter(|p| p.connected)
+                    .map(|p| (uuid::Uuid::new_v4().simple().to_string(), p.id.clone()))
+                    .collect();
+                Ok(RoomHandoff {
+                    snapshot: RoomSnapshot {
+                        map_id: room.map_id.clone(),
+                        rules_variant: room.rules_variant.clone(),
+                        game_options: room.game_options,
+                        state,
+                    },
+                    private: room.private,
+                    join_token: room.join_token.clone(),
+                    listing: room.listing.clone(),
+                    seats,
+                    records: RoomRecords::of(room),
+                })
+            })
+            .await?;
+        let written = match prepared {
+            Ok(handoff) => leases
+                .hand_off(room_id, &handoff, to_url)
+                .await
+                .map(|_| handoff)
+                .map_err(RoomError::Storage),
+            Err(e) => Err(e),
+        };
+        let handoff = match written {
+            Ok(handoff) => handoff,
+            Err(e) => {
+                self.rooms.write().await.insert(room_id.to_string(), room);
+                return Err(e);
+            }
+        };
+
+        // 全員を外すと部屋タスクも終了する
+        let (players, spectators) = room
+            .call(|room| (std::mem::take(&mut room.players), std::mem::take(&mut room.spectators)))
+            .await
+            .unwrap_or_default();
+        let separator = if to_url.contains('?') { '&' } else { '?' };
+        for player in players.iter().filter(|p| p.connected) {
+            let url = match handoff.seats.iter().find(|(_, seat)| **seat == player.id) {
+                Some((secret, _)) => format!("{}{}handoff={}", to_url, separator, secret),
+                None => to_url.to_string(),
+            };
+            let _ = player.transport.send(ServerMessage::Reconnect { url }).await;
+            let _ = player.transport.close().await;
+        }
+        for spectator in &spectators {
+            let msg = ServerMessage::Reconnect {
+                url: to_url.to_string(),
+            };
+            let _ = spectator.transport.send(msg).await;
+            let _ = spectator.transport.close().await;
         }
+        Ok(())
+    }
+
+    /// このノードの進行中の部屋をすべて to_url に移す（スケールダウン前に使う）
+    /// 戻り値は移した部屋と、移せなかった部屋とその理由（ロビー・終了後の部屋は GameNotStarted）
+    pub async fn drain(&self, to_url: &str) -> Result<(Vec<RoomId>, Vec<(RoomId, RoomError)>), RoomError> {
+        if self.leases.is_none() {
+            return Err(RoomError::HandoffDisabled);
+        }
+        let room_ids: Vec<RoomId> = self.rooms.read().await.keys().cloned().collect();
+        let mut handed_off = Vec::new();
+        let mut failed = Vec::new();
+        for room_id in room_ids {
+            match self.hand_off(&room_id, to_url).await {
+                Ok(()) => handed_off.push(room_id),
+                Err(e) => failed.push((room_id, e)),
+            }
+        }
+        Ok((handed_off, failed))
+    }
+
+    /// マップデータをロード
+    pub fn load_map(&self, map_id: &str) -> Result<MapData, RoomError> {
+        Self::find_map(&self.maps, map_id)
+    }
+
+    fn find_map(maps: &MapCatalog, map_id: &str) -> Result<MapData, RoomError> {
+        maps.get(map_id)
+            .cloned()
+            .ok_or_else(|| RoomError::UnknownMap {
+                map_id: map_id.to_string(),
+                available: maps.ids().collect::<Vec<_>>().join(", "),
+            })
     }
 
     /// 部屋作成
+    /// 戻り値の3番目は部屋の join_token。未知のマップ・ルール名なら Err
     pub async fn create_room(
         &self,
         host_name: String,
         map_id: String,
+        options: RoomOptions,
+        identity: Option<&AuthIdentity>,
         transport: Arc<dyn Transport>,
-    ) -> (RoomId, PlayerId) {
-        let room_id = Self::generate_room_id();
-        let player_id = uuid::Uuid::new_v4().to_string();
-
-        let room = Room::new(
-            room_id.clone(),
-            player_id.clone(),
-            host_name,
-            map_id,
+    ) -> Result<(RoomId, PlayerId, String), RoomError> {
+        self.load_map(&map_id)?;
+        let rules_variant = options
+            .rules_variant
+            .unwrap_or_else(|| DEFAULT_RULES_VARIANT.to_string());
+        if !self.engines.contains(&rules_variant) {
+            return Err(RoomError::UnknownRulesVariant {
+                name: rules_variant,
+                available: self.engines.names().join(", "),
+            });
+        }
+
+        let room_id = self.claim_room_id().await?;
+        let player_id = Self::player_id_for(identity);
+        let profile = self.load_profile(identity, &host_name).await;
+
+        let settings = RoomSettings {
+            private: options.private,
+            max_players: options
+                .max_players
+                .unwrap_or(self.max_players_per_room)
+                .clamp(MIN_PLAYERS, self.max_players_per_room),
+            series_games: options
+                .series_games
+                .filter(|&n| n > 1)
+                .map(|n| n.min(MAX_SERIES_GAMES)),
+            game_options: GameOptions {
+                init_mode: if options.short_game {
+                    InitMode::Short
+                } else {
+                    InitMode::Standard
+                },
+                max_turn_count: options
+                    .max_turn_count
+                    .filter(|&n| n > 0)
+                    .map(|n| n.min(MAX_TURN_COUNT)),
+                pause_at_branches: options.pause_at_branches,
+                global_event_interval: options
+                    .global_event_interval
+                    .filter(|&n| n > 0)
+                    .map(|n| n.min(MAX_TURN_COUNT)),
+                stall_limit: options.stall_limit,
+                seed: options.seed.filter(|&s| (1..=MAX_SEED).contains(&s)),
+            },
+            rules_variant,
+            spectator_delay: options
+                .spectator_delay_secs
+                .map(|s| Duration::from_secs(s as u64).min(MAX_SPECTATOR_DELAY))
+                .unwrap_or_default(),
+            listing: RoomListing::new(options.title, options.tags, options.language),
+        };
+        self.webhooks.notify(WebhookEvent::RoomCreated {
+            room_id: room_id.clone(),
+            map_id: map_id.clone(),
+            host_name: host_name.clone(),
+            private: settings.private,
+        });
+
+        let host = Player {
+            id: player_id.clone(),
+            name: host_name,
+            user_id: identity.map(|i| i.user_id.clone()),
+            profile,
             transport,
-            self.max_players_per_room,
-        );
+            connected: true,
+        };
+        let mut room = Room::new(room_id.clone(), host, map_id, settings);
+        room.debug_log_dir = self.debug_log_dir.clone();
+        let join_token = room.join_token.clone();
 
-        let mut rooms = self.rooms.write().await;
-        rooms.insert(room_id.clone(), room);
+        let handle = RoomHandle::spawn(room, self.rooms.clone(), self.abandoned_grace());
+        self.rooms.write().await.insert(room_id.clone(), handle);
 
-        (room_id, player_id)
+        Ok((room_id, player_id, join_token))
     }
 
     /// 部屋参加
+    /// 認証済みプレイヤーが進行中のゲームに戻る場合は再参加として扱い、戻り値の2番目が true
+    /// handoff は別のノードから移った部屋の引き継ぎ用の合言葉（Reconnect の URL に付いていたもの）
     pub async fn join_room(
         &self,
         room_id: &str,
         player_name: String,
+        token: Option<&str>,
+        handoff: Option<&str>,
+        identity: Option<&AuthIdentity>,
         transport: Arc<dyn Transport>,
-    ) -> Result<PlayerId, String> {
-        let mut rooms = self.rooms.write().await;
-        let room = rooms
-            .get_mut(room_id)
-            .ok_or_else(|| "room not found".to_string())?;
+    ) -> Result<(PlayerId, bool), RoomError> {
+        let profile = self.load_profile(identity, &player_name).await;
+        let player_id = Self::player_id_for(identity);
+        let user_id = identity.map(|i| i.user_id.clone());
+        let token = token.map(str::to_string);
+        let handoff = handoff.map(str::to_string);
+
+        self.room_or_adopt(room_id).await?.call(move |room| {
+            // 移る前の席（合言葉が合えば招待URLのトークンは要らない）
+            let handoff = handoff.filter(|secret| room.handoff_seats.contains_key(secret));
+            let handoff_seat = handoff.as_ref().map(|secret| room.handoff_seats[secret].clone());
+
+            // 非公開部屋は招待URLのトークンが必要
+            if handoff_seat.is_none() && !room.is_token_valid(token.as_deref()) {
+                return Err(RoomError::InvalidJoinToken);
+            }
 
-        if room.status != RoomStatus::Lobby {
-            return Err("room is not in lobby state".to_string());
-        }
+            // スナップショットから復元した部屋では、空いている席のプレイヤーとして参加する
+            let claimed = room.restored && room.status == RoomStatus::Playing;
+            let player_id = match handoff_seat.or_else(|| room.unclaimed_seat().filter(|_| claimed)) {
+                Some(seat) => seat,
+                None => player_id,
+            };
 
-        if room.is_full() {
-            return Err("room is full".to_string());
-        }
+            if room.banned.contains(&player_id) {
+                return Err(RoomError::Banned);
+            }
 
-        let player_id = uuid::Uuid::new_v4().to_string();
-        let player = crate::room::models::Player {
-            id: player_id.clone(),
-            name: player_name,
-            transport,
-        };
-        room.players.push(player);
+            // 切断中の席には戻れる（接続中なら二重参加）
+            let existing = room.players.iter().position(|p| p.id == player_id);
+            if existing.is_some_and(|i| room.players[i].connected) {
+                return Err(RoomError::AlreadyInRoom);
+            }
+
+            // 切断していた認証済みプレイヤーのゲーム復帰
+            let rejoining = (user_id.is_some() || claimed || handoff.is_some())
+                && room.status == RoomStatus::Playing
+                && room
+                    .game_state
+                    .as_ref()
+                    .is_some_and(|gs| gs.player_by_id(&player_id).is_some());
+
+            if !rejoining {
+                if room.status != RoomStatus::Lobby {
+                    return Err(RoomError::NotInLobby);
+                }
 
-        Ok(player_id)
+                if room.is_full() {
+                    return Err(RoomError::RoomFull);
+                }
+            }
+
+            if rejoining {
+                if let Some(i) = existing {
+                    room.players.remove(i);
+                }
+                if let Some(p) = room.game_state.as_mut().and_then(|gs| gs.player_by_id_mut(&player_id)) {
+                    p.connected = true;
+                }
+                // 全員の接続が切れて自動で止めていたゲームは、誰かが戻ったら再開する
+                if room.auto_paused {
+                    room.paused = false;
+                    room.auto_paused = false;
+                }
+            }
+
+            let player = Player {
+                id: player_id.clone(),
+                name: player_name,
+                user_id,
+                profile,
+                transport,
+                connected: true,
+            };
+            room.players.push(player);
+            if let Some(secret) = &handoff {
+                room.handoff_seats.remove(secret);
+            }
+
+            Ok((player_id, rejoining))
+        })
+        .await?
     }
 
-    /// 部屋退出
-    pub async fn leave_room(&self, room_id: &str, player_id: &str) -> Result<(), String> {
-        let mut rooms = self.rooms.write().await;
-        let room = rooms
-            .get_mut(room_id)
-            .ok_or_else(|| "room not found".to_string())?;
+    /// 部屋退出（切断を含む）。部屋の全員に送るメッセージを返す
+    /// ゲーム中のプレイヤーは席を残して切断中にし（PlayerDisconnected）、手番なら飛ばす。それ以外は部屋から外す（PlayerLeft）
+    /// 接続中のプレイヤーがいなくなると部屋タスクが終了し、一覧からも削除される
+    pub async fn leave_room(&self, room_id: &str, player_id: &str) -> Result<Vec<ServerMessage>, RoomError> {
+        let player_id = player_id.to_string();
+        let webhooks = self.webhooks.clone();
+        let profiles = self.profiles.clone();
+        self.room(room_id).await?.call(move |room| {
+            let idx = room
+                .players
+                .iter()
+                .position(|p| p.id == player_id && p.connected)
+                .ok_or(RoomError::PlayerNotFound)?;
+            let in_game = room.status == RoomStatus::Playing
+                && room
+                    .game_state
+                    .as_ref()
+                    .is_some_and(|gs| gs.player_by_id(&player_id).is_some());
+            if !in_game {
+                room.players.remove(idx);
+                return Ok(vec![ServerMessage::PlayerLeft { player_id }]);
+            }
 
-        let before = room.players.len();
-        room.players.retain(|p| p.id != player_id);
+            room.disconnect_player(&player_id);
+            let mut msgs = vec![ServerMessage::PlayerDisconnected { player_id }];
+            Self::skip_disconnected_turn(&webhooks, &profiles, room, &mut msgs);
+            Ok(msgs)
+        })
+        .await?
+    }
 
-        if room.players.len() == before {
-            return Err("player not found in room".to_string());
+    /// 手番のプレイヤーが切断中なら、何もせずにその手番を終える（選択待ちも取り消す）
+    /// 一時停止中や、接続中で引退していないプレイヤーが他にいない場合は何もしない（戻るのを待つ）
+    fn skip_disconnected_turn(
+        webhooks: &WebhookNotifier,
+        profiles: &ProfileStore,
+        room: &mut Room,
+        msgs: &mut Vec<ServerMessage>,
+    ) {
+        if room.paused || room.status != RoomStatus::Playing {
+            return;
         }
-
-        // 部屋が空になったら削除
-        if room.players.is_empty() {
-            let room_id = room_id.to_string();
-            rooms.remove(&room_id);
+        let Some(state) = room.game_state.as_ref() else {
+            return;
+        };
+        if state.current_player().connected
+            || state.phase == TurnPhase::Moving
+            || !state.players.iter().any(|p| p.connected && !p.retired)
+        {
+            return;
         }
 
-        Ok(())
+        let mut skipped = state.clone();
+        skipped.phase = TurnPhase::TurnEnd;
+        skipped.pending_steps = 0;
+        skipped.chosen_path = None;
+        room.apply_state(skipped, &[]);
+        Self::advance_turn(webhooks, profiles, room, msgs);
+        msgs.push(Self::build_game_sync(room));
     }
 
     /// ゲーム開始
@@ -129,44 +583,53 @@ impl RoomManager {
         &self,
         room_id: &str,
         player_id: &str,
-    ) -> Result<Vec<ServerMessage>, String> {
-        let mut rooms = self.rooms.write().await;
-        let room = rooms
-            .get_mut(room_id)
-            .ok_or_else(|| "room not found".to_string())?;
-
-        // ホストのみ開始可能
-        if room.host != player_id {
-            return Err("only host can start game".to_string());
-        }
-
-        let map = Self::load_map(&room.map_id)?;
-        let game_state = room.start_game(map)?;
-
-        let turn_order: Vec<PlayerId> = game_state.players.iter().map(|p| p.id.clone()).collect();
-        let board = game_state.board.clone();
-        let players = game_state.players.clone();
-        let careers = game_state.careers.clone();
-        let houses = game_state.houses_for_sale.clone();
-
-        let mut msgs = vec![ServerMessage::GameStarted {
-            turn_order,
-            board,
-            players,
-            careers,
-            houses,
-        }];
-
-        // スタートマスが分岐の場合、最初のプレイヤーに選択を求める
-        if let Some(gs) = &room.game_state {
-            if gs.phase == TurnPhase::ChoosingPath {
-                // init 後に ChoosingPath になることはないので通常ここには来ない
+    ) -> Result<Vec<ServerMessage>, RoomError> {
+        let player_id = player_id.to_string();
+        let maps = self.maps.clone();
+        let engines = self.engines.clone();
+        let webhooks = self.webhooks.clone();
+
+        self.room(room_id).await?.call(move |room| {
+            // ホストのみ開始可能
+            if room.host != player_id {
+                return Err(RoomError::NotHost);
             }
-        }
 
-        msgs.push(self.build_game_sync(room));
+            let map = Self::find_map(&maps, &room.map_id)?;
+            let room_id = room.id.clone();
+            let game_state = room.start_game(map, &engines)?;
 
-        Ok(msgs)
+            let turn_order: Vec<PlayerId> = game_state.players.iter().map(|p| p.id.clone()).collect();
+            webhooks.notify(WebhookEvent::GameStarted {
+                room_id,
+                players: game_state.players.iter().map(|p| p.name.clone()).collect(),
+            });
+            let board = game_state.catalog.board.clone();
+            let players = game_state.players.clone();
+            let careers = game_state.catalog.careers.clone();
+            let houses = game_state.catalog.houses_for_sale.clone();
+
+            let mut msgs = vec![ServerMessage::GameStarted {
+                turn_order,
+                board,
+                players,
+                careers,
+                houses,
+                seed: game_state.seed,
+            }];
+
+            // スタートマスが分岐の場合、最初のプレイヤーに選択を求める
+            if let Some(gs) = &room.game_state {
+                if gs.phase == TurnPhase::ChoosingPath {
+                    // init 後に ChoosingPath になることはないので通常ここには来ない
+                }
+            }
+
+            msgs.push(Self::build_game_sync(room));
+
+            Ok(msgs)
+        })
+        .await?
     }
 
     /// ルーレット回転
@@ -174,67 +637,60 @@ impl RoomManager {
         &self,
         room_id: &str,
         player_id: &str,
-    ) -> Result<Vec<ServerMessage>, String> {
-        let mut rooms = self.rooms.write().await;
-        let room = rooms
-            .get_mut(room_id)
-            .ok_or_else(|| "room not found".to_string())?;
-
-        let engine = room.engine.as_ref().ok_or("game not started")?;
-        let state = room.game_state.as_ref().ok_or("no game state")?;
-
-        // 手番チェック
-        let current_player_id = state.players[state.current_turn].id.clone();
-        if current_player_id != player_id {
-            return Err("not your turn".to_string());
-        }
-        if state.phase != TurnPhase::WaitingForSpin {
-            return Err("not in spin phase".to_string());
-        }
-
-        // ルーレット
-        let (new_state, spin_result) = engine.spin(state);
-        let value = spin_result.value;
-
-        // 移動
-        let (moved_state, events) = engine.advance(&new_state, value);
-        let final_position = moved_state.players[moved_state.current_turn].position;
-        let phase = moved_state.phase;
-
-        room.game_state = Some(moved_state);
-
-        let mut msgs = Vec::new();
-        msgs.push(ServerMessage::RouletteResult {
-            player_id: player_id.to_string(),
-            value,
-        });
-        msgs.push(ServerMessage::PlayerMoved {
-            player_id: player_id.to_string(),
-            position: final_position,
-        });
+        action_token: Option<String>,
+    ) -> Result<ActionOutcome, RoomError> {
+        let player_id = player_id.to_string();
+        let webhooks = self.webhooks.clone();
+        let profiles = self.profiles.clone();
+
+        self.room(room_id).await?.call(move |room| {
+            Self::idempotent(room, &player_id, action_token, |room| {
+                let engine = room.engine.as_ref().ok_or(RoomError::GameNotStarted)?;
+                let state = room.game_state.as_ref().ok_or(RoomError::GameNotStarted)?;
+
+                // 手番チェック
+                let current_player_id = state.players[state.current_turn].id.clone();
+                if current_player_id != player_id {
+                    return Err(RoomError::NotYourTurn);
+                }
+                if state.phase != TurnPhase::WaitingForSpin {
+                    return Err(RoomError::WrongPhase("spin"));
+                }
 
-        // イベント処理結果
-        for event in &events {
-            if let GameEvent::ChoiceRequired { choices } = event {
-                msgs.push(ServerMessage::ChoiceRequired {
-                    choices: choices
-                        .iter()
-                        .map(|c| crate::protocol::Choice {
-                            id: c.id.clone(),
-                            label: c.label.clone(),
-                        })
-                        .collect(),
+                // ルーレット
+                let (new_state, spin_result) = engine.spin(state);
+                let value = spin_result.value;
+
+                // 移動
+                let (moved_state, events) = engine.advance(&new_state, value);
+                let final_position = moved_state.players[moved_state.current_turn].position;
+                let phase = moved_state.phase;
+
+                room.apply_state(moved_state, &events);
+
+                let last_spin = spin_result.spins.last().copied().unwrap_or(value);
+                let (animation_ms, segment) = Self::roulette_animation(last_spin);
+                let mut msgs = Vec::new();
+                msgs.push(ServerMessage::RouletteResult {
+                    player_id: player_id.clone(),
+                    value,
+                    spins: spin_result.spins,
+                    animation_ms,
+                    segment,
                 });
-            }
-        }
+                Self::push_move_messages(&mut msgs, &player_id, final_position, &events);
+                Self::push_system_messages(&mut msgs, room, &events);
 
-        // TurnEnd の場合は自動的にターンを進める
-        if phase == TurnPhase::TurnEnd {
-            self.advance_turn(room, &mut msgs);
-        }
+                // TurnEnd の場合は自動的にターンを進める
+                if phase == TurnPhase::TurnEnd {
+                    Self::advance_turn(&webhooks, &profiles, room, &mut msgs);
+                }
 
-        msgs.push(self.build_game_sync(room));
-        Ok(msgs)
+                msgs.push(Self::build_game_sync(room));
+                Ok(msgs)
+            })
+        })
+        .await?
     }
 
     /// 分岐選択
@@ -243,35 +699,54 @@ impl RoomManager {
         room_id: &str,
         player_id: &str,
         path_index: usize,
-    ) -> Result<Vec<ServerMessage>, String> {
-        let mut rooms = self.rooms.write().await;
-        let room = rooms
-            .get_mut(room_id)
-            .ok_or_else(|| "room not found".to_string())?;
-
-        let engine = room.engine.as_ref().ok_or("game not started")?;
-        let state = room.game_state.as_ref().ok_or("no game state")?;
-
-        let current_player_id = state.players[state.current_turn].id.clone();
-        if current_player_id != player_id {
-            return Err("not your turn".to_string());
-        }
-        if state.phase != TurnPhase::ChoosingPath {
-            return Err("not in path choice phase".to_string());
-        }
-
-        let new_state = engine.choose_path(state, path_index);
-        let phase = new_state.phase;
-        room.game_state = Some(new_state);
+        action_token: Option<String>,
+    ) -> Result<ActionOutcome, RoomError> {
+        let player_id = player_id.to_string();
+        let webhooks = self.webhooks.clone();
+        let profiles = self.profiles.clone();
+
+        self.room(room_id).await?.call(move |room| {
+            Self::idempotent(room, &player_id, action_token, |room| {
+                let engine = room.engine.as_ref().ok_or(RoomError::GameNotStarted)?;
+                let state = room.game_state.as_ref().ok_or(RoomError::GameNotStarted)?;
+
+                let current_player_id = state.players[state.current_turn].id.clone();
+                if current_player_id != player_id {
+                    return Err(RoomError::NotYourTurn);
+                }
+                if state.phase != TurnPhase::ChoosingPath {
+                    return Err(RoomError::WrongPhase("path choice"));
+                }
+                state
+                    .check_choice(&path_index.to_string())
+                    .map_err(RoomError::InvalidChoice)?;
+
+                let mut new_state = engine.choose_path(state, path_index);
+                let mut events = Vec::new();
+                let mut msgs = Vec::new();
+
+                // 移動途中の分岐だった場合は残りの歩数を進める
+                if new_state.phase == TurnPhase::Moving {
+                    let (moved_state, moved_events) = engine.advance(&new_state, new_state.pending_steps);
+                    let final_position = moved_state.players[moved_state.current_turn].position;
+                    Self::push_move_messages(&mut msgs, &player_id, final_position, &moved_events);
+                    new_state = moved_state;
+                    events = moved_events;
+                }
 
-        let mut msgs = Vec::new();
+                let phase = new_state.phase;
+                room.apply_state(new_state, &events);
+                Self::push_system_messages(&mut msgs, room, &events);
 
-        if phase == TurnPhase::TurnEnd {
-            self.advance_turn(room, &mut msgs);
-        }
+                if phase == TurnPhase::TurnEnd {
+                    Self::advance_turn(&webhooks, &profiles, room, &mut msgs);
+                }
 
-        msgs.push(self.build_game_sync(room));
-        Ok(msgs)
+                msgs.push(Self::build_game_sync(room));
+                Ok(msgs)
+            })
+        })
+        .await?
     }
 
     /// アクション選択（家購入、保険加入、訴訟対象など）
@@ -280,133 +755,563 @@ impl RoomManager {
         room_id: &str,
         player_id: &str,
         action_id: String,
-    ) -> Result<Vec<ServerMessage>, String> {
-        let mut rooms = self.rooms.write().await;
-        let room = rooms
-            .get_mut(room_id)
-            .ok_or_else(|| "room not found".to_string())?;
+        action_token: Option<String>,
+    ) -> Result<ActionOutcome, RoomError> {
+        let player_id = player_id.to_string();
+        let webhooks = self.webhooks.clone();
+        let profiles = self.profiles.clone();
+
+        self.room(room_id).await?.call(move |room| {
+            Self::idempotent(room, &player_id, action_token, |room| {
+                let engine = room.engine.as_ref().ok_or(RoomError::GameNotStarted)?;
+                let state = room.game_state.as_ref().ok_or(RoomError::GameNotStarted)?;
+
+                let current_player_id = state.players[state.current_turn].id.clone();
+                if current_player_id != player_id {
+                    return Err(RoomError::NotYourTurn);
+                }
+                if state.phase != TurnPhase::ChoosingAction {
+                    return Err(RoomError::WrongPhase("action choice"));
+                }
+                state.check_choice(&action_id).map_err(RoomError::InvalidChoice)?;
+
+                // action_id からPlayerAction を構築
+                let action = driver::parse_action(&action_id, state);
+                let (new_state, events) = engine.resolve_action(state, action);
+                let phase = new_state.phase;
+                room.apply_state(new_state, &events);
+
+                let mut msgs = Vec::new();
+                Self::push_teleport_messages(&mut msgs, &events);
+                Self::push_system_messages(&mut msgs, room, &events);
+
+                // 新たな ChoiceRequired が発生した場合
+                Self::push_choice_messages(&mut msgs, &events);
+
+                if phase == TurnPhase::TurnEnd {
+                    Self::advance_turn(&webhooks, &profiles, room, &mut msgs);
+                }
+
+                msgs.push(Self::build_game_sync(room));
+                Ok(msgs)
+            })
+        })
+        .await?
+    }
+
+    /// 株券の売却（自分の手番のルーレット前のみ。手番は進まない）
+    pub async fn sell_stock(
+        &self,
+        room_id: &str,
+        player_id: &str,
+        stock_id: String,
+        action_token: Option<String>,
+    ) -> Result<ActionOutcome, RoomError> {
+        let action = PlayerAction::SellStock { stock_id };
+        self.asset_action(room_id, player_id, action, action_token).await
+    }
+
+    /// 家を抵当に入れる（自分の手番のルーレット前のみ。手番は進まない）
+    pub async fn mortgage_house(
+        &self,
+        room_id: &str,
+        player_id: &str,
+        house_id: String,
+        action_token: Option<String>,
+    ) -> Result<ActionOutcome, RoomError> {
+        let action = PlayerAction::MortgageHouse { house_id };
+        self.asset_action(room_id, player_id, action, action_token).await
+    }
+
+    /// 抵当に入れた家の買い戻し（自分の手番のルーレット前のみ。手番は進まない）
+    pub async fn redeem_house(
+        &self,
+        room_id: &str,
+        player_id: &str,
+        house_id: String,
+        action_token: Option<String>,
+    ) -> Result<ActionOutcome, RoomError> {
+        let action = PlayerAction::RedeemHouse { house_id };
+        self.asset_action(room_id, player_id, action, action_token).await
+    }
 
-        let engine = room.engine.as_ref().ok_or("game not started")?;
-        let state = room.game_state.as_ref().ok_or("no game state")?;
+    /// ルーレット前の資産の操作（株の売却・家の抵当と買い戻し）
+    async fn asset_action(
+        &self,
+        room_id: &str,
+        player_id: &str,
+        action: PlayerAction,
+        action_token: Option<String>,
+    ) -> Result<ActionOutcome, RoomError> {
+        let player_id = player_id.to_string();
+
+        self.room(room_id).await?.call(move |room| {
+            Self::idempotent(room, &player_id, action_token, |room| {
+                let engine = room.engine.as_ref().ok_or(RoomError::GameNotStarted)?;
+                let state = room.game_state.as_ref().ok_or(RoomError::GameNotStarted)?;
+
+                let current_player_id = state.players[state.current_turn].id.clone();
+                if current_player_id != player_id {
+                    return Err(RoomError::NotYourTurn);
+                }
+                if state.phase != TurnPhase::WaitingForSpin {
+                    return Err(RoomError::WrongPhase("spin"));
+                }
+                let player = state.current_player();
+                match &action {
+                    PlayerAction::SellStock { stock_id } if !player.stocks.iter().any(|s| &s.id == stock_id) => {
+                        return Err(RoomError::StockNotOwned(stock_id.clone()));
+                    }
+                    PlayerAction::MortgageHouse { house_id }
+                        if !player.houses.iter().any(|h| &h.id == house_id && !h.mortgaged) =>
+                    {
+                        return Err(RoomError::HouseNotOwned(house_id.clone()));
+                    }
+                    PlayerAction::RedeemHouse { house_id } => {
+                        let house = player
+                            .houses
+                            .iter()
+                            .find(|h| &h.id == house_id && h.mortgaged)
+                            .ok_or_else(|| RoomError::HouseNotOwned(house_id.clone()))?;
+                        if house.redeem_cost() > player.money {
+                            return Err(RoomError::InsufficientFunds(house.redeem_cost()));
+                        }
+                    }
+                    _ => {}
+                }
+
+                let (new_state, events) = engine.resolve_action(state, action);
+                room.apply_state(new_state, &events);
+
+                let mut msgs = Vec::new();
+                Self::push_system_messages(&mut msgs, room, &events);
+                msgs.push(Self::build_game_sync(room));
+                Ok(msgs)
+            })
+        })
+        .await?
+    }
 
-        let current_player_id = state.players[state.current_turn].id.clone();
-        if current_player_id != player_id {
-            return Err("not your turn".to_string());
+    /// action_token が適用済みなら最初の結果を返し、そうでなければ apply を実行して結果を覚える
+    fn idempotent(
+        room: &mut Room,
+        player_id: &str,
+        action_token: Option<String>,
+        apply: impl FnOnce(&mut Room) -> Result<Vec<ServerMessage>, RoomError>,
+    ) -> Result<ActionOutcome, RoomError> {
+        if let Some(msgs) = action_token
+            .as_deref()
+            .and_then(|token| room.recent_actions.get(player_id, token))
+        {
+            return Ok(ActionOutcome::Duplicate(msgs.clone()));
         }
-        if state.phase != TurnPhase::ChoosingAction {
-            return Err("not in action choice phase".to_string());
+        if room.paused {
+            return Err(RoomError::GamePaused);
         }
+        let msgs = apply(room)?;
+        if let Some(token) = action_token {
+            room.recent_actions.insert(player_id, token, msgs.clone());
+        }
+        Ok(ActionOutcome::Applied(msgs))
+    }
 
-        // action_id からPlayerAction を構築
-        let action = self.parse_action(&action_id, state);
-        let (new_state, events) = engine.resolve_action(state, action);
-        let phase = new_state.phase;
-        room.game_state = Some(new_state);
+    /// 移動結果（経路と、停止マスで発生した選択）をメッセージにする
+    fn push_move_messages(
+        msgs: &mut Vec<ServerMessage>,
+        player_id: &str,
+        position: usize,
+        events: &[GameEvent],
+    ) {
+        let (path, paydays_passed) = events
+            .iter()
+            .find_map(|e| match e {
+                GameEvent::PlayerMoved { path, paydays_passed, .. } => Some((path.clone(), *paydays_passed)),
+                _ => None,
+            })
+            .unwrap_or_default();
+        msgs.push(ServerMessage::PlayerMoved {
+            player_id: player_id.to_string(),
+            position,
+            path,
+            paydays_passed,
+        });
+        Self::push_teleport_messages(msgs, events);
+        Self::push_choice_messages(msgs, events);
+    }
 
-        let mut msgs = Vec::new();
+    fn push_teleport_messages(msgs: &mut Vec<ServerMessage>, events: &[GameEvent]) {
+        for event in events {
+            if let GameEvent::PlayerTeleported { player_id, from, to } = event {
+                msgs.push(ServerMessage::PlayerTeleported {
+                    player_id: player_id.clone(),
+                    from: *from,
+                    to: *to,
+                });
+            }
+        }
+    }
 
-        // 新たな ChoiceRequired が発生した場合
-        for event in &events {
+    fn push_choice_messages(msgs: &mut Vec<ServerMessage>, events: &[GameEvent]) {
+        for event in events {
             if let GameEvent::ChoiceRequired { choices } = event {
-                msgs.push(ServerMessage::ChoiceRequired {
-                    choices: choices
-                        .iter()
-                        .map(|c| crate::protocol::Choice {
-                            id: c.id.clone(),
-                            label: c.label.clone(),
-                        })
-                        .collect(),
-                });
+                msgs.push(Self::choice_message(choices));
             }
         }
+    }
 
-        if phase == TurnPhase::TurnEnd {
-            self.advance_turn(room, &mut msgs);
+    /// 主な出来事をチャット欄向けの SystemMessage にする
+    fn push_system_messages(msgs: &mut Vec<ServerMessage>, room: &mut Room, events: &[GameEvent]) {
+        let Some(state) = &room.game_state else {
+            return;
+        };
+        let turn_number = state.turn_number;
+        for text in narration::narrate(state, events) {
+            let label = text.fallback_text();
+            room.push_event(TimelineEntry {
+                turn_number,
+                text: text.clone(),
+                label: label.clone(),
+            });
+            msgs.push(ServerMessage::SystemMessage { text, label });
         }
+    }
 
-        msgs.push(self.build_game_sync(room));
-        Ok(msgs)
+    fn choice_message(choices: &[GameChoice]) -> ServerMessage {
+        ServerMessage::ChoiceRequired {
+            choices: choices
+                .iter()
+                .map(|c| crate::protocol::Choice {
+                    id: c.id.clone(),
+                    label: c.label.clone(),
+                    text: c.text.clone(),
+                    preview: c.preview.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    /// 認証済みプレイヤーの戦績・レーティングをプロフィールに反映（1位が勝利）
+    fn record_results(
+        profiles: &ProfileStore,
+        room: &Room,
+        rankings: &[crate::protocol::RankingEntry],
+        achievements: &[AchievementAward],
+    ) {
+        let results: Vec<GameResult> = rankings
+            .iter()
+            .filter_map(|r| {
+                let player = room.find_player(&r.player_id)?;
+                Some(GameResult {
+                    user_id: player.user_id.clone()?,
+                    rank: r.rank,
+                    achievements: achievements
+                        .iter()
+                        .filter(|a| a.player_id == r.player_id)
+                        .map(|a| a.achievement)
+                        .collect(),
+                })
+            })
+            .collect();
+        if results.is_empty() {
+            return;
+        }
+        let profiles = profiles.clone();
+        tokio::spawn(async move {
+            profiles.record_game(&results).await;
+        });
     }
 
-    /// action_id 文字列から PlayerAction を解析
-    fn parse_action(&self, action_id: &str, state: &GameState) -> PlayerAction {
-        let current_pos = state.players[state.current_turn].position;
-        let tile = state.board.tile(current_pos);
-        let tile_type = tile.map(|t| &t.tile_type);
+    /// 認証済みプレイヤーの好きな色を更新し、部屋内のキャッシュにも反映する
+    pub async fn update_profile(
+        &self,
+        room_id: &str,
+        player_id: &str,
+        favorite_color: Option<String>,
+    ) -> Result<Profile, RoomError> {
+        let room = self.room(room_id).await?;
+        let user_id = {
+            let player_id = player_id.to_string();
+            room.call(move |room| {
+                let player = room.find_player(&player_id).ok_or(RoomError::PlayerNotFound)?;
+                player.user_id.clone().ok_or(RoomError::NotAuthenticated)
+            })
+            .await??
+        };
 
-        match tile_type {
-            Some(crate::game::state::TileType::House) => {
-                if action_id == "skip" {
-                    PlayerAction::SkipAction
-                } else {
-                    PlayerAction::BuyHouse {
-                        house_id: action_id.to_string(),
-                    }
+        let profile = self
+            .profiles
+            .set_favorite_color(&user_id, favorite_color)
+            .await
+            .ok_or(RoomError::ProfileNotFound)?;
+
+        let player_id = player_id.to_string();
+        let cached = profile.clone();
+        let _ = room
+            .call(move |room| {
+                if let Some(player) = room.players.iter_mut().find(|p| p.id == player_id) {
+                    player.profile = Some(cached);
                 }
-            }
-            Some(crate::game::state::TileType::Insurance) => match action_id {
-                "life" => PlayerAction::BuyInsurance {
-                    insurance_type: crate::game::state::InsuranceType::Life,
-                },
-                "auto" => PlayerAction::BuyInsurance {
-                    insurance_type: crate::game::state::InsuranceType::Auto,
-                },
-                _ => PlayerAction::SkipAction,
-            },
-            Some(crate::game::state::TileType::Lawsuit) => PlayerAction::SelectLawsuitTarget {
-                target_id: action_id.to_string(),
-            },
-            _ => PlayerAction::SkipAction,
-        }
+            })
+            .await;
+        Ok(profile)
     }
 
     /// ターン進行 + ゲーム終了チェック
-    fn advance_turn(&self, room: &mut Room, msgs: &mut Vec<ServerMessage>) {
+    fn advance_turn(
+        webhooks: &WebhookNotifier,
+        profiles: &ProfileStore,
+        room: &mut Room,
+        msgs: &mut Vec<ServerMessage>,
+    ) {
         let engine = room.engine.as_ref().unwrap();
         let state = room.game_state.as_ref().unwrap();
 
         if engine.is_finished(state) {
-            let rankings = engine.rankings(state);
-            room.status = RoomStatus::Finished;
+            let rankings = Self::build_rankings(room);
+            let achievements = room.achievements.awards(state);
+            let recaps = room.recap.recaps(state);
+            webhooks.notify(WebhookEvent::GameEnded {
+                room_id: room.id.clone(),
+                winner_id: rankings.first().map(|r| r.player_id.clone()),
+                rankings: rankings.clone(),
+            });
+            Self::record_results(profiles, room, &rankings, &achievements);
+            let series_msg = room.series.as_mut().map(|series| {
+                series.record(&rankings);
+                ServerMessage::SeriesStandings {
+                    games_played: series.games_played,
+                    total_games: series.total_games,
+                    standings: series.standings(),
+                    finished: series.is_complete(),
+                }
+            });
             msgs.push(ServerMessage::GameEnded {
-                rankings: rankings
-                    .iter()
-                    .map(|r| crate::protocol::RankingEntry {
-                        player_id: r.player_id.clone(),
-                        player_name: r.player_name.clone(),
-                        total_assets: r.total_assets,
-                        rank: r.rank,
-                    })
-                    .collect(),
+                rankings,
+                achievements,
+                recaps,
+                duration_ms: room.game_elapsed_ms(),
+                arrivals: state.arrivals.clone(),
             });
+            msgs.extend(series_msg);
+            room.finish_game();
             return;
         }
 
-        let new_state = engine.end_turn(state);
+        let (new_state, events) = engine.scheduled_events(&engine.end_turn(state));
         let next_player_id = new_state.players[new_state.current_turn].id.clone();
         let current_turn = new_state.current_turn;
-        room.game_state = Some(new_state);
+        room.apply_state(new_state, &events);
 
         msgs.push(ServerMessage::TurnChanged {
             current_turn,
             player_id: next_player_id,
         });
+        for event in &events {
+            if let GameEvent::GlobalEvent { kind } = event {
+                msgs.push(ServerMessage::GlobalEvent {
+                    kind: *kind,
+                    label: kind.fallback_text().to_string(),
+                });
+            }
+        }
+        Self::push_system_messages(msgs, room, &events);
+        msgs.push(ServerMessage::Standings {
+            rankings: Self::build_rankings(room),
+        });
     }
 
-    /// GameSync メッセージを構築
-    fn build_game_sync(&self, room: &Room) -> ServerMessage {
-        let state = room.game_state.as_ref().unwrap();
-        ServerMessage::GameSync {
-            players: state.players.clone(),
+    /// 現在の資産による順位（ゲーム開始前なら空）
+    fn build_rankings(room: &Room) -> Vec<crate::protocol::RankingEntry> {
+        let (Some(engine), Some(state)) = (&room.engine, &room.game_state) else {
+            return Vec::new();
+        };
+        engine
+            .rankings(state)
+            .into_iter()
+            .map(|r| crate::protocol::RankingEntry {
+                player_id: r.player_id,
+                player_name: r.player_name,
+                total_assets: r.total_assets,
+                breakdown: r.breakdown,
+                rank: r.rank,
+                arrival_order: r.arrival_order,
+            })
+            .collect()
+    }
+
+    /// 途中経過の順位（RequestStandings 向け）
+    pub async fn standings(&self, room_id: &str) -> Result<Vec<crate::protocol::RankingEntry>, RoomError> {
+        self.room(room_id)
+            .await?
+            .call(|room| {
+                if room.game_state.is_none() {
+                    return Err(RoomError::GameNotStarted);
+                }
+                Ok(Self::build_rankings(room))
+            })
+            .await?
+    }
+
+    /// 進行中のゲームの全体状態（再参加・再同期したプレイヤー向け）
+    /// 選択待ちなら最後に ChoiceRequired を付ける
+    pub async fn game_snapshot(&self, room_id: &str) -> Option<Vec<ServerMessage>> {
+        let room = self.room(room_id).await.ok()?;
+        room.call(|room| {
+            let mut msgs = Self::build_game_snapshot(room)?;
+            if let Some(pending) = room.game_state.as_ref().and_then(|gs| gs.pending_choice.as_ref()) {
+                msgs.push(Self::choice_message(&pending.choices));
+            }
+            Some(msgs)
+        })
+        .await
+        .ok()?
+    }
+
+    /// GameStarted と GameSync（ゲーム中でなければ None）
+    fn build_game_snapshot(room: &Room) -> Option<Vec<ServerMessage>> {
+        let state = room.game_state.as_ref()?;
+        Some(vec![
+            ServerMessage::GameStarted {
+                turn_order: state.players.iter().map(|p| p.id.clone()).collect(),
+                board: state.catalog.board.clone(),
+                players: state.players.clone(),
+                careers: state.catalog.careers.clone(),
+                houses: state.catalog.houses_for_sale.clone(),
+                seed: state.seed,
+            },
+            Self::build_game_sync(room),
+        ])
+    }
+
+    /// 観戦者として参加する。ゲーム中なら盤面と状態を遅延キュー経由で送る
+    pub async fn join_spectator(
+        &self,
+        room_id: &str,
+        name: String,
+        token: Option<&str>,
+        transport: Arc<dyn Transport>,
+    ) -> Result<PlayerId, RoomError> {
+        let token = token.map(str::to_string);
+        self.room_or_adopt(room_id)
+            .await?
+            .call(move |room| {
+                if !room.is_token_valid(token.as_deref()) {
+                    return Err(RoomError::InvalidJoinToken);
+                }
+                if room.spectators.len() >= MAX_SPECTATORS {
+                    return Err(RoomError::RoomFull);
+                }
+                let id = uuid::Uuid::new_v4().to_string();
+                room.add_spectator(Spectator {
+                    id: id.clone(),
+                    name,
+                    transport: transport.clone(),
+                });
+                if let (Some(feed), Some(msgs)) = (&room.spectator_feed, Self::build_game_snapshot(room)) {
+                    for msg in msgs.iter().filter_map(|m| EncodedMessage::encode(m).ok()) {
+                        feed.push(msg, vec![transport.clone()]);
+                    }
+                }
+                Ok(id)
+            })
+            .await?
+    }
+
+    pub async fn leave_spectator(&self, room_id: &str, spectator_id: &str) {
+        let Ok(room) = self.room(room_id).await else {
+            return;
+        };
+        let spectator_id = spectator_id.to_string();
+        let _ = room.call(move |room| room.spectators.retain(|s| s.id != spectator_id)).await;
+    }
+
+    /// 管理APIの観察者として登録する。遅延なしで、現在の部屋の状態（ゲーム中なら盤面と状態も）を最初に送る
+    pub async fn join_observer(&self, room_id: &str, transport: Arc<dyn Transport>) -> Result<String, RoomError> {
+        let observer = transport.clone();
+        let (id, msgs) = self
+            .room(room_id)
+            .await?
+            .call(move |room| {
+                let id = uuid::Uuid::new_v4().to_string();
+                let info = Self::build_room_info(room);
+                let mut msgs = vec![ServerMessage::RoomState {
+                    room_id: room.id.clone(),
+                    player_id: id.clone(),
+                    players: info.players,
+                    status: info.status,
+                }];
+                msgs.extend(Self::build_game_snapshot(room).unwrap_or_default());
+                room.observers.push(Spectator {
+                    id: id.clone(),
+                    name: "admin".to_string(),
+                    transport: observer,
+                });
+                (id, msgs)
+            })
+            .await?;
+        for msg in msgs {
+            let _ = transport.send(msg).await;
+        }
+        Ok(id)
+    }
+
+    pub async fn leave_observer(&self, room_id: &str, observer_id: &str) {
+        let Ok(room) = self.room(room_id).await else {
+            return;
+        };
+        let observer_id = observer_id.to_string();
+        let _ = room.call(move |room| room.observers.retain(|o| o.id != observer_id)).await;
+    }
+
+    /// 観戦者（と管理APIの観察者）だけに即座に送る（観戦者チャット）
+    pub async fn broadcast_spectators(&self, room_id: &str, msg: &ServerMessage) {
+        let Ok(msg) = EncodedMessage::encode(msg) else {
+            return;
+        };
+        let Ok(room) = self.room(room_id).await else {
+            return;
+        };
+        let transports = room
+            .call(|room| {
+                room.spectators
+                    .iter()
+                    .chain(&room.observers)
+                    .map(|s| s.transport.clone())
+                    .collect::<Vec<_>>()
+            })
+            .await
+            .unwrap_or_default();
+        for transport in transports {
+            let _ = transport.send_raw(msg.clone()).await;
+        }
+    }
+
+    /// GameSync メッセージを構築
+    fn build_game_sync(room: &Room) -> ServerMessage {
+        let state = room.game_state.as_ref().unwrap();
+        ServerMessage::GameSync {
+            players: state.players.clone(),
             current_turn: state.current_turn,
             phase: state.phase,
+            turn_number: state.turn_number,
+            max_turns: state.max_turns,
+            pending_choice: state.pending_choice.clone(),
+            paused: room.paused,
+            game_started_at: state.game_started_at,
+            stock_price: state.stock_price,
+            arrivals: state.arrivals.clone(),
+            tax_cut: state.tax_cut,
         }
     }
 
     /// 部屋情報取得（API用の安全なコピー）
     pub async fn get_room_info(&self, room_id: &str) -> Option<RoomInfo> {
-        let rooms = self.rooms.read().await;
-        rooms.get(room_id).map(|room| RoomInfo {
+        let room = self.room(room_id).await.ok()?;
+        room.call(|room| Self::build_room_info(room)).await.ok()
+    }
+
+    fn build_room_info(room: &Room) -> RoomInfo {
+        RoomInfo {
             id: room.id.clone(),
             players: room
                 .players
@@ -414,22 +1319,746 @@ impl RoomManager {
                 .map(|p| crate::protocol::PlayerInfo {
                     id: p.id.clone(),
                     name: p.name.clone(),
+                    profile: p.profile.clone(),
                 })
                 .collect(),
             status: room.status.to_string(),
             map_id: room.map_id.clone(),
             player_count: room.players.len(),
             max_players: room.max_players,
+            private: room.private,
+            rules_variant: room.rules_variant.clone(),
+            title: room.listing.title.clone(),
+            tags: room.listing.tags.clone(),
+            language: room.listing.language.clone(),
+            created_at: room.created_at,
+            game_started_at: room.game_started_at,
+            finished_at: room.finished_at,
+        }
+    }
+
+    /// 公開API用の部屋情報取得
+    /// 非公開部屋はトークンが一致しない限り存在と人数のみ返す
+    pub async fn get_public_room_info(
+        &self,
+        room_id: &str,
+        token: Option<&str>,
+    ) -> Option<PublicRoomInfo> {
+        let room = self.room(room_id).await.ok()?;
+        let token = token.map(str::to_string);
+        room.call(move |room| {
+            if room.is_token_valid(token.as_deref()) {
+                PublicRoomInfo::Full(Box::new(Self::build_room_info(room)))
+            } else {
+                PublicRoomInfo::Summary(RoomSummary {
+                    id: room.id.clone(),
+                    private: true,
+                    player_count: room.players.len(),
+                    max_players: room.max_players,
+                })
+            }
         })
+        .await
+        .ok()
     }
 
-    /// 部屋内の全プレイヤーにメッセージをブロードキャスト
-    pub async fn broadcast(&self, room_id: &str, msg: &ServerMessage) {
-        let rooms = self.rooms.read().await;
-        if let Some(room) = rooms.get(room_id) {
-            for player in &room.players {
-                let _ = player.transport.send(msg.clone()).await;
+    /// 全部屋の一覧（管理API用）
+    pub async fn list_rooms(&self) -> Vec<AdminRoomInfo> {
+        let handles: Vec<RoomHandle> = self.rooms.read().await.values().cloned().collect();
+        let mut list: Vec<AdminRoomInfo> = futures_util::future::join_all(handles.iter().map(|room| {
+            room.call(|room| AdminRoomInfo {
+                age_secs: now_ms().saturating_sub(room.created_at) / 1000,
+                room: Self::build_room_info(room),
+            })
+        }))
+        .await
+        .into_iter()
+        .flatten()
+        .collect();
+        list.sort_by_key(|r| std::cmp::Reverse(r.age_secs));
+        list
+    }
+
+    /// 公開ロビーの部屋一覧（非公開部屋を除き、新しい順に最大 limit 件）
+    pub async fn list_public_rooms(&self, filter: RoomFilter, limit: usize) -> Vec<RoomInfo> {
+        let handles: Vec<RoomHandle> = self.rooms.read().await.values().cloned().collect();
+        let filter = Arc::new(filter);
+        let mut list: Vec<RoomInfo> = futures_util::future::join_all(handles.iter().map(|room| {
+            let filter = filter.clone();
+            room.call(move |room| {
+                let listed = !room.private && filter.matches(&room.status, &room.listing);
+                listed.then(|| Self::build_room_info(room))
+            })
+        }))
+        .await
+        .into_iter()
+        .flatten()
+        .flatten()
+        .collect();
+        list.sort_by_key(|r| std::cmp::Reverse(r.created_at));
+        list.truncate(limit);
+        list
+    }
+
+    /// 部屋のゲーム状態のコピーを取得（管理API用）
+    pub async fn get_game_state(&self, room_id: &str) -> Result<GameState, RoomError> {
+        self.room(room_id)
+            .await?
+            .call(|room| room.game_state.clone().ok_or(RoomError::GameNotStarted))
+            .await?
+    }
+
+    /// チャットを履歴に残す（ミュート中のプレイヤーなら Muted）
+    pub async fn record_chat(&self, room_id: &str, line: ChatLine) -> Result<(), RoomError> {
+        self.room(room_id)
+            .await?
+            .call(move |room| {
+                if room.muted.contains(&line.player_id) {
+                    return Err(RoomError::Muted);
+                }
+                room.push_chat(line);
+                Ok(())
+            })
+            .await?
+    }
+
+    /// スタンプ・入力中通知を送れるか（ミュート中なら Muted、target が部屋にいなければ PlayerNotFound）
+    pub async fn check_reaction(
+        &self,
+        room_id: &str,
+        player_id: &str,
+        target: Option<&str>,
+    ) -> Result<(), RoomError> {
+        let player_id = player_id.to_string();
+        let target = target.map(str::to_string);
+        self.room(room_id)
+            .await?
+            .call(move |room| {
+                if room.muted.contains(&player_id) {
+                    return Err(RoomError::Muted);
+                }
+                if target.is_some_and(|t| room.find_player(&t).is_none()) {
+                    return Err(RoomError::PlayerNotFound);
+                }
+                Ok(())
+            })
+            .await?
+    }
+
+    /// ホストが終了したゲームからロビーに戻す
+    /// 各プレイヤーに自分の player_id を入れた RoomState を送る
+    pub async fn return_to_lobby(&self, room_id: &str, host_id: &str) -> Result<(), RoomError> {
+        let host_id = host_id.to_string();
+        let recipients = self
+            .room(room_id)
+            .await?
+            .call(move |room| {
+                if room.host != host_id {
+                    return Err(RoomError::NotHost);
+                }
+                if room.status != RoomStatus::Finished {
+                    return Err(RoomError::GameNotFinished);
+                }
+                room.return_to_lobby();
+                let info = Self::build_room_info(room);
+                Ok(room
+                    .players
+                    .iter()
+                    .map(|p| {
+                        let msg = ServerMessage::RoomState {
+                            room_id: info.id.clone(),
+                            player_id: p.id.clone(),
+                            players: info.players.clone(),
+                            status: info.status.clone(),
+                        };
+                        (p.transport.clone(), msg)
+                    })
+                    .collect::<Vec<_>>())
+            })
+            .await??;
+        for (transport, msg) in recipients {
+            let _ = transport.send(msg).await;
+        }
+        Ok(())
+    }
+
+    /// 退出投票を始める。締め切りの判定に使う投票番号と、全員に送るメッセージを返す
+    /// 投票できるのが開始した本人だけなら即座に可決する
+    pub async fn start_kick_vote(
+        &self,
+        room_id: &str,
+        player_id: &str,
+        target: &str,
+    ) -> Result<(u64, Vec<ServerMessage>), RoomError> {
+        let player_id = player_id.to_string();
+        let target = target.to_string();
+        let webhooks = self.webhooks.clone();
+        let profiles = self.profiles.clone();
+        let (vote_id, msgs, kicked) = self
+            .room(room_id)
+            .await?
+            .call(move |room| {
+                if room.status != RoomStatus::Playing {
+                    return Err(RoomError::GameNotStarted);
+                }
+                if player_id == target {
+                    return Err(RoomError::CannotTargetSelf);
+                }
+                if !room.find_player(&player_id).is_some_and(|p| p.connected) {
+                    return Err(RoomError::NotEligibleToVote);
+                }
+                if !room.find_player(&target).is_some_and(|p| p.connected) {
+                    return Err(RoomError::PlayerNotFound);
+                }
+                if room.kick_vote.is_some() {
+                    return Err(RoomError::KickVoteInProgress);
+                }
+
+                let vote_id = room.next_vote_id;
+                room.next_vote_id += 1;
+                let voters = room
+                    .players
+                    .iter()
+                    .filter(|p| p.connected && p.id != target)
+                    .map(|p| p.id.clone());
+                let vote = KickVote::new(vote_id, target.clone(), player_id.clone(), voters);
+                let mut msgs = vec![ServerMessage::KickVoteStarted {
+                    target,
+                    started_by: player_id,
+                    required: vote.required(),
+                    expires_in_ms: KICK_VOTE_TIMEOUT.as_millis() as u64,
+                }];
+                room.kick_vote = Some(vote);
+                let kicked = Self::tally_kick_vote(&webhooks, &profiles, room, &mut msgs, false);
+                Ok((vote_id, msgs, kicked))
+            })
+            .await??;
+        Self::notify_vote_kicked(kicked).await;
+        Ok((vote_id, msgs))
+    }
+
+    /// 進行中の退出投票に賛成・反対する。全員に送るメッセージを返す
+    pub async fn cast_kick_vote(
+        &self,
+        room_id: &str,
+        player_id: &str,
+        approve: bool,
+    ) -> Result<Vec<ServerMessage>, RoomError> {
+        let player_id = player_id.to_string();
+        let webhooks = self.webhooks.clone();
+        let profiles = self.profiles.clone();
+        let (msgs, kicked) = self
+            .room(room_id)
+            .await?
+            .call(move |room| {
+                let vote = room.kick_vote.as_mut().ok_or(RoomError::NoKickVote)?;
+                vote.cast(&player_id, approve)?;
+                let mut msgs = Vec::new();
+                let kicked = Self::tally_kick_vote(&webhooks, &profiles, room, &mut msgs, true);
+                Ok((msgs, kicked))
+            })
+            .await??;
+        Self::notify_vote_kicked(kicked).await;
+        Ok(msgs)
+    }
+
+    /// 締め切りを過ぎた退出投票を否決する（既に決着していれば何もしない）
+    pub async fn expire_kick_vote(&self, room_id: &str, vote_id: u64) -> Vec<ServerMessage> {
+        let Ok(room) = self.room(room_id).await else {
+            return Vec::new();
+        };
+        room.call(move |room| {
+            if room.kick_vote.as_ref().is_none_or(|v| v.id != vote_id) {
+                return Vec::new();
             }
+            let vote = room.kick_vote.take().unwrap();
+            vec![ServerMessage::KickVoteEnded {
+                target: vote.target,
+                passed: false,
+            }]
+        })
+        .await
+        .unwrap_or_default()
+    }
+
+    /// 退出投票を集計し、決着したら終了する。可決なら対象を切断中にして、その transport を返す
+    fn tally_kick_vote(
+        webhooks: &WebhookNotifier,
+        profiles: &ProfileStore,
+        room: &mut Room,
+        msgs: &mut Vec<ServerMessage>,
+        report_progress: bool,
+    ) -> Option<Arc<dyn Transport>> {
+        let vote = room.kick_vote.as_ref()?;
+        let status = vote.status();
+        if status == VoteStatus::Pending {
+            if report_progress {
+                msgs.push(ServerMessage::KickVoteProgress {
+                    approvals: vote.approvals(),
+                    rejections: vote.rejections(),
+                    required: vote.required(),
+                });
+            }
+            return None;
+        }
+
+        let vote = room.kick_vote.take()?;
+        let passed = status == VoteStatus::Passed;
+        msgs.push(ServerMessage::KickVoteEnded {
+            target: vote.target.clone(),
+            passed,
+        });
+        if !passed {
+            return None;
+        }
+        let transport = room.disconnect_player(&vote.target);
+        msgs.push(ServerMessage::PlayerDisconnected { player_id: vote.target });
+        Self::skip_disconnected_turn(webhooks, profiles, room, msgs);
+        transport
+    }
+
+    /// 投票で退出させたプレイヤーに理由を通知して切断する
+    async fn notify_vote_kicked(transport: Option<Arc<dyn Transport>>) {
+        if let Some(transport) = transport {
+            let reason = "投票により退出させられました".to_string();
+            let _ = transport.send(ServerMessage::Kicked { reason }).await;
+            let _ = transport.close().await;
+        }
+    }
+
+    /// ホストがゲームを一時停止・再開する
+    /// 状態が変わった場合は GamePaused / GameResumed に続けて全員に送るメッセージ（再開時に切断中の手番を飛ばした結果）を返す
+    pub async fn set_paused(
+        &self,
+        room_id: &str,
+        host_id: &str,
+        paused: bool,
+    ) -> Result<Option<Vec<ServerMessage>>, RoomError> {
+        let host_id = host_id.to_string();
+        let webhooks = self.webhooks.clone();
+        let profiles = self.profiles.clone();
+        self.room(room_id)
+            .await?
+            .call(move |room| {
+                if room.host != host_id {
+                    return Err(RoomError::NotHost);
+                }
+                if room.status != RoomStatus::Playing {
+                    return Err(RoomError::GameNotStarted);
+                }
+                if room.paused == paused {
+                    return Ok(None);
+                }
+                room.paused = paused;
+                room.auto_paused = false;
+                let mut msgs = Vec::new();
+                Self::skip_disconnected_turn(&webhooks, &profiles, room, &mut msgs);
+                Ok(Some(msgs))
+            })
+            .await?
+    }
+
+    /// ホストがプレイヤーのチャットをミュート・解除する
+    pub async fn set_muted(
+        &self,
+        room_id: &str,
+        host_id: &str,
+        target_id: &str,
+        muted: bool,
+    ) -> Result<(), RoomError> {
+        let host_id = host_id.to_string();
+        let target_id = target_id.to_string();
+        self.room(room_id)
+            .await?
+            .call(move |room| {
+                Self::check_host_target(room, &host_id, &target_id)?;
+                if muted {
+                    room.muted.insert(target_id);
+                } else {
+                    room.muted.remove(&target_id);
+                }
+                Ok(())
+            })
+            .await?
+    }
+
+    /// ホストがプレイヤーを追放する（切断し、以後の参加を拒否する）
+    /// ゲーム中は席を残して切断中にし、手番なら飛ばす（ゲームが止まらないように）
+    pub async fn ban_player(&self, room_id: &str, host_id: &str, target_id: &str) -> Result<(), RoomError> {
+        let host_id = host_id.to_string();
+        let target = target_id.to_string();
+        let webhooks = self.webhooks.clone();
+        let profiles = self.profiles.clone();
+        let (transport, msgs) = self
+            .room(room_id)
+            .await?
+            .call(move |room| {
+                Self::check_host_target(room, &host_id, &target)?;
+                room.banned.insert(target.clone());
+                Ok(Self::evict_player(&webhooks, &profiles, room, &target))
+            })
+            .await??;
+
+        if let Some(transport) = transport {
+            let reason = "ホストにより部屋から追放されました".to_string();
+            let _ = transport.send(ServerMessage::Kicked { reason }).await;
+            let _ = transport.close().await;
+        }
+        for msg in &msgs {
+            self.broadcast(room_id, msg).await;
+        }
+        Ok(())
+    }
+
+    /// ホストや管理者がプレイヤーを外す。部屋の全員に送るメッセージと、接続中だった場合はその transport を返す
+    /// ゲームに席があれば退出と同じく切断中にして手番を飛ばし（PlayerDisconnected）、ロビーでは部屋から外す（PlayerLeft）
+    fn evict_player(
+        webhooks: &WebhookNotifier,
+        profiles: &ProfileStore,
+        room: &mut Room,
+        player_id: &str,
+    ) -> (Option<Arc<dyn Transport>>, Vec<ServerMessage>) {
+        let player_id = player_id.to_string();
+        let in_game = room.status == RoomStatus::Playing
+            && room
+                .game_state
+                .as_ref()
+                .is_some_and(|gs| gs.player_by_id(&player_id).is_some());
+        if !in_game {
+            let Some(idx) = room.players.iter().position(|p| p.id == player_id) else {
+                return (None, Vec::new());
+            };
+            let player = room.players.remove(idx);
+            let transport = player.connected.then_some(player.transport);
+            return (transport, vec![ServerMessage::PlayerLeft { player_id }]);
+        }
+
+        let transport = room.disconnect_player(&player_id);
+        let mut msgs = Vec::new();
+        if transport.is_some() {
+            msgs.push(ServerMessage::PlayerDisconnected { player_id });
+        }
+        Self::skip_disconnected_turn(webhooks, profiles, room, &mut msgs);
+        (transport, msgs)
+    }
+
+    /// ホストによる他プレイヤーへの操作の共通チェック
+    fn check_host_target(room: &Room, host_id: &str, target_id: &str) -> Result<(), RoomError> {
+        if room.host != host_id {
+            return Err(RoomError::NotHost);
+        }
+        if host_id == target_id {
+            return Err(RoomError::CannotTargetSelf);
+        }
+        let in_game = room
+            .game_state
+            .as_ref()
+            .is_some_and(|gs| gs.player_by_id(target_id).is_some());
+        if room.find_player(target_id).is_none() && !in_game {
+            return Err(RoomError::PlayerNotFound);
+        }
+        Ok(())
+    }
+
+    /// 直近のゲームの出来事（古い順に最大 count 件、既定 RECENT_EVENTS_PAGE 件）
+    pub async fn recent_events(&self, room_id: &str, count: Option<usize>) -> Result<Vec<TimelineEntry>, RoomError> {
+        let count = count.unwrap_or(RECENT_EVENTS_PAGE).min(RECENT_EVENTS_LEN);
+        self.room(room_id)
+            .await?
+            .call(move |room| {
+                if room.game_state.is_none() {
+                    return Err(RoomError::GameNotStarted);
+                }
+                let skip = room.event_log.len().saturating_sub(count);
+                Ok(room.event_log[skip..].to_vec())
+            })
+            .await?
+    }
+
+    /// 直近のチャット（参加・再参加したプレイヤー向け）
+    pub async fn chat_history(&self, room_id: &str) -> Vec<ChatLine> {
+        let Ok(room) = self.room(room_id).await else {
+            return Vec::new();
+        };
+        room.call(|room| room.chat_history.iter().cloned().collect())
+            .await
+            .unwrap_or_default()
+    }
+
+    /// 公開API用のゲーム状態取得
+    /// 非公開部屋は join_token が必要。観戦の配信遅延がある部屋では返さない
+    pub async fn public_game_state(&self, room_id: &str, token: Option<&str>) -> Result<PublicGameState, RoomError> {
+        let token = token.map(str::to_string);
+        self.room(room_id)
+            .await?
+            .call(move |room| {
+                if !room.is_token_valid(token.as_deref()) {
+                    return Err(RoomError::InvalidJoinToken);
+                }
+                if !room.spectator_delay.is_zero() {
+                    return Err(RoomError::SpectatorDelayed);
+                }
+                let state = room.game_state.as_ref().ok_or(RoomError::GameNotStarted)?;
+                Ok(PublicGameState {
+                    room_id: room.id.clone(),
+                    phase: state.phase,
+                    turn_number: state.turn_number,
+                    current_player_id: state.players.get(state.current_turn).map(|p| p.id.clone()),
+                    paused: room.paused,
+                    players: state
+                        .players
+                        .iter()
+                        .map(|p| PublicPlayerState {
+                            id: p.id.clone(),
+                            name: p.name.clone(),
+                            position: p.position,
+                            money: p.money,
+                            retired: p.retired,
+                            connected: p.connected,
+                        })
+                        .collect(),
+                })
+            })
+            .await?
+    }
+
+    /// 現在のゲームの台帳（offset 件目から最大 limit 件。limit は MAX_LEDGER_PAGE まで）
+    pub async fn ledger_page(
+        &self,
+        room_id: &str,
+        viewer: LedgerViewer<'_>,
+        offset: usize,
+        limit: Option<usize>,
+    ) -> Result<LedgerPage, RoomError> {
+        let (viewer_id, token) = match viewer {
+            LedgerViewer::Player(id) => (Some(id.to_string()), None),
+            LedgerViewer::Token(token) => (None, token.map(str::to_string)),
+        };
+        let limit = limit.unwrap_or(LEDGER_PAGE_SIZE).min(MAX_LEDGER_PAGE);
+        self.room(room_id)
+            .await?
+            .call(move |room| {
+                let allowed = match &viewer_id {
+                    Some(id) => room.find_player(id).is_some(),
+                    None => room.is_token_valid(token.as_deref()),
+                };
+                if !allowed {
+                    return Err(RoomError::InvalidJoinToken);
+                }
+                if room.game_state.is_none() {
+                    return Err(RoomError::GameNotStarted);
+                }
+                Ok(LedgerPage {
+                    offset,
+                    total: room.ledger.len(),
+                    entries: room.ledger.page(offset, limit).to_vec(),
+                })
+            })
+            .await?
+    }
+
+    /// 終了したゲームの記録（出来事の全件と最終順位）
+    /// 非公開部屋は join_token が必要。ロビーに戻るまで取得できる
+    pub async fn game_export(&self, room_id: &str, token: Option<&str>) -> Result<GameExport, RoomError> {
+        let token = token.map(str::to_string);
+        self.room(room_id)
+            .await?
+            .call(move |room| {
+                if !room.is_token_valid(token.as_deref()) {
+                    return Err(RoomError::InvalidJoinToken);
+                }
+                if room.status != RoomStatus::Finished {
+                    return Err(RoomError::GameNotFinished);
+                }
+                Ok(GameExport {
+                    room_id: room.id.clone(),
+                    map_id: room.map_id.clone(),
+                    rules_variant: room.rules_variant.clone(),
+                    started_at: room.game_started_at,
+                    finished_at: room.finished_at,
+                    standings: Self::build_rankings(room),
+                    events: room.event_log.clone(),
+                })
+            })
+            .await?
+    }
+
+    /// 終了したゲームの結果カード（共有画像の描画向け）
+    /// 非公開部屋は join_token が必要。ロビーに戻るまで取得できる。private は非公開部屋か（キャッシュの範囲に使う）
+    pub async fn result_card(&self, room_id: &str, token: Option<&str>) -> Result<(ResultCard, bool), RoomError> {
+        let token = token.map(str::to_string);
+        self.room(room_id)
+            .await?
+            .call(move |room| {
+                if !room.is_token_valid(token.as_deref()) {
+                    return Err(RoomError::InvalidJoinToken);
+                }
+                let (RoomStatus::Finished, Some(state), Some(finished_at)) =
+                    (&room.status, &room.game_state, room.finished_at)
+                else {
+                    return Err(RoomError::GameNotFinished);
+                };
+                let rankings: Vec<ResultCardRanking> = Self::build_rankings(room)
+                    .into_iter()
+                    .map(|r| ResultCardRanking {
+                        rank: r.rank,
+                        player_id: r.player_id,
+                        player_name: r.player_name,
+                        total_assets: r.total_assets,
+                    })
+                    .collect();
+                let card = ResultCard {
+                    room_id: room.id.clone(),
+                    map_id: room.map_id.clone(),
+                    winner: rankings.first().cloned(),
+                    rankings,
+                    achievements: room.achievements.awards(state),
+                    duration_ms: finished_at.saturating_sub(room.game_started_at.unwrap_or(finished_at)),
+                    finished_at,
+                };
+                Ok((card, room.private))
+            })
+            .await?
+    }
+
+    /// 部屋の設定とゲーム状態のスナップショット（管理API用）
+    pub async fn snapshot(&self, room_id: &str) -> Result<RoomSnapshot, RoomError> {
+        self.room(room_id)
+            .await?
+            .call(|room| {
+                let state = room.game_state.clone().ok_or(RoomError::GameNotStarted)?;
+                Ok(RoomSnapshot {
+                    map_id: room.map_id.clone(),
+                    rules_variant: room.rules_variant.clone(),
+                    game_options: room.game_options,
+                    state,
+                })
+            })
+            .await?
+    }
+
+    /// スナップショットから新しい部屋を作る（管理API用）
+    /// 戻り値は部屋IDと join_token。参加者はターン順に元のプレイヤーの席を引き継ぐ
+    pub async fn restore_snapshot(&self, snapshot: RoomSnapshot) -> Result<(RoomId, String), RoomError> {
+        if !self.engines.contains(&snapshot.rules_variant) {
+            return Err(RoomError::UnknownRulesVariant {
+                name: snapshot.rules_variant,
+                available: self.engines.names().join(", "),
+            });
+        }
+        let map = self.load_map(&snapshot.map_id).ok();
+        let room_id = self.claim_room_id().await?;
+        let mut room = Room::restore(room_id.clone(), snapshot, &self.engines, map)?;
+        if let Some(dir) = self.debug_log_dir.clone() {
+            room.enable_debug_log(dir);
+        }
+        let join_token = room.join_token.clone();
+
+        let handle = RoomHandle::spawn(room, self.rooms.clone(), self.abandoned_grace());
+        self.rooms.write().await.insert(room_id.clone(), handle);
+        Ok((room_id, join_token))
+    }
+
+    /// 部屋を強制終了し、全プレイヤーに理由を通知して切断する
+    pub async fn close_room(&self, room_id: &str, reason: String) -> Result<(), RoomError> {
+        let room = self
+            .rooms
+            .write()
+            .await
+            .remove(room_id)
+            .ok_or(RoomError::RoomNotFound)?;
+        // 全員を外すと部屋タスクも終了する
+        let players = room.call(|room| std::mem::take(&mut room.players)).await?;
+
+        let Ok(msg) = EncodedMessage::encode(&ServerMessage::RoomClosed { reason }) else {
+            return Ok(());
+        };
+        for player in &players {
+            let _ = player.transport.send_raw(msg.clone()).await;
+            let _ = player.transport.close().await;
+        }
+        Ok(())
+    }
+
+    /// プレイヤーを部屋から外し、本人に理由を通知して切断する
+    /// ゲーム中は席を残して切断中にし、手番なら飛ばす（追放と同じ）
+    pub async fn kick_player(
+        &self,
+        room_id: &str,
+        player_id: &str,
+        reason: String,
+    ) -> Result<(), RoomError> {
+        let player_id = player_id.to_string();
+        let webhooks = self.webhooks.clone();
+        let profiles = self.profiles.clone();
+        let (transport, msgs) = self
+            .room(room_id)
+            .await?
+            .call(move |room| {
+                if room.find_player(&player_id).is_none() {
+                    return Err(RoomError::PlayerNotFound);
+                }
+                Ok(Self::evict_player(&webhooks, &profiles, room, &player_id))
+            })
+            .await??;
+
+        if let Some(transport) = transport {
+            let _ = transport.send(ServerMessage::Kicked { reason }).await;
+            let _ = transport.close().await;
+        }
+        for msg in &msgs {
+            self.broadcast(room_id, msg).await;
+        }
+        Ok(())
+    }
+
+    /// 部屋内のプレイヤーの送信先（except_id を除く）
+    async fn transports(&self, room_id: &str, except_id: Option<&str>) -> Vec<Arc<dyn Transport>> {
+        let Ok(room) = self.room(room_id).await else {
+            return Vec::new();
+        };
+        let except_id = except_id.map(str::to_string);
+        room.call(move |room| {
+            room.players
+                .iter()
+                .filter(|p| p.connected && except_id.as_deref() != Some(p.id.as_str()))
+                .map(|p| p.transport.clone())
+                .collect()
+        })
+        .await
+        .unwrap_or_default()
+    }
+
+    /// 部屋内の全プレイヤーにメッセージをブロードキャスト（エンコードは1回だけ）
+    /// 観戦者には遅延キュー経由で送る。Broadcaster を設定していれば他のノードにも流す
+    pub async fn broadcast(&self, room_id: &str, msg: &ServerMessage) {
+        let Ok(msg) = EncodedMessage::encode(msg) else {
+            return;
+        };
+        self.broadcaster.publish(room_id, &msg);
+        self.deliver(room_id, msg).await;
+    }
+
+    /// このノードにいる部屋のプレイヤー・観戦者に配る（他のノードから届いたメッセージもここを通る）
+    pub async fn deliver(&self, room_id: &str, msg: EncodedMessage) {
+        let Ok(room) = self.room(room_id).await else {
+            return;
+        };
+        let spectator_msg = msg.clone();
+        let transports = room
+            .call(move |room| {
+                room.feed_spectators(spectator_msg);
+                room.players
+                    .iter()
+                    .filter(|p| p.connected)
+                    .map(|p| p.transport.clone())
+                    .chain(room.observers.iter().map(|o| o.transport.clone()))
+                    .collect::<Vec<_>>()
+            })
+            .await
+            .unwrap_or_default();
+        for transport in transports {
+            let _ = transport.send_raw(msg.clone()).await;
         }
     }
 
@@ -440,13 +2069,11 @@ impl RoomManager {
         except_id: &str,
         msg: &ServerMessage,
     ) {
-        let rooms = self.rooms.read().await;
-        if let Some(room) = rooms.get(room_id) {
-            for player in &room.players {
-                if player.id != except_id {
-                    let _ = player.transport.send(msg.clone()).await;
-                }
-            }
+        let Ok(msg) = EncodedMessage::encode(msg) else {
+            return;
+        };
+        for transport in self.transports(room_id, Some(except_id)).await {
+            let _ = transport.send_raw(msg.clone()).await;
         }
     }
 }
@@ -460,4 +2087,442 @@ pub struct RoomInfo {
     pub map_id: String,
     pub player_count: usize,
     pub max_players: usize,
+    pub private: bool,
+    pub rules_variant: String,
+    /// 公開ロビーに表示する部屋名・タグ・言語
+    pub title: Option<String>,
+    pub tags: Vec<String>,
+    pub language: Option<String>,
+    /// 部屋の作成時刻（UNIX ミリ秒）
+    pub created_at: u64,
+    /// 直近のゲームの開始・終了時刻（UNIX ミリ秒）
+    pub game_started_at: Option<u64>,
+    pub finished_at: Option<u64>,
+}
+
+/// 管理API用のルーム情報
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct AdminRoomInfo {
+    #[serde(flatten)]
+    pub room: RoomInfo,
+    /// 作成からの経過秒数
+    pub age_secs: u64,
+}
+
+/// 非公開部屋の概要（プレイヤー名などを含まない）
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct RoomSummary {
+    pub id: RoomId,
+    pub private: bool,
+    pub player_count: usize,
+    pub max_players: usize,
+}
+
+/// 手番の操作の結果
+#[derive(Debug)]
+pub enum ActionOutcome {
+    /// 状態を更新した（全員にブロードキャストする）
+    Applied(Vec<ServerMessage>),
+    /// 適用済みの action_token の再送（状態は変えていない。本人にだけ最初の結果を返す）
+    Duplicate(Vec<ServerMessage>),
+}
+
+/// 台帳の閲覧者
+#[derive(Debug, Clone, Copy)]
+pub enum LedgerViewer<'a> {
+    /// 部屋の参加者（WebSocket）
+    Player(&'a str),
+    /// 部屋情報APIと同じく、非公開部屋は join_token が必要（HTTP）
+    Token(Option<&'a str>),
+}
+
+/// 台帳の1ページ
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct LedgerPage {
+    pub offset: usize,
+    /// 台帳の全件数
+    pub total: usize,
+    pub entries: Vec<LedgerEntry>,
+}
+
+/// 終了したゲームの記録（GET /api/room/:id/export）
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct GameExport {
+    pub room_id: RoomId,
+    pub map_id: String,
+    pub rules_variant: String,
+    /// ゲームの開始・終了時刻（UNIX ミリ秒）
+    pub started_at: Option<u64>,
+    pub finished_at: Option<u64>,
+    pub standings: Vec<crate::protocol::RankingEntry>,
+    pub events: Vec<TimelineEntry>,
+}
+
+/// 結果カードAPIのレスポンス（GET /api/room/:id/result-card）
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ResultCard {
+    pub room_id: RoomId,
+    pub map_id: String,
+    /// 1位のプレイヤー（rankings の先頭）
+    pub winner: Option<ResultCardRanking>,
+    pub rankings: Vec<ResultCardRanking>,
+    /// このゲームで各プレイヤーが獲得した実績
+    pub achievements: Vec<AchievementAward>,
+    /// ゲーム開始から終了までの時間（ミリ秒）
+    pub duration_ms: u64,
+    /// 終了時刻（UNIX ミリ秒）
+    pub finished_at: u64,
+}
+
+/// 結果カードの順位1件
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ResultCardRanking {
+    pub rank: u32,
+    pub player_id: PlayerId,
+    pub player_name: String,
+    pub total_assets: i64,
+}
+
+/// ゲーム状態APIのレスポンス（配信オーバーレイ向け。所持品や乱数シードなどは含めない）
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct PublicGameState {
+    pub room_id: RoomId,
+    pub phase: TurnPhase,
+    pub turn_number: u32,
+    /// 手番のプレイヤー（全員が引退していれば None）
+    pub current_player_id: Option<PlayerId>,
+    pub paused: bool,
+    pub players: Vec<PublicPlayerState>,
+}
+
+/// ゲーム状態APIのプレイヤー1人分
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct PublicPlayerState {
+    pub id: PlayerId,
+    pub name: String,
+    pub position: usize,
+    pub money: i64,
+    pub retired: bool,
+    pub connected: bool,
+}
+
+/// 部屋情報APIのレスポンス
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(untagged)]
+pub enum PublicRoomInfo {
+    Full(Box<RoomInfo>),
+    Summary(RoomSummary),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::ClientMessage;
+    use crate::storage::MemoryStorage;
+    use crate::transport::traits::Result as TransportResult;
+    use async_trait::async_trait;
+
+    struct NullTransport;
+
+    #[async_trait]
+    impl Transport for NullTransport {
+        async fn send_raw(&self, _msg: EncodedMessage) -> TransportResult<()> {
+            Ok(())
+        }
+        async fn recv(&mut self) -> TransportResult<ClientMessage> {
+            Err("closed".into())
+        }
+        async fn close(&self) -> TransportResult<()> {
+            Ok(())
+        }
+    }
+
+    fn manager() -> RoomManager {
+        RoomManager::new(
+            6,
+            WebhookNotifier::new(&[]),
+            ProfileStore::new(Arc::new(MemoryStorage::new())),
+            MapCatalog::builtin(),
+            Duration::ZERO,
+        )
+    }
+
+    /// 2人でゲームを始め、ホストでない側の手番にする。（部屋ID, ホスト, もう1人）を返す
+    async fn start_with_guest_turn(manager: &RoomManager) -> (RoomId, PlayerId, PlayerId) {
+        let (room_id, host, _) = manager
+            .create_room(
+                "Alice".to_string(),
+                "classic".to_string(),
+                RoomOptions::default(),
+                None,
+                Arc::new(NullTransport),
+            )
+            .await
+            .unwrap();
+        let (guest, _) = manager
+            .join_room(&room_id, "Bob".to_string(), None, None, None, Arc::new(NullTransport))
+            .await
+            .unwrap();
+        manager.start_game(&room_id, &host).await.unwrap();
+        let turn_of = guest.clone();
+        manager
+            .room(&room_id)
+            .await
+            .unwrap()
+            .call(move |room| {
+                let gs = room.game_state.as_mut().unwrap();
+                gs.current_turn = gs.players.iter().position(|p| p.id == turn_of).unwrap();
+            })
+            .await
+            .unwrap();
+        (room_id, host, guest)
+    }
+
+    async fn current_player(manager: &RoomManager, room_id: &str) -> PlayerId {
+        manager
+            .room(room_id)
+            .await
+            .unwrap()
+            .call(|room| room.game_state.as_ref().unwrap().current_player().id.clone())
+            .await
+            .unwrap()
+    }
+
+    fn identity(user_id: &str) -> AuthIdentity {
+        AuthIdentity {
+            user_id: user_id.to_string(),
+            name: None,
+        }
+    }
+
+    /// 部屋の状態を読む
+    async fn inspect<R, F>(manager: &RoomManager, room_id: &str, f: F) -> R
+    where
+        R: Send + 'static,
+        F: FnOnce(&mut Room) -> R + Send + 'static,
+    {
+        manager.room(room_id).await.unwrap().call(f).await.unwrap()
+    }
+
+    /// 認証済みの Alice（ホスト）と Carol、匿名の Bob と Dave でゲームを始めた部屋をノード a から b に移す
+    /// 移す前に1回ルーレットを回し、Carol を追放し、Bob をミュートしてから切断させる
+    /// （部屋ID, b, Dave, 移す前の台帳・出来事・チャットの件数）を返す
+    async fn hand_off_to_b() -> (RoomId, RoomManager, PlayerId, (usize, usize, usize)) {
+        let storage: Arc<dyn crate::storage::Storage> = Arc::new(MemoryStorage::new());
+        let node = |url: &str| {
+            manager().with_leases(Some(LeaseStore::new(storage.clone(), url.to_string(), Duration::from_secs(30))))
+        };
+        let (a, b) = (node("ws://a/ws"), node("ws://b/ws"));
+        let (room_id, alice, _) = a
+            .create_room(
+                "Alice".to_string(),
+                "classic".to_string(),
+                RoomOptions::default(),
+                Some(&identity("u-alice")),
+                Arc::new(NullTransport),
+            )
+            .await
+            .unwrap();
+        let mut seats = Vec::new();
+        for (name, identity) in [("Bob", None), ("Carol", Some(identity("u-carol"))), ("Dave", None)] {
+            let (player_id, _) = a
+                .join_room(&room_id, name.to_string(), None, None, identity.as_ref(), Arc::new(NullTransport))
+                .await
+                .unwrap();
+            seats.push(player_id);
+        }
+        let [bob, carol, dave] = <[PlayerId; 3]>::try_from(seats).unwrap();
+        a.start_game(&room_id, &alice).await.unwrap();
+        let turn = current_player(&a, &room_id).await;
+        a.spin_roulette(&room_id, &turn, None).await.unwrap();
+
+        a.ban_player(&room_id, &alice, &carol).await.unwrap();
+        a.set_muted(&room_id, &alice, &bob, true).await.unwrap();
+        a.leave_room(&room_id, &bob).await.unwrap();
+        let records = inspect(&a, &room_id, |room| {
+            room.push_chat(ChatLine {
+                player_id: "u-alice".to_string(),
+                player_name: "Alice".to_string(),
+                text: "hi".to_string(),
+            });
+            (room.ledger.len(), room.event_log.len(), room.chat_history.len())
+        })
+        .await;
+        a.hand_off(&room_id, "ws://b/ws").await.unwrap();
+        (room_id, b, dave, records)
+    }
+
+    #[tokio::test]
+    async fn test_ban_during_game_skips_turn() {
+        let manager = manager();
+        let (room_id, host, guest) = start_with_guest_turn(&manager).await;
+
+        manager.ban_player(&room_id, &host, &guest).await.unwrap();
+
+        // 席は切断中として残り、手番はホストに移る
+        assert_eq!(current_player(&manager, &room_id).await, host);
+        let (seated, connected) = manager
+            .room(&room_id)
+            .await
+            .unwrap()
+            .call(move |room| {
+                let seat = room.game_state.as_ref().unwrap().player_by_id(&guest).unwrap();
+                (room.find_player(&guest).is_some(), seat.connected)
+            })
+            .await
+            .unwrap();
+        assert!(seated);
+        assert!(!connected);
+    }
+
+    #[tokio::test]
+    async fn test_handoff_keeps_moderation_and_records() {
+        let (room_id, b, _, records) = hand_off_to_b().await;
+
+        // 追放したプレイヤーは移した先でも戻れない
+        let carol = b
+            .join_room(&room_id, "Carol".to_string(), None, None, Some(&identity("u-carol")), Arc::new(NullTransport))
+            .await;
+        assert!(matches!(carol, Err(RoomError::Banned)));
+
+        let (host, muted, restored, moved) = inspect(&b, &room_id, |room| {
+            (
+                room.host.clone(),
+                room.muted.len(),
+                room.restored,
+                (room.ledger.len(), room.event_log.len(), room.chat_history.len()),
+            )
+        })
+        .await;
+        assert_eq!(host, "u-alice");
+        assert_eq!(muted, 1);
+        assert!(!restored);
+        assert_eq!(moved, records);
+
+        // 認証済みのプレイヤーは自分の席に戻る
+        let alice = b
+            .join_room(&room_id, "Alice".to_string(), None, None, Some(&identity("u-alice")), Arc::new(NullTransport))
+            .await
+            .unwrap();
+        assert_eq!(alice, ("u-alice".to_string(), true));
+    }
+
+    #[tokio::test]
+    async fn test_handoff_seats_need_secret() {
+        let (room_id, b, dave, _) = hand_off_to_b().await;
+
+        // 合言葉の無い参加者は、切断中の席（Bob）や他人の席を引き継げない
+        let stranger = b
+            .join_room(&room_id, "Mallory".to_string(), None, None, None, Arc::new(NullTransport))
+            .await;
+        assert!(matches!(stranger, Err(RoomError::NotInLobby)));
+        let stranger = b
+            .join_room(&room_id, "Eve".to_string(), None, None, Some(&identity("u-eve")), Arc::new(NullTransport))
+            .await;
+        assert!(matches!(stranger, Err(RoomError::NotInLobby)));
+
+        // 移す時に接続していた匿名のプレイヤーは合言葉で元の席に戻る
+        let seat = dave.clone();
+        let secret = inspect(&b, &room_id, move |room| {
+            room.handoff_seats
+                .iter()
+                .find(|(_, id)| **id == seat)
+                .map(|(secret, _)| secret.clone())
+        })
+        .await
+        .unwrap();
+        let rejoined = b
+            .join_room(&room_id, "Dave".to_string(), None, Some(&secret), None, Arc::new(NullTransport))
+            .await
+            .unwrap();
+        assert_eq!(rejoined, (dave, true));
+    }
+
+    #[tokio::test]
+    async fn test_observer_receives_broadcasts() {
+        use crate::transport::sse_channel;
+        use futures_util::StreamExt;
+
+        let manager = manager();
+        let (room_id, _, guest) = start_with_guest_turn(&manager).await;
+        let (sender, stream) = sse_channel();
+        let observer_id = manager.join_observer(&room_id, Arc::new(sender)).await.unwrap();
+        let mut stream = Box::pin(stream);
+
+        // 部屋の状態・盤面・ゲームの状態を最初に受け取る
+        for _ in 0..3 {
+            assert!(stream.next().await.is_some());
+        }
+        manager.leave_room(&room_id, &guest).await.unwrap();
+        manager
+            .broadcast(&room_id, &ServerMessage::PlayerDisconnected { player_id: guest })
+            .await;
+        assert!(stream.next().await.is_some());
+
+        // 観察者はプレイヤーにも観戦者にも数えない
+        let counts = manager
+            .room(&room_id)
+            .await
+            .unwrap()
+            .call(|room| (room.players.len(), room.spectators.len(), room.observers.len()))
+            .await
+            .unwrap();
+        assert_eq!(counts, (2, 0, 1));
+        manager.leave_observer(&room_id, &observer_id).await;
+        // 部屋から外れると送信側が破棄されてストリームが終わる
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_admin_kick_during_game_skips_turn() {
+        let manager = manager();
+        let (room_id, host, guest) = start_with_guest_turn(&manager).await;
+
+        manager.kick_player(&room_id, &guest, "test".to_string()).await.unwrap();
+
+        assert_eq!(current_player(&manager, &room_id).await, host);
+        // 追放と違って戻ってこられる
+        let banned = manager
+            .room(&room_id)
+            .await
+            .unwrap()
+            .call(move |room| room.banned.contains(&guest))
+            .await
+            .unwrap();
+        assert!(!banned);
+    }
+
+    #[tokio::test]
+    async fn test_admin_kick_in_lobby_removes_player() {
+        let manager = manager();
+        let (room_id, _, _) = manager
+            .create_room(
+                "Alice".to_string(),
+                "classic".to_string(),
+                RoomOptions::default(),
+                None,
+                Arc::new(NullTransport),
+            )
+            .await
+            .unwrap();
+        let (guest, _) = manager
+            .join_room(&room_id, "Bob".to_string(), None, None, None, Arc::new(NullTransport))
+            .await
+            .unwrap();
+
+        manager.kick_player(&room_id, &guest, "test".to_string()).await.unwrap();
+
+        let players = manager
+            .room(&room_id)
+            .await
+            .unwrap()
+            .call(|room| room.players.len())
+            .await
+            .unwrap();
+        assert_eq!(players, 1);
+        assert!(matches!(
+            manager.kick_player(&room_id, &guest, "test".to_string()).await,
+            Err(RoomError::PlayerNotFound)
+        ));
+    }
 }
@@ -1,5 +1,12 @@
+pub mod actor;
+pub mod error;
+pub mod lease;
 pub mod manager;
 pub mod models;
+pub mod series;
+pub mod spectator;
+pub mod vote;
 
+pub use error::RoomError;
 pub use manager::RoomManager;
-pub use models::{Room, RoomStatus};
+pub use models::{Room, RoomOptions, RoomSettings, RoomStatus};
@@ -1,10 +1,177 @@
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::PathBuf;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-use crate::game::{ClassicGameEngine, GameEngine, GameState, MapData};
-use crate::protocol::{PlayerId, RoomId};
-use crate::transport::traits::Transport;
+use crate::game::state::{GameOptions, InitMode};
+use crate::game::trace::TracingEngine;
+use crate::game::{
+    AchievementTracker, EngineRegistry, GameEngine, GameEvent, GameState, Ledger, MapData, RecapTracker,
+};
+use crate::metrics::METRICS;
+use crate::profile::Profile;
+use crate::room::error::RoomError;
+use crate::room::series::Series;
+use crate::room::spectator::{Spectator, SpectatorFeed};
+use crate::room::vote::KickVote;
+use crate::protocol::{ChatLine, PlayerId, RoomId, ServerMessage, TimelineEntry};
+use crate::transport::traits::{EncodedMessage, Transport};
+
+/// ゲーム開始に必要な最少人数
+pub const MIN_PLAYERS: usize = 2;
+
+/// シリーズ戦の最大ゲーム数
+pub const MAX_SERIES_GAMES: u32 = 10;
+
+/// ターン数上限ルールで指定できる最大値
+pub const MAX_TURN_COUNT: u32 = 999;
+
+/// 指定できる seed の最大値（JavaScript の数値で正確に表せる範囲）
+pub const MAX_SEED: u64 = (1 << 53) - 1;
+
+/// 部屋作成時にホストが指定するオプション（未検証）
+#[derive(Debug, Clone, Default)]
+pub struct RoomOptions {
+    pub private: bool,
+    /// 定員。未指定ならサーバー上限
+    pub max_players: Option<usize>,
+    /// シリーズ戦のゲーム数。未指定または1以下なら単発ゲーム
+    pub series_games: Option<u32>,
+    /// 短縮ゲーム（途中から職業付き・収入2倍で開始）
+    pub short_game: bool,
+    /// ターン数上限
+    pub max_turn_count: Option<u32>,
+    /// 移動の途中でも分岐で止まる
+    pub pause_at_branches: bool,
+    /// エンジンのルール名（未指定なら classic）
+    pub rules_variant: Option<String>,
+    /// 観戦者への配信遅延（秒）
+    pub spectator_delay_secs: Option<u32>,
+    /// 全員に影響する出来事の間隔（ターン数）
+    pub global_event_interval: Option<u32>,
+    /// 続けて見送れる回数（超えると放置税）
+    pub stall_limit: Option<u32>,
+    /// 乱数の seed。未指定ならゲームごとに選ぶ
+    pub seed: Option<u64>,
+    /// 公開ロビーに表示する部屋名
+    pub title: Option<String>,
+    /// 公開ロビーで絞り込むタグ
+    pub tags: Vec<String>,
+    /// 部屋の言語・地域（"ja" / "ja-JP" など）
+    pub language: Option<String>,
+}
+
+/// 部屋名の最大文字数
+pub const MAX_ROOM_TITLE_LEN: usize = 40;
+
+/// 部屋ごとのタグの最大個数
+pub const MAX_ROOM_TAGS: usize = 5;
+
+/// タグ1つの最大文字数
+pub const MAX_ROOM_TAG_LEN: usize = 20;
+
+/// 言語・地域の最大文字数（"zh-Hant-TW" などが入る長さ）
+const MAX_LANGUAGE_LEN: usize = 16;
+
+/// 公開ロビーに表示する部屋名・タグ・言語（作成時にホストが決める）
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoomListing {
+    pub title: Option<String>,
+    pub tags: Vec<String>,
+    pub language: Option<String>,
+}
+
+impl RoomListing {
+    /// 制御文字を除いて長さを切り詰め、空のものと重複したタグ（大文字小文字を区別しない）を捨てる
+    /// 言語は英数字とハイフンだけのものを受け付け、それ以外は未指定として扱う
+    pub fn new(title: Option<String>, tags: Vec<String>, language: Option<String>) -> Self {
+        let mut kept: Vec<String> = Vec::new();
+        for tag in tags {
+            let tag = clean_text(&tag, MAX_ROOM_TAG_LEN);
+            if !tag.is_empty() && !kept.iter().any(|t| t.to_lowercase() == tag.to_lowercase()) {
+                kept.push(tag);
+            }
+        }
+        kept.truncate(MAX_ROOM_TAGS);
+        Self {
+            title: title
+                .map(|t| clean_text(&t, MAX_ROOM_TITLE_LEN))
+                .filter(|t| !t.is_empty()),
+            tags: kept,
+            language: language.map(|l| l.trim().to_string()).filter(|l| {
+                !l.is_empty()
+                    && l.len() <= MAX_LANGUAGE_LEN
+                    && l.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+            }),
+        }
+    }
+}
+
+/// 制御文字を除いて前後の空白を落とし、max_len 文字までにする
+fn clean_text(text: &str, max_len: usize) -> String {
+    let cleaned: String = text.chars().filter(|c| !c.is_control()).collect();
+    cleaned.trim().chars().take(max_len).collect::<String>().trim_end().to_string()
+}
+
+/// 公開ロビーの絞り込み（GET /api/rooms）。指定の無い条件は全てに一致する
+#[derive(Debug, Clone, Default)]
+pub struct RoomFilter {
+    /// lobby / playing / finished
+    pub status: Option<String>,
+    /// すべてを含む部屋（大文字小文字を区別しない）
+    pub tags: Vec<String>,
+    /// "ja" は "ja" と "ja-JP" のどちらにも一致する（大文字小文字を区別しない）
+    pub language: Option<String>,
+}
+
+impl RoomFilter {
+    pub fn matches(&self, status: &RoomStatus, listing: &RoomListing) -> bool {
+        if self.status.as_ref().is_some_and(|s| !s.eq_ignore_ascii_case(&status.to_string())) {
+            return false;
+        }
+        let has_tag = |tag: &String| listing.tags.iter().any(|t| t.to_lowercase() == tag.to_lowercase());
+        if !self.tags.iter().all(has_tag) {
+            return false;
+        }
+        match (&self.language, &listing.language) {
+            (None, _) => true,
+            (Some(_), None) => false,
+            (Some(want), Some(lang)) => {
+                let lang = lang.to_ascii_lowercase();
+                let want = want.to_ascii_lowercase();
+                lang == want || lang.starts_with(&format!("{}-", want))
+            }
+        }
+    }
+}
+
+/// RoomManager が検証済みの部屋設定
+#[derive(Debug, Clone)]
+pub struct RoomSettings {
+    pub private: bool,
+    /// 定員（サーバー上限でクランプ済み）
+    pub max_players: usize,
+    /// シリーズ戦のゲーム数（2..=MAX_SERIES_GAMES）
+    pub series_games: Option<u32>,
+    pub game_options: GameOptions,
+    /// EngineRegistry に登録済みのルール名
+    pub rules_variant: String,
+    /// 観戦者への配信遅延（MAX_SPECTATOR_DELAY まで）
+    pub spectator_delay: Duration,
+    pub listing: RoomListing,
+}
+
+/// 進行中のゲームを別の部屋（別のサーバー）で再現するためのスナップショット（管理API用）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoomSnapshot {
+    pub map_id: String,
+    pub rules_variant: String,
+    pub game_options: GameOptions,
+    /// game::persist の版付き形式（古い版のスナップショットも読み込み時に移行する）
+    #[serde(with = "crate::game::persist")]
+    pub state: GameState,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum RoomStatus {
@@ -23,11 +190,64 @@ impl std::fmt::Display for RoomStatus {
     }
 }
 
+/// 現在時刻（UNIX ミリ秒）
+pub fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// seed の指定が無いゲームの seed（1〜MAX_SEED）
+fn random_seed() -> u64 {
+    use rand::RngExt;
+    rand::rng().random_range(1..=MAX_SEED)
+}
+
+/// 部屋ごとに残すチャットの件数
+pub const CHAT_HISTORY_LEN: usize = 50;
+
+/// RecentEvents で一度に返すゲームの出来事の最大件数
+pub const RECENT_EVENTS_LEN: usize = 100;
+
+/// プレイヤーごとに覚えておく適用済み action_token の数
+pub const RECENT_ACTIONS_PER_PLAYER: usize = 16;
+
+/// 適用済みの操作（action_token）と、その時に返したメッセージ
+#[derive(Debug, Default)]
+pub struct RecentActions {
+    by_player: HashMap<PlayerId, VecDeque<(String, Vec<ServerMessage>)>>,
+}
+
+impl RecentActions {
+    pub fn get(&self, player_id: &str, token: &str) -> Option<&Vec<ServerMessage>> {
+        self.by_player
+            .get(player_id)?
+            .iter()
+            .find(|(t, _)| t == token)
+            .map(|(_, msgs)| msgs)
+    }
+
+    /// 古いものから捨てる
+    pub fn insert(&mut self, player_id: &str, token: String, msgs: Vec<ServerMessage>) {
+        let recent = self.by_player.entry(player_id.to_string()).or_default();
+        if recent.len() >= RECENT_ACTIONS_PER_PLAYER {
+            recent.pop_front();
+        }
+        recent.push_back((token, msgs));
+    }
+}
+
 /// 接続済みプレイヤー
 pub struct Player {
     pub id: PlayerId,
     pub name: String,
+    /// 認証済みユーザーID（匿名プレイヤーは None）
+    pub user_id: Option<String>,
+    pub profile: Option<Profile>,
     pub transport: Arc<dyn Transport>,
+    /// false ならゲーム中に接続が切れて再接続を待っている（transport は使えない）
+    pub connected: bool,
 }
 
 /// 部屋
@@ -37,71 +257,444 @@ pub struct Room {
     pub players: Vec<Player>,
     pub status: RoomStatus,
     pub map_id: String,
-    pub created_at: Instant,
+    /// 部屋の作成時刻（UNIX ミリ秒）
+    pub created_at: u64,
+    /// 直近のゲームの開始・終了時刻（UNIX ミリ秒）。次のゲームを始めるまで残す
+    pub game_started_at: Option<u64>,
+    pub finished_at: Option<u64>,
     pub max_players: usize,
+    /// 非公開部屋か（部屋情報APIで詳細を隠す）
+    pub private: bool,
+    /// 非公開部屋の詳細閲覧・参加に必要なトークン
+    pub join_token: String,
+    /// 公開ロビーに表示する部屋名・タグ・言語
+    pub listing: RoomListing,
+    /// シリーズ戦の集計（単発ゲームなら None）
+    pub series: Option<Series>,
+    pub game_options: GameOptions,
+    pub rules_variant: String,
     pub game_state: Option<GameState>,
     pub engine: Option<Box<dyn GameEngine>>,
     pub map_data: Option<MapData>,
+    /// 現在のゲームの所持金の台帳
+    pub ledger: Ledger,
+    /// 現在のゲームの実績の集計
+    pub achievements: AchievementTracker,
+    /// 現在のゲームの振り返りの集計
+    pub recap: RecapTracker,
+    pub recent_actions: RecentActions,
+    /// 直近のチャット（CHAT_HISTORY_LEN 件まで。ゲームをまたいで残す）
+    pub chat_history: VecDeque<ChatLine>,
+    /// 現在のゲームの出来事（台帳と同じくゲームの終わりまで全て残す）
+    pub event_log: Vec<TimelineEntry>,
+    /// ホストがミュートしたプレイヤー（チャットを配信しない）
+    pub muted: HashSet<PlayerId>,
+    /// ホストが追放したプレイヤー（再参加できない。認証済みならユーザーIDがプレイヤーIDなので再接続も拒否される）
+    pub banned: HashSet<PlayerId>,
+    /// スナップショットから復元した部屋か（参加者が空いている席を順に引き継ぐ。別のノードから移した部屋は false）
+    pub restored: bool,
+    /// 別のノードから引き継いだ部屋の、引き継ぎ用の合言葉 → 席（使ったら消す）
+    pub handoff_seats: HashMap<String, PlayerId>,
+    /// ホストがゲームを一時停止中か（ゲーム操作を受け付けない）
+    pub paused: bool,
+    /// 全員の接続が切れたために自動で一時停止したか（誰かが戻れば再開する）
+    pub auto_paused: bool,
+    /// 進行中の退出投票
+    pub kick_vote: Option<KickVote>,
+    /// 次に始める退出投票の番号
+    pub next_vote_id: u64,
+    pub spectators: Vec<Spectator>,
+    /// 観戦者への配信遅延
+    pub spectator_delay: Duration,
+    /// 観戦者向けの遅延キュー（最初の観戦者が来たときに作る）
+    pub spectator_feed: Option<SpectatorFeed>,
+    /// 管理APIの観察者（遅延なしでブロードキャストを受け取る。観戦者の上限や一覧には数えない）
+    pub observers: Vec<Spectator>,
+    /// 状態遷移のデバッグログ（<dir>/<部屋ID>.ndjson）を書くディレクトリ
+    pub debug_log_dir: Option<PathBuf>,
 }
 
 impl Room {
-    pub fn new(
-        id: RoomId,
-        host_id: PlayerId,
-        host_name: String,
-        map_id: String,
-        transport: Arc<dyn Transport>,
-        max_players: usize,
-    ) -> Self {
-        let host = Player {
-            id: host_id.clone(),
-            name: host_name,
-            transport,
-        };
+    pub fn new(id: RoomId, host: Player, map_id: String, settings: RoomSettings) -> Self {
         Self {
             id,
-            host: host_id,
+            host: host.id.clone(),
             players: vec![host],
             status: RoomStatus::Lobby,
             map_id,
-            created_at: Instant::now(),
-            max_players,
+            created_at: now_ms(),
+            game_started_at: None,
+            finished_at: None,
+            max_players: settings.max_players,
+            private: settings.private,
+            join_token: uuid::Uuid::new_v4().simple().to_string(),
+            listing: settings.listing,
+            series: settings.series_games.map(Series::new),
+            game_options: settings.game_options,
+            rules_variant: settings.rules_variant,
             game_state: None,
             engine: None,
             map_data: None,
+            ledger: Ledger::new(),
+            achievements: AchievementTracker::new(),
+            recap: RecapTracker::new(),
+            recent_actions: RecentActions::default(),
+            chat_history: VecDeque::new(),
+            event_log: Vec::new(),
+            muted: HashSet::new(),
+            banned: HashSet::new(),
+            restored: false,
+            handoff_seats: HashMap::new(),
+            paused: false,
+            auto_paused: false,
+            kick_vote: None,
+            next_vote_id: 0,
+            spectators: Vec::new(),
+            observers: Vec::new(),
+            spectator_delay: settings.spectator_delay,
+            spectator_feed: None,
+            debug_log_dir: None,
         }
     }
 
+    /// スナップショットからゲーム中の部屋を作る
+    /// 参加者はまだいない。非公開部屋として作り、参加には join_token が必要
+    pub fn restore(
+        id: RoomId,
+        snapshot: RoomSnapshot,
+        engines: &EngineRegistry,
+        map: Option<MapData>,
+    ) -> Result<Self, RoomError> {
+        let state = snapshot.state;
+        if state.players.is_empty() {
+            return Err(RoomError::InvalidSnapshot("no players".to_string()));
+        }
+        if state.current_turn >= state.players.len() {
+            return Err(RoomError::InvalidSnapshot("current_turn out of range".to_string()));
+        }
+        if state.players.iter().any(|p| state.catalog.board.tile(p.position).is_none()) {
+            return Err(RoomError::InvalidSnapshot("player position out of board".to_string()));
+        }
+        let engine = engines
+            .create(&snapshot.rules_variant, snapshot.game_options)
+            .map_err(RoomError::Engine)?;
+
+        Ok(Self {
+            id,
+            host: state.players[0].id.clone(),
+            players: Vec::new(),
+            status: RoomStatus::Playing,
+            map_id: snapshot.map_id,
+            created_at: now_ms(),
+            game_started_at: state.game_started_at,
+            finished_at: None,
+            max_players: state.players.len(),
+            private: true,
+            join_token: uuid::Uuid::new_v4().simple().to_string(),
+            listing: RoomListing::default(),
+            series: None,
+            game_options: snapshot.game_options,
+            rules_variant: snapshot.rules_variant,
+            game_state: Some(state),
+            engine: Some(engine),
+            map_data: map,
+            ledger: Ledger::new(),
+            achievements: AchievementTracker::new(),
+            recap: RecapTracker::new(),
+            recent_actions: RecentActions::default(),
+            chat_history: VecDeque::new(),
+            event_log: Vec::new(),
+            muted: HashSet::new(),
+            banned: HashSet::new(),
+            restored: true,
+            handoff_seats: HashMap::new(),
+            paused: false,
+            auto_paused: false,
+            kick_vote: None,
+            next_vote_id: 0,
+            spectators: Vec::new(),
+            observers: Vec::new(),
+            spectator_delay: Duration::ZERO,
+            spectator_feed: None,
+            debug_log_dir: None,
+        })
+    }
+
+    /// チャットを履歴に追加する（古いものから捨てる）
+    pub fn push_chat(&mut self, line: ChatLine) {
+        if self.chat_history.len() >= CHAT_HISTORY_LEN {
+            self.chat_history.pop_front();
+        }
+        self.chat_history.push_back(line);
+    }
+
+    /// ゲームの出来事を記録する
+    pub fn push_event(&mut self, entry: TimelineEntry) {
+        self.event_log.push(entry);
+    }
+
+    /// エンジンの操作結果を反映し、所持金の増減を台帳に追記する
+    /// 提示した選択肢は選択が済むまで GameState の pending_choice に残す
+    pub fn apply_state(&mut self, mut state: GameState, events: &[GameEvent]) {
+        self.ledger.record(&state, events, now_ms());
+        self.achievements.observe(&state, events);
+        self.recap.observe(&state, events);
+        state.update_pending_choice(events);
+        self.game_state = Some(state);
+    }
+
+    /// 全員の接続が切れた部屋を残すか。誰かが席に戻れるゲーム中なら一時停止して true
+    /// 匿名のプレイヤーは接続ごとに別のIDになり席に戻れないので、認証済みのプレイヤーがいない部屋は残さない
+    /// （スナップショットから復元した部屋は誰でも空いている席を引き継げ、別のノードから移した部屋は合言葉で戻れるので残す）
+    pub fn pause_abandoned(&mut self) -> bool {
+        if self.status != RoomStatus::Playing {
+            return false;
+        }
+        let rejoinable = self.restored
+            || !self.handoff_seats.is_empty()
+            || self.players.iter().any(|p| p.user_id.is_some());
+        if !rejoinable {
+            return false;
+        }
+        if !self.paused {
+            self.paused = true;
+            self.auto_paused = true;
+        }
+        true
+    }
+
+    /// 復元した部屋で、まだ誰も引き継いでいない席（ターン順で最初のもの）
+    pub fn unclaimed_seat(&self) -> Option<PlayerId> {
+        let state = self.game_state.as_ref()?;
+        state
+            .players
+            .iter()
+            .find(|p| {
+                self.find_player(&p.id).is_none_or(|pl| !pl.connected)
+                    && !self.banned.contains(&p.id)
+                    && !self.handoff_seats.values().any(|seat| seat == &p.id)
+            })
+            .map(|p| p.id.clone())
+    }
+
+    /// ゲーム中のプレイヤーを切断中にする（席は残す）。接続中だった場合はその transport を返す
+    pub fn disconnect_player(&mut self, player_id: &str) -> Option<Arc<dyn Transport>> {
+        if let Some(p) = self.game_state.as_mut().and_then(|gs| gs.player_by_id_mut(player_id)) {
+            p.connected = false;
+        }
+        let player = self.players.iter_mut().find(|p| p.id == player_id && p.connected)?;
+        player.connected = false;
+        Some(player.transport.clone())
+    }
+
+    /// 観戦者を追加する（遅延キューが無ければ作る）
+    pub fn add_spectator(&mut self, spectator: Spectator) {
+        let delay = self.spectator_delay;
+        self.spectator_feed.get_or_insert_with(|| SpectatorFeed::spawn(delay));
+        self.spectators.push(spectator);
+    }
+
+    /// ブロードキャストを観戦者向けの遅延キューに積む
+    pub fn feed_spectators(&self, msg: EncodedMessage) {
+        if let Some(feed) = &self.spectator_feed {
+            feed.push(msg, self.spectators.iter().map(|s| s.transport.clone()).collect());
+        }
+    }
+
+    /// ゲーム開始からの経過時間（ミリ秒。一時停止中も数える）
+    pub fn game_elapsed_ms(&self) -> u64 {
+        self.game_state
+            .as_ref()
+            .and_then(|s| s.game_started_at)
+            .map(|started| now_ms().saturating_sub(started))
+            .unwrap_or(0)
+    }
+
+    /// 接続中のプレイヤーがいるか
+    pub fn has_connected_players(&self) -> bool {
+        self.players.iter().any(|p| p.connected)
+    }
+
     pub fn is_full(&self) -> bool {
         self.players.len() >= self.max_players
     }
 
+    /// 公開部屋、またはトークンが一致する場合に true
+    pub fn is_token_valid(&self, token: Option<&str>) -> bool {
+        !self.private || token == Some(self.join_token.as_str())
+    }
+
     pub fn find_player(&self, player_id: &str) -> Option<&Player> {
         self.players.iter().find(|p| p.id == player_id)
     }
 
+    /// 終了したゲームを片付けてロビーに戻す（切断中のプレイヤーは外す）
+    /// 全ゲームを終えたシリーズ戦は同じゲーム数で集計し直す
+    pub fn return_to_lobby(&mut self) {
+        self.players.retain(|p| p.connected);
+        self.status = RoomStatus::Lobby;
+        self.game_state = None;
+        self.engine = None;
+        self.map_data = None;
+        self.ledger = Ledger::new();
+        self.achievements = AchievementTracker::new();
+        self.recap = RecapTracker::new();
+        self.event_log.clear();
+        self.recent_actions = RecentActions::default();
+        self.paused = false;
+        self.auto_paused = false;
+        self.kick_vote = None;
+        self.restored = false;
+        if let Some(series) = self.series.as_mut().filter(|s| s.is_complete()) {
+            *series = Series::new(series.total_games);
+        }
+    }
+
+    /// 状態遷移のデバッグログを有効にする（進行中のゲームがあれば、そのエンジンも包み直す）
+    pub fn enable_debug_log(&mut self, dir: PathBuf) {
+        self.debug_log_dir = Some(dir);
+        if let Some(engine) = self.engine.take() {
+            self.engine = Some(self.traced(engine));
+        }
+    }
+
+    /// debug_log_dir があればエンジンを TracingEngine で包む（ログを開けなければそのまま使う）
+    fn traced(&self, engine: Box<dyn GameEngine>) -> Box<dyn GameEngine> {
+        let Some(dir) = &self.debug_log_dir else {
+            return engine;
+        };
+        let path = dir.join(format!("{}.ndjson", self.id));
+        let file = std::fs::create_dir_all(dir).and_then(|_| {
+            std::fs::OpenOptions::new().create(true).append(true).open(&path)
+        });
+        match file {
+            Ok(file) => Box::new(TracingEngine::new(engine, Box::new(file))),
+            Err(e) => {
+                eprintln!("failed to open debug log {}: {}", path.display(), e);
+                engine
+            }
+        }
+    }
+
+    /// ゲームを終了済みにし、終了時刻とゲームの長さを記録する
+    pub fn finish_game(&mut self) {
+        let now = now_ms();
+        self.status = RoomStatus::Finished;
+        self.finished_at = Some(now);
+        if let Some(started) = self.game_started_at {
+            METRICS
+                .game_duration
+                .observe(Duration::from_millis(now.saturating_sub(started)));
+        }
+    }
+
     /// ゲーム開始: エンジン初期化 + ゲーム状態生成
-    pub fn start_game(&mut self, map: MapData) -> Result<&GameState, String> {
-        if self.status != RoomStatus::Lobby {
-            return Err("room is not in lobby state".to_string());
+    /// シリーズ戦の途中なら、終了後の部屋で同じ顔ぶれのまま次のゲームを開始できる
+    pub fn start_game(
+        &mut self,
+        map: MapData,
+        engines: &EngineRegistry,
+    ) -> Result<&GameState, RoomError> {
+        let next_in_series = self.status == RoomStatus::Finished
+            && self.series.as_ref().is_some_and(|s| !s.is_complete());
+        if self.status != RoomStatus::Lobby && !next_in_series {
+            return Err(RoomError::NotInLobby);
         }
-        if self.players.len() < 2 {
-            return Err("need at least 2 players".to_string());
+        // 前のゲームで切断したまま戻らなかったプレイヤーは次のゲームに含めない
+        self.players.retain(|p| p.connected);
+        if self.players.len() < MIN_PLAYERS {
+            return Err(RoomError::NotEnoughPlayers(MIN_PLAYERS));
+        }
+
+        if self.game_options.init_mode == InitMode::Short && map.short_start_tile.is_none() {
+            return Err(RoomError::ShortGameUnsupported);
         }
 
-        let engine = ClassicGameEngine::new();
+        // seed の指定が無ければゲームごとに選ぶ（シリーズ戦の各ゲームが同じ展開にならないように）
+        let options = GameOptions {
+            seed: Some(self.game_options.seed.unwrap_or_else(random_seed)),
+            ..self.game_options
+        };
+        let engine = engines
+            .create(&self.rules_variant, options)
+            .map_err(RoomError::Engine)?;
+        let engine = self.traced(engine);
         let player_info: Vec<(PlayerId, String)> = self
             .players
             .iter()
             .map(|p| (p.id.clone(), p.name.clone()))
             .collect();
 
-        let game_state = engine.init(player_info, &map);
+        let now = now_ms();
+        let lobby_since = self.finished_at.unwrap_or(self.created_at);
+        METRICS
+            .lobby_wait
+            .observe(Duration::from_millis(now.saturating_sub(lobby_since)));
+        let mut game_state = engine.init(player_info, &map);
+        game_state.game_started_at = Some(now);
+        self.game_started_at = Some(now);
+        self.finished_at = None;
         self.game_state = Some(game_state);
-        self.engine = Some(Box::new(engine));
+        self.engine = Some(engine);
+        self.ledger = Ledger::new();
+        self.achievements = AchievementTracker::new();
+        self.recap = RecapTracker::new();
+        self.event_log.clear();
         self.map_data = Some(map);
         self.status = RoomStatus::Playing;
+        self.paused = false;
+        self.auto_paused = false;
+        self.kick_vote = None;
 
         Ok(self.game_state.as_ref().unwrap())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recent_actions_forget_oldest() {
+        let mut recent = RecentActions::default();
+        for i in 0..=RECENT_ACTIONS_PER_PLAYER {
+            recent.insert("p1", format!("a{}", i), vec![ServerMessage::RoomClosed { reason: i.to_string() }]);
+        }
+        assert!(recent.get("p1", "a0").is_none());
+        assert!(recent.get("p1", "a1").is_some());
+        assert!(recent.get("p2", "a1").is_none());
+    }
+
+    #[test]
+    fn test_room_listing_and_filter() {
+        let listing = RoomListing::new(
+            Some("  みんなで\n遊ぼう  ".to_string()),
+            vec![
+                "初心者歓迎".to_string(),
+                "Casual".to_string(),
+                "casual".to_string(),
+                " ".to_string(),
+                "x".repeat(MAX_ROOM_TAG_LEN + 5),
+            ],
+            Some("ja-JP".to_string()),
+        );
+        assert_eq!(listing.title.as_deref(), Some("みんなで遊ぼう"));
+        assert_eq!(listing.tags, vec!["初心者歓迎", "Casual", &"x".repeat(MAX_ROOM_TAG_LEN)]);
+        assert_eq!(listing.language.as_deref(), Some("ja-JP"));
+        assert_eq!(RoomListing::new(Some(" ".to_string()), Vec::new(), Some("ja_JP".to_string())), RoomListing::default());
+
+        let filter = |status: Option<&str>, tags: &[&str], language: Option<&str>| RoomFilter {
+            status: status.map(str::to_string),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            language: language.map(str::to_string),
+        };
+        let lobby = RoomStatus::Lobby;
+        assert!(filter(None, &[], None).matches(&lobby, &listing));
+        assert!(filter(Some("lobby"), &["初心者歓迎", "CASUAL"], Some("ja")).matches(&lobby, &listing));
+        assert!(filter(None, &[], Some("JA-jp")).matches(&lobby, &listing));
+        assert!(!filter(Some("playing"), &[], None).matches(&lobby, &listing));
+        assert!(!filter(None, &["初心者歓迎", "ranked"], None).matches(&lobby, &listing));
+        assert!(!filter(None, &[], Some("j")).matches(&lobby, &listing));
+        assert!(!filter(None, &[], Some("ja")).matches(&lobby, &RoomListing::default()));
+    }
+}
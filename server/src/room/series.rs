@@ -0,0 +1,109 @@
+//! 複数ゲームのシリーズ（トーナメント）戦の集計
+
+use serde::{Deserialize, Serialize};
+
+use crate::protocol::{PlayerId, RankingEntry, SeriesStanding};
+
+/// 1ゲームごとの順位ポイントを合計していくシリーズ
+/// 1位が参加人数分、以降1点ずつ減る（最下位は1点）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Series {
+    /// 予定ゲーム数
+    pub total_games: u32,
+    pub games_played: u32,
+    scores: Vec<SeriesStanding>,
+}
+
+impl Series {
+    pub fn new(total_games: u32) -> Self {
+        Self {
+            total_games,
+            games_played: 0,
+            scores: Vec::new(),
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.games_played >= self.total_games
+    }
+
+    /// 1ゲーム分の順位を加算
+    pub fn record(&mut self, rankings: &[RankingEntry]) {
+        let player_count = rankings.len() as u32;
+        for r in rankings {
+            let points = player_count + 1 - r.rank.min(player_count);
+            let entry = self.entry_mut(&r.player_id, &r.player_name);
+            entry.points += points;
+            if r.rank == 1 {
+                entry.wins += 1;
+            }
+        }
+        self.games_played += 1;
+    }
+
+    /// ポイント順（同点は勝利数、さらに同点ならプレイヤーID順）
+    pub fn standings(&self) -> Vec<SeriesStanding> {
+        let mut standings = self.scores.clone();
+        standings.sort_by(|a, b| {
+            b.points
+                .cmp(&a.points)
+                .then(b.wins.cmp(&a.wins))
+                .then(a.player_id.cmp(&b.player_id))
+        });
+        standings
+    }
+
+    fn entry_mut(&mut self, player_id: &PlayerId, player_name: &str) -> &mut SeriesStanding {
+        let idx = match self.scores.iter().position(|s| &s.player_id == player_id) {
+            Some(idx) => idx,
+            None => {
+                self.scores.push(SeriesStanding {
+                    player_id: player_id.clone(),
+                    player_name: player_name.to_string(),
+                    points: 0,
+                    wins: 0,
+                });
+                self.scores.len() - 1
+            }
+        };
+        &mut self.scores[idx]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ranking(order: &[&str]) -> Vec<RankingEntry> {
+        order
+            .iter()
+            .enumerate()
+            .map(|(i, id)| RankingEntry {
+                player_id: id.to_string(),
+                player_name: id.to_string(),
+                total_assets: 0,
+                breakdown: Default::default(),
+                rank: i as u32 + 1,
+                arrival_order: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_series_accumulates_points() {
+        let mut series = Series::new(2);
+        series.record(&ranking(&["a", "b", "c"]));
+        assert!(!series.is_complete());
+        series.record(&ranking(&["b", "c", "a"]));
+        assert!(series.is_complete());
+
+        let standings = series.standings();
+        // b: 2+3=5, a: 3+1=4, c: 1+2=3
+        let order: Vec<(&str, u32)> = standings
+            .iter()
+            .map(|s| (s.player_id.as_str(), s.points))
+            .collect();
+        assert_eq!(order, vec![("b", 5), ("a", 4), ("c", 3)]);
+        assert_eq!(standings[0].wins, 1);
+    }
+}
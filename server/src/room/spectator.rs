@@ -0,0 +1,59 @@
+//! 観戦者
+//!
+//! 観戦者はゲームに参加せず、部屋へのブロードキャストを受け取るだけ。部屋の occupied 判定にも数えない。
+//! 配信遅延（spectator_delay）のある部屋では、ブロードキャストを遅延キュー（SpectatorFeed）に積み、
+//! 届いた順に遅らせて送る（配信中の画面から手の内を伝えられないようにする）。
+//! 観戦者同士のチャットはプレイヤーには届かず、遅延もしない。
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+
+use crate::protocol::PlayerId;
+use crate::transport::traits::{EncodedMessage, Transport};
+
+/// 1部屋あたりの観戦者の上限
+pub const MAX_SPECTATORS: usize = 50;
+
+/// 部屋作成時に指定できる配信遅延の上限
+pub const MAX_SPECTATOR_DELAY: Duration = Duration::from_secs(300);
+
+pub struct Spectator {
+    pub id: PlayerId,
+    pub name: String,
+    pub transport: Arc<dyn Transport>,
+}
+
+type Delivery = (Instant, EncodedMessage, Vec<Arc<dyn Transport>>);
+
+/// 観戦者向けの遅延キュー（部屋ごとに1つ。送信先はブロードキャストした時点の観戦者）
+pub struct SpectatorFeed {
+    delay: Duration,
+    tx: mpsc::UnboundedSender<Delivery>,
+}
+
+impl SpectatorFeed {
+    /// 送信タスクを起動する。キューの送信口（Room）が破棄されると終了する
+    pub fn spawn(delay: Duration) -> Self {
+        let (tx, mut rx) = mpsc::unbounded_channel::<Delivery>();
+        tokio::spawn(async move {
+            while let Some((due, msg, recipients)) = rx.recv().await {
+                tokio::time::sleep_until(due).await;
+                for transport in recipients {
+                    let _ = transport.send_raw(msg.clone()).await;
+                }
+            }
+        });
+        Self { delay, tx }
+    }
+
+    /// delay 後に recipients へ送る
+    pub fn push(&self, msg: EncodedMessage, recipients: Vec<Arc<dyn Transport>>) {
+        if recipients.is_empty() {
+            return;
+        }
+        let _ = self.tx.send((Instant::now() + self.delay, msg, recipients));
+    }
+}
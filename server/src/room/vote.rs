@@ -0,0 +1,115 @@
+//! 反応のないプレイヤーを退出させる投票（ホスト不在のゲーム向け）
+
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use crate::protocol::PlayerId;
+use crate::room::error::RoomError;
+
+/// 投票の締め切り（過ぎたら否決）
+pub const KICK_VOTE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 集計結果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VoteStatus {
+    Pending,
+    Passed,
+    Rejected,
+}
+
+/// 進行中の投票。投票できるのは開始時点で接続中だった対象以外のプレイヤー
+#[derive(Debug, Clone)]
+pub struct KickVote {
+    /// 締め切りのタイマーが別の投票を打ち切らないための通し番号
+    pub id: u64,
+    pub target: PlayerId,
+    pub started_by: PlayerId,
+    voters: HashSet<PlayerId>,
+    ballots: HashMap<PlayerId, bool>,
+}
+
+impl KickVote {
+    /// 開始したプレイヤーは賛成に数える
+    pub fn new(id: u64, target: PlayerId, started_by: PlayerId, voters: impl IntoIterator<Item = PlayerId>) -> Self {
+        let mut ballots = HashMap::new();
+        ballots.insert(started_by.clone(), true);
+        Self {
+            id,
+            target,
+            started_by,
+            voters: voters.into_iter().collect(),
+            ballots,
+        }
+    }
+
+    /// 可決に必要な賛成数（投票できる人数の過半数）
+    pub fn required(&self) -> usize {
+        self.voters.len() / 2 + 1
+    }
+
+    /// 投票する（締め切りまでは何度でも変えられる）
+    pub fn cast(&mut self, voter: &str, approve: bool) -> Result<(), RoomError> {
+        if !self.voters.contains(voter) {
+            return Err(RoomError::NotEligibleToVote);
+        }
+        self.ballots.insert(voter.to_string(), approve);
+        Ok(())
+    }
+
+    pub fn approvals(&self) -> usize {
+        self.ballots.values().filter(|&&a| a).count()
+    }
+
+    pub fn rejections(&self) -> usize {
+        self.ballots.values().filter(|&&a| !a).count()
+    }
+
+    /// 反対が多く、残りが全員賛成しても届かなければ否決
+    pub fn status(&self) -> VoteStatus {
+        if self.approvals() >= self.required() {
+            VoteStatus::Passed
+        } else if self.voters.len() - self.rejections() < self.required() {
+            VoteStatus::Rejected
+        } else {
+            VoteStatus::Pending
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vote(voters: &[&str]) -> KickVote {
+        KickVote::new(
+            1,
+            "target".to_string(),
+            voters[0].to_string(),
+            voters.iter().map(|v| v.to_string()),
+        )
+    }
+
+    #[test]
+    fn test_majority_passes() {
+        let mut v = vote(&["a", "b", "c", "d"]);
+        assert_eq!(v.required(), 3);
+        assert_eq!(v.status(), VoteStatus::Pending);
+        v.cast("b", true).unwrap();
+        assert_eq!(v.status(), VoteStatus::Pending);
+        v.cast("c", true).unwrap();
+        assert_eq!(v.status(), VoteStatus::Passed);
+        assert_eq!(v.cast("target", true), Err(RoomError::NotEligibleToVote));
+    }
+
+    #[test]
+    fn test_rejected_when_majority_unreachable() {
+        let mut v = vote(&["a", "b", "c"]);
+        v.cast("b", false).unwrap();
+        assert_eq!(v.status(), VoteStatus::Pending);
+        v.cast("c", false).unwrap();
+        assert_eq!(v.status(), VoteStatus::Rejected);
+
+        // 投票できるのが開始した本人だけなら即可決
+        assert_eq!(vote(&["a"]).status(), VoteStatus::Passed);
+    }
+}
@@ -0,0 +1,129 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use serde_json::Value;
+use tokio::sync::Mutex;
+
+use crate::storage::traits::{Result, Storage};
+
+/// JSON ファイルによるストレージ
+/// コレクションごとに `<dir>/<collection>.json` を1ファイルとして保持し、
+/// 書き込みのたびに一時ファイル経由で置き換える（小規模運用向け）
+pub struct FileStorage {
+    dir: PathBuf,
+    cache: Mutex<HashMap<String, BTreeMap<String, Value>>>,
+}
+
+impl FileStorage {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn path(&self, collection: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", collection))
+    }
+
+    async fn load(&self, collection: &str) -> Result<BTreeMap<String, Value>> {
+        match tokio::fs::read(self.path(collection)).await {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .map_err(|e| format!("corrupt storage file {}: {}", collection, e)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(BTreeMap::new()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    async fn save(&self, collection: &str, entries: &BTreeMap<String, Value>) -> Result<()> {
+        tokio::fs::create_dir_all(&self.dir)
+            .await
+            .map_err(|e| e.to_string())?;
+        let json = serde_json::to_vec_pretty(entries).map_err(|e| e.to_string())?;
+        let path = self.path(collection);
+        let tmp = path.with_extension("json.tmp");
+        tokio::fs::write(&tmp, json)
+            .await
+            .map_err(|e| e.to_string())?;
+        tokio::fs::rename(&tmp, &path)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// コレクションを（未ロードならファイルから読み込んで）操作する
+    async fn with_collection<T>(
+        &self,
+        collection: &str,
+        f: impl FnOnce(&mut BTreeMap<String, Value>) -> (T, bool),
+    ) -> Result<T> {
+        let mut cache = self.cache.lock().await;
+        if !cache.contains_key(collection) {
+            let loaded = self.load(collection).await?;
+            cache.insert(collection.to_string(), loaded);
+        }
+        let entries = cache.get_mut(collection).expect("collection loaded above");
+        let (result, dirty) = f(entries);
+        if dirty {
+            self.save(collection, entries).await?;
+        }
+        Ok(result)
+    }
+}
+
+#[async_trait]
+impl Storage for FileStorage {
+    async fn get(&self, collection: &str, key: &str) -> Result<Option<Value>> {
+        self.with_collection(collection, |c| (c.get(key).cloned(), false))
+            .await
+    }
+
+    async fn put(&self, collection: &str, key: &str, value: Value) -> Result<()> {
+        self.with_collection(collection, |c| {
+            c.insert(key.to_string(), value);
+            ((), true)
+        })
+        .await
+    }
+
+    async fn delete(&self, collection: &str, key: &str) -> Result<()> {
+        self.with_collection(collection, |c| {
+            let removed = c.remove(key).is_some();
+            ((), removed)
+        })
+        .await
+    }
+
+    async fn list(&self, collection: &str) -> Result<Vec<(String, Value)>> {
+        self.with_collection(collection, |c| {
+            (c.iter().map(|(k, v)| (k.clone(), v.clone())).collect(), false)
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_persists_across_instances() {
+        let dir = std::env::temp_dir().join(format!("nine-life-storage-{}", uuid::Uuid::new_v4()));
+
+        let storage = FileStorage::new(&dir);
+        storage
+            .put("profiles", "u1", serde_json::json!({ "wins": 3 }))
+            .await
+            .unwrap();
+
+        let reopened = FileStorage::new(&dir);
+        let value = reopened.get("profiles", "u1").await.unwrap();
+        assert_eq!(value, Some(serde_json::json!({ "wins": 3 })));
+        assert_eq!(reopened.list("profiles").await.unwrap().len(), 1);
+
+        reopened.delete("profiles", "u1").await.unwrap();
+        assert!(FileStorage::new(&dir).get("profiles", "u1").await.unwrap().is_none());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,52 @@
+use std::collections::{BTreeMap, HashMap};
+
+use async_trait::async_trait;
+use serde_json::Value;
+use tokio::sync::RwLock;
+
+use crate::storage::traits::{Result, Storage};
+
+/// プロセス内メモリのストレージ（再起動で消える。開発・テスト用）
+#[derive(Default)]
+pub struct MemoryStorage {
+    collections: RwLock<HashMap<String, BTreeMap<String, Value>>>,
+}
+
+impl MemoryStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl Storage for MemoryStorage {
+    async fn get(&self, collection: &str, key: &str) -> Result<Option<Value>> {
+        let collections = self.collections.read().await;
+        Ok(collections.get(collection).and_then(|c| c.get(key)).cloned())
+    }
+
+    async fn put(&self, collection: &str, key: &str, value: Value) -> Result<()> {
+        let mut collections = self.collections.write().await;
+        collections
+            .entry(collection.to_string())
+            .or_default()
+            .insert(key.to_string(), value);
+        Ok(())
+    }
+
+    async fn delete(&self, collection: &str, key: &str) -> Result<()> {
+        let mut collections = self.collections.write().await;
+        if let Some(c) = collections.get_mut(collection) {
+            c.remove(key);
+        }
+        Ok(())
+    }
+
+    async fn list(&self, collection: &str) -> Result<Vec<(String, Value)>> {
+        let collections = self.collections.read().await;
+        Ok(collections
+            .get(collection)
+            .map(|c| c.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+            .unwrap_or_default())
+    }
+}
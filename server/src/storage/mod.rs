@@ -0,0 +1,7 @@
+pub mod file;
+pub mod memory;
+pub mod traits;
+
+pub use file::FileStorage;
+pub use memory::MemoryStorage;
+pub use traits::*;
@@ -0,0 +1,16 @@
+use async_trait::async_trait;
+use serde_json::Value;
+
+pub type StorageError = String;
+pub type Result<T> = std::result::Result<T, StorageError>;
+
+/// 永続化ストレージの抽象化
+/// コレクション（名前空間）ごとにキー → JSON値を保存する
+#[async_trait]
+pub trait Storage: Send + Sync {
+    async fn get(&self, collection: &str, key: &str) -> Result<Option<Value>>;
+    async fn put(&self, collection: &str, key: &str, value: Value) -> Result<()>;
+    async fn delete(&self, collection: &str, key: &str) -> Result<()>;
+    /// コレクション内の全エントリ（キー順）
+    async fn list(&self, collection: &str) -> Result<Vec<(String, Value)>>;
+}
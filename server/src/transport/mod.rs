@@ -1,5 +1,7 @@
+pub mod sse;
 pub mod traits;
 pub mod websocket;
 
 pub use traits::*;
-pub use websocket::split_websocket;
+pub use sse::{sse_channel, SseSender};
+pub use websocket::{split_websocket, BackpressurePolicy};
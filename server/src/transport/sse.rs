@@ -0,0 +1,75 @@
+//! Server-Sent Events による観戦専用のトランスポート
+//!
+//! WebSocket を張れないクライアント向けに、部屋へのブロードキャストを JSON の `data:` としてそのまま流す。
+//! 受信はできないため、観戦者（チャット不可）としてだけ使う。
+
+use std::convert::Infallible;
+
+use async_trait::async_trait;
+use axum::response::sse::Event;
+use futures_util::Stream;
+use tokio::sync::mpsc;
+use tokio::sync::mpsc::error::TrySendError;
+
+use crate::protocol::ClientMessage;
+use crate::transport::traits::{EncodedMessage, Result, Transport};
+
+/// 1接続あたりの送信キューの長さ（溢れた分は捨てる）
+const SSE_QUEUE_CAPACITY: usize = 256;
+
+/// SSE の送信側
+pub struct SseSender {
+    tx: mpsc::Sender<EncodedMessage>,
+}
+
+/// SSE の送信側と、レスポンスに渡すイベントのストリームを作る
+/// ストリームは送信側が全て破棄されると終わる
+pub fn sse_channel() -> (SseSender, impl Stream<Item = std::result::Result<Event, Infallible>>) {
+    let (tx, rx) = mpsc::channel::<EncodedMessage>(SSE_QUEUE_CAPACITY);
+    let stream = futures_util::stream::unfold(rx, |mut rx| async move {
+        let msg = rx.recv().await?;
+        let event = Event::default().data(String::from_utf8_lossy(msg.json()));
+        Some((Ok(event), rx))
+    });
+    (SseSender { tx }, stream)
+}
+
+#[async_trait]
+impl Transport for SseSender {
+    async fn send_raw(&self, msg: EncodedMessage) -> Result<()> {
+        match self.tx.try_send(msg) {
+            Ok(()) => Ok(()),
+            Err(TrySendError::Full(_)) => Err("send queue full".into()),
+            Err(TrySendError::Closed(_)) => Err("connection closed".into()),
+        }
+    }
+
+    async fn recv(&mut self) -> Result<ClientMessage> {
+        Err("SseSender does not support recv".into())
+    }
+
+    async fn close(&self) -> Result<()> {
+        // ストリームは部屋から外れて送信側が破棄された時点で終わる
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::ServerMessage;
+    use futures_util::StreamExt;
+
+    #[tokio::test]
+    async fn test_sse_stream_ends_when_sender_dropped() {
+        let (sender, stream) = sse_channel();
+        let msg = ServerMessage::PlayerLeft {
+            player_id: "p1".to_string(),
+        };
+        sender.send(msg).await.unwrap();
+        drop(sender);
+
+        let events: Vec<_> = stream.collect().await;
+        assert_eq!(events.len(), 1);
+    }
+}
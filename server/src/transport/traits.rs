@@ -1,13 +1,90 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
 use async_trait::async_trait;
+use bytes::Bytes;
 
-use crate::protocol::{ClientMessage, ServerMessage};
+use crate::protocol::{ClientMessage, ServerFrame, ServerMessage};
 
 pub type TransportError = Box<dyn std::error::Error + Send + Sync>;
 pub type Result<T> = std::result::Result<T, TransportError>;
 
+/// シリアライズ済みの ServerMessage（エンコードした時刻を server_ts として添えた ServerFrame）
+/// ブロードキャストでは1回だけエンコードし、全員で同じバッファを共有する
+#[derive(Debug, Clone)]
+pub struct EncodedMessage {
+    json: Bytes,
+    game_sync: bool,
+}
+
+impl EncodedMessage {
+    pub fn encode(msg: &ServerMessage) -> serde_json::Result<Self> {
+        let server_ts = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        let frame = ServerFrame { message: msg, server_ts };
+        Ok(Self {
+            json: Bytes::from(serde_json::to_vec(&frame)?),
+            game_sync: matches!(msg, ServerMessage::GameSync { .. }),
+        })
+    }
+
+    /// エンコード済みの JSON から組み立てる（他のノードから届いたもの）
+    pub fn from_json(json: Bytes, game_sync: bool) -> Self {
+        Self { json, game_sync }
+    }
+
+    /// JSON（UTF-8）
+    pub fn json(&self) -> &Bytes {
+        &self.json
+    }
+
+    /// GameSync か（未送信の古いものは新しいものに置き換えてよい）
+    pub fn is_game_sync(&self) -> bool {
+        self.game_sync
+    }
+}
+
 #[async_trait]
 pub trait Transport: Send + Sync {
-    async fn send(&self, msg: ServerMessage) -> Result<()>;
+    async fn send(&self, msg: ServerMessage) -> Result<()> {
+        self.send_raw(EncodedMessage::encode(&msg)?).await
+    }
+    /// エンコード済みのメッセージを送る
+    async fn send_raw(&self, msg: EncodedMessage) -> Result<()>;
     async fn recv(&mut self) -> Result<ClientMessage>;
     async fn close(&self) -> Result<()>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encoded_message() {
+        let sync = ServerMessage::GameSync {
+            players: vec![],
+            current_turn: 0,
+            phase: crate::game::TurnPhase::WaitingForSpin,
+            turn_number: 1,
+            max_turns: None,
+            pending_choice: None,
+            paused: false,
+            game_started_at: None,
+            stock_price: 0,
+            arrivals: vec![],
+            tax_cut: false,
+        };
+        let encoded = EncodedMessage::encode(&sync).unwrap();
+        assert!(encoded.is_game_sync());
+        let decoded: ServerMessage = serde_json::from_slice(encoded.json()).unwrap();
+        assert!(matches!(decoded, ServerMessage::GameSync { turn_number: 1, .. }));
+        let frame: serde_json::Value = serde_json::from_slice(encoded.json()).unwrap();
+        assert!(frame["server_ts"].as_u64().is_some_and(|ts| ts > 0));
+
+        let left = ServerMessage::PlayerLeft {
+            player_id: "p1".to_string(),
+        };
+        assert!(!EncodedMessage::encode(&left).unwrap().is_game_sync());
+    }
+}
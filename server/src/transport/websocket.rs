@@ -1,35 +1,179 @@
 use async_trait::async_trait;
-use axum::extract::ws::{Message, WebSocket};
+use axum::extract::ws::{Message, Utf8Bytes, WebSocket};
 use futures_util::stream::{SplitSink, SplitStream};
 use futures_util::{SinkExt, StreamExt};
-use std::sync::Arc;
-use tokio::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::{mpsc, Notify};
+use tokio::task::AbortHandle;
 
-use crate::protocol::{ClientMessage, ServerMessage};
-use crate::transport::traits::{Result, Transport};
+use crate::protocol::ClientMessage;
+use crate::transport::traits::{EncodedMessage, Result, Transport};
+
+/// 遅いクライアントへの送信ポリシー
+#[derive(Debug, Clone, Copy)]
+pub struct BackpressurePolicy {
+    /// 1接続あたりの送信キューの長さ
+    pub queue_capacity: usize,
+    /// 未送信の GameSync を最新の1件にまとめる
+    pub coalesce_game_sync: bool,
+    /// キューが溢れた状態がこの時間続いたら切断する
+    pub disconnect_after: Duration,
+}
+
+impl Default for BackpressurePolicy {
+    fn default() -> Self {
+        Self {
+            queue_capacity: 256,
+            coalesce_game_sync: true,
+            disconnect_after: Duration::from_secs(10),
+        }
+    }
+}
+
+/// キューに積む単位。Sync は最新の GameSync の世代番号
+enum Outgoing {
+    Frame(Message),
+    Sync(u64),
+}
+
+/// 送信側・書き込みタスク・受信側で共有する状態
+#[derive(Default)]
+struct Connection {
+    /// まだ書き込んでいない最新の GameSync（世代番号付き）
+    latest_sync: Mutex<Option<(u64, Message)>>,
+    sync_generation: AtomicU64,
+    /// キューが溢れ始めた時刻
+    full_since: Mutex<Option<Instant>>,
+    /// 遅いクライアントとして切断した
+    degraded: AtomicBool,
+    /// 切断したことを受信側に知らせる
+    disconnected: Notify,
+}
+
+impl Connection {
+    /// GameSync を差し替え、キューに積む世代番号を返す（古い世代はスキップされる）
+    fn store_sync(&self, frame: Message) -> u64 {
+        let generation = self.sync_generation.fetch_add(1, Ordering::Relaxed) + 1;
+        *self.latest_sync.lock().unwrap() = Some((generation, frame));
+        generation
+    }
+
+    /// 指定世代（None なら世代を問わず）の GameSync を取り出す
+    fn take_sync(&self, generation: Option<u64>) -> Option<Message> {
+        let mut latest = self.latest_sync.lock().unwrap();
+        match latest.as_ref() {
+            Some((g, _)) if generation.is_none_or(|generation| generation == *g) => {
+                latest.take().map(|(_, frame)| frame)
+            }
+            _ => None,
+        }
+    }
+
+    /// 溢れている時間（溢れていなければ記録を始める）
+    fn full_for(&self) -> Duration {
+        self.full_since
+            .lock()
+            .unwrap()
+            .get_or_insert_with(Instant::now)
+            .elapsed()
+    }
+
+    fn clear_full(&self) {
+        *self.full_since.lock().unwrap() = None;
+    }
+}
 
 /// WebSocket の sender 側のみを保持する Transport 実装
 /// RoomManager にプレイヤー単位で登録し、ブロードキャスト送信に使う
+///
+/// 送信はキューに積むだけで待たない。実際の書き込みは接続ごとの書き込みタスクが行うため、
+/// 遅いクライアントがいても他のプレイヤーへの送信は遅れない
 #[derive(Clone)]
 pub struct WsSender {
-    sender: Arc<Mutex<SplitSink<WebSocket, Message>>>,
+    queue: mpsc::Sender<Outgoing>,
+    writer: AbortHandle,
+    connection: Arc<Connection>,
+    policy: BackpressurePolicy,
 }
 
 impl WsSender {
-    pub fn new(sender: SplitSink<WebSocket, Message>) -> Self {
+    pub fn new(sender: SplitSink<WebSocket, Message>, policy: BackpressurePolicy) -> Self {
+        Self::with_connection(sender, policy, Arc::default())
+    }
+
+    fn with_connection(
+        mut sender: SplitSink<WebSocket, Message>,
+        policy: BackpressurePolicy,
+        connection: Arc<Connection>,
+    ) -> Self {
+        let (queue, mut rx) = mpsc::channel::<Outgoing>(policy.queue_capacity.max(1));
+        let shared = connection.clone();
+        let writer = tokio::spawn(async move {
+            while let Some(item) = rx.recv().await {
+                let frame = match item {
+                    Outgoing::Frame(frame) => Some(frame),
+                    Outgoing::Sync(generation) => shared.take_sync(Some(generation)),
+                };
+                // キューが空になったら、積めずに残っていた GameSync も送る
+                let pending_sync = rx.is_empty().then(|| shared.take_sync(None)).flatten();
+                for frame in frame.into_iter().chain(pending_sync) {
+                    let closing = matches!(frame, Message::Close(_));
+                    if sender.send(frame).await.is_err() || closing {
+                        return;
+                    }
+                }
+            }
+        })
+        .abort_handle();
         Self {
-            sender: Arc::new(Mutex::new(sender)),
+            queue,
+            writer,
+            connection,
+            policy,
+        }
+    }
+
+    /// 書き込みタスクを止め、受信側にも切断させる
+    fn disconnect(&self) {
+        self.writer.abort();
+        self.connection.degraded.store(true, Ordering::Relaxed);
+        self.connection.disconnected.notify_one();
+    }
+
+    /// キューに積む。溢れている間のメッセージは捨て（GameSync は最新のものを後で送る）、
+    /// 溢れた状態が続いたら切断する
+    fn enqueue(&self, item: Outgoing) -> Result<()> {
+        match self.queue.try_send(item) {
+            Ok(()) => {
+                self.connection.clear_full();
+                Ok(())
+            }
+            Err(TrySendError::Full(_)) => {
+                if self.connection.full_for() >= self.policy.disconnect_after {
+                    self.disconnect();
+                    return Err("slow client disconnected".into());
+                }
+                Err("send queue full".into())
+            }
+            Err(TrySendError::Closed(_)) => Err("connection closed".into()),
         }
     }
 }
 
 #[async_trait]
 impl Transport for WsSender {
-    async fn send(&self, msg: ServerMessage) -> Result<()> {
-        let json = serde_json::to_string(&msg)?;
-        let mut sender = self.sender.lock().await;
-        sender.send(Message::Text(json.into())).await?;
-        Ok(())
+    async fn send_raw(&self, msg: EncodedMessage) -> Result<()> {
+        let frame = Message::Text(Utf8Bytes::try_from(msg.json().clone())?);
+        if self.policy.coalesce_game_sync && msg.is_game_sync() {
+            let generation = self.connection.store_sync(frame);
+            // 積めなくても書き込みタスクがキューを空にした時点で送られる
+            let _ = self.enqueue(Outgoing::Sync(generation));
+            return Ok(());
+        }
+        self.enqueue(Outgoing::Frame(frame))
     }
 
     async fn recv(&mut self) -> Result<ClientMessage> {
@@ -38,8 +182,9 @@ impl Transport for WsSender {
     }
 
     async fn close(&self) -> Result<()> {
-        let mut sender = self.sender.lock().await;
-        sender.send(Message::Close(None)).await?;
+        if self.enqueue(Outgoing::Frame(Message::Close(None))).is_err() {
+            self.writer.abort();
+        }
         Ok(())
     }
 }
@@ -47,17 +192,33 @@ impl Transport for WsSender {
 /// WebSocket の receiver 側をラップするヘルパー
 pub struct WsReceiver {
     receiver: SplitStream<WebSocket>,
+    connection: Arc<Connection>,
 }
 
 impl WsReceiver {
     pub fn new(receiver: SplitStream<WebSocket>) -> Self {
-        Self { receiver }
+        Self {
+            receiver,
+            connection: Arc::default(),
+        }
+    }
+
+    /// 送信が追いつかず切断した接続か
+    pub fn is_degraded(&self) -> bool {
+        self.connection.degraded.load(Ordering::Relaxed)
     }
 
     /// 次のクライアントメッセージを受信する
+    /// 遅いクライアントとして切断した場合も Err を返す
     pub async fn recv(&mut self) -> Result<ClientMessage> {
         loop {
-            match self.receiver.next().await {
+            let next = tokio::select! {
+                next = self.receiver.next() => next,
+                _ = self.connection.disconnected.notified() => {
+                    return Err("slow client disconnected".into());
+                }
+            };
+            match next {
                 Some(Ok(Message::Text(text))) => {
                     let msg: ClientMessage = serde_json::from_str(&text)?;
                     return Ok(msg);
@@ -82,7 +243,26 @@ impl WsReceiver {
 }
 
 /// WebSocket を sender/receiver に分割する
-pub fn split_websocket(socket: WebSocket) -> (WsSender, WsReceiver) {
+pub fn split_websocket(socket: WebSocket, policy: BackpressurePolicy) -> (WsSender, WsReceiver) {
     let (sender, receiver) = socket.split();
-    (WsSender::new(sender), WsReceiver::new(receiver))
+    let receiver = WsReceiver::new(receiver);
+    let sender = WsSender::with_connection(sender, policy, receiver.connection.clone());
+    (sender, receiver)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stale_game_sync_is_skipped() {
+        let connection = Connection::default();
+        let first = connection.store_sync(Message::Text("1".into()));
+        let second = connection.store_sync(Message::Text("2".into()));
+
+        // 古い世代の印では何も送らず、最新の印で最新の GameSync を送る
+        assert!(connection.take_sync(Some(first)).is_none());
+        assert!(matches!(connection.take_sync(Some(second)), Some(Message::Text(t)) if t.as_str() == "2"));
+        assert!(connection.take_sync(None).is_none());
+    }
 }
@@ -0,0 +1,284 @@
+use std::sync::Arc;
+
+use axum::extract::{FromRef, Path, Query, Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::sse::{KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{delete, get, post};
+use axum::{Json, Router};
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+
+use crate::auth::constant_time_eq;
+use crate::config::ConfigReloader;
+use crate::game::GameState;
+use crate::metrics::{Offender, METRICS};
+use crate::protocol::{ClientMessage, ServerMessage};
+use crate::room::manager::AdminRoomInfo;
+use crate::room::models::RoomSnapshot;
+use crate::room::{RoomError, RoomManager};
+use crate::transport::{sse_channel, Transport};
+use crate::worker::{self, WorkerRequest};
+
+/// 管理API ルーター（/admin 配下にネストする）
+/// 全エンドポイントが `Authorization: Bearer <admin_token>` を要求する
+pub fn router<S>(admin_token: String) -> Router<S>
+where
+    Arc<RoomManager>: FromRef<S>,
+    Arc<ConfigReloader>: FromRef<S>,
+    S: Clone + Send + Sync + 'static,
+{
+    Router::new()
+        .route("/rooms", get(list_rooms).post(restore_room))
+        .route("/rooms/{id}", delete(close_room))
+        .route("/rooms/{id}/state", get(room_state))
+        .route("/rooms/{id}/snapshot", get(room_snapshot))
+        .route("/rooms/{id}/events", get(room_events))
+        .route("/rooms/{id}/players/{player_id}", delete(disconnect_player))
+        .route("/rooms/{id}/players/{player_id}/actions", post(force_action))
+        .route("/rooms/{id}/handoff", post(hand_off_room))
+        .route("/drain", post(drain))
+        .route("/metrics", get(metrics))
+        .route("/reload-config", post(reload_config))
+        .route("/offenders", get(offenders))
+        .route_layer(middleware::from_fn_with_state(
+            Arc::<str>::from(admin_token),
+            require_admin_token,
+        ))
+}
+
+/// Bearer トークン検証ミドルウェア
+async fn require_admin_token(
+    State(admin_token): State<Arc<str>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let authorized = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .is_some_and(|token| constant_time_eq(token.as_bytes(), admin_token.as_bytes()));
+
+    if authorized {
+        next.run(request).await
+    } else {
+        StatusCode::UNAUTHORIZED.into_response()
+    }
+}
+
+/// 操作理由（クライアントへの通知に使う）
+#[derive(Debug, Default, Deserialize)]
+pub struct ReasonBody {
+    pub reason: Option<String>,
+}
+
+impl ReasonBody {
+    fn reason_or_default(self) -> String {
+        self.reason
+            .unwrap_or_else(|| "管理者により操作されました".to_string())
+    }
+}
+
+/// GET /admin/rooms 全部屋の一覧
+async fn list_rooms(State(room_manager): State<Arc<RoomManager>>) -> Json<Vec<AdminRoomInfo>> {
+    Json(room_manager.list_rooms().await)
+}
+
+/// GET /admin/metrics 計測値（Prometheus のテキスト形式）
+async fn metrics() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4; charset=utf-8")],
+        METRICS.render(),
+    )
+}
+
+/// 失敗の多い送信元IPの件数
+#[derive(Debug, Deserialize)]
+pub struct OffendersQuery {
+    /// 件数（既定 20、最大 MAX_OFFENDERS）
+    pub limit: Option<usize>,
+}
+
+/// GET /admin/offenders で返す最大件数
+const MAX_OFFENDERS: usize = 100;
+
+/// GET /admin/offenders 部屋に入るまでの失敗が多い送信元IP（失敗の多い順）
+async fn offenders(Query(query): Query<OffendersQuery>) -> Json<Vec<Offender>> {
+    let limit = query.limit.unwrap_or(20).min(MAX_OFFENDERS);
+    Json(METRICS.connections.top_offenders(limit))
+}
+
+/// POST /admin/reload-config 設定を読み直す（SIGHUP と同じ）
+async fn reload_config(State(reloader): State<Arc<ConfigReloader>>) -> Response {
+    match reloader.reload() {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(message) => (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "code": "INVALID_CONFIG", "message": message })),
+        )
+            .into_response(),
+    }
+}
+
+/// GET /admin/rooms/:id/state 部屋の GameState
+async fn room_state(
+    Path(room_id): Path<String>,
+    State(room_manager): State<Arc<RoomManager>>,
+) -> Result<Json<GameState>, RoomError> {
+    room_manager
+        .get_game_state(&room_id)
+        .await
+        .map(Json)
+}
+
+/// GET /admin/rooms/:id/snapshot 部屋を再現するためのスナップショット
+async fn room_snapshot(
+    Path(room_id): Path<String>,
+    State(room_manager): State<Arc<RoomManager>>,
+) -> Result<Json<RoomSnapshot>, RoomError> {
+    room_manager.snapshot(&room_id).await.map(Json)
+}
+
+/// スナップショットから復元した部屋
+#[derive(Debug, Serialize)]
+pub struct RestoredRoom {
+    pub room_id: String,
+    pub join_token: String,
+}
+
+/// POST /admin/rooms スナップショットから部屋を復元
+async fn restore_room(
+    State(room_manager): State<Arc<RoomManager>>,
+    Json(snapshot): Json<RoomSnapshot>,
+) -> Result<(StatusCode, Json<RestoredRoom>), RoomError> {
+    let (room_id, join_token) = room_manager.restore_snapshot(snapshot).await?;
+    Ok((StatusCode::CREATED, Json(RestoredRoom { room_id, join_token })))
+}
+
+/// DELETE /admin/rooms/:id 部屋を強制終了
+async fn close_room(
+    Path(room_id): Path<String>,
+    State(room_manager): State<Arc<RoomManager>>,
+    body: Option<Json<ReasonBody>>,
+) -> Result<StatusCode, RoomError> {
+    let reason = body.map(|Json(b)| b).unwrap_or_default().reason_or_default();
+    room_manager
+        .close_room(&room_id, reason)
+        .await
+        .map(|_| StatusCode::NO_CONTENT)
+}
+
+/// DELETE /admin/rooms/:id/players/:player_id プレイヤーを切断
+async fn disconnect_player(
+    Path((room_id, player_id)): Path<(String, String)>,
+    State(room_manager): State<Arc<RoomManager>>,
+    body: Option<Json<ReasonBody>>,
+) -> Result<StatusCode, RoomError> {
+    let reason = body.map(|Json(b)| b).unwrap_or_default().reason_or_default();
+    room_manager
+        .kick_player(&room_id, &player_id, reason)
+        .await
+        .map(|_| StatusCode::NO_CONTENT)
+}
+
+/// POST /admin/rooms/:id/players/:player_id/actions プレイヤーに代わってゲーム操作を送る
+/// プレイヤーの WebSocket からの操作と同じ検証を通り、本人への返信（エラーを含む）を返す
+async fn force_action(
+    Path((room_id, player_id)): Path<(String, String)>,
+    State(room_manager): State<Arc<RoomManager>>,
+    Json(message): Json<ClientMessage>,
+) -> Json<Vec<ServerMessage>> {
+    let request = WorkerRequest {
+        room_id,
+        player_id,
+        message,
+    };
+    Json(worker::dispatch(&room_manager, request).await)
+}
+
+/// ストリームが破棄された（管理ツールが切断した）ら観察者を外す
+struct ObserverGuard {
+    room_manager: Arc<RoomManager>,
+    room_id: String,
+    observer_id: String,
+}
+
+impl Drop for ObserverGuard {
+    fn drop(&mut self) {
+        let room_manager = self.room_manager.clone();
+        let room_id = std::mem::take(&mut self.room_id);
+        let observer_id = std::mem::take(&mut self.observer_id);
+        tokio::spawn(async move { room_manager.leave_observer(&room_id, &observer_id).await });
+    }
+}
+
+/// GET /admin/rooms/:id/events 部屋へのブロードキャストを遅延なしで流す（Server-Sent Events）
+/// 非公開部屋のトークンや配信遅延、観戦者の上限に関係なく、最初に部屋の状態を送ってから全てのメッセージを流す
+async fn room_events(
+    Path(room_id): Path<String>,
+    State(room_manager): State<Arc<RoomManager>>,
+) -> Result<impl IntoResponse, RoomError> {
+    let (sender, stream) = sse_channel();
+    let sender: Arc<dyn Transport> = Arc::new(sender);
+    let observer_id = room_manager.join_observer(&room_id, sender).await?;
+
+    let guard = ObserverGuard {
+        room_manager,
+        room_id,
+        observer_id,
+    };
+    // guard をストリームに持たせ、ストリームと一緒に破棄する
+    let stream = stream.map(move |event| {
+        let _ = &guard;
+        event
+    });
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+/// 部屋の移し先
+#[derive(Debug, Deserialize)]
+pub struct HandoffBody {
+    /// 移し先のノードの WebSocket の URL（移し先の NODE_URL）
+    pub url: String,
+}
+
+/// POST /admin/rooms/:id/handoff 進行中の部屋を別のノードに移す
+async fn hand_off_room(
+    Path(room_id): Path<String>,
+    State(room_manager): State<Arc<RoomManager>>,
+    Json(body): Json<HandoffBody>,
+) -> Result<StatusCode, RoomError> {
+    room_manager
+        .hand_off(&room_id, &body.url)
+        .await
+        .map(|_| StatusCode::NO_CONTENT)
+}
+
+/// 移せなかった部屋
+#[derive(Debug, Serialize)]
+pub struct HandoffFailure {
+    pub room_id: String,
+    pub code: &'static str,
+}
+
+/// POST /admin/drain の結果
+#[derive(Debug, Serialize)]
+pub struct DrainReport {
+    pub handed_off: Vec<String>,
+    pub failed: Vec<HandoffFailure>,
+}
+
+/// POST /admin/drain このノードの進行中の部屋をすべて別のノードに移す（スケールダウン前に使う）
+async fn drain(
+    State(room_manager): State<Arc<RoomManager>>,
+    Json(body): Json<HandoffBody>,
+) -> Result<Json<DrainReport>, RoomError> {
+    let (handed_off, failed) = room_manager.drain(&body.url).await?;
+    let failed = failed
+        .into_iter()
+        .map(|(room_id, e)| HandoffFailure { room_id, code: e.code() })
+        .collect();
+    Ok(Json(DrainReport { handed_off, failed }))
+}
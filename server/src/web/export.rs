@@ -0,0 +1,165 @@
+//! 終了したゲームの記録のダウンロード（GET /api/room/:id/export?format=json|csv）
+//!
+//! 出来事の全件と最終順位を1つのファイルにする。CSV は1行目が見出しで、kind 列が standing（最終順位）か
+//! event（出来事）かを表す。Excel でそのまま開けるよう先頭に BOM を付ける。
+
+use axum::extract::{Path, Query, State};
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use std::sync::Arc;
+
+use crate::room::manager::GameExport;
+use crate::room::{RoomError, RoomManager};
+
+/// ファイルの形式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Json,
+    Csv,
+}
+
+/// 書き出しの指定（?format=&token=、非公開部屋は token も必要）
+#[derive(Debug, serde::Deserialize)]
+pub struct ExportQuery {
+    #[serde(default)]
+    pub format: ExportFormat,
+    pub token: Option<String>,
+}
+
+const CSV_HEADER: &str = "kind,turn_number,rank,player_id,player_name,total_assets,label";
+
+/// 記録のダウンロード
+/// GET /api/room/:id/export でゲームが終了した部屋の記録をファイルとして返す（ロビーに戻ると消える）
+pub async fn room_export(
+    Path(room_id): Path<String>,
+    Query(query): Query<ExportQuery>,
+    State(room_manager): State<Arc<RoomManager>>,
+) -> Result<Response, RoomError> {
+    let export = room_manager.game_export(&room_id, query.token.as_deref()).await?;
+    let (content_type, extension, body) = match query.format {
+        ExportFormat::Json => (
+            "application/json",
+            "json",
+            serde_json::to_string_pretty(&export).map_err(|e| RoomError::Storage(e.to_string()))?,
+        ),
+        ExportFormat::Csv => ("text/csv; charset=utf-8", "csv", to_csv(&export)),
+    };
+    let disposition = format!("attachment; filename=\"9life-{}.{}\"", export.room_id, extension);
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+/// 最終順位（順位順）と出来事（古い順）を CSV にする
+pub fn to_csv(export: &GameExport) -> String {
+    let mut out = String::from("\u{feff}");
+    out.push_str(CSV_HEADER);
+    out.push_str("\r\n");
+    for entry in &export.standings {
+        push_row(
+            &mut out,
+            &[
+                "standing",
+                "",
+                &entry.rank.to_string(),
+                &entry.player_id,
+                &entry.player_name,
+                &entry.total_assets.to_string(),
+                "",
+            ],
+        );
+    }
+    for event in &export.events {
+        push_row(
+            &mut out,
+            &["event", &event.turn_number.to_string(), "", "", "", "", &event.label],
+        );
+    }
+    out
+}
+
+/// 表計算ソフトが数式として解釈する先頭文字（プレイヤー名などから数式を注入されないよう ' を前に付ける）
+const FORMULA_PREFIXES: [char; 6] = ['=', '+', '-', '@', '\t', '\r'];
+
+fn push_row(out: &mut String, fields: &[&str]) {
+    for (i, field) in fields.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        let escaped;
+        let field = if field.starts_with(FORMULA_PREFIXES) {
+            escaped = format!("'{}", field);
+            escaped.as_str()
+        } else {
+            field
+        };
+        if field.contains([',', '"', '\r', '\n']) {
+            out.push('"');
+            out.push_str(&field.replace('"', "\"\""));
+            out.push('"');
+        } else {
+            out.push_str(field);
+        }
+    }
+    out.push_str("\r\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::state::AssetBreakdown;
+    use crate::game::text::SystemText;
+    use crate::protocol::{RankingEntry, TimelineEntry};
+
+    #[test]
+    fn test_csv_quotes_fields() {
+        let export = GameExport {
+            room_id: "ROOM01".to_string(),
+            map_id: "classic".to_string(),
+            rules_variant: "classic".to_string(),
+            started_at: Some(1),
+            finished_at: Some(2),
+            standings: vec![RankingEntry {
+                player_id: "p1".to_string(),
+                player_name: "Alice, \"A\"".to_string(),
+                total_assets: 1200,
+                breakdown: AssetBreakdown::default(),
+                rank: 1,
+                arrival_order: Some(1),
+            }],
+            events: vec![TimelineEntry {
+                turn_number: 3,
+                text: SystemText::Married {
+                    player_name: "Alice".to_string(),
+                },
+                label: "Aliceが結婚した".to_string(),
+            }],
+        };
+        let csv = to_csv(&export);
+        let lines: Vec<&str> = csv.trim_start_matches('\u{feff}').split("\r\n").collect();
+        assert_eq!(lines[0], CSV_HEADER);
+        assert_eq!(lines[1], "standing,,1,p1,\"Alice, \"\"A\"\"\",1200,");
+        assert_eq!(lines[2], "event,3,,,,,Aliceが結婚した");
+        assert_eq!(lines[3], "");
+    }
+
+    #[test]
+    fn test_csv_neutralizes_formulas() {
+        let mut out = String::new();
+        push_row(
+            &mut out,
+            &["=HYPERLINK(\"x\")", "+1", "-2", "@SUM(A1)", "\tx", "\rx", "a=b"],
+        );
+        assert_eq!(
+            out,
+            "\"'=HYPERLINK(\"\"x\"\")\",'+1,'-2,'@SUM(A1),'\tx,\"'\rx\",a=b\r\n"
+        );
+    }
+}
@@ -1,21 +1,434 @@
-use axum::extract::Path;
-use axum::http::StatusCode;
-use axum::response::Html;
+pub mod admin;
+pub mod export;
+pub mod qr;
+pub mod spa;
+pub mod template;
+
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::sse::{KeepAlive, Sse};
+use axum::response::{Html, IntoResponse};
+use futures_util::StreamExt;
+
+use qr::QrCode;
+
+use crate::map::analysis::{self, MapAnalysis};
+use crate::profile::{LeaderboardEntry, ProfileStore};
+use crate::protocol::ServerMessage;
+use crate::room::manager::{LedgerPage, LedgerViewer, PublicGameState, PublicRoomInfo, RoomInfo};
+use crate::room::models::RoomFilter;
+use crate::room::{RoomError, RoomManager};
+use crate::transport::{sse_channel, Transport};
+
+/// 非公開部屋向けのトークン付きクエリ（?token=...）
+#[derive(Debug, serde::Deserialize)]
+pub struct TokenQuery {
+    pub token: Option<String>,
+}
+
+const INVITE_LAYOUT: &str = include_str!("templates/invite.html");
+const INVITE_ROOM: &str = include_str!("templates/invite_room.html");
+const INVITE_PRIVATE: &str = include_str!("templates/invite_private.html");
+const INVITE_NOT_FOUND: &str = include_str!("templates/invite_not_found.html");
+const API_DOCS: &str = include_str!("templates/api_docs.html");
+const OPENAPI_JSON: &str = include_str!("openapi.json");
 
 /// 招待ページハンドラ
-/// GET /room/:id で招待HTMLを返す
-pub async fn invite_page(Path(_room_id): Path<String>) -> Html<&'static str> {
-    Html(include_str!("templates/invite.html"))
+/// GET /room/:id で部屋の現在の状態を埋め込んだ招待HTMLを返す
+pub async fn invite_page(
+    Path(room_id): Path<String>,
+    Query(query): Query<TokenQuery>,
+    State(room_manager): State<Arc<RoomManager>>,
+) -> (StatusCode, Html<String>) {
+    let info = room_manager
+        .get_public_room_info(&room_id, query.token.as_deref())
+        .await;
+
+    let (status, title, content) = match info {
+        Some(PublicRoomInfo::Full(info)) => {
+            let token_query = match (&query.token, info.private) {
+                (Some(token), true) => format!("?token={}", token),
+                _ => String::new(),
+            };
+            let map_name = room_manager
+                .load_map(&info.map_id)
+                .map(|m| m.name)
+                .unwrap_or_else(|_| info.map_id.clone());
+            let status_label = match info.status.as_str() {
+                "lobby" => "待機中",
+                "playing" => "ゲーム中",
+                "finished" => "終了",
+                other => other,
+            };
+            let player_list: String = info
+                .players
+                .iter()
+                .map(|p| format!("<li>{}</li>", template::escape_html(&p.name)))
+                .collect();
+
+            let content = template::render(
+                INVITE_ROOM,
+                &[
+                    ("room_id", template::escape_html(&info.id)),
+                    ("status", template::escape_html(&info.status)),
+                    ("status_label", status_label.to_string()),
+                    ("map_name", template::escape_html(&map_name)),
+                    ("player_count", info.player_count.to_string()),
+                    ("max_players", info.max_players.to_string()),
+                    ("player_list", player_list),
+                    (
+                        "qr_url",
+                        template::escape_html(&format!("/room/{}/qr.svg{}", info.id, token_query)),
+                    ),
+                    (
+                        "join_url",
+                        template::escape_html(&format!("9life://join/{}{}", info.id, token_query)),
+                    ),
+                ],
+            );
+            (StatusCode::OK, format!("部屋 {} に参加", info.id), content)
+        }
+        Some(PublicRoomInfo::Summary(summary)) => {
+            let content = template::render(
+                INVITE_PRIVATE,
+                &[
+                    ("room_id", template::escape_html(&summary.id)),
+                    ("player_count", summary.player_count.to_string()),
+                    ("max_players", summary.max_players.to_string()),
+                ],
+            );
+            (StatusCode::OK, "非公開の部屋".to_string(), content)
+        }
+        None => (
+            StatusCode::NOT_FOUND,
+            "部屋が見つかりません".to_string(),
+            INVITE_NOT_FOUND.to_string(),
+        ),
+    };
+
+    let page = template::render(
+        INVITE_LAYOUT,
+        &[
+            ("title", template::escape_html(&title)),
+            ("content", content),
+        ],
+    );
+    (status, Html(page))
+}
+
+/// 招待QRコード
+/// GET /room/:id/qr.svg で招待URLをエンコードしたSVGを返す
+/// 非公開部屋はトークンが一致する場合のみ、トークン付きURLをエンコードする
+pub async fn invite_qr(
+    Path(room_id): Path<String>,
+    Query(query): Query<TokenQuery>,
+    headers: HeaderMap,
+    State(room_manager): State<Arc<RoomManager>>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let info = match room_manager
+        .get_public_room_info(&room_id, query.token.as_deref())
+        .await
+    {
+        Some(PublicRoomInfo::Full(info)) => info,
+        _ => return Err(StatusCode::NOT_FOUND),
+    };
+
+    let mut invite_url = format!("{}/room/{}", public_base_url(&headers), room_id);
+    if let (true, Some(token)) = (info.private, &query.token) {
+        invite_url.push_str(&format!("?token={}", token));
+    }
+    let qr = QrCode::encode(invite_url.as_bytes()).ok_or(StatusCode::URI_TOO_LONG)?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "image/svg+xml"),
+            (header::CACHE_CONTROL, "public, max-age=3600"),
+        ],
+        qr.to_svg(),
+    ))
+}
+
+/// リクエストヘッダから外部公開URLのベース（scheme://host）を組み立てる
+/// リバースプロキシ（Fly.io）配下では X-Forwarded-Proto を優先する
+fn public_base_url(headers: &HeaderMap) -> String {
+    let host = headers
+        .get(header::HOST)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("localhost");
+    let scheme = headers
+        .get("x-forwarded-proto")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("http");
+    format!("{}://{}", scheme, host)
 }
 
 /// 部屋情報API
 /// GET /api/room/:id で部屋情報をJSONで返す
+/// 非公開部屋は ?token= が一致しない限り存在と人数のみ返す
 pub async fn room_info(
     Path(room_id): Path<String>,
-    axum::extract::State(room_manager): axum::extract::State<std::sync::Arc<crate::room::RoomManager>>,
-) -> Result<axum::Json<crate::room::manager::RoomInfo>, StatusCode> {
-    match room_manager.get_room_info(&room_id).await {
+    Query(query): Query<TokenQuery>,
+    State(room_manager): State<Arc<RoomManager>>,
+) -> Result<axum::Json<PublicRoomInfo>, StatusCode> {
+    match room_manager
+        .get_public_room_info(&room_id, query.token.as_deref())
+        .await
+    {
         Some(info) => Ok(axum::Json(info)),
         None => Err(StatusCode::NOT_FOUND),
     }
 }
+
+/// ゲーム状態API
+/// GET /api/room/:id/state で盤面の位置・所持金・フェーズ・手番を返す（WebSocket なしでポーリングする配信オーバーレイ向け）
+pub async fn room_state(
+    Path(room_id): Path<String>,
+    Query(query): Query<TokenQuery>,
+    State(room_manager): State<Arc<RoomManager>>,
+) -> Result<axum::Json<PublicGameState>, RoomError> {
+    room_manager
+        .public_game_state(&room_id, query.token.as_deref())
+        .await
+        .map(axum::Json)
+}
+
+/// 結果カードAPI
+/// GET /api/room/:id/result-card でゲーム終了後の勝者・順位・実績・所要時間を返す（共有画像を描くクライアント・ボット向け）
+/// 同じゲームの結果は変わらないので ETag を付け、If-None-Match が一致すれば 304 を返す
+pub async fn room_result_card(
+    Path(room_id): Path<String>,
+    Query(query): Query<TokenQuery>,
+    headers: HeaderMap,
+    State(room_manager): State<Arc<RoomManager>>,
+) -> Result<axum::response::Response, RoomError> {
+    let (card, private) = room_manager
+        .result_card(&room_id, query.token.as_deref())
+        .await?;
+    let etag = format!("\"{}-{}\"", card.room_id, card.finished_at);
+    let cache_control = if private {
+        "private, max-age=300"
+    } else {
+        "public, max-age=300"
+    };
+    let cache_headers = [
+        (header::ETAG, etag.clone()),
+        (header::CACHE_CONTROL, cache_control.to_string()),
+    ];
+    let not_modified = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.split(',').any(|tag| tag.trim() == etag));
+    if not_modified {
+        return Ok((StatusCode::NOT_MODIFIED, cache_headers).into_response());
+    }
+    Ok((cache_headers, axum::Json(card)).into_response())
+}
+
+/// 観戦ストリームの指定（?token=&name=）
+#[derive(Debug, serde::Deserialize)]
+pub struct EventsQuery {
+    pub token: Option<String>,
+    pub name: Option<String>,
+}
+
+/// ストリームが破棄された（クライアントが切断した）ら観戦者を外す
+struct SpectatorGuard {
+    room_manager: Arc<RoomManager>,
+    room_id: String,
+    spectator_id: String,
+}
+
+impl Drop for SpectatorGuard {
+    fn drop(&mut self) {
+        let room_manager = self.room_manager.clone();
+        let room_id = std::mem::take(&mut self.room_id);
+        let spectator_id = std::mem::take(&mut self.spectator_id);
+        tokio::spawn(async move { room_manager.leave_spectator(&room_id, &spectator_id).await });
+    }
+}
+
+/// 観戦ストリーム（Server-Sent Events）
+/// GET /api/room/:id/events で部屋へのブロードキャストを JSON のイベントとして流す（観戦専用）
+pub async fn room_events(
+    Path(room_id): Path<String>,
+    Query(query): Query<EventsQuery>,
+    State(room_manager): State<Arc<RoomManager>>,
+) -> Result<impl IntoResponse, RoomError> {
+    let (sender, stream) = sse_channel();
+    let sender: Arc<dyn Transport> = Arc::new(sender);
+    let name = query.name.unwrap_or_else(|| "観戦者".to_string());
+    let spectator_id = room_manager
+        .join_spectator(&room_id, name, query.token.as_deref(), sender.clone())
+        .await?;
+    if let Some(info) = room_manager.get_room_info(&room_id).await {
+        let room_state = ServerMessage::RoomState {
+            room_id: room_id.clone(),
+            player_id: spectator_id.clone(),
+            players: info.players,
+            status: info.status,
+        };
+        let _ = sender.send(room_state).await;
+    }
+    drop(sender);
+
+    let guard = SpectatorGuard {
+        room_manager,
+        room_id,
+        spectator_id,
+    };
+    // guard をストリームに持たせ、ストリームと一緒に破棄する
+    let stream = stream.map(move |event| {
+        let _ = &guard;
+        event
+    });
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+/// 台帳のページ指定（?offset=&limit=、非公開部屋は ?token= も必要）
+#[derive(Debug, serde::Deserialize)]
+pub struct LedgerQuery {
+    pub token: Option<String>,
+    #[serde(default)]
+    pub offset: usize,
+    pub limit: Option<usize>,
+}
+
+/// 台帳API
+/// GET /api/room/:id/ledger で現在のゲームの所持金の増減履歴を返す
+pub async fn room_ledger(
+    Path(room_id): Path<String>,
+    Query(query): Query<LedgerQuery>,
+    State(room_manager): State<Arc<RoomManager>>,
+) -> Result<axum::Json<LedgerPage>, RoomError> {
+    room_manager
+        .ledger_page(&room_id, LedgerViewer::Token(query.token.as_deref()), query.offset, query.limit)
+        .await
+        .map(axum::Json)
+}
+
+/// 部屋一覧の絞り込み（?status=&tags=&language=&limit=、tags はカンマ区切り・件数は既定 50・最大 100）
+#[derive(Debug, serde::Deserialize)]
+pub struct RoomListQuery {
+    pub status: Option<String>,
+    pub tags: Option<String>,
+    pub language: Option<String>,
+    pub limit: Option<usize>,
+}
+
+/// 公開ロビーAPI
+/// GET /api/rooms で非公開でない部屋を新しい順に返す（タグはすべてを含む部屋、言語は "ja" で "ja-JP" にも一致）
+pub async fn room_list(
+    Query(query): Query<RoomListQuery>,
+    State(room_manager): State<Arc<RoomManager>>,
+) -> axum::Json<Vec<RoomInfo>> {
+    let filter = RoomFilter {
+        status: query.status.filter(|s| !s.is_empty()),
+        tags: query
+            .tags
+            .iter()
+            .flat_map(|tags| tags.split(','))
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .map(str::to_string)
+            .collect(),
+        language: query.language.filter(|l| !l.is_empty()),
+    };
+    let limit = query.limit.unwrap_or(50).min(100);
+    axum::Json(room_manager.list_public_rooms(filter, limit).await)
+}
+
+/// リーダーボードの件数指定（?limit=、既定 50・最大 100）
+#[derive(Debug, serde::Deserialize)]
+pub struct LeaderboardQuery {
+    pub limit: Option<usize>,
+}
+
+/// リーダーボードAPI
+/// GET /api/leaderboard でレーティング上位のプレイヤーを返す
+pub async fn leaderboard(
+    Query(query): Query<LeaderboardQuery>,
+    State(profiles): State<ProfileStore>,
+) -> axum::Json<Vec<LeaderboardEntry>> {
+    let limit = query.limit.unwrap_or(50).min(100);
+    axum::Json(profiles.leaderboard(limit).await)
+}
+
+/// OpenAPI ドキュメント
+/// GET /api/openapi.json で HTTP API の仕様（手書き: web/openapi.json）を返す
+pub async fn openapi_spec() -> impl IntoResponse {
+    ([(header::CONTENT_TYPE, "application/json")], OPENAPI_JSON)
+}
+
+/// API ドキュメント（Swagger UI）
+/// GET /api/docs で /api/openapi.json を表示する
+pub async fn api_docs() -> Html<&'static str> {
+    Html(API_DOCS)
+}
+
+/// JSON Schema API
+/// GET /api/schema/:name でマップ定義（map）・プロトコル（client-message / server-message）のスキーマを返す
+pub async fn json_schema(Path(name): Path<String>) -> Result<impl IntoResponse, StatusCode> {
+    let schema = crate::map::schema::schema(&name)
+        .or_else(|| crate::protocol::schema::schema(&name))
+        .ok_or(StatusCode::NOT_FOUND)?;
+    Ok(([(header::CONTENT_TYPE, "application/schema+json")], schema))
+}
+
+/// マップ解析API
+/// GET /api/maps/:id/analysis でルート長・到達不能マス・分岐などを返す
+pub async fn map_analysis(
+    Path(map_id): Path<String>,
+    State(room_manager): State<Arc<RoomManager>>,
+) -> Result<axum::Json<MapAnalysis>, RoomError> {
+    let map = room_manager.load_map(&map_id)?;
+    Ok(axum::Json(analysis::analyze(&map)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    fn collect_refs<'a>(value: &'a Value, refs: &mut Vec<&'a str>) {
+        match value {
+            Value::Object(map) => {
+                if let Some(Value::String(r)) = map.get("$ref") {
+                    refs.push(r);
+                }
+                map.values().for_each(|v| collect_refs(v, refs));
+            }
+            Value::Array(items) => items.iter().for_each(|v| collect_refs(v, refs)),
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn test_openapi_spec_is_consistent() {
+        let spec: Value = serde_json::from_str(OPENAPI_JSON).unwrap();
+
+        // $ref がすべて components 内を指している
+        let mut refs = Vec::new();
+        collect_refs(&spec, &mut refs);
+        for r in refs {
+            let pointer = r.strip_prefix('#').unwrap();
+            assert!(spec.pointer(pointer).is_some(), "dangling $ref: {}", r);
+        }
+
+        // マップ解析のレスポンスと required が一致する
+        let catalog = crate::map::MapCatalog::builtin();
+        let analysis = serde_json::to_value(analysis::analyze(catalog.get("classic").unwrap())).unwrap();
+        let schema = &spec["components"]["schemas"]["MapAnalysis"];
+        let mut required: Vec<&str> = schema["required"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| v.as_str().unwrap())
+            .collect();
+        let mut actual: Vec<&str> = analysis.as_object().unwrap().keys().map(String::as_str).collect();
+        required.sort();
+        actual.sort();
+        assert_eq!(required, actual);
+    }
+}
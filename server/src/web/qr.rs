@@ -0,0 +1,554 @@
+//! 招待URL用の最小限の QR コードエンコーダ
+//!
+//! バイトモード・誤り訂正レベル M・バージョン 1〜10 のみ対応する。
+//! 招待URLは長くても数十バイトなので、これで十分な容量（最大 213 バイト）がある。
+
+/// 対応する最大バージョン
+const MAX_VERSION: usize = 10;
+
+/// 誤り訂正レベル M のブロックあたり ECC コード語数（バージョン 1〜10）
+const ECC_CODEWORDS_PER_BLOCK: [usize; MAX_VERSION] = [10, 16, 26, 18, 24, 16, 18, 22, 22, 26];
+
+/// 誤り訂正レベル M のブロック数（バージョン 1〜10）
+const NUM_ERROR_CORRECTION_BLOCKS: [usize; MAX_VERSION] = [1, 1, 1, 2, 2, 4, 4, 4, 5, 5];
+
+/// フォーマット情報における誤り訂正レベル M のビット列
+const ECC_FORMAT_BITS_M: u32 = 0;
+
+/// 生成済み QR コード（正方形のモジュール配列）
+pub struct QrCode {
+    size: usize,
+    modules: Vec<bool>,
+}
+
+impl QrCode {
+    /// バイト列を QR コードにエンコードする。容量を超える場合は None
+    pub fn encode(data: &[u8]) -> Option<Self> {
+        let version = (1..=MAX_VERSION).find(|&v| data.len() <= Self::data_capacity(v))?;
+        let codewords = Self::add_ecc_and_interleave(&Self::data_codewords(data, version), version);
+
+        let mut builder = Builder::new(version);
+        builder.draw_function_patterns();
+        builder.draw_codewords(&codewords);
+
+        // ペナルティが最小のマスクを採用
+        let mut best: Option<(u32, Vec<bool>)> = None;
+        for mask in 0..8 {
+            let mut candidate = builder.clone();
+            candidate.apply_mask(mask);
+            candidate.draw_format_bits(mask);
+            let penalty = candidate.penalty_score();
+            if best.as_ref().is_none_or(|(p, _)| penalty < *p) {
+                best = Some((penalty, candidate.modules));
+            }
+        }
+        let (_, modules) = best?;
+
+        Some(Self {
+            size: builder.size,
+            modules,
+        })
+    }
+
+    /// 一辺のモジュール数
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// (x, y) のモジュールが暗か
+    pub fn is_dark(&self, x: usize, y: usize) -> bool {
+        self.modules[y * self.size + x]
+    }
+
+    /// 4モジュールのクワイエットゾーン付き SVG を生成する
+    pub fn to_svg(&self) -> String {
+        let border = 4;
+        let dim = self.size + border * 2;
+        let mut path = String::new();
+        for y in 0..self.size {
+            for x in 0..self.size {
+                if self.is_dark(x, y) {
+                    path.push_str(&format!("M{},{}h1v1h-1z", x + border, y + border));
+                }
+            }
+        }
+        format!(
+            concat!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+                "<svg xmlns=\"http://www.w3.org/2000/svg\" version=\"1.1\" ",
+                "viewBox=\"0 0 {dim} {dim}\" shape-rendering=\"crispEdges\">\n",
+                "<rect width=\"100%\" height=\"100%\" fill=\"#ffffff\"/>\n",
+                "<path d=\"{path}\" fill=\"#000000\"/>\n",
+                "</svg>\n"
+            ),
+            dim = dim,
+            path = path
+        )
+    }
+
+    /// バージョンの全コード語数から ECC を除いたデータコード語数
+    fn num_data_codewords(version: usize) -> usize {
+        num_raw_data_modules(version) / 8
+            - ECC_CODEWORDS_PER_BLOCK[version - 1] * NUM_ERROR_CORRECTION_BLOCKS[version - 1]
+    }
+
+    /// バイトモードで格納できる最大バイト数
+    fn data_capacity(version: usize) -> usize {
+        let header_bits = 4 + char_count_bits(version);
+        (Self::num_data_codewords(version) * 8 - header_bits) / 8
+    }
+
+    /// モード指示子・文字数・データ・終端・埋め草を並べたデータコード語
+    fn data_codewords(data: &[u8], version: usize) -> Vec<u8> {
+        let capacity_bits = Self::num_data_codewords(version) * 8;
+        let mut bits = BitBuffer::default();
+        bits.append(0b0100, 4);
+        bits.append(data.len() as u32, char_count_bits(version));
+        for &b in data {
+            bits.append(b as u32, 8);
+        }
+
+        let terminator = (capacity_bits - bits.len()).min(4);
+        bits.append(0, terminator);
+        let pad_to_byte = (8 - bits.len() % 8) % 8;
+        bits.append(0, pad_to_byte);
+        for pad in [0xEC, 0x11].iter().cycle() {
+            if bits.len() >= capacity_bits {
+                break;
+            }
+            bits.append(*pad, 8);
+        }
+
+        bits.into_bytes()
+    }
+
+    /// ブロック分割・Reed-Solomon 符号付与・インターリーブ
+    fn add_ecc_and_interleave(data: &[u8], version: usize) -> Vec<u8> {
+        let num_blocks = NUM_ERROR_CORRECTION_BLOCKS[version - 1];
+        let block_ecc_len = ECC_CODEWORDS_PER_BLOCK[version - 1];
+        let raw_codewords = num_raw_data_modules(version) / 8;
+        let num_short_blocks = num_blocks - raw_codewords % num_blocks;
+        let short_block_len = raw_codewords / num_blocks;
+
+        let divisor = reed_solomon_divisor(block_ecc_len);
+        let mut blocks: Vec<Vec<u8>> = Vec::with_capacity(num_blocks);
+        let mut k = 0;
+        for i in 0..num_blocks {
+            let len = short_block_len - block_ecc_len + usize::from(i >= num_short_blocks);
+            let mut block = data[k..k + len].to_vec();
+            k += len;
+            let ecc = reed_solomon_remainder(&block, &divisor);
+            if i < num_short_blocks {
+                // 長いブロックと列を揃えるためのダミー
+                block.push(0);
+            }
+            block.extend(ecc);
+            blocks.push(block);
+        }
+
+        let mut result = Vec::with_capacity(raw_codewords);
+        for i in 0..blocks[0].len() {
+            for (j, block) in blocks.iter().enumerate() {
+                if i != short_block_len - block_ecc_len || j >= num_short_blocks {
+                    result.push(block[i]);
+                }
+            }
+        }
+        result
+    }
+}
+
+/// 文字数指示子のビット数（バイトモード）
+fn char_count_bits(version: usize) -> usize {
+    if version <= 9 {
+        8
+    } else {
+        16
+    }
+}
+
+/// 機能パターンを除いたデータ領域のモジュール数
+fn num_raw_data_modules(version: usize) -> usize {
+    let mut result = (16 * version + 128) * version + 64;
+    if version >= 2 {
+        let num_align = version / 7 + 2;
+        result -= (25 * num_align - 10) * num_align - 55;
+        if version >= 7 {
+            result -= 36;
+        }
+    }
+    result
+}
+
+/// 位置合わせパターンの中心座標
+fn alignment_pattern_positions(version: usize) -> Vec<usize> {
+    if version == 1 {
+        return Vec::new();
+    }
+    let size = version * 4 + 17;
+    let num_align = version / 7 + 2;
+    let step = (version * 4 + num_align * 2 + 1) / (num_align * 2 - 2) * 2;
+    let mut result: Vec<usize> = (0..num_align - 1).map(|i| size - 7 - i * step).collect();
+    result.push(6);
+    result.reverse();
+    result
+}
+
+// ============================================================
+// Reed-Solomon (GF(2^8), 原始多項式 0x11D)
+// ============================================================
+
+fn gf_multiply(x: u8, y: u8) -> u8 {
+    let mut z: u32 = 0;
+    for i in (0..8).rev() {
+        z = (z << 1) ^ ((z >> 7) * 0x11D);
+        z ^= ((y as u32 >> i) & 1) * x as u32;
+    }
+    z as u8
+}
+
+fn reed_solomon_divisor(degree: usize) -> Vec<u8> {
+    let mut result = vec![0u8; degree];
+    result[degree - 1] = 1;
+    let mut root: u8 = 1;
+    for _ in 0..degree {
+        for j in 0..degree {
+            result[j] = gf_multiply(result[j], root);
+            if j + 1 < degree {
+                result[j] ^= result[j + 1];
+            }
+        }
+        root = gf_multiply(root, 0x02);
+    }
+    result
+}
+
+fn reed_solomon_remainder(data: &[u8], divisor: &[u8]) -> Vec<u8> {
+    let mut result = vec![0u8; divisor.len()];
+    for &b in data {
+        let factor = b ^ result.remove(0);
+        result.push(0);
+        for (r, &d) in result.iter_mut().zip(divisor) {
+            *r ^= gf_multiply(d, factor);
+        }
+    }
+    result
+}
+
+// ============================================================
+// ビットバッファ
+// ============================================================
+
+#[derive(Default)]
+struct BitBuffer {
+    bits: Vec<bool>,
+}
+
+impl BitBuffer {
+    fn append(&mut self, value: u32, len: usize) {
+        for i in (0..len).rev() {
+            self.bits.push((value >> i) & 1 != 0);
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.bits.len()
+    }
+
+    fn into_bytes(self) -> Vec<u8> {
+        self.bits
+            .chunks(8)
+            .map(|chunk| chunk.iter().fold(0u8, |acc, &b| (acc << 1) | u8::from(b)))
+            .collect()
+    }
+}
+
+// ============================================================
+// モジュール配置
+// ============================================================
+
+#[derive(Clone)]
+struct Builder {
+    version: usize,
+    size: usize,
+    modules: Vec<bool>,
+    is_function: Vec<bool>,
+}
+
+impl Builder {
+    fn new(version: usize) -> Self {
+        let size = version * 4 + 17;
+        Self {
+            version,
+            size,
+            modules: vec![false; size * size],
+            is_function: vec![false; size * size],
+        }
+    }
+
+    fn get(&self, x: usize, y: usize) -> bool {
+        self.modules[y * self.size + x]
+    }
+
+    fn set_function(&mut self, x: usize, y: usize, dark: bool) {
+        self.modules[y * self.size + x] = dark;
+        self.is_function[y * self.size + x] = true;
+    }
+
+    fn draw_function_patterns(&mut self) {
+        let size = self.size;
+
+        // タイミングパターン
+        for i in 0..size {
+            self.set_function(6, i, i % 2 == 0);
+            self.set_function(i, 6, i % 2 == 0);
+        }
+
+        // 位置検出パターン（3隅）
+        self.draw_finder_pattern(3, 3);
+        self.draw_finder_pattern(size - 4, 3);
+        self.draw_finder_pattern(3, size - 4);
+
+        // 位置合わせパターン（位置検出パターンと重なる3隅を除く）
+        let positions = alignment_pattern_positions(self.version);
+        let last = positions.len().saturating_sub(1);
+        for (i, &x) in positions.iter().enumerate() {
+            for (j, &y) in positions.iter().enumerate() {
+                let overlaps_finder = (i == 0 && (j == 0 || j == last)) || (i == last && j == 0);
+                if !overlaps_finder {
+                    self.draw_alignment_pattern(x, y);
+                }
+            }
+        }
+
+        // フォーマット情報の領域を予約（マスク決定後に上書き）
+        self.draw_format_bits(0);
+        self.draw_version();
+    }
+
+    fn draw_finder_pattern(&mut self, cx: usize, cy: usize) {
+        for dy in -4i32..=4 {
+            for dx in -4i32..=4 {
+                let x = cx as i32 + dx;
+                let y = cy as i32 + dy;
+                if x < 0 || y < 0 || x >= self.size as i32 || y >= self.size as i32 {
+                    continue;
+                }
+                let dist = dx.abs().max(dy.abs());
+                self.set_function(x as usize, y as usize, dist != 2 && dist != 4);
+            }
+        }
+    }
+
+    fn draw_alignment_pattern(&mut self, cx: usize, cy: usize) {
+        for dy in -2i32..=2 {
+            for dx in -2i32..=2 {
+                let x = (cx as i32 + dx) as usize;
+                let y = (cy as i32 + dy) as usize;
+                self.set_function(x, y, dx.abs().max(dy.abs()) != 1);
+            }
+        }
+    }
+
+    fn draw_format_bits(&mut self, mask: u8) {
+        let data = (ECC_FORMAT_BITS_M << 3) | mask as u32;
+        let mut rem = data;
+        for _ in 0..10 {
+            rem = (rem << 1) ^ ((rem >> 9) * 0x537);
+        }
+        let bits = ((data << 10) | rem) ^ 0x5412;
+        let bit = |i: usize| (bits >> i) & 1 != 0;
+        let size = self.size;
+
+        // 左上
+        for i in 0..=5 {
+            self.set_function(8, i, bit(i));
+        }
+        self.set_function(8, 7, bit(6));
+        self.set_function(8, 8, bit(7));
+        self.set_function(7, 8, bit(8));
+        for i in 9..15 {
+            self.set_function(14 - i, 8, bit(i));
+        }
+
+        // 右上・左下
+        for i in 0..8 {
+            self.set_function(size - 1 - i, 8, bit(i));
+        }
+        for i in 8..15 {
+            self.set_function(8, size - 15 + i, bit(i));
+        }
+        self.set_function(8, size - 8, true);
+    }
+
+    fn draw_version(&mut self) {
+        if self.version < 7 {
+            return;
+        }
+        let mut rem = self.version as u32;
+        for _ in 0..12 {
+            rem = (rem << 1) ^ ((rem >> 11) * 0x1F25);
+        }
+        let bits = ((self.version as u32) << 12) | rem;
+        for i in 0..18 {
+            let dark = (bits >> i) & 1 != 0;
+            let a = self.size - 11 + i % 3;
+            let b = i / 3;
+            self.set_function(a, b, dark);
+            self.set_function(b, a, dark);
+        }
+    }
+
+    /// 右下から2列ずつジグザグにデータを配置
+    fn draw_codewords(&mut self, data: &[u8]) {
+        let size = self.size;
+        let total_bits = data.len() * 8;
+        let mut i = 0;
+        let mut right = size as i32 - 1;
+        while right >= 1 {
+            if right == 6 {
+                right = 5;
+            }
+            for vert in 0..size {
+                for j in 0..2 {
+                    let x = (right - j) as usize;
+                    let upward = ((right + 1) & 2) == 0;
+                    let y = if upward { size - 1 - vert } else { vert };
+                    if !self.is_function[y * size + x] && i < total_bits {
+                        self.modules[y * size + x] = (data[i >> 3] >> (7 - (i & 7))) & 1 != 0;
+                        i += 1;
+                    }
+                }
+            }
+            right -= 2;
+        }
+    }
+
+    fn apply_mask(&mut self, mask: u8) {
+        for y in 0..self.size {
+            for x in 0..self.size {
+                let invert = match mask {
+                    0 => (x + y) % 2 == 0,
+                    1 => y % 2 == 0,
+                    2 => x % 3 == 0,
+                    3 => (x + y) % 3 == 0,
+                    4 => (x / 3 + y / 2) % 2 == 0,
+                    5 => x * y % 2 + x * y % 3 == 0,
+                    6 => (x * y % 2 + x * y % 3) % 2 == 0,
+                    _ => ((x + y) % 2 + x * y % 3) % 2 == 0,
+                };
+                let idx = y * self.size + x;
+                if invert && !self.is_function[idx] {
+                    self.modules[idx] = !self.modules[idx];
+                }
+            }
+        }
+    }
+
+    /// JIS X 0510 の失点計算（N1〜N4）
+    fn penalty_score(&self) -> u32 {
+        let size = self.size;
+        let mut score = 0u32;
+
+        let lines: Vec<Vec<bool>> = (0..size)
+            .map(|y| (0..size).map(|x| self.get(x, y)).collect())
+            .chain((0..size).map(|x| (0..size).map(|y| self.get(x, y)).collect()))
+            .collect();
+
+        const FINDER_LIKE: [bool; 11] = [
+            true, false, true, true, true, false, true, false, false, false, false,
+        ];
+        for line in &lines {
+            // N1: 同色モジュールの連続
+            let mut run = 1;
+            for i in 1..=size {
+                if i < size && line[i] == line[i - 1] {
+                    run += 1;
+                } else {
+                    if run >= 5 {
+                        score += 3 + (run - 5);
+                    }
+                    run = 1;
+                }
+            }
+            // N3: 1:1:3:1:1 の位置検出パターン類似
+            for window in line.windows(11) {
+                let forward = window.iter().zip(FINDER_LIKE.iter()).all(|(a, b)| a == b);
+                let backward = window.iter().zip(FINDER_LIKE.iter().rev()).all(|(a, b)| a == b);
+                if forward || backward {
+                    score += 40;
+                }
+            }
+        }
+
+        // N2: 2x2 の同色ブロック
+        for y in 0..size - 1 {
+            for x in 0..size - 1 {
+                let c = self.get(x, y);
+                if c == self.get(x + 1, y) && c == self.get(x, y + 1) && c == self.get(x + 1, y + 1) {
+                    score += 3;
+                }
+            }
+        }
+
+        // N4: 暗モジュール比率の偏り
+        let dark = self.modules.iter().filter(|&&m| m).count();
+        let total = size * size;
+        let k = (dark * 20).abs_diff(total * 10).div_ceil(total).saturating_sub(1);
+        score += k as u32 * 10;
+
+        score
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_version_selection() {
+        let short = QrCode::encode(b"https://example.com/room/ABC123").unwrap();
+        assert_eq!(short.size(), 3 * 4 + 17);
+
+        assert!(QrCode::encode(&[b'a'; 213]).is_some());
+        assert!(QrCode::encode(&[b'a'; 214]).is_none());
+    }
+
+    #[test]
+    fn test_finder_patterns() {
+        let qr = QrCode::encode(b"9life").unwrap();
+        let size = qr.size();
+        for (cx, cy) in [(3, 3), (size - 4, 3), (3, size - 4)] {
+            assert!(qr.is_dark(cx, cy));
+            assert!(qr.is_dark(cx - 3, cy - 3));
+            assert!(!qr.is_dark(cx - 2, cy - 2));
+        }
+        // 固定の暗モジュール
+        assert!(qr.is_dark(8, size - 8));
+    }
+
+    #[test]
+    fn test_reed_solomon_codeword_has_roots() {
+        // データ + ECC を多項式とみなすと α^0..α^(n-1) が根になる
+        let data = QrCode::data_codewords(b"https://example.com/room/ABC123", 3);
+        let ecc_len = ECC_CODEWORDS_PER_BLOCK[2];
+        let divisor = reed_solomon_divisor(ecc_len);
+        let mut codeword = data.clone();
+        codeword.extend(reed_solomon_remainder(&data, &divisor));
+
+        let mut root = 1u8;
+        for _ in 0..ecc_len {
+            let value = codeword.iter().fold(0u8, |acc, &c| gf_multiply(acc, root) ^ c);
+            assert_eq!(value, 0);
+            root = gf_multiply(root, 0x02);
+        }
+    }
+
+    #[test]
+    fn test_svg_output() {
+        let svg = QrCode::encode(b"9life").unwrap().to_svg();
+        assert!(svg.starts_with("<?xml"));
+        assert!(svg.contains("viewBox=\"0 0 29 29\""));
+        assert!(svg.trim_end().ends_with("</svg>"));
+    }
+}
@@ -0,0 +1,95 @@
+use std::path::{Path, PathBuf};
+
+use axum::http::{header, StatusCode, Uri};
+use axum::response::{IntoResponse, Response};
+
+/// クライアント（Vite ビルド成果物 dist/）の配信
+/// 存在しないパスは index.html を返し、React 側のルーティングに任せる
+pub async fn serve(root: &Path, uri: Uri) -> Response {
+    let request_path = uri.path();
+
+    // API 系の未定義パスは SPA にフォールバックさせない
+    if request_path.starts_with("/api/") || request_path.starts_with("/admin/") {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+
+    let Some(relative) = sanitize_path(request_path) else {
+        return StatusCode::BAD_REQUEST.into_response();
+    };
+
+    let file_path = root.join(&relative);
+    if let Some(response) = serve_file(&file_path).await {
+        return response;
+    }
+
+    // 拡張子付きのパス（アセット）が無い場合は 404、それ以外は SPA フォールバック
+    if relative.extension().is_some() {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    serve_file(&root.join("index.html"))
+        .await
+        .unwrap_or_else(|| StatusCode::NOT_FOUND.into_response())
+}
+
+/// URLパスを root 以下の相対パスに変換する。`..` などを含む場合は None
+fn sanitize_path(request_path: &str) -> Option<PathBuf> {
+    let mut relative = PathBuf::new();
+    for segment in request_path.split('/') {
+        match segment {
+            "" | "." => continue,
+            ".." => return None,
+            s if s.contains('\\') || s.contains('\0') => return None,
+            s => relative.push(s),
+        }
+    }
+    if relative.as_os_str().is_empty() {
+        relative.push("index.html");
+    }
+    Some(relative)
+}
+
+async fn serve_file(path: &Path) -> Option<Response> {
+    let metadata = tokio::fs::metadata(path).await.ok()?;
+    if !metadata.is_file() {
+        return None;
+    }
+    let body = tokio::fs::read(path).await.ok()?;
+
+    // Vite の assets/ はファイル名にハッシュが付くので長期キャッシュ可
+    let cache_control = if path.components().any(|c| c.as_os_str() == "assets") {
+        "public, max-age=31536000, immutable"
+    } else {
+        "no-cache"
+    };
+
+    Some(
+        (
+            [
+                (header::CONTENT_TYPE, content_type(path)),
+                (header::CACHE_CONTROL, cache_control),
+            ],
+            body,
+        )
+            .into_response(),
+    )
+}
+
+fn content_type(path: &Path) -> &'static str {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("html") => "text/html; charset=utf-8",
+        Some("js") | Some("mjs") => "text/javascript; charset=utf-8",
+        Some("css") => "text/css; charset=utf-8",
+        Some("json") => "application/json",
+        Some("svg") => "image/svg+xml",
+        Some("png") => "image/png",
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        Some("ico") => "image/x-icon",
+        Some("woff") => "font/woff",
+        Some("woff2") => "font/woff2",
+        Some("wasm") => "application/wasm",
+        Some("txt") => "text/plain; charset=utf-8",
+        _ => "application/octet-stream",
+    }
+}
@@ -0,0 +1,29 @@
+//! `{{key}}` 形式のプレースホルダを置換するだけの最小限のテンプレート
+
+/// テンプレート中の `{{key}}` を値で置換する
+/// 値はエスケープしないので、ユーザー入力は呼び出し側で `escape_html` すること
+pub fn render(template: &str, vars: &[(&str, String)]) -> String {
+    let mut output = template.to_string();
+    for (key, value) in vars {
+        output = output.replace(&format!("{{{{{}}}}}", key), value);
+    }
+    output
+}
+
+/// HTML 特殊文字のエスケープ
+/// `{` もエスケープし、入れ子のテンプレートでプレースホルダとして解釈されないようにする
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            '{' => escaped.push_str("&#123;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,157 @@
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+/// 送信タイムアウト（接続〜ステータス行受信まで）
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 送信先URL（http:// のみ対応。TLS が必要な場合は中継サーバーを挟む）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpUrl {
+    pub host: String,
+    pub port: u16,
+    pub path: String,
+}
+
+impl HttpUrl {
+    pub fn parse(url: &str) -> Result<Self, String> {
+        let rest = url
+            .strip_prefix("http://")
+            .ok_or_else(|| format!("unsupported url (http:// only): {}", url))?;
+        let (authority, path) = match rest.find('/') {
+            Some(i) => (&rest[..i], &rest[i..]),
+            None => (rest, "/"),
+        };
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => (
+                host,
+                port.parse()
+                    .map_err(|_| format!("invalid port in url: {}", url))?,
+            ),
+            None => (authority, 80),
+        };
+        if host.is_empty() {
+            return Err(format!("missing host in url: {}", url));
+        }
+        Ok(Self {
+            host: host.to_string(),
+            port,
+            path: path.to_string(),
+        })
+    }
+}
+
+/// JSON を POST し、レスポンスのステータスコードを返す
+pub async fn post_json(url: &HttpUrl, body: &str) -> Result<u16, String> {
+    tokio::time::timeout(TIMEOUT, post_json_inner(url, body))
+        .await
+        .map_err(|_| "timed out".to_string())?
+}
+
+async fn post_json_inner(url: &HttpUrl, body: &str) -> Result<u16, String> {
+    let mut stream = TcpStream::connect((url.host.as_str(), url.port))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let request = format!(
+        "POST {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: nine-life-server\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        url.path,
+        url.host,
+        body.len(),
+        body
+    );
+    stream
+        .write_all(request.as_bytes())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    // ステータス行だけ読めば十分
+    let mut buf = [0u8; 64];
+    let n = stream.read(&mut buf).await.map_err(|e| e.to_string())?;
+    let status_line = String::from_utf8_lossy(&buf[..n]);
+    status_line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse().ok())
+        .ok_or_else(|| format!("invalid response: {}", status_line))
+}
+
+/// 読み込むレスポンスの上限
+const MAX_RESPONSE_BYTES: usize = 64 * 1024;
+
+/// JSON を POST し、ステータスコードとレスポンス本文を返す
+/// chunked で返されないよう HTTP/1.0 で送る
+pub async fn post_json_for_body(url: &HttpUrl, body: &str, timeout: Duration) -> Result<(u16, String), String> {
+    tokio::time::timeout(timeout, post_json_for_body_inner(url, body))
+        .await
+        .map_err(|_| "timed out".to_string())?
+}
+
+async fn post_json_for_body_inner(url: &HttpUrl, body: &str) -> Result<(u16, String), String> {
+    let mut stream = TcpStream::connect((url.host.as_str(), url.port))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let request = format!(
+        "POST {} HTTP/1.0\r\nHost: {}\r\nUser-Agent: nine-life-server\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+        url.path,
+        url.host,
+        body.len(),
+        body
+    );
+    stream
+        .write_all(request.as_bytes())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut response = Vec::new();
+    stream
+        .take(MAX_RESPONSE_BYTES as u64)
+        .read_to_end(&mut response)
+        .await
+        .map_err(|e| e.to_string())?;
+    parse_response(&response)
+}
+
+fn parse_response(response: &[u8]) -> Result<(u16, String), String> {
+    let text = String::from_utf8_lossy(response);
+    let (head, body) = text
+        .split_once("\r\n\r\n")
+        .ok_or_else(|| "incomplete response".to_string())?;
+    let status = head
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse().ok())
+        .ok_or_else(|| format!("invalid response: {}", head.lines().next().unwrap_or_default()))?;
+    Ok((status, body.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_url() {
+        let url = HttpUrl::parse("http://bot.local:8080/hooks/9life").unwrap();
+        assert_eq!(url.host, "bot.local");
+        assert_eq!(url.port, 8080);
+        assert_eq!(url.path, "/hooks/9life");
+
+        let url = HttpUrl::parse("http://example.com").unwrap();
+        assert_eq!(url.port, 80);
+        assert_eq!(url.path, "/");
+
+        assert!(HttpUrl::parse("https://discord.com/api/webhooks/x").is_err());
+        assert!(HttpUrl::parse("http://:80/").is_err());
+    }
+
+    #[test]
+    fn test_parse_response() {
+        let (status, body) =
+            parse_response(b"HTTP/1.0 200 OK\r\nContent-Type: application/json\r\n\r\n{\"action\":\"allow\"}").unwrap();
+        assert_eq!(status, 200);
+        assert_eq!(body, r#"{"action":"allow"}"#);
+        assert!(parse_response(b"HTTP/1.0 200 OK\r\n").is_err());
+    }
+}
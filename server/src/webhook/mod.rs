@@ -0,0 +1,74 @@
+pub mod client;
+
+use serde::Serialize;
+
+use crate::protocol::{PlayerId, RankingEntry, RoomId};
+use client::HttpUrl;
+
+/// 外部に通知する部屋のライフサイクルイベント
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum WebhookEvent {
+    RoomCreated {
+        room_id: RoomId,
+        map_id: String,
+        host_name: String,
+        private: bool,
+    },
+    GameStarted {
+        room_id: RoomId,
+        players: Vec<String>,
+    },
+    GameEnded {
+        room_id: RoomId,
+        winner_id: Option<PlayerId>,
+        rankings: Vec<RankingEntry>,
+    },
+}
+
+/// Webhook 送信器
+/// 送信はバックグラウンドタスクで行い、ゲーム処理をブロックしない
+#[derive(Clone, Default)]
+pub struct WebhookNotifier {
+    urls: Vec<HttpUrl>,
+}
+
+impl WebhookNotifier {
+    /// 不正なURLは警告を出して無視する
+    pub fn new(urls: &[String]) -> Self {
+        let urls = urls
+            .iter()
+            .filter_map(|url| match HttpUrl::parse(url) {
+                Ok(url) => Some(url),
+                Err(e) => {
+                    eprintln!("webhook disabled: {}", e);
+                    None
+                }
+            })
+            .collect();
+        Self { urls }
+    }
+
+    pub fn notify(&self, event: WebhookEvent) {
+        if self.urls.is_empty() {
+            return;
+        }
+        let body = match serde_json::to_string(&event) {
+            Ok(body) => body,
+            Err(e) => {
+                eprintln!("webhook serialize failed: {}", e);
+                return;
+            }
+        };
+        for url in self.urls.clone() {
+            let body = body.clone();
+            tokio::spawn(async move {
+                match client::post_json(&url, &body).await {
+                    Ok(status) if (200..300).contains(&status) => {}
+                    Ok(status) => eprintln!("webhook {}{} returned {}", url.host, url.path, status),
+                    Err(e) => eprintln!("webhook {}{} failed: {}", url.host, url.path, e),
+                }
+            });
+        }
+    }
+}
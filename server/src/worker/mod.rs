@@ -0,0 +1,341 @@
+//! 接続処理（ゲートウェイ）とゲーム処理（部屋ワーカー）の境界
+//!
+//! WebSocket の接続を持つゲートウェイ（main.rs の handle_socket）は、部屋に入った後のゲーム操作を WorkerRequest にして
+//! WorkerLink に渡し、本人への返信だけを受け取る。部屋全体への配信はワーカーが RoomManager::broadcast で行う。
+//! Ping・チャット・入力中表示・リアクションは接続ごとの連投制限と合わせてゲートウェイが処理する。
+//! 要求と返信は serde で読み書きできる形にしてあり、同じプロセス内では LocalWorker が mpsc で受け渡す。
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{mpsc, oneshot, Mutex};
+
+use crate::protocol::{ClientMessage, PlayerId, RoomId, ServerMessage};
+use crate::room::manager::{ActionOutcome, LedgerViewer};
+use crate::room::vote::KICK_VOTE_TIMEOUT;
+use crate::room::{RoomError, RoomManager};
+
+/// ワーカーへの要求の待ち行列の長さ（溢れたらゲートウェイを待たせる）
+const WORKER_QUEUE: usize = 1024;
+
+/// 部屋に入っているプレイヤーからのゲーム操作
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkerRequest {
+    pub room_id: RoomId,
+    pub player_id: PlayerId,
+    pub message: ClientMessage,
+}
+
+/// ゲートウェイからワーカーへの経路
+#[async_trait]
+pub trait WorkerLink: Send + Sync {
+    /// 要求を処理し、本人への返信を返す
+    async fn request(&self, request: WorkerRequest) -> Vec<ServerMessage>;
+}
+
+type Job = (WorkerRequest, oneshot::Sender<Vec<ServerMessage>>);
+
+/// 同じプロセス内のワーカー（mpsc で要求を受け、workers 個のタスクで並行に処理する）
+/// 同じ接続からの要求は返信を待ってから次を送るため、並行に処理しても順序は入れ替わらない
+pub struct LocalWorker {
+    tx: mpsc::Sender<Job>,
+}
+
+impl LocalWorker {
+    pub fn spawn(room_manager: Arc<RoomManager>, workers: usize) -> Self {
+        let (tx, rx) = mpsc::channel::<Job>(WORKER_QUEUE);
+        let rx = Arc::new(Mutex::new(rx));
+        for _ in 0..workers.max(1) {
+            let rx = rx.clone();
+            let room_manager = room_manager.clone();
+            tokio::spawn(async move {
+                loop {
+                    let Some((request, reply)) = rx.lock().await.recv().await else {
+                        return;
+                    };
+                    let _ = reply.send(dispatch(&room_manager, request).await);
+                }
+            });
+        }
+        Self { tx }
+    }
+}
+
+#[async_trait]
+impl WorkerLink for LocalWorker {
+    async fn request(&self, request: WorkerRequest) -> Vec<ServerMessage> {
+        let (reply_tx, reply_rx) = oneshot::channel();
+        if self.tx.send((request, reply_tx)).await.is_err() {
+            return Vec::new();
+        }
+        reply_rx.await.unwrap_or_default()
+    }
+}
+
+/// 1つの要求を処理する（部屋全体への配信はここで行い、本人への返信を返す）
+pub async fn dispatch(room_manager: &Arc<RoomManager>, request: WorkerRequest) -> Vec<ServerMessage> {
+    let WorkerRequest {
+        room_id,
+        player_id,
+        message,
+    } = request;
+    let room_id = room_id.as_str();
+    let result = match message {
+        ClientMessage::UpdateProfile { favorite_color } => {
+            match room_manager.update_profile(room_id, &player_id, favorite_color).await {
+                Ok(profile) => {
+                    let msg = ServerMessage::PlayerProfileUpdated {
+                        player_id: player_id.clone(),
+                        profile,
+                    };
+                    room_manager.broadcast(room_id, &msg).await;
+                    Ok(Vec::new())
+                }
+                Err(e) => Err(e),
+            }
+        }
+        ClientMessage::MutePlayer { player_id: target } => {
+            mute_player(room_manager, room_id, &player_id, target, true).await
+        }
+        ClientMessage::UnmutePlayer { player_id: target } => {
+            mute_player(room_manager, room_id, &player_id, target, false).await
+        }
+        ClientMessage::BanPlayer { player_id: target } => room_manager
+            .ban_player(room_id, &player_id, &target)
+            .await
+            .map(|_| Vec::new()),
+        ClientMessage::PauseGame => pause_game(room_manager, room_id, &player_id, true).await,
+        ClientMessage::ResumeGame => pause_game(room_manager, room_id, &player_id, false).await,
+        ClientMessage::ReturnToLobby => room_manager
+            .return_to_lobby(room_id, &player_id)
+            .await
+            .map(|_| Vec::new()),
+        ClientMessage::StartKickVote { target } => {
+            match room_manager.start_kick_vote(room_id, &player_id, &target).await {
+                Ok((vote_id, msgs)) => {
+                    broadcast_all(room_manager, room_id, msgs).await;
+                    let room_manager = room_manager.clone();
+                    let room_id = room_id.to_string();
+                    tokio::spawn(async move {
+                        tokio::time::sleep(KICK_VOTE_TIMEOUT).await;
+                        let msgs = room_manager.expire_kick_vote(&room_id, vote_id).await;
+                        broadcast_all(&room_manager, &room_id, msgs).await;
+                    });
+                    Ok(Vec::new())
+                }
+                Err(e) => Err(e),
+            }
+        }
+        ClientMessage::CastKickVote { approve } => {
+            match room_manager.cast_kick_vote(room_id, &player_id, approve).await {
+                Ok(msgs) => {
+                    broadcast_all(room_manager, room_id, msgs).await;
+                    Ok(Vec::new())
+                }
+                Err(e) => Err(e),
+            }
+        }
+        ClientMessage::RequestGameSync => room_manager
+            .game_snapshot(room_id)
+            .await
+            .ok_or(RoomError::GameNotStarted),
+        ClientMessage::RequestStandings => room_manager
+            .standings(room_id)
+            .await
+            .map(|rankings| vec![ServerMessage::Standings { rankings }]),
+        ClientMessage::GetRecentEvents { count } => room_manager
+            .recent_events(room_id, count)
+            .await
+            .map(|events| vec![ServerMessage::RecentEvents { events }]),
+        ClientMessage::RequestLedger { offset, limit } => room_manager
+            .ledger_page(room_id, LedgerViewer::Player(&player_id), offset.unwrap_or(0), limit)
+            .await
+            .map(|page| {
+                vec![ServerMessage::LedgerPage {
+                    offset: page.offset,
+                    total: page.total,
+                    entries: page.entries,
+                }]
+            }),
+        ClientMessage::LeaveRoom => {
+            // 管理者による切断・部屋削除済みなら通知不要
+            if let Ok(msgs) = room_manager.leave_room(room_id, &player_id).await {
+                broadcast_all(room_manager, room_id, msgs).await;
+            }
+            Ok(Vec::new())
+        }
+        ClientMessage::StartGame => match room_manager.start_game(room_id, &player_id).await {
+            Ok(msgs) => {
+                broadcast_all(room_manager, room_id, msgs).await;
+                Ok(Vec::new())
+            }
+            Err(e) => Err(e),
+        },
+        ClientMessage::SpinRoulette { action_token } => {
+            let outcome = room_manager.spin_roulette(room_id, &player_id, action_token).await;
+            apply_outcome(room_manager, room_id, outcome).await
+        }
+        ClientMessage::ChoicePath {
+            path_index,
+            action_token,
+        } => {
+            let outcome = room_manager
+                .choose_path(room_id, &player_id, path_index, action_token)
+                .await;
+            apply_outcome(room_manager, room_id, outcome).await
+        }
+        ClientMessage::ChoiceAction {
+            action_id,
+            action_token,
+        } => {
+            let outcome = room_manager
+                .choose_action(room_id, &player_id, action_id, action_token)
+                .await;
+            apply_outcome(room_manager, room_id, outcome).await
+        }
+        ClientMessage::SellStock {
+            stock_id,
+            action_token,
+        } => {
+            let outcome = room_manager
+                .sell_stock(room_id, &player_id, stock_id, action_token)
+                .await;
+            apply_outcome(room_manager, room_id, outcome).await
+        }
+        ClientMessage::MortgageHouse {
+            house_id,
+            action_token,
+        } => {
+            let outcome = room_manager
+                .mortgage_house(room_id, &player_id, house_id, action_token)
+                .await;
+            apply_outcome(room_manager, room_id, outcome).await
+        }
+        ClientMessage::RedeemHouse {
+            house_id,
+            action_token,
+        } => {
+            let outcome = room_manager
+                .redeem_house(room_id, &player_id, house_id, action_token)
+                .await;
+            apply_outcome(room_manager, room_id, outcome).await
+        }
+        _ => {
+            return vec![ServerMessage::Error {
+                code: "UNKNOWN_MESSAGE".to_string(),
+                message: "Unrecognized message type".to_string(),
+                valid_choices: None,
+            }];
+        }
+    };
+    result.unwrap_or_else(|e| vec![ServerMessage::from(e)])
+}
+
+async fn broadcast_all(room_manager: &RoomManager, room_id: &str, msgs: Vec<ServerMessage>) {
+    for msg in msgs {
+        room_manager.broadcast(room_id, &msg).await;
+    }
+}
+
+/// 手番の操作の結果を配る（再送された操作の結果は本人にだけ返す）
+async fn apply_outcome(
+    room_manager: &RoomManager,
+    room_id: &str,
+    outcome: Result<ActionOutcome, RoomError>,
+) -> Result<Vec<ServerMessage>, RoomError> {
+    match outcome? {
+        ActionOutcome::Applied(msgs) => {
+            broadcast_all(room_manager, room_id, msgs).await;
+            Ok(Vec::new())
+        }
+        ActionOutcome::Duplicate(msgs) => Ok(msgs),
+    }
+}
+
+/// ミュート・解除を反映して全員に通知する
+async fn mute_player(
+    room_manager: &RoomManager,
+    room_id: &str,
+    host_id: &str,
+    target: String,
+    muted: bool,
+) -> Result<Vec<ServerMessage>, RoomError> {
+    room_manager.set_muted(room_id, host_id, &target, muted).await?;
+    let msg = ServerMessage::PlayerMuteChanged {
+        player_id: target,
+        muted,
+    };
+    room_manager.broadcast(room_id, &msg).await;
+    Ok(Vec::new())
+}
+
+/// 一時停止・再開を反映して全員に通知する（既にその状態なら何もしない）
+async fn pause_game(
+    room_manager: &RoomManager,
+    room_id: &str,
+    host_id: &str,
+    paused: bool,
+) -> Result<Vec<ServerMessage>, RoomError> {
+    let Some(msgs) = room_manager.set_paused(room_id, host_id, paused).await? else {
+        return Ok(Vec::new());
+    };
+    let player_id = host_id.to_string();
+    let msg = if paused {
+        ServerMessage::GamePaused { player_id }
+    } else {
+        ServerMessage::GameResumed { player_id }
+    };
+    room_manager.broadcast(room_id, &msg).await;
+    broadcast_all(room_manager, room_id, msgs).await;
+    Ok(Vec::new())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::map::MapCatalog;
+    use crate::profile::ProfileStore;
+    use crate::storage::MemoryStorage;
+    use crate::webhook::WebhookNotifier;
+
+    fn error_code(msgs: &[ServerMessage]) -> Option<&str> {
+        match msgs {
+            [ServerMessage::Error { code, .. }] => Some(code),
+            _ => None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_local_worker_replies_to_requester() {
+        let room_manager = Arc::new(RoomManager::new(
+            6,
+            WebhookNotifier::new(&[]),
+            ProfileStore::new(Arc::new(MemoryStorage::new())),
+            MapCatalog::builtin(),
+            Duration::ZERO,
+        ));
+        let worker = LocalWorker::spawn(room_manager, 2);
+        let request = |message| WorkerRequest {
+            room_id: "NOROOM".to_string(),
+            player_id: "p1".to_string(),
+            message,
+        };
+
+        let msgs = worker.request(request(ClientMessage::RequestStandings)).await;
+        assert_eq!(error_code(&msgs), Some("ROOM_NOT_FOUND"));
+        // ゲートウェイが処理するメッセージはワーカーでは扱わない
+        let msgs = worker.request(request(ClientMessage::Ping { client_ts: 1 })).await;
+        assert_eq!(error_code(&msgs), Some("UNKNOWN_MESSAGE"));
+        // 部屋が無くても退出は黙って終わる
+        assert!(worker.request(request(ClientMessage::LeaveRoom)).await.is_empty());
+
+        // ノードをまたぐときもそのまま送れる
+        let json = serde_json::to_string(&request(ClientMessage::PauseGame)).unwrap();
+        let decoded: WorkerRequest = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.room_id, "NOROOM");
+        assert!(matches!(decoded.message, ClientMessage::PauseGame));
+    }
+}